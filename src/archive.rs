@@ -0,0 +1,132 @@
+// src/archive.rs
+
+use crate::{favorites::FAVORITES_FILE, history, recent::RECENT_FILE, session::SESSION_FILE, theme};
+use std::{
+    env,
+    fs::{self, DirBuilder},
+    io,
+    os::unix::fs::DirBuilderExt,
+    path::{Path, PathBuf},
+    process::{self, Command},
+};
+
+/// Files under the state directory that are part of the setup; run history, drafts and the run
+/// lock belong to this machine and stay behind.
+const STATE_FILES: [&str; 3] = [SESSION_FILE, RECENT_FILE, FAVORITES_FILE];
+/// Written at the archive's root so imports can tell a configuration archive from any tarball.
+const MARKER_FILE: &str = "redcent-config";
+const FORMAT_VERSION: u32 = 1;
+
+/// Where the configuration and the state live.
+pub struct Dirs {
+    /// The directory holding the config file, e.g. `~/.config/redcent-tui`.
+    pub config: PathBuf,
+    pub state: PathBuf,
+}
+
+impl Dirs {
+    /// This user's directories; `None` without a home directory.
+    pub fn current() -> Option<Dirs> {
+        Some(Dirs { config: theme::config_path()?.parent()?.to_path_buf(), state: history::state_dir()? })
+    }
+}
+
+/// Bundles the whole config directory and the session and recently used items into a gzipped
+/// tarball at `archive`, with `config/` and `state/` at its root. Returns the bundled files,
+/// relative to the archive.
+pub fn export(dirs: &Dirs, archive: &Path) -> io::Result<Vec<PathBuf>> {
+    let staging = Staging::new("export")?;
+    let mut bundled = Vec::new();
+    if dirs.config.is_dir() {
+        copy_tree(&dirs.config, &staging.0.join("config"), Path::new("config"), &mut bundled)?;
+    }
+    for name in STATE_FILES {
+        let from = dirs.state.join(name);
+        if from.is_file() {
+            fs::create_dir_all(staging.0.join("state"))?;
+            fs::copy(&from, staging.0.join("state").join(name))?;
+            bundled.push(Path::new("state").join(name));
+        }
+    }
+    if bundled.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "there is no configuration or session to export yet"));
+    }
+    fs::write(staging.0.join(MARKER_FILE), format!("version = {}\n", FORMAT_VERSION))?;
+    let archive = env::current_dir()?.join(archive);
+    tar(&["-czf".as_ref(), archive.as_os_str(), "-C".as_ref(), staging.0.as_os_str(), ".".as_ref()])?;
+    Ok(bundled)
+}
+
+/// Unpacks an archive written by [`export`] over the current configuration and state. Returns
+/// the files written. Only regular files are taken, and from the state only the files
+/// [`export`] bundles.
+pub fn import(dirs: &Dirs, archive: &Path) -> io::Result<Vec<PathBuf>> {
+    let staging = Staging::new("import")?;
+    tar(&["-xzf".as_ref(), archive.as_os_str(), "-C".as_ref(), staging.0.as_os_str(), "--no-same-owner".as_ref()])?;
+    let version = fs::read_to_string(staging.0.join(MARKER_FILE))
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "not a redcent-tui configuration archive"))?;
+    if version.trim() != format!("version = {}", FORMAT_VERSION) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("unsupported archive format ({})", version.trim())));
+    }
+    let mut installed = Vec::new();
+    let config = staging.0.join("config");
+    if config.is_dir() {
+        copy_tree(&config, &dirs.config, &dirs.config, &mut installed)?;
+    }
+    for name in STATE_FILES {
+        let from = staging.0.join("state").join(name);
+        if fs::symlink_metadata(&from).is_ok_and(|meta| meta.is_file()) {
+            fs::create_dir_all(&dirs.state)?;
+            fs::copy(&from, dirs.state.join(name))?;
+            installed.push(dirs.state.join(name));
+        }
+    }
+    Ok(installed)
+}
+
+/// Copies the regular files and directories under `from` to `to`, recording each file as
+/// `shown` joined with its path below `from`. Symbolic links are skipped, so an archive cannot
+/// point a write outside the target directories.
+fn copy_tree(from: &Path, to: &Path, shown: &Path, copied: &mut Vec<PathBuf>) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    let mut entries: Vec<_> = fs::read_dir(from)?.collect::<Result<_, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let kind = entry.file_type()?;
+        let name = entry.file_name();
+        if kind.is_dir() {
+            copy_tree(&entry.path(), &to.join(&name), &shown.join(&name), copied)?;
+        } else if kind.is_file() {
+            fs::copy(entry.path(), to.join(&name))?;
+            copied.push(shown.join(&name));
+        }
+    }
+    Ok(())
+}
+
+fn tar(args: &[&std::ffi::OsStr]) -> io::Result<()> {
+    let out = Command::new("tar").args(args).output()?;
+    if out.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("tar failed: {}", String::from_utf8_lossy(&out.stderr).trim())))
+    }
+}
+
+/// A scratch directory under the temp directory, removed when dropped.
+struct Staging(PathBuf);
+
+impl Staging {
+    fn new(purpose: &str) -> io::Result<Staging> {
+        let dir = env::temp_dir().join(format!("redcent-{}-{}-{}", purpose, process::id(), history::timestamp_now()));
+        // Private, and never one left behind by someone else.
+        DirBuilder::new().mode(0o700).create(&dir)?;
+        Ok(Staging(dir))
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
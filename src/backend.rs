@@ -0,0 +1,235 @@
+// src/backend.rs
+
+use crate::OsDistribution;
+
+/// Renders package-management commands for a target distribution family.
+///
+/// Item scripts call these methods instead of hard-coding a package manager, so each
+/// distro's quirks live in one implementation.
+pub trait PackageBackend {
+    /// Short name of the package manager, e.g. `dnf`.
+    fn name(&self) -> &'static str;
+    /// Command installing the given packages non-interactively.
+    fn install(&self, packages: &[&str]) -> String;
+    /// Command installing a package group or environment.
+    fn group_install(&self, group: &str) -> String;
+    /// Command enabling an already-configured repository.
+    fn enable_repo(&self, repo: &str) -> String;
+    /// Command that succeeds only if all the given packages are installed.
+    fn query(&self, packages: &[&str]) -> String;
+    /// Distribution the scripts are authored for, which their `{{#if <id>}}` blocks are resolved
+    /// against; see [`crate::render_conditionals`]. Plain backends do not know it.
+    fn distro(&self) -> OsDistribution {
+        OsDistribution::Unknown
+    }
+}
+
+/// The DNF backend used on RHEL and CentOS Stream.
+pub struct Dnf;
+
+impl PackageBackend for Dnf {
+    fn name(&self) -> &'static str {
+        "dnf"
+    }
+
+    fn install(&self, packages: &[&str]) -> String {
+        format!("sudo dnf install -y {}", packages.join(" "))
+    }
+
+    fn group_install(&self, group: &str) -> String {
+        format!("sudo dnf groupinstall -y '{}'", group)
+    }
+
+    fn enable_repo(&self, repo: &str) -> String {
+        format!("sudo dnf config-manager --set-enabled {}", repo)
+    }
+
+    fn query(&self, packages: &[&str]) -> String {
+        format!("rpm -q {}", packages.join(" "))
+    }
+}
+
+/// The APT backend used on Debian and Ubuntu when authoring scripts for mixed fleets.
+pub struct Apt;
+
+impl PackageBackend for Apt {
+    fn name(&self) -> &'static str {
+        "apt"
+    }
+
+    fn install(&self, packages: &[&str]) -> String {
+        format!("sudo env DEBIAN_FRONTEND=noninteractive apt-get install -y {}", packages.join(" "))
+    }
+
+    fn group_install(&self, group: &str) -> String {
+        format!("sudo tasksel install {}", group)
+    }
+
+    fn enable_repo(&self, repo: &str) -> String {
+        format!("sudo add-apt-repository -y {}", repo)
+    }
+
+    fn query(&self, packages: &[&str]) -> String {
+        format!("dpkg-query -W {}", packages.join(" "))
+    }
+}
+
+/// Ties a backend to the distribution scripts are authored for, as returned by
+/// [`OsDistribution::package_backend`].
+pub struct Targeted(pub OsDistribution, pub Box<dyn PackageBackend>);
+
+impl PackageBackend for Targeted {
+    fn name(&self) -> &'static str {
+        self.1.name()
+    }
+
+    fn install(&self, packages: &[&str]) -> String {
+        self.1.install(packages)
+    }
+
+    fn group_install(&self, group: &str) -> String {
+        self.1.group_install(group)
+    }
+
+    fn enable_repo(&self, repo: &str) -> String {
+        self.1.enable_repo(repo)
+    }
+
+    fn query(&self, packages: &[&str]) -> String {
+        self.1.query(packages)
+    }
+
+    fn distro(&self) -> OsDistribution {
+        self.0
+    }
+}
+
+/// Base URL offered for offline mode: a RHEL or CentOS DVD, or a Debian or Ubuntu ISO, mounted at `/mnt/iso`.
+pub const DEFAULT_OFFLINE_BASEURL: &str = "file:///mnt/iso";
+
+/// DNF repositories written by [`offline_repo_setup`].
+const OFFLINE_DNF_REPOS: &str = "redcent-offline-*";
+/// APT source list written by [`offline_repo_setup`], relative to `/etc/apt`.
+const OFFLINE_APT_LIST: &str = "sources.list.d/redcent-offline.list";
+
+/// Wraps a backend so packages are installed only from the local repository that
+/// [`offline_repo_setup`] configures, for hosts without internet access.
+pub struct Offline(pub Box<dyn PackageBackend>);
+
+impl Offline {
+    fn restrict(&self, command: String) -> String {
+        match self.0.name() {
+            "apt" => command.replacen("apt-get ", &format!("apt-get -o Dir::Etc::sourcelist={} -o Dir::Etc::sourceparts=- ", OFFLINE_APT_LIST), 1),
+            _ => command.replacen("dnf ", &format!("dnf --disablerepo='*' --enablerepo='{}' ", OFFLINE_DNF_REPOS), 1),
+        }
+    }
+}
+
+impl PackageBackend for Offline {
+    fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    fn install(&self, packages: &[&str]) -> String {
+        self.restrict(self.0.install(packages))
+    }
+
+    fn group_install(&self, group: &str) -> String {
+        self.restrict(self.0.group_install(group))
+    }
+
+    fn enable_repo(&self, repo: &str) -> String {
+        self.0.enable_repo(repo)
+    }
+
+    fn query(&self, packages: &[&str]) -> String {
+        self.0.query(packages)
+    }
+
+    fn distro(&self) -> OsDistribution {
+        self.0.distro()
+    }
+}
+
+/// Commands pointing the package manager of `target` at the local repository under `baseurl`:
+/// the `BaseOS` and `AppStream` trees of a DVD or synced mirror for DNF, or an APT archive for
+/// the release's codename. `REDCENT_BASEURL` overrides `baseurl` when the script runs.
+pub fn offline_repo_setup(target: OsDistribution, baseurl: &str) -> String {
+    let quoted: String = baseurl.chars().flat_map(|c| {
+        let escape = matches!(c, '"' | '\\' | '$' | '`').then_some('\\');
+        escape.into_iter().chain([c])
+    }).collect();
+    let mut setup = format!("# Packages come from the local repository only; set REDCENT_BASEURL to use another one.\nredcent_baseurl=\"${{REDCENT_BASEURL:-{}}}\"\n", quoted);
+    match target {
+        OsDistribution::Debian | OsDistribution::Ubuntu => {
+            setup.push_str(&format!(
+                "echo \"deb ${{redcent_baseurl}} $(. /etc/os-release; echo \"$VERSION_CODENAME\") main\" | sudo tee /etc/apt/{} > /dev/null\n",
+                OFFLINE_APT_LIST,
+            ));
+            setup.push_str(&format!("sudo apt-get -o Dir::Etc::sourcelist={} -o Dir::Etc::sourceparts=- update", OFFLINE_APT_LIST));
+        }
+        OsDistribution::Rhel | OsDistribution::Centos | OsDistribution::Unknown => {
+            let key = if target == OsDistribution::Centos { "RPM-GPG-KEY-centosofficial" } else { "RPM-GPG-KEY-redhat-release" };
+            setup.push_str("sudo tee /etc/yum.repos.d/redcent-offline.repo > /dev/null <<REDCENT_EOF\n");
+            for (n, tree) in ["BaseOS", "AppStream"].iter().enumerate() {
+                if n > 0 {
+                    setup.push('\n');
+                }
+                setup.push_str(&format!(
+                    "[redcent-offline-{}]\nname=Offline {}\nbaseurl=${{redcent_baseurl}}/{}\ngpgcheck=1\ngpgkey=file:///etc/pki/rpm-gpg/{}\n",
+                    tree.to_lowercase(), tree, tree, key,
+                ));
+            }
+            setup.push_str("REDCENT_EOF");
+        }
+    }
+    setup
+}
+
+/// Proxy the host reaches package mirrors and other downloads through; empty values are unset.
+#[derive(Default, Clone, PartialEq)]
+pub struct Proxy {
+    pub http: String,
+    pub https: String,
+    /// Comma-separated hosts, domains and networks reached directly.
+    pub no_proxy: String,
+}
+
+impl Proxy {
+    pub fn is_set(&self) -> bool {
+        !self.http.is_empty() || !self.https.is_empty()
+    }
+
+    /// Commands exporting the proxy variables for the rest of the script and configuring the
+    /// package manager of `target` to use the proxy, since `sudo` drops the variables.
+    pub fn setup(&self, target: OsDistribution) -> String {
+        let quote = |value: &str| format!("'{}'", value.replace('\'', "'\\''"));
+        let mut exports = Vec::new();
+        for (name, value) in [("http_proxy", &self.http), ("https_proxy", &self.https), ("no_proxy", &self.no_proxy)] {
+            if !value.is_empty() {
+                exports.push(format!("export {}={} {}=\"${}\"", name, quote(value), name.to_uppercase(), name));
+            }
+        }
+        let mut setup = exports.join("\n");
+        match target {
+            OsDistribution::Debian | OsDistribution::Ubuntu => {
+                let mut conf = String::new();
+                for (scheme, value) in [("http", &self.http), ("https", &self.https)] {
+                    if !value.is_empty() {
+                        conf.push_str(&format!("Acquire::{}::Proxy \"{}\";\n", scheme, value.replace('"', "")));
+                    }
+                }
+                setup.push_str(&format!("\nprintf '%s' {} | sudo tee /etc/apt/apt.conf.d/95redcent-proxy > /dev/null", quote(&conf)));
+            }
+            OsDistribution::Rhel | OsDistribution::Centos | OsDistribution::Unknown => {
+                // dnf.conf takes one proxy for every repository; mirrors are mostly HTTPS.
+                let proxy = if self.https.is_empty() { &self.http } else { &self.https };
+                setup.push_str(&format!(
+                    "\nsudo sed -i '/^proxy=/d' /etc/dnf/dnf.conf\necho {} | sudo tee -a /etc/dnf/dnf.conf > /dev/null",
+                    quote(&format!("proxy={}", proxy)),
+                ));
+            }
+        }
+        setup
+    }
+}
@@ -0,0 +1,71 @@
+// src/clipboard.rs
+
+use std::{
+    env,
+    io::{self, IsTerminal, Write},
+    process::{Command, Stdio},
+};
+
+/// Largest OSC 52 payload sent; several terminals silently drop longer sequences.
+const OSC52_LIMIT: usize = 100_000;
+
+/// Copies `text` to the system clipboard and returns how, e.g. `OSC 52 and wl-copy`.
+///
+/// The OSC 52 escape sequence asks the terminal itself to set the clipboard, so it also reaches the
+/// admin's machine over SSH (and through tmux with `set-clipboard` on). Terminals that ignore it
+/// (VTE-based ones, the Linux console) give no answer, so a local `wl-copy` or `xclip` is run as
+/// well whenever a display is available.
+pub fn copy(text: &str) -> Result<String, String> {
+    let mut ways = Vec::new();
+    let encoded = base64(text.as_bytes());
+    let console = matches!(env::var("TERM").as_deref(), Ok("linux") | Ok("dumb"));
+    if io::stdout().is_terminal() && !console && encoded.len() <= OSC52_LIMIT {
+        let mut stdout = io::stdout();
+        stdout.write_all(format!("\x1b]52;c;{}\x07", encoded).as_bytes())
+            .and_then(|_| stdout.flush())
+            .map_err(|e| format!("could not write to the terminal: {}", e))?;
+        ways.push("OSC 52");
+    }
+    let tools: [(&str, &str, &[&str]); 2] = [
+        ("WAYLAND_DISPLAY", "wl-copy", &[]),
+        ("DISPLAY", "xclip", &["-selection", "clipboard"]),
+    ];
+    if let Some((_, tool, _)) = tools.iter().find(|(display, tool, args)| env::var_os(display).is_some() && pipe(tool, args, text)) {
+        ways.push(tool);
+    }
+    if ways.is_empty() {
+        return Err(if encoded.len() > OSC52_LIMIT {
+            "the script is too long for OSC 52 and neither wl-copy nor xclip could take it".to_string()
+        } else {
+            "this terminal does not support OSC 52 and neither wl-copy nor xclip is available".to_string()
+        });
+    }
+    Ok(ways.join(" and "))
+}
+
+/// Feeds `text` to `program` on standard input; whether it ran and succeeded.
+fn pipe(program: &str, args: &[&str], text: &str) -> bool {
+    let Ok(mut child) = Command::new(program).args(args)
+        .stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null()).spawn() else {
+        return false;
+    };
+    let written = child.stdin.take().is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+    child.wait().is_ok_and(|status| status.success()) && written
+}
+
+/// Standard base64 with padding, as OSC 52 expects.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| group | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
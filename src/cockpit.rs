@@ -0,0 +1,91 @@
+// src/cockpit.rs
+
+use crate::history::{self, RunRecord};
+use redcent_tui::{script_order, MenuNode, PackageBackend};
+use std::{cell::RefCell, fs, io, rc::Rc};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const METADATA_FILE: &str = "metadata.json";
+/// Copy of the latest run's metadata under the state directory, read by the Cockpit companion page.
+const STATUS_FILE: &str = "cockpit-status.json";
+
+/// What a run applies: the target and, per item, its ID, name, tags and verification commands, as JSON.
+pub struct RunManifest {
+    json: String,
+}
+
+impl RunManifest {
+    /// Describes the items currently selected in `tree`, in script order.
+    pub fn from_selection(tree: &Rc<RefCell<MenuNode>>, target: &str, backend: &dyn PackageBackend) -> RunManifest {
+        let items: Vec<String> = script_order(tree, backend).iter().filter_map(|(item, _)| {
+            let item = item.borrow();
+            let script = item.script(backend)?;
+            Some(format!(
+                "{{\"id\": {}, \"name\": {}, \"tags\": {}, \"verify\": {}}}",
+                json_string(script.id),
+                json_string(item.name()),
+                json_array(item.tags(backend)),
+                json_array(&script.verify),
+            ))
+        }).collect();
+        RunManifest { json: format!("{{\"target\": {}, \"items\": [{}]}}", json_string(target), items.join(", ")) }
+    }
+
+    /// The manifest stored with an earlier run, for re-running it from history. Runs recorded
+    /// before manifests existed get an empty one.
+    pub fn from_record(record: &RunRecord) -> RunManifest {
+        let json = fs::read_to_string(record.dir.join(MANIFEST_FILE))
+            .unwrap_or_else(|_| "{\"target\": null, \"items\": []}".to_string());
+        RunManifest { json }
+    }
+}
+
+/// Writes `metadata.json` into the run directory and mirrors it to [`STATUS_FILE`]. Called when the
+/// run starts (`exit_code` is `None`) and again when it ends.
+pub fn write_metadata(record: &RunRecord, manifest: &RunManifest, exit_code: Option<i32>) -> io::Result<()> {
+    fs::write(record.dir.join(MANIFEST_FILE), &manifest.json)?;
+    let finished = exit_code.map(|_| json_string(&iso_timestamp(&history::timestamp_now()))).unwrap_or_else(|| "null".to_string());
+    let metadata = format!(
+        "{{\n  \"version\": 1,\n  \"run\": {},\n  \"started\": {},\n  \"finished\": {},\n  \"exit_code\": {},\n  \"drift\": \"unchecked\",\n  \"manifest\": {}\n}}\n",
+        json_string(&record.name),
+        json_string(&iso_timestamp(&record.name)),
+        finished,
+        exit_code.map(|code| code.to_string()).unwrap_or_else(|| "null".to_string()),
+        manifest.json,
+    );
+    fs::write(record.dir.join(METADATA_FILE), &metadata)?;
+    if let Some(dir) = history::state_dir() {
+        fs::write(dir.join(STATUS_FILE), &metadata)?;
+    }
+    Ok(())
+}
+
+/// Turns a `YYYYMMDD-HHMMSS[-n]` run name into `YYYY-MM-DDTHH:MM:SSZ`.
+fn iso_timestamp(name: &str) -> String {
+    if name.len() < 15 || !name.is_char_boundary(15) || name.as_bytes()[8] != b'-' {
+        return name.to_string();
+    }
+    format!("{}-{}-{}T{}:{}:{}Z", &name[0..4], &name[4..6], &name[6..8], &name[9..11], &name[11..13], &name[13..15])
+}
+
+pub fn json_array<S: AsRef<str>>(values: impl IntoIterator<Item = S>) -> String {
+    let values: Vec<String> = values.into_iter().map(|v| json_string(v.as_ref())).collect();
+    format!("[{}]", values.join(", "))
+}
+
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
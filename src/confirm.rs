@@ -0,0 +1,148 @@
+// src/confirm.rs
+
+use crate::{centered_rect, form::WizardOutcome, space::{SpaceCheck, Verdict}};
+use crossterm::event::KeyCode;
+use redcent_tui::Stage;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
+    Frame,
+};
+
+/// Modal shown before a script is executed directly; the user has to type `yes` to proceed. A
+/// selection that does not fit on the disk cannot be run from here.
+pub struct ConfirmRun {
+    pub input: String,
+    stages: Vec<Stage>,
+    transactions: usize,
+    reboot: bool,
+    escalation: &'static str,
+    space: SpaceCheck,
+}
+
+impl ConfirmRun {
+    /// `package_manager` is the backend name (`dnf`, `apt`) used to count package transactions in `script`.
+    pub fn new(stages: Vec<Stage>, script: &str, package_manager: &str, reboot: bool, escalation: &'static str, space: SpaceCheck) -> ConfirmRun {
+        ConfirmRun { input: String::new(), stages, transactions: count_transactions(script, package_manager), reboot, escalation, space }
+    }
+
+    fn component_count(&self) -> usize {
+        self.stages.iter().map(|stage| stage.items.len()).sum()
+    }
+
+    /// Reboots the selection needs, counting the final one when it is requested even if no item needs it.
+    fn estimated_reboots(&self) -> usize {
+        let needed = self.stages.iter().filter(|stage| stage.reboot_after).count();
+        match self.stages.last() {
+            Some(last) if self.reboot && !last.reboot_after => needed + 1,
+            _ => needed,
+        }
+    }
+
+    /// One-line timeline: `Stage 1 (4) ── reboot ──▶ Stage 2 (2)`.
+    fn timeline(&self) -> Line<'static> {
+        let mut spans = Vec::new();
+        for (n, stage) in self.stages.iter().enumerate() {
+            spans.push(Span::styled(
+                format!(" Stage {} ({}) ", n + 1, stage.items.len()),
+                Style::default().fg(Color::Black).bg(Color::Cyan),
+            ));
+            let last = n + 1 == self.stages.len();
+            if stage.reboot_after || (last && self.reboot) {
+                spans.push(Span::styled(" ── reboot ──▶ ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
+            } else if !last {
+                spans.push(Span::raw(" ──▶ "));
+            }
+        }
+        Line::from(spans)
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> WizardOutcome {
+        match code {
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => { self.input.pop(); },
+            KeyCode::Enter if self.input.trim().eq_ignore_ascii_case("yes") && self.space.verdict() != Verdict::DoesNotFit => {
+                return WizardOutcome::Finish;
+            }
+            KeyCode::Esc => return WizardOutcome::Cancel,
+            _ => {}
+        }
+        WizardOutcome::Continue
+    }
+
+    pub fn draw(&self, f: &mut Frame) {
+        let area = centered_rect(70, 70, f.size());
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Block::default().title("Run script as root?").borders(Borders::ALL).border_style(Style::default().fg(Color::Red)),
+            area,
+        );
+        let chunks = Layout::default().direction(Direction::Vertical).margin(2)
+            .constraints([Constraint::Length(3), Constraint::Length(2), Constraint::Length(3), Constraint::Min(3), Constraint::Length(2), Constraint::Length(3)])
+            .split(area);
+
+        let reboot = if self.reboot { "The system WILL REBOOT when the script finishes." } else { "No reboot." };
+        let summary = format!(
+            "{} component(s), {} package transaction(s), estimated reboot(s): {}, escalation: {}.\n{}",
+            self.component_count(), self.transactions, self.estimated_reboots(), self.escalation, reboot,
+        );
+        let summary_style = if self.reboot { Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD) } else { Style::default() };
+        f.render_widget(Paragraph::new(summary).style(summary_style).wrap(Wrap { trim: true }), chunks[0]);
+
+        let (space, space_style) = match self.space.verdict() {
+            Verdict::Fits | Verdict::Unknown => (format!("Disk space: {}.", self.space.summary()), Style::default()),
+            Verdict::Tight => (
+                format!("Disk space is tight: {}.", self.space.summary()),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ),
+            Verdict::DoesNotFit => (
+                format!("Not enough disk space: {}.", self.space.summary()),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+        };
+        f.render_widget(Paragraph::new(space).style(space_style).wrap(Wrap { trim: true }), chunks[1]);
+
+        f.render_widget(Paragraph::new(self.timeline()).block(Block::default().title("Timeline").borders(Borders::ALL)), chunks[2]);
+
+        let items: Vec<ListItem> = if self.component_count() == 0 {
+            vec![ListItem::new("(no components selected)")]
+        } else {
+            let many = self.stages.len() > 1;
+            self.stages.iter().enumerate().flat_map(|(n, stage)| {
+                let header = many.then(|| ListItem::new(format!("Stage {}", n + 1)).style(Style::default().fg(Color::Cyan)));
+                let reboot = (many && stage.reboot_after)
+                    .then(|| ListItem::new("  ⟳ reboot required").style(Style::default().fg(Color::Yellow)));
+                header.into_iter()
+                    .chain(stage.items.iter().map(|name| ListItem::new(format!("• {}", name))))
+                    .chain(reboot)
+            }).collect()
+        };
+        f.render_widget(List::new(items).block(Block::default().title("Selected Components").borders(Borders::ALL)), chunks[3]);
+
+        if self.space.verdict() == Verdict::DoesNotFit {
+            let prompt = "Free up space or deselect items before running; press Esc to go back.";
+            f.render_widget(Paragraph::new(prompt).style(Style::default().fg(Color::Red)), chunks[4]);
+            return;
+        }
+        f.render_widget(Paragraph::new("Type 'yes' and press Enter to run, or Esc to go back."), chunks[4]);
+        f.render_widget(
+            Paragraph::new(self.input.as_str()).block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Yellow))),
+            chunks[5],
+        );
+    }
+}
+
+/// Counts the non-comment script lines that invoke the package manager.
+fn count_transactions(script: &str, package_manager: &str) -> usize {
+    let binary = match package_manager {
+        "apt" => "apt-get",
+        other => other,
+    };
+    script.lines()
+        .map(str::trim_start)
+        .filter(|line| !line.starts_with('#'))
+        .filter(|line| line.split_whitespace().any(|word| word == binary))
+        .count()
+}
@@ -0,0 +1,73 @@
+// src/console.rs
+
+use std::{
+    env,
+    io::{self, IsTerminal},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Set by `--no-color`.
+static NO_COLOR: AtomicBool = AtomicBool::new(false);
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const BLUE: &str = "\x1b[34m";
+
+/// Turns colors off for the rest of the process, as `--no-color` asks.
+pub fn disable_color() {
+    NO_COLOR.store(true, Ordering::Relaxed);
+}
+
+/// Whether to color what goes to a stream: only terminals get escape sequences, and `--no-color`,
+/// a non-empty `NO_COLOR` (<https://no-color.org>) or `TERM=dumb` turn them off.
+fn colored(is_terminal: bool) -> bool {
+    is_terminal
+        && !NO_COLOR.load(Ordering::Relaxed)
+        && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+        && env::var("TERM").map_or(true, |term| term != "dumb")
+}
+
+fn paint(text: &str, style: &str, is_terminal: bool) -> String {
+    if colored(is_terminal) { format!("{}{}{}", style, text, RESET) } else { text.to_string() }
+}
+
+/// A step of what happens after the TUI, e.g. `==> Running the script with sudo`.
+pub fn heading(text: &str) {
+    println!("{}", paint(&format!("==> {}", text), &format!("{}{}", BOLD, BLUE), io::stdout().is_terminal()));
+}
+
+/// A side note such as where a run was recorded.
+pub fn note(text: &str) {
+    println!("{}", paint(text, DIM, io::stdout().is_terminal()));
+}
+
+/// Prints `text` between rules naming it; only the rules are colored, so the text can be copied
+/// as is.
+pub fn listing(title: &str, text: &str) {
+    let terminal = io::stdout().is_terminal();
+    println!("{}", paint(&format!("--- {} ---", title), DIM, terminal));
+    println!("{}", text.trim_end());
+    println!("{}", paint(&"-".repeat(title.len() + 8), DIM, terminal));
+}
+
+pub fn success(text: &str) {
+    println!("{}", paint(text, &format!("{}{}", BOLD, GREEN), io::stdout().is_terminal()));
+}
+
+pub fn failure(text: &str) {
+    println!("{}", paint(text, &format!("{}{}", BOLD, RED), io::stdout().is_terminal()));
+}
+
+/// Prints `Warning: text` to standard error.
+pub fn warning(text: &str) {
+    eprintln!("{} {}", paint("Warning:", &format!("{}{}", BOLD, YELLOW), io::stderr().is_terminal()), text);
+}
+
+/// Prints `Error: text` to standard error.
+pub fn error(text: &str) {
+    eprintln!("{} {}", paint("Error:", &format!("{}{}", BOLD, RED), io::stderr().is_terminal()), text);
+}
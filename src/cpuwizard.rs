@@ -0,0 +1,241 @@
+// src/cpuwizard.rs
+
+use crate::{
+    centered_rect,
+    form::{Field, FieldKind, Step, Wizard, WizardOutcome},
+    theme::Theme,
+};
+use redcent_tui::{validate, MenuNode, PackageBackend};
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use std::{cell::RefCell, collections::BTreeSet, process::Command, rc::Rc};
+
+/// ID of the item configured through this wizard.
+pub const CPU_ISOLATION_ITEM: &str = "virt.cpu-isolation";
+
+/// Cores drawn per row of the picker.
+const CORES_PER_ROW: usize = 8;
+
+/// A physical core and the logical CPUs (hardware threads) it runs, from `lscpu -p`.
+pub struct Core {
+    pub node: u32,
+    pub socket: u32,
+    pub cpus: Vec<u32>,
+}
+
+/// Reads the online CPUs from `lscpu -p=CPU,CORE,SOCKET,NODE` and groups them into cores,
+/// ordered by NUMA node, socket and core.
+pub fn read_topology() -> Vec<Core> {
+    let Ok(output) = Command::new("lscpu").arg("-p=CPU,CORE,SOCKET,NODE").output() else {
+        return Vec::new();
+    };
+    let mut cores: Vec<(u32, u32, u32, Vec<u32>)> = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines().filter(|line| !line.starts_with('#')) {
+        // Machines without NUMA leave the node column empty.
+        let columns: Vec<u32> = line.split(',').map(|column| column.parse().unwrap_or(0)).collect();
+        let [cpu, core, socket, node] = columns[..] else { continue };
+        match cores.iter_mut().find(|(n, s, c, _)| (*n, *s, *c) == (node, socket, core)) {
+            Some((.., cpus)) => cpus.push(cpu),
+            None => cores.push((node, socket, core, vec![cpu])),
+        }
+    }
+    cores.sort_by_key(|(node, socket, core, _)| (*node, *socket, *core));
+    cores.into_iter().map(|(node, socket, _, cpus)| Core { node, socket, cpus }).collect()
+}
+
+/// Joins CPU numbers into the kernel's list syntax, e.g. `2-7,10-15`.
+pub fn cpu_list(cpus: &BTreeSet<u32>) -> String {
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for &cpu in cpus {
+        match ranges.last_mut() {
+            Some((_, last)) if *last + 1 == cpu => *last = cpu,
+            _ => ranges.push((cpu, cpu)),
+        }
+    }
+    ranges.iter()
+        .map(|&(first, last)| if first == last { first.to_string() } else { format!("{}-{}", first, last) })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// A CPU list that leaves CPU 0, which handles boot-time and timer work, to housekeeping.
+fn isolated_cpus(value: &str) -> Result<(), String> {
+    if validate::cpu_list_numbers(value)?.contains(&0) {
+        return Err("CPU 0 must stay a housekeeping CPU".to_string());
+    }
+    Ok(())
+}
+
+/// Wizard for CPU isolation: pick the isolated cores on a map of the host's NUMA nodes, then
+/// review the commands. Without a readable topology the CPU list is typed in instead.
+pub struct CpuWizard {
+    pub item: Rc<RefCell<MenuNode>>,
+    pub wizard: Wizard,
+    cores: Vec<Core>,
+    isolated: Vec<bool>,
+    /// Index into `cores` of the highlighted core.
+    cursor: usize,
+    message: Option<String>,
+}
+
+impl CpuWizard {
+    pub fn new(item: &Rc<RefCell<MenuNode>>) -> CpuWizard {
+        let field = match &*item.borrow() {
+            MenuNode::Item { params, .. } => params.iter().find(|p| p.key == "isolated_cores").map(Field::from_param),
+            MenuNode::Menu { .. } => None,
+        };
+        let mut field = field.unwrap_or_else(|| Field::new("isolated_cores", "Isolated CPUs", FieldKind::Text, ""));
+        field.set_check("Isolated CPUs, e.g. 2-7,10-15 (CPU 0 stays for housekeeping)", isolated_cpus);
+        let cores = read_topology();
+        let chosen = validate::cpu_list_numbers(&field.value).unwrap_or_default();
+        let isolated = cores.iter().map(|core| core.cpus.iter().all(|cpu| chosen.contains(cpu))).collect();
+        let steps = vec![Step::new("Pick isolated CPUs", vec![field.required()]), Step::review("Review commands")];
+        CpuWizard { item: item.clone(), wizard: Wizard::new("CPU Isolation", steps), cores, isolated, cursor: 0, message: None }
+    }
+
+    /// Whether the core map is shown, rather than the wizard's own step.
+    fn picking(&self) -> bool {
+        !self.cores.is_empty() && !self.wizard.on_review()
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> WizardOutcome {
+        if !self.picking() {
+            return self.wizard.handle_key(code);
+        }
+        self.message = None;
+        match code {
+            KeyCode::Esc => return WizardOutcome::Cancel,
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.cores.len() - 1),
+            KeyCode::Up | KeyCode::Down => self.move_row(code == KeyCode::Down),
+            KeyCode::Char(' ') => {
+                if self.cores[self.cursor].cpus.contains(&0) {
+                    self.message = Some("CPU 0 handles boot-time and timer work and must stay a housekeeping CPU".to_string());
+                } else {
+                    self.isolated[self.cursor] = !self.isolated[self.cursor];
+                }
+            }
+            KeyCode::Enter => {
+                let list = cpu_list(&self.isolated_set());
+                if list.is_empty() {
+                    self.message = Some("Pick at least one core to isolate".to_string());
+                    return WizardOutcome::Continue;
+                }
+                if let Some(field) = self.wizard.field_mut("isolated_cores") {
+                    field.value = list;
+                }
+                return self.wizard.handle_key(KeyCode::Enter);
+            }
+            _ => {}
+        }
+        WizardOutcome::Continue
+    }
+
+    fn isolated_set(&self) -> BTreeSet<u32> {
+        self.cores.iter().zip(&self.isolated).filter(|(_, isolated)| **isolated).flat_map(|(core, _)| core.cpus.iter().copied()).collect()
+    }
+
+    /// The picker's rows as indexes into `cores`; a NUMA node always starts a new row.
+    fn rows(&self) -> Vec<Vec<usize>> {
+        let mut rows: Vec<Vec<usize>> = Vec::new();
+        for (i, core) in self.cores.iter().enumerate() {
+            match rows.last_mut() {
+                Some(row) if row.len() < CORES_PER_ROW && self.cores[row[0]].node == core.node => row.push(i),
+                _ => rows.push(vec![i]),
+            }
+        }
+        rows
+    }
+
+    /// Moves the highlight to the same column of the row above or below.
+    fn move_row(&mut self, down: bool) {
+        let rows = self.rows();
+        let Some((row, column)) = rows.iter().enumerate()
+            .find_map(|(r, row)| row.iter().position(|&i| i == self.cursor).map(|c| (r, c))) else { return };
+        let target = if down { row + 1 } else { row.wrapping_sub(1) };
+        if let Some(next) = rows.get(target) {
+            self.cursor = next[column.min(next.len() - 1)];
+        }
+    }
+
+    /// NUMA nodes whose every CPU is isolated, leaving their memory without a local housekeeping CPU.
+    fn nodes_without_housekeeping(&self) -> Vec<u32> {
+        let nodes: BTreeSet<u32> = self.cores.iter().map(|core| core.node).collect();
+        nodes.into_iter()
+            .filter(|node| self.cores.iter().zip(&self.isolated).all(|(core, isolated)| core.node != *node || *isolated))
+            .collect()
+    }
+
+    /// Writes the wizard's values back to the item.
+    pub fn apply(&self) {
+        self.wizard.apply(&self.item);
+    }
+
+    pub fn draw(&self, f: &mut Frame, theme: &Theme, backend: &dyn PackageBackend) {
+        if !self.picking() {
+            let review = if self.wizard.on_review() { self.wizard.preview(&self.item, backend) } else { String::new() };
+            self.wizard.draw(f, theme, &review);
+            return;
+        }
+        let area = centered_rect(70, 70, f.size());
+        f.render_widget(Clear, area);
+        f.render_widget(Block::default().title("CPU Isolation - Step 1/2: Pick isolated CPUs").borders(Borders::ALL), area);
+        let hint = "←→↑↓ to move, Space to isolate or release a core with its sibling threads, Enter to continue, Esc to cancel:";
+        let hint_height = if hint.chars().count() > area.width.saturating_sub(4) as usize { 2 } else { 1 };
+        let chunks = Layout::default().direction(Direction::Vertical).margin(2)
+            .constraints([Constraint::Length(hint_height), Constraint::Min(0)].as_ref()).split(area);
+        f.render_widget(Paragraph::new(hint).wrap(Wrap { trim: true }), chunks[0]);
+
+        let labels: Vec<String> = self.cores.iter()
+            .map(|core| core.cpus.iter().map(u32::to_string).collect::<Vec<_>>().join(","))
+            .collect();
+        let width = labels.iter().map(String::len).max().unwrap_or(0);
+        let mut lines = Vec::new();
+        let mut node = None;
+        for row in self.rows() {
+            let first = &self.cores[row[0]];
+            if node != Some(first.node) {
+                node = Some(first.node);
+                let sockets: BTreeSet<u32> = self.cores.iter().filter(|core| core.node == first.node).map(|core| core.socket).collect();
+                let sockets = sockets.iter().map(u32::to_string).collect::<Vec<_>>().join(", ");
+                if !lines.is_empty() {
+                    lines.push(Line::from(""));
+                }
+                lines.push(Line::styled(format!("NUMA node {} (socket {})", first.node, sockets), theme.title));
+            }
+            let mut spans = vec![Span::raw("  ")];
+            for i in row {
+                // Brackets mark isolated cores in every color scheme.
+                let (open, close) = if self.isolated[i] { ('[', ']') } else { (' ', ' ') };
+                let style = match (i == self.cursor, self.isolated[i]) {
+                    (true, _) => theme.highlight,
+                    (false, true) => theme.warning,
+                    (false, false) => Style::default(),
+                };
+                spans.push(Span::styled(format!("{}{:^width$}{}", open, labels[i], close), style));
+                spans.push(Span::raw(" "));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        let isolated = self.isolated_set();
+        let housekeeping: BTreeSet<u32> = self.cores.iter().flat_map(|core| core.cpus.iter().copied()).filter(|cpu| !isolated.contains(cpu)).collect();
+        let describe = |cpus: &BTreeSet<u32>| if cpus.is_empty() { "none".to_string() } else { format!("{} ({} CPUs)", cpu_list(cpus), cpus.len()) };
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![Span::styled("Isolated:     ", theme.warning), Span::raw(describe(&isolated))]));
+        lines.push(Line::from(vec![Span::styled("Housekeeping: ", theme.muted), Span::raw(describe(&housekeeping))]));
+        for node in self.nodes_without_housekeeping() {
+            lines.push(Line::styled(format!("NUMA node {} keeps no housekeeping CPU; its interrupts and kernel threads run on other nodes", node), theme.warning));
+        }
+        if let Some(message) = &self.message {
+            lines.push(Line::styled(message.clone(), theme.error));
+        }
+        f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }).block(Block::default().borders(Borders::ALL).title("Cores")), chunks[1]);
+    }
+}
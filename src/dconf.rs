@@ -0,0 +1,71 @@
+// src/dconf.rs
+
+/// Builder for a system-wide dconf keyfile under /etc/dconf/db/local.d, with optional locks.
+pub struct DconfPolicy {
+    name: &'static str,
+    sections: Vec<(&'static str, Vec<DconfKey>)>,
+}
+
+struct DconfKey {
+    key: &'static str,
+    value: &'static str,
+    locked: bool,
+}
+
+impl DconfPolicy {
+    /// Creates an empty policy written to `/etc/dconf/db/local.d/<name>`.
+    pub fn new(name: &'static str) -> DconfPolicy {
+        DconfPolicy { name, sections: Vec::new() }
+    }
+
+    /// Sets a default value that users may still override.
+    pub fn set(self, path: &'static str, key: &'static str, value: &'static str) -> DconfPolicy {
+        self.add(path, key, value, false)
+    }
+
+    /// Sets a value and locks it so users cannot change it.
+    pub fn lock(self, path: &'static str, key: &'static str, value: &'static str) -> DconfPolicy {
+        self.add(path, key, value, true)
+    }
+
+    fn add(mut self, path: &'static str, key: &'static str, value: &'static str, locked: bool) -> DconfPolicy {
+        let entry = DconfKey { key, value, locked };
+        match self.sections.iter_mut().find(|(section, _)| *section == path) {
+            Some((_, keys)) => keys.push(entry),
+            None => self.sections.push((path, vec![entry])),
+        }
+        self
+    }
+
+    /// Renders the shell commands that write the profile, keyfile and locks, then run `dconf update`.
+    pub fn render(&self) -> String {
+        let mut keyfile = String::new();
+        let mut locks = String::new();
+        for (path, keys) in &self.sections {
+            if !keyfile.is_empty() {
+                keyfile.push('\n');
+            }
+            keyfile.push_str(&format!("[{}]\n", path));
+            for entry in keys {
+                keyfile.push_str(&format!("{}={}\n", entry.key, entry.value));
+                if entry.locked {
+                    locks.push_str(&format!("/{}/{}\n", path, entry.key));
+                }
+            }
+        }
+
+        let mut script = String::new();
+        script.push_str("sudo mkdir -p /etc/dconf/profile /etc/dconf/db/local.d/locks\n");
+        script.push_str(&heredoc("/etc/dconf/profile/user", "user-db:user\nsystem-db:local\n"));
+        script.push_str(&heredoc(&format!("/etc/dconf/db/local.d/{}", self.name), &keyfile));
+        if !locks.is_empty() {
+            script.push_str(&heredoc(&format!("/etc/dconf/db/local.d/locks/{}", self.name), &locks));
+        }
+        script.push_str("sudo dconf update");
+        script
+    }
+}
+
+fn heredoc(path: &str, content: &str) -> String {
+    format!("sudo tee {} > /dev/null <<'REDCENT_EOF'\n{}REDCENT_EOF\n", path, content)
+}
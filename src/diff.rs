@@ -0,0 +1,197 @@
+// src/diff.rs
+
+use crossterm::event::KeyCode;
+use crate::theme::Theme;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+#[derive(PartialEq, Clone, Copy)]
+enum RowKind {
+    Same,
+    /// A removed line shown next to the line that replaced it.
+    Changed,
+    Removed,
+    Added,
+}
+
+/// One line of the side-by-side view; a side is `None` where the other side has no counterpart.
+struct Row {
+    kind: RowKind,
+    left: Option<(usize, String)>,
+    right: Option<(usize, String)>,
+}
+
+enum Op<'a> {
+    Same(usize, usize, &'a str),
+    Removed(usize, &'a str),
+    Added(usize, &'a str),
+}
+
+/// Line-level diff of `old` against `new` via their longest common subsequence, as (1-based) numbered operations.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Op<'a>> {
+    let (n, m) = (old.len(), new.len());
+    // lcs[i][j] is the LCS length of old[i..] and new[j..].
+    let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i * (m + 1) + j] = if old[i] == new[j] {
+                lcs[(i + 1) * (m + 1) + j + 1] + 1
+            } else {
+                lcs[(i + 1) * (m + 1) + j].max(lcs[i * (m + 1) + j + 1])
+            };
+        }
+    }
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            ops.push(Op::Same(i + 1, j + 1, old[i]));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lcs[(i + 1) * (m + 1) + j] >= lcs[i * (m + 1) + j + 1]) {
+            ops.push(Op::Removed(i + 1, old[i]));
+            i += 1;
+        } else {
+            ops.push(Op::Added(j + 1, new[j]));
+            j += 1;
+        }
+    }
+    ops
+}
+
+/// Pairs each run of removed lines with the added lines that follow it, so replacements sit side by side.
+fn side_by_side(ops: Vec<Op>) -> Vec<Row> {
+    let mut rows = Vec::new();
+    let mut removed: Vec<(usize, String)> = Vec::new();
+    let mut added: Vec<(usize, String)> = Vec::new();
+    let flush = |rows: &mut Vec<Row>, removed: &mut Vec<(usize, String)>, added: &mut Vec<(usize, String)>| {
+        let len = removed.len().max(added.len());
+        let mut left = removed.drain(..);
+        let mut right = added.drain(..);
+        for _ in 0..len {
+            let (l, r) = (left.next(), right.next());
+            let kind = match (&l, &r) {
+                (Some(_), Some(_)) => RowKind::Changed,
+                (Some(_), None) => RowKind::Removed,
+                _ => RowKind::Added,
+            };
+            rows.push(Row { kind, left: l, right: r });
+        }
+    };
+    for op in ops {
+        match op {
+            Op::Same(a, b, text) => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push(Row { kind: RowKind::Same, left: Some((a, text.to_string())), right: Some((b, text.to_string())) });
+            }
+            Op::Removed(a, text) => removed.push((a, text.to_string())),
+            Op::Added(b, text) => added.push((b, text.to_string())),
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+/// Scrollable side-by-side comparison of two scripts.
+pub struct DiffView {
+    left_title: String,
+    right_title: String,
+    rows: Vec<Row>,
+    scroll: usize,
+    viewport_height: usize,
+}
+
+impl DiffView {
+    pub fn new(left_title: impl Into<String>, old: &str, right_title: impl Into<String>, new: &str) -> DiffView {
+        let old: Vec<&str> = old.lines().collect();
+        let new: Vec<&str> = new.lines().collect();
+        let rows = side_by_side(diff_lines(&old, &new));
+        DiffView { left_title: left_title.into(), right_title: right_title.into(), rows, scroll: 0, viewport_height: 0 }
+    }
+
+    /// Counts of removed and added lines.
+    pub fn stats(&self) -> (usize, usize) {
+        let removed = self.rows.iter().filter(|row| row.kind != RowKind::Same && row.left.is_some()).count();
+        let added = self.rows.iter().filter(|row| row.kind != RowKind::Same && row.right.is_some()).count();
+        (removed, added)
+    }
+
+    /// Rows where a block of changes starts.
+    fn hunk_starts(&self) -> Vec<usize> {
+        (0..self.rows.len())
+            .filter(|&i| self.rows[i].kind != RowKind::Same && (i == 0 || self.rows[i - 1].kind == RowKind::Same))
+            .collect()
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.rows.len().saturating_sub(self.viewport_height.max(1))
+    }
+
+    /// Handles scrolling keys; returns `false` for keys the caller should handle.
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        let page = self.viewport_height.max(1);
+        match code {
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = (self.scroll + 1).min(self.max_scroll()),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(page),
+            KeyCode::PageDown => self.scroll = (self.scroll + page).min(self.max_scroll()),
+            KeyCode::Home => self.scroll = 0,
+            KeyCode::End => self.scroll = self.max_scroll(),
+            KeyCode::Char('n') => {
+                if let Some(&next) = self.hunk_starts().iter().find(|&&start| start > self.scroll) {
+                    self.scroll = next.min(self.max_scroll());
+                }
+            }
+            KeyCode::Char('N') => {
+                if let Some(&prev) = self.hunk_starts().iter().rev().find(|&&start| start < self.scroll) {
+                    self.scroll = prev;
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn draw(&mut self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let columns = Layout::default().direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(area);
+        self.viewport_height = area.height.saturating_sub(2) as usize;
+        self.scroll = self.scroll.min(self.max_scroll());
+
+        let width = self.rows.iter()
+            .flat_map(|row| [row.left.as_ref(), row.right.as_ref()])
+            .flatten()
+            .map(|(n, _)| n.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let visible = &self.rows[self.scroll..(self.scroll + self.viewport_height).min(self.rows.len())];
+        let side = |pick: fn(&Row) -> &Option<(usize, String)>, changed: Style| -> Vec<Line> {
+            visible.iter().map(|row| match pick(row) {
+                Some((n, text)) => {
+                    let style = match row.kind {
+                        RowKind::Same => Style::default(),
+                        _ => changed,
+                    };
+                    Line::from(vec![
+                        Span::styled(format!("{:>width$} ", n, width = width), theme.muted),
+                        Span::styled(text.clone(), style),
+                    ])
+                }
+                None => Line::from(Span::styled("~", theme.muted)),
+            }).collect()
+        };
+        let (removed, added) = self.stats();
+        let left = Paragraph::new(side(|row| &row.left, theme.error))
+            .block(Block::default().borders(Borders::ALL).title(format!("{} (-{})", self.left_title, removed)));
+        let right = Paragraph::new(side(|row| &row.right, theme.added))
+            .block(Block::default().borders(Borders::ALL).title(format!("{} (+{})", self.right_title, added)));
+        f.render_widget(left, columns[0]);
+        f.render_widget(right, columns[1]);
+    }
+}
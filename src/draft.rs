@@ -0,0 +1,158 @@
+// src/draft.rs
+
+use crate::{
+    form::check_param,
+    history::{self, state_dir},
+};
+use redcent_tui::{find_item, has_script_order, ordered_selected_items, set_script_order, MenuNode};
+use std::{
+    cell::RefCell,
+    fs, io,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+pub const DRAFT_FILE: &str = "draft";
+/// How often the event loop wakes up without input to let the draft catch up.
+pub const TICK: Duration = Duration::from_secs(1);
+/// How long the selection has to stay unchanged after a toggle before the draft is written.
+const DEBOUNCE: Duration = Duration::from_secs(2);
+/// Upper bound on the age of the draft while the selection keeps changing.
+const INTERVAL: Duration = Duration::from_secs(30);
+
+/// The selection as draft file lines: the target, whether the script order was arranged, then each
+/// selected item in script order with its parameter values.
+pub fn snapshot(tree: &Rc<RefCell<MenuNode>>, target: &str) -> String {
+    let mut out = format!("target = {}\n", target);
+    if has_script_order(tree) {
+        out.push_str("order = arranged\n");
+    }
+    for item in ordered_selected_items(tree) {
+        let item = item.borrow();
+        out.push_str(&format!("item = {}\n", item.id()));
+        if let MenuNode::Item { params, .. } = &*item {
+            for param in params {
+                // Values are single-line form input; a stray newline must not split the record.
+                out.push_str(&format!("param = {} {}={}\n", item.id(), param.key, param.value.replace('\n', " ")));
+            }
+        }
+    }
+    out
+}
+
+/// Keeps the draft file in step with the selection, so a crashed terminal or dropped SSH session
+/// does not lose it. The file is removed on a clean exit.
+pub struct Autosave {
+    /// Snapshot last written (or restored from), compared against the current one on every tick.
+    saved: String,
+    changed_at: Option<Instant>,
+    written_at: Instant,
+}
+
+impl Autosave {
+    pub fn new(initial: String) -> Autosave {
+        Autosave { saved: initial, changed_at: None, written_at: Instant::now() }
+    }
+
+    /// Writes `current` once it has been stable for [`DEBOUNCE`], or after [`INTERVAL`] at the latest.
+    pub fn tick(&mut self, current: String) -> io::Result<()> {
+        if current == self.saved {
+            self.changed_at = None;
+            return Ok(());
+        }
+        let now = Instant::now();
+        let changed_at = *self.changed_at.get_or_insert(now);
+        if now.duration_since(changed_at) < DEBOUNCE && now.duration_since(self.written_at) < INTERVAL {
+            return Ok(());
+        }
+        self.written_at = now;
+        self.changed_at = None;
+        // On failure `saved` stays stale, so the write is retried once the debounce has passed again.
+        write(&current)?;
+        self.saved = current;
+        Ok(())
+    }
+}
+
+fn write(snapshot: &str) -> io::Result<()> {
+    let dir = state_dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "cannot determine state directory"))?;
+    fs::create_dir_all(&dir)?;
+    let content = format!("# Draft selection, offered for restore after an unclean exit.\nsaved = {}\n{}", history::timestamp_now(), snapshot);
+    // Written next to the draft and renamed over it, so a crash mid-write cannot leave it truncated.
+    let tmp = dir.join(format!("{}.tmp", DRAFT_FILE));
+    fs::write(&tmp, content)?;
+    fs::rename(tmp, dir.join(DRAFT_FILE))
+}
+
+/// Removes the draft file; called on a clean exit and when the user declines to restore it.
+pub fn discard() {
+    if let Some(dir) = state_dir() {
+        let _ = fs::remove_file(dir.join(DRAFT_FILE));
+    }
+}
+
+/// A selection left behind by a session that did not exit cleanly.
+pub struct Draft {
+    /// Run-name style timestamp of the last write.
+    pub saved: String,
+    pub target: String,
+    pub items: Vec<String>,
+    /// Whether `items` are in an order arranged by the user rather than tree order.
+    pub arranged: bool,
+    /// (item ID, parameter key, value)
+    pub params: Vec<(String, String, String)>,
+}
+
+impl Draft {
+    /// Reads the draft file; `None` if there is none or it selects nothing.
+    pub fn load() -> Option<Draft> {
+        let content = fs::read_to_string(state_dir()?.join(DRAFT_FILE)).ok()?;
+        let mut draft = Draft { saved: String::new(), target: String::new(), items: Vec::new(), arranged: false, params: Vec::new() };
+        for (key, value) in content.lines().filter(|line| !line.starts_with('#')).filter_map(|line| line.split_once(" = ")) {
+            match key {
+                "saved" => draft.saved = value.to_string(),
+                "target" => draft.target = value.to_string(),
+                "order" => draft.arranged = value == "arranged",
+                "item" => draft.items.push(value.to_string()),
+                "param" => {
+                    if let Some((id, rest)) = value.split_once(' ')
+                        && let Some((param, value)) = rest.split_once('=')
+                    {
+                        draft.params.push((id.to_string(), param.to_string(), value.to_string()));
+                    }
+                }
+                _ => {}
+            }
+        }
+        (!draft.items.is_empty()).then_some(draft)
+    }
+
+    /// Fills in the saved parameter values and selects the items through the dependency rules, in
+    /// their saved script order.
+    /// Returns the number of newly selected items and the reasons for refused ones, plus IDs no
+    /// longer in the tree and rejected values, which leave their parameter as it was.
+    pub fn apply(&self, tree: &Rc<RefCell<MenuNode>>) -> (usize, Vec<String>) {
+        let mut rejected = Vec::new();
+        for (id, key, value) in &self.params {
+            if let Some(item) = find_item(tree, id)
+                && let MenuNode::Item { params, .. } = &mut *item.borrow_mut()
+                && let Some(param) = params.iter_mut().find(|param| param.key == key)
+            {
+                // The file may have been edited by hand: values get the checks their form field has.
+                match check_param(param, value) {
+                    Ok(()) => param.value = value.clone(),
+                    Err(reason) => rejected.push(format!("{}: {} rejected: {}", id, key, reason)),
+                }
+            }
+        }
+        let (found, missing): (Vec<_>, Vec<_>) = self.items.iter().map(|id| (id, find_item(tree, id))).partition(|(_, item)| item.is_some());
+        let items: Vec<_> = found.into_iter().filter_map(|(_, item)| item).collect();
+        let (changed, mut refused) = redcent_tui::select_items(tree, &items);
+        if self.arranged {
+            set_script_order(&items.into_iter().filter(|item| item.borrow().is_selected()).collect::<Vec<_>>());
+        }
+        refused.extend(missing.into_iter().map(|(id, _)| format!("{} no longer exists", id)));
+        refused.extend(rejected);
+        (changed, refused)
+    }
+}
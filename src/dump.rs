@@ -0,0 +1,116 @@
+// src/dump.rs
+
+use crate::{
+    cockpit::{json_array, json_string},
+    draft::Draft,
+    privilege::Escalation,
+    session::{self, Json},
+};
+use redcent_tui::{script_order, MenuNode, Model};
+
+/// Version of the selection document; bumped when fields change meaning or go away.
+const FORMAT_VERSION: u32 = 1;
+
+/// Describes the selection as JSON for image build pipelines: the target, how commands gain root,
+/// the reboot policy and, per item in script order, its ID, phase, parameters and commands as
+/// they appear in the generated script, which is included whole as well.
+pub fn selection_json(model: &Model, escalation: Escalation, reboot: bool) -> String {
+    let backend = model.backend.as_ref();
+    let mut items = Vec::new();
+    let mut required_by = Vec::new();
+    for (item, phase) in script_order(&model.menu_tree, backend) {
+        let item = item.borrow();
+        let MenuNode::Item { params, .. } = &*item else { continue };
+        let Some(script) = item.script(backend) else { continue };
+        if script.requires_reboot {
+            required_by.push(script.id);
+        }
+        let params: Vec<String> = params.iter().map(|param| format!("{}: {}", json_string(param.key), json_string(&param.value))).collect();
+        let commands = script.commands.iter().filter(|cmd| !cmd.is_comment()).map(|cmd| escalation.apply(&cmd.code));
+        items.push(format!(
+            "    {{\n      \"id\": {},\n      \"name\": {},\n      \"phase\": {},\n      \"requires_reboot\": {},\n      \"params\": {{{}}},\n      \"commands\": {}\n    }}",
+            json_string(script.id),
+            json_string(item.name()),
+            json_string(&format!("{:?}", phase)),
+            script.requires_reboot,
+            params.join(", "),
+            json_array(commands),
+        ));
+    }
+    let prefix = escalation.prefix().trim();
+    let items = if items.is_empty() { "[]".to_string() } else { format!("[\n{}\n  ]", items.join(",\n")) };
+    format!(
+        "{{\n  \"version\": {},\n  \"generator\": {},\n  \"target\": {{\"distro\": {}, \"package_manager\": {}}},\n  \"escalation\": {},\n  \"reboot\": {{\"final_reboot\": {}, \"required_by\": {}}},\n  \"items\": {},\n  \"problems\": {},\n  \"script\": {}\n}}\n",
+        FORMAT_VERSION,
+        json_string(&format!("redcent-tui {}", env!("CARGO_PKG_VERSION"))),
+        json_string(&model.target_name()),
+        json_string(backend.name()),
+        if prefix.is_empty() { "null".to_string() } else { json_string(prefix) },
+        reboot,
+        json_array(required_by),
+        items,
+        json_array(model.problems().iter().map(|problem| problem.describe())),
+        json_string(&escalation.apply(&model.generate_commands(reboot))),
+    )
+}
+
+/// Reads a selection for `--apply`: a document written by [`selection_json`], a `session.json`, or
+/// a bare array of item IDs. Items are resolved by ID when the selection is applied, through the
+/// same dependency rules as a restored session.
+pub fn parse_selection(text: &str) -> Result<Draft, String> {
+    let json = session::parse(text).ok_or("not valid JSON")?;
+    let mut draft = Draft { saved: String::new(), target: String::new(), items: Vec::new(), arranged: false, params: Vec::new() };
+    let entries = match &json {
+        Json::Array(entries) => entries,
+        Json::Object(fields) => {
+            let field = |key: &str| fields.iter().find(|(k, _)| k == key).map(|(_, value)| value);
+            draft.target = match field("target") {
+                Some(Json::String(target)) => target.clone(),
+                Some(Json::Object(target)) => target.iter().find_map(|(k, value)| match value {
+                    Json::String(distro) if k == "distro" => Some(distro.clone()),
+                    _ => None,
+                }).unwrap_or_default(),
+                _ => String::new(),
+            };
+            draft.arranged = matches!(field("arranged"), Some(Json::Bool(true)));
+            if let Some(Json::Array(params)) = field("params") {
+                draft.params.extend(params.iter().filter_map(|param| {
+                    let Json::Object(param) = param else { return None };
+                    let get = |key: &str| param.iter().find_map(|(k, value)| match value {
+                        Json::String(s) if k == key => Some(s.clone()),
+                        _ => None,
+                    });
+                    Some((get("item")?, get("key")?, get("value")?))
+                }));
+            }
+            match field("items") {
+                Some(Json::Array(entries)) => entries,
+                _ => return Err("no \"items\" array".to_string()),
+            }
+        }
+        _ => return Err("expected an object or an array of item IDs".to_string()),
+    };
+    for entry in entries {
+        match entry {
+            Json::String(id) => draft.items.push(id.clone()),
+            Json::Object(item) => {
+                let Some(Json::String(id)) = item.iter().find(|(k, _)| k == "id").map(|(_, value)| value) else {
+                    return Err("an item has no \"id\"".to_string());
+                };
+                if let Some((_, Json::Object(params))) = item.iter().find(|(k, _)| k == "params") {
+                    for (key, value) in params {
+                        if let Json::String(value) = value {
+                            draft.params.push((id.clone(), key.clone(), value.clone()));
+                        }
+                    }
+                }
+                draft.items.push(id.clone());
+            }
+            _ => return Err("items must be IDs or objects with an \"id\"".to_string()),
+        }
+    }
+    if draft.items.is_empty() {
+        return Err("no items to select".to_string());
+    }
+    Ok(draft)
+}
@@ -0,0 +1,111 @@
+// src/events.rs
+
+use crossterm::event::{self, Event};
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// How long the input thread waits for a terminal event before checking whether it should stop.
+/// The terminal is only shared for this long at a time, e.g. with the cursor position query of
+/// the inline viewport.
+const INPUT_POLL: Duration = Duration::from_millis(50);
+
+/// What wakes the event loop.
+pub enum AppEvent {
+    /// A key, mouse, paste or resize event from the terminal.
+    Input(Event),
+    /// A background task has something new to show: script output, a remote host's status,
+    /// finished preflight checks.
+    Wake,
+    /// Nothing happened for a tick; time-based parts of the screen, such as the elapsed time of a
+    /// run or the draft's autosave, catch up.
+    Tick,
+}
+
+/// The event loop's source: terminal input is read on its own thread and merged with the wakes
+/// of background tasks, so neither blocks the other.
+pub struct Events {
+    receiver: Receiver<io::Result<AppEvent>>,
+    waker: Waker,
+    stop: Arc<AtomicBool>,
+    input: Option<JoinHandle<()>>,
+}
+
+impl Events {
+    /// Starts reading terminal input; it stops when the `Events` is dropped, so nothing is read
+    /// from the terminal once the TUI has been left, e.g. a script's sudo prompt.
+    pub fn start() -> Events {
+        let (sender, receiver) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let input = {
+            let (sender, stop) = (sender.clone(), stop.clone());
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let event = match event::poll(INPUT_POLL) {
+                        Ok(false) => continue,
+                        Ok(true) => event::read(),
+                        Err(e) => Err(e),
+                    };
+                    // A read error ends the input and is passed on to the loop.
+                    let failed = event.is_err();
+                    if sender.send(event.map(AppEvent::Input)).is_err() || failed {
+                        break;
+                    }
+                }
+            })
+        };
+        Events { receiver, waker: Waker { sender, pending: Arc::new(AtomicBool::new(false)) }, stop, input: Some(input) }
+    }
+
+    /// A handle for background tasks to wake the loop with.
+    pub fn waker(&self) -> Waker {
+        self.waker.clone()
+    }
+
+    /// Waits up to `tick` for the next event.
+    pub fn next(&self, tick: Duration) -> io::Result<AppEvent> {
+        match self.receiver.recv_timeout(tick) {
+            Ok(Ok(AppEvent::Wake)) => {
+                // Wakes sent from now on are for news arriving after the coming pass.
+                self.waker.pending.store(false, Ordering::Relaxed);
+                Ok(AppEvent::Wake)
+            }
+            Ok(event) => event,
+            Err(RecvTimeoutError::Timeout) => Ok(AppEvent::Tick),
+            Err(RecvTimeoutError::Disconnected) => Err(io::Error::new(io::ErrorKind::BrokenPipe, "terminal input closed")),
+        }
+    }
+}
+
+impl Drop for Events {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(input) = self.input.take() {
+            let _ = input.join();
+        }
+    }
+}
+
+/// Wakes the event loop from a background thread. Wakes are coalesced: until the loop has taken
+/// one, further ones are dropped, so a chatty script redraws the screen once per pass rather than
+/// once per read.
+#[derive(Clone)]
+pub struct Waker {
+    sender: Sender<io::Result<AppEvent>>,
+    pending: Arc<AtomicBool>,
+}
+
+impl Waker {
+    pub fn wake(&self) {
+        if !self.pending.swap(true, Ordering::Relaxed) {
+            let _ = self.sender.send(Ok(AppEvent::Wake));
+        }
+    }
+}
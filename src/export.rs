@@ -0,0 +1,101 @@
+// src/export.rs
+
+/// Path the embedded script is written to on the provisioned machine.
+const REMOTE_PATH: &str = "/tmp/redcent-setup.sh";
+
+/// Wraps `script` in Terraform/OpenTofu `file` and `remote-exec` provisioner blocks that upload
+/// and run it. The blocks go inside the resource that creates the VM, next to its `connection` block.
+pub fn terraform_provisioner(script: &str, target: &str) -> String {
+    // Inside a heredoc, `${` and `%{` start template sequences; doubling the sigil escapes them.
+    let escaped = script.replace("${", "$${").replace("%{", "%%{");
+    let delimiter = heredoc_delimiter(&escaped, "REDCENT_EOT");
+    let mut out = format!("# Provisioners generated for {} by RHEL/CentOS TUI Manager.\n", target);
+    out.push_str("# Place them inside the resource that creates the VM; it also needs a connection block.\n");
+    out.push_str("provisioner \"file\" {\n");
+    out.push_str(&format!("  content     = <<{}\n{}\n{}\n", delimiter, escaped.trim_end(), delimiter));
+    out.push_str(&format!("  destination = \"{}\"\n", REMOTE_PATH));
+    out.push_str("}\n\n");
+    out.push_str("provisioner \"remote-exec\" {\n");
+    out.push_str("  inline = [\n");
+    out.push_str(&format!("    \"sudo bash {}\",\n", REMOTE_PATH));
+    out.push_str(&format!("    \"rm -f {}\",\n", REMOTE_PATH));
+    out.push_str("  ]\n");
+    out.push_str("}\n");
+    out
+}
+
+/// Wraps `script` in a `#cloud-config` document that writes it to disk and runs it on first boot,
+/// usable as a VM's user data or as one part of a multi-part cloud-init configuration.
+pub fn cloud_init(script: &str, target: &str) -> String {
+    let mut out = String::from("#cloud-config\n");
+    out.push_str(&format!("# Generated for {} by RHEL/CentOS TUI Manager.\n", target));
+    out.push_str("write_files:\n");
+    out.push_str(&format!("  - path: {}\n", REMOTE_PATH));
+    out.push_str("    permissions: '0700'\n");
+    out.push_str("    content: |\n");
+    for line in script.trim_end().lines() {
+        if line.is_empty() {
+            out.push('\n');
+        } else {
+            out.push_str(&format!("      {}\n", line));
+        }
+    }
+    out.push_str("runcmd:\n");
+    out.push_str(&format!("  - [bash, {}]\n", REMOTE_PATH));
+    out
+}
+
+/// `base`, suffixed with a number if a line of `content` already consists of it.
+fn heredoc_delimiter(content: &str, base: &str) -> String {
+    let mut delimiter = base.to_string();
+    let mut n = 1;
+    while content.lines().any(|line| line.trim() == delimiter) {
+        delimiter = format!("{}{}", base, n);
+        n += 1;
+    }
+    delimiter
+}
+
+/// A Packer HCL `shell` provisioner running `script_file`, which is saved next to the template.
+/// The stanza goes inside the template's `build` block; `reboot` marks the script as ending in a
+/// reboot so Packer waits for the machine to come back.
+pub fn packer_provisioner(script_file: &str, target: &str, reboot: bool) -> String {
+    let mut out = format!("# Shell provisioner generated for {} by RHEL/CentOS TUI Manager.\n", target);
+    out.push_str(&format!("# Place it inside the build block; {} must sit next to this template.\n", script_file));
+    let mut attributes = vec![
+        ("script", format!("\"${{path.root}}/{}\"", script_file)),
+        ("execute_command", "\"chmod +x {{ .Path }}; {{ .Vars }} sudo -E bash '{{ .Path }}'\"".to_string()),
+    ];
+    if reboot {
+        attributes.push(("expect_disconnect", "true".to_string()));
+    }
+    // Aligned on `=` the way `packer fmt` writes consecutive attributes.
+    let width = attributes.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    out.push_str("provisioner \"shell\" {\n");
+    for (key, value) in attributes {
+        out.push_str(&format!("  {:<width$} = {}\n", key, value, width = width));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// A Vagrantfile running `script` as an inline shell provisioner on a box matching `target`,
+/// for trying a selection on a local VM before applying it to real hosts.
+pub fn vagrantfile(script: &str, target: &str) -> String {
+    let (box_name, note) = match target {
+        "Rhel" => ("generic/rhel9", "  # RHEL boxes need a subscription: register with subscription-manager before provisioning.\n"),
+        "Debian" => ("debian/bookworm64", ""),
+        "Ubuntu" => ("ubuntu/jammy64", ""),
+        _ => ("centos/stream9", ""),
+    };
+    let delimiter = heredoc_delimiter(script, "REDCENT_SCRIPT");
+    let mut out = format!("# Vagrantfile generated for {} by RHEL/CentOS TUI Manager.\n", target);
+    // A quoted heredoc delimiter turns off Ruby interpolation and escapes, so the script is passed on verbatim.
+    out.push_str(&format!("REDCENT_SETUP = <<'{}'\n{}\n{}\n\n", delimiter, script.trim_end(), delimiter));
+    out.push_str("Vagrant.configure(\"2\") do |config|\n");
+    out.push_str(&format!("  config.vm.box = \"{}\" # adjust to the release you target\n", box_name));
+    out.push_str(note);
+    out.push_str("  config.vm.provision \"shell\", inline: REDCENT_SETUP, privileged: true\n");
+    out.push_str("end\n");
+    out
+}
@@ -0,0 +1,40 @@
+// src/favorites.rs
+
+use crate::history::state_dir;
+use redcent_tui::{find_item, MenuNode};
+use std::{cell::RefCell, fs, io, rc::Rc};
+
+pub const FAVORITES_FILE: &str = "favorites";
+
+/// IDs of the items marked as favorites, in the order they were added.
+pub fn load() -> Vec<String> {
+    let Some(path) = state_dir().map(|dir| dir.join(FAVORITES_FILE)) else { return Vec::new() };
+    fs::read_to_string(path)
+        .map(|content| content.lines().map(str::trim).filter(|id| !id.is_empty()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Adds `id` to the favorites, or removes it if it is one, and writes the list back to the state
+/// file. Returns whether it is a favorite now.
+pub fn toggle(favorites: &mut Vec<String>, id: &str) -> io::Result<bool> {
+    let added = !favorites.iter().any(|existing| existing == id);
+    if added {
+        favorites.push(id.to_string());
+    } else {
+        favorites.retain(|existing| existing != id);
+    }
+    let dir = state_dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "cannot determine state directory"))?;
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(FAVORITES_FILE), favorites.iter().map(|id| format!("{}\n", id)).collect::<String>())?;
+    Ok(added)
+}
+
+/// Builds the "Favorites" menu from the items in `tree` matching `favorites`, shared with their
+/// real parents like [`crate::recent::recent_menu`]. IDs this tree does not have are skipped.
+pub fn favorites_menu(tree: &Rc<RefCell<MenuNode>>, favorites: &[String]) -> Option<Rc<RefCell<MenuNode>>> {
+    let children: Vec<_> = favorites.iter().filter_map(|id| find_item(tree, id)).collect();
+    if children.is_empty() {
+        return None;
+    }
+    Some(Rc::new(RefCell::new(MenuNode::Menu { name: "Favorites".to_string(), children })))
+}
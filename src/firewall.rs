@@ -0,0 +1,159 @@
+// src/firewall.rs
+
+use crate::theme::Theme;
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
+    Frame,
+};
+use std::process::Command;
+
+/// A firewalld zone from `firewall-cmd --list-all-zones`, with its settings in listing order.
+pub struct Zone {
+    pub name: String,
+    pub default: bool,
+    pub active: bool,
+    pub settings: Vec<(String, String)>,
+}
+
+impl Zone {
+    fn setting(&self, key: &str) -> &str {
+        self.settings.iter().find(|(k, _)| k == key).map_or("", |(_, value)| value.as_str())
+    }
+}
+
+/// Parses the listing: a zone header such as `public (default, active)` at the start of a line,
+/// then its indented `key: value` settings. Forward ports and rich rules follow their key on
+/// tab-indented lines of their own, and are kept one per line.
+pub fn parse_zones(listing: &str) -> Vec<Zone> {
+    let mut zones: Vec<Zone> = Vec::new();
+    for line in listing.lines().filter(|line| !line.trim().is_empty()) {
+        if !line.starts_with(char::is_whitespace) {
+            let (name, flags) = line.split_once(' ').unwrap_or((line, ""));
+            zones.push(Zone { name: name.to_string(), default: flags.contains("default"), active: flags.contains("active"), settings: Vec::new() });
+        } else if let Some(zone) = zones.last_mut() {
+            match (line.starts_with('\t'), zone.settings.last_mut(), line.trim().split_once(':')) {
+                (true, Some((_, value)), _) => {
+                    if !value.is_empty() {
+                        value.push('\n');
+                    }
+                    value.push_str(line.trim());
+                }
+                (false, _, Some((key, value))) => zone.settings.push((key.to_string(), value.trim().to_string())),
+                _ => {}
+            }
+        }
+    }
+    zones
+}
+
+/// Read-only view of the running firewalld configuration: a table of the zones with their
+/// interfaces, services and ports, and every setting of the highlighted zone below it.
+pub struct FirewallStatus {
+    zones: Vec<Zone>,
+    error: Option<String>,
+    selected: usize,
+    /// Names of the selected items whose scripts call `firewall-cmd`.
+    changing: Vec<String>,
+}
+
+impl FirewallStatus {
+    pub fn probe(changing: Vec<String>) -> FirewallStatus {
+        let (mut zones, error) = match Command::new("firewall-cmd").arg("--list-all-zones").output() {
+            Ok(output) if output.status.success() => (parse_zones(&String::from_utf8_lossy(&output.stdout)), None),
+            // firewall-cmd reports "FirewallD is not running" on stdout.
+            Ok(output) => {
+                let message = [&output.stderr, &output.stdout].iter()
+                    .find_map(|stream| String::from_utf8_lossy(stream).lines().next().map(str::to_string))
+                    .unwrap_or_else(|| "firewall-cmd failed".to_string());
+                (Vec::new(), Some(message))
+            }
+            Err(e) => (Vec::new(), Some(format!("firewall-cmd not available: {}", e))),
+        };
+        // Zones in use first; the listing itself is alphabetical.
+        zones.sort_by_key(|zone| !(zone.active || zone.default));
+        FirewallStatus { zones, error, selected: 0, changing }
+    }
+
+    /// Returns false when the pane is left.
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Down if !self.zones.is_empty() => self.selected = (self.selected + 1) % self.zones.len(),
+            KeyCode::Up if !self.zones.is_empty() => self.selected = (self.selected + self.zones.len() - 1) % self.zones.len(),
+            KeyCode::Char('r') => *self = FirewallStatus::probe(std::mem::take(&mut self.changing)),
+            KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('W') => return false,
+            _ => {}
+        }
+        true
+    }
+
+    pub fn draw(&self, f: &mut Frame, theme: &Theme) {
+        let note_height = if self.changing.is_empty() { 0 } else { 3 };
+        let chunks = Layout::default().direction(Direction::Vertical)
+            .constraints([Constraint::Length(note_height), Constraint::Min(5), Constraint::Length(10), Constraint::Length(3)].as_ref())
+            .split(f.size());
+        if !self.changing.is_empty() {
+            let note = Paragraph::new(Line::from(vec![
+                Span::styled("Selected items that change the firewall: ", theme.warning),
+                Span::raw(self.changing.join(", ")),
+            ])).block(Block::default().borders(Borders::ALL));
+            f.render_widget(note, chunks[0]);
+        }
+
+        let block = Block::default().borders(Borders::ALL).title("Firewall (firewalld zones)");
+        if let Some(error) = &self.error {
+            f.render_widget(Paragraph::new(Line::styled(error.clone(), theme.warning)).wrap(Wrap { trim: true }).block(block), chunks[1]);
+        } else {
+            let list = |value: &str| {
+                let values: Vec<&str> = value.split_whitespace().collect();
+                if values.is_empty() { "-".to_string() } else { values.join(", ") }
+            };
+            let rows: Vec<Row> = self.zones.iter().map(|zone| {
+                let mut name = zone.name.clone();
+                if zone.default {
+                    name.push_str(" (default)");
+                }
+                let bound = format!("{} {}", zone.setting("interfaces"), zone.setting("sources"));
+                let style = if zone.active { theme.added } else { theme.muted };
+                Row::new(vec![
+                    Cell::from(name).style(style),
+                    Cell::from(list(&bound)),
+                    Cell::from(list(zone.setting("services"))),
+                    Cell::from(list(zone.setting("ports"))),
+                ])
+            }).collect();
+            let table = Table::new(rows, [Constraint::Length(20), Constraint::Percentage(20), Constraint::Percentage(35), Constraint::Percentage(25)])
+                .header(Row::new(vec!["Zone", "Interfaces/sources", "Services", "Ports"]).style(theme.title))
+                .block(block)
+                .highlight_style(theme.highlight)
+                .highlight_symbol(">> ");
+            let mut state = TableState::default();
+            state.select(Some(self.selected).filter(|_| !self.zones.is_empty()));
+            f.render_stateful_widget(table, chunks[1], &mut state);
+        }
+
+        let (title, lines) = match self.zones.get(self.selected) {
+            Some(zone) => {
+                let mut lines = Vec::new();
+                for (key, value) in zone.settings.iter().filter(|(_, value)| !value.is_empty()) {
+                    if value.contains('\n') {
+                        lines.push(Line::styled(format!("{}:", key), theme.muted));
+                        lines.extend(value.lines().map(|entry| Line::raw(format!("  {}", entry))));
+                    } else {
+                        lines.push(Line::from(vec![Span::styled(format!("{}: ", key), theme.muted), Span::raw(value.clone())]));
+                    }
+                }
+                (format!("Zone {}", zone.name), lines)
+            }
+            None => ("Zone".to_string(), Vec::new()),
+        };
+        f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }).block(Block::default().borders(Borders::ALL).title(title)), chunks[2]);
+
+        let footer = Paragraph::new("Zone [↑↓] | [r] Refresh | [Esc/W] Back | [q] Quit")
+            .style(theme.footer)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(footer, chunks[3]);
+    }
+}
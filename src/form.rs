@@ -0,0 +1,671 @@
+// src/form.rs
+
+use crate::{centered_rect, labwizard::DESKTOP_PRESETS, theme::Theme};
+use redcent_tui::{validate, MenuNode, PackageBackend, Param, Proxy};
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use std::{cell::RefCell, fs, process::Command, rc::Rc};
+
+/// What the caller should do after a popup handled a key.
+pub enum WizardOutcome {
+    Continue,
+    Cancel,
+    Finish,
+}
+
+/// NFS export options offered for `options` parameters.
+const EXPORT_OPTIONS: [&str; 6] = ["rw", "ro", "sync", "async", "no_subtree_check", "no_root_squash"];
+
+/// How a scheduled job is installed, offered for `schedule_type` parameters.
+pub const SCHEDULE_TYPES: [&str; 2] = ["systemd timer", "cron"];
+
+/// Checks a non-blank field value, returning the message shown under the field when it is
+/// rejected; see [`redcent_tui::validate`] for the typed ones.
+pub type Validator = fn(&str) -> Result<(), String>;
+
+pub enum FieldKind {
+    Text,
+    /// Text shown masked.
+    Password,
+    /// One of the options; the value is the option's label.
+    Select(Vec<String>),
+    /// Any of the options; the value is the chosen labels, comma-separated.
+    MultiSelect(Vec<String>),
+}
+
+pub struct Field {
+    pub key: &'static str,
+    label: String,
+    kind: FieldKind,
+    pub value: String,
+    /// Blank required fields are flagged and keep the wizard on their step.
+    required: bool,
+    validator: Option<Validator>,
+    /// Highlighted option of select fields.
+    cursor: usize,
+    /// Why the value was rejected, shown under the field until it is fixed.
+    error: Option<String>,
+}
+
+impl Field {
+    pub fn new(key: &'static str, label: impl Into<String>, kind: FieldKind, value: impl Into<String>) -> Field {
+        let mut value = value.into();
+        let mut cursor = 0;
+        // A select field always holds one of its options, the first if the value is not among them.
+        if let FieldKind::Select(options) = &kind {
+            cursor = options.iter().position(|option| *option == value).unwrap_or(0);
+            value = options.get(cursor).cloned().unwrap_or_default();
+        }
+        Field { key, label: label.into(), kind, value, required: false, validator: None, cursor, error: None }
+    }
+
+    /// A field for an item parameter, typed by its key: passwords are masked, paths, addresses,
+    /// names and numbers validated, export options and toggles picked from a list. Every item
+    /// parameter has its check here, which [`check_param`] applies to values from files as well.
+    pub fn from_param(param: &Param) -> Field {
+        let (kind, validator): (FieldKind, Option<Validator>) = match param.key {
+            "password" => (FieldKind::Password, Some(initial_password)),
+            "path" => (FieldKind::Text, Some(validate::plain_path)),
+            "wallpaper" => (FieldKind::Text, Some(validate::plain_path)),
+            "org" => (FieldKind::Text, Some(organization)),
+            "clients" => (FieldKind::Text, Some(validate::nfs_client)),
+            "gateway" | "dhcp_start" | "dhcp_end" => (FieldKind::Text, Some(validate::ip)),
+            "netmask" => (FieldKind::Text, Some(validate::ipv4)),
+            "options" => (FieldKind::MultiSelect(EXPORT_OPTIONS.iter().map(|option| option.to_string()).collect()), None),
+            "nopasswd" => (FieldKind::Select(vec!["no".to_string(), "yes".to_string()]), None),
+            "apply_updates" => (FieldKind::Select(vec!["yes".to_string(), "no".to_string()]), None),
+            "upgrade_type" => (FieldKind::Select(vec!["security".to_string(), "default".to_string()]), None),
+            "env_file" | "sudo_file" => (FieldKind::Text, Some(validate::drop_in_name)),
+            "env_vars" => (FieldKind::Text, Some(validate::env_assignments)),
+            "sudo_user" => (FieldKind::Text, Some(sudo_principal)),
+            "sudo_commands" => (FieldKind::Text, Some(sudo_commands)),
+            "job_name" => (FieldKind::Text, Some(validate::drop_in_name)),
+            "command" => (FieldKind::Text, Some(job_command)),
+            "job_user" | "admin_user" | "key_user" => (FieldKind::Text, Some(validate::user_name)),
+            "prefix" => (FieldKind::Text, Some(account_prefix)),
+            "count" => (FieldKind::Text, Some(account_count)),
+            "desktop" => (FieldKind::Select(DESKTOP_PRESETS.iter().map(|(label, _)| label.to_string()).collect()), None),
+            "max_days" | "seconds" | "interval" | "alive_count" | "idle" | "lock" | "maxretry" => (FieldKind::Text, Some(validate::number)),
+            "bantime" => (FieldKind::Text, Some(ban_time)),
+            "ssh_key" => (FieldKind::Text, Some(validate::ssh_public_key)),
+            "schedule_type" => (FieldKind::Select(SCHEDULE_TYPES.iter().map(|kind| kind.to_string()).collect()), None),
+            "schedule" => (FieldKind::Text, Some(schedule)),
+            "luks_device" => (FieldKind::Text, Some(validate::block_device)),
+            "tpm2_pcrs" => (FieldKind::Text, Some(validate::tpm2_pcrs)),
+            "mtu" => (FieldKind::Text, Some(validate::mtu)),
+            "name" | "bridge" | "iface" => (FieldKind::Text, Some(validate::interface_name)),
+            "ping_target" => (FieldKind::Text, Some(validate::host)),
+            "isolated_cores" => (FieldKind::Text, Some(validate::cpu_list)),
+            "copr" => (FieldKind::Text, Some(validate::copr_project)),
+            "exporter_version" => (FieldKind::Text, Some(validate::semver)),
+            "toolset_version" | "stream" | "shell" | "ext_version" => (FieldKind::Text, Some(validate::number)),
+            "k8s_version" => (FieldKind::Text, Some(kubernetes_minor)),
+            "hostname" => (FieldKind::Text, Some(validate::hostname)),
+            "ntp_servers" => (FieldKind::Text, Some(validate::hosts)),
+            "locale" => (FieldKind::Text, Some(validate::locale)),
+            // Typed in when this machine cannot list them.
+            "timezone" => match timezones() {
+                zones if zones.is_empty() => (FieldKind::Text, Some(validate::timezone)),
+                zones => (FieldKind::Select(zones), None),
+            },
+            _ => (FieldKind::Text, None),
+        };
+        Field { required: param.required, validator, ..Field::new(param.key, param.label, kind, param.value.clone()) }
+    }
+
+    pub fn required(mut self) -> Field {
+        self.required = true;
+        self
+    }
+
+    pub fn validate(mut self, validator: Validator) -> Field {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// Changes what the field asks for and how it is checked, dropping a message about the old check.
+    pub fn set_check(&mut self, label: &str, validator: Validator) {
+        self.label = label.to_string();
+        self.validator = Some(validator);
+        self.error = None;
+    }
+
+    fn is_missing(&self) -> bool {
+        self.required && self.value.trim().is_empty()
+    }
+
+    fn options(&self) -> &[String] {
+        match &self.kind {
+            FieldKind::Select(options) | FieldKind::MultiSelect(options) => options,
+            _ => &[],
+        }
+    }
+
+    fn is_text(&self) -> bool {
+        matches!(self.kind, FieldKind::Text | FieldKind::Password)
+    }
+
+    /// Rejects a blank required value and runs the validator on a non-blank one, recording the
+    /// message; returns whether the value was accepted.
+    fn check(&mut self) -> bool {
+        let value = self.value.trim();
+        self.error = match self.validator {
+            _ if value.is_empty() => self.required.then(|| "a value is required".to_string()),
+            Some(validator) => validator(value).err(),
+            None => None,
+        };
+        self.error.is_none()
+    }
+
+    fn chosen(&self) -> Vec<&str> {
+        self.value.split(',').map(str::trim).filter(|choice| !choice.is_empty()).collect()
+    }
+
+    fn toggle_choice(&mut self) {
+        let Some(option) = self.options().get(self.cursor).cloned() else { return };
+        let mut chosen: Vec<&str> = self.chosen();
+        if chosen.contains(&option.as_str()) {
+            chosen.retain(|choice| *choice != option);
+        } else {
+            chosen.push(&option);
+        }
+        // Keep the declaration order whatever order the options were toggled in.
+        let ordered: Vec<&str> = self.options().iter().map(String::as_str).filter(|option| chosen.contains(option)).collect();
+        self.value = ordered.join(",");
+    }
+
+    /// Highlights the first option starting with `typed`, or else the first containing it, ignoring
+    /// case; returns whether one matched.
+    fn jump_to(&mut self, typed: &str) -> bool {
+        let typed = typed.to_lowercase();
+        let options = self.options();
+        let found = options.iter().position(|option| option.to_lowercase().starts_with(&typed))
+            .or_else(|| options.iter().position(|option| option.to_lowercase().contains(&typed)));
+        let Some(cursor) = found else { return false };
+        self.cursor = cursor;
+        if let FieldKind::Select(options) = &self.kind {
+            self.value = options[cursor].clone();
+        }
+        true
+    }
+
+    fn move_cursor(&mut self, forward: bool) {
+        let len = self.options().len();
+        if len == 0 {
+            return;
+        }
+        self.cursor = if forward { (self.cursor + 1) % len } else { (self.cursor + len - 1) % len };
+        if let FieldKind::Select(options) = &self.kind {
+            self.value = options[self.cursor].clone();
+        }
+    }
+
+    /// The field's contents as shown inside a box `width` columns wide; multi-select options are
+    /// packed into as many lines as they need.
+    fn display(&self, width: u16) -> Vec<Line<'_>> {
+        match &self.kind {
+            FieldKind::Password => vec![Line::from("*".repeat(self.value.chars().count()))],
+            FieldKind::Select(options) => vec![Line::from(format!("< {} >", options.get(self.cursor).map(String::as_str).unwrap_or("")))],
+            FieldKind::MultiSelect(options) => {
+                let chosen = self.chosen();
+                let inner = width.saturating_sub(2) as usize;
+                let mut lines: Vec<Vec<Span>> = vec![Vec::new()];
+                let mut used = 0;
+                for (i, option) in options.iter().enumerate() {
+                    let mark = if chosen.contains(&option.as_str()) { "[x]" } else { "[ ]" };
+                    let text = format!("{} {}  ", mark, option);
+                    let len = text.chars().count();
+                    if used > 0 && used + len > inner {
+                        lines.push(Vec::new());
+                        used = 0;
+                    }
+                    used += len;
+                    let style = if i == self.cursor { Style::default().add_modifier(Modifier::UNDERLINED) } else { Style::default() };
+                    lines.last_mut().unwrap().push(Span::styled(text, style));
+                }
+                lines.into_iter().map(Line::from).collect()
+            }
+            FieldKind::Text => vec![Line::from(self.value.as_str())],
+        }
+    }
+}
+
+/// Checks a value for `param` that did not come through its form, such as one read back from a
+/// selection file, the way [`Field::from_param`] would: list fields only take their options, text
+/// fields go through their validator. A blank value passes and is reported as missing later.
+pub fn check_param(param: &Param, value: &str) -> Result<(), String> {
+    // Form input is one line; a line break could end the heredoc a value is passed through.
+    if value.contains(['\n', '\r']) {
+        return Err("must be a single line".to_string());
+    }
+    let mut field = Field { required: false, ..Field::from_param(&param.with_value(value)) };
+    if field.is_text() {
+        return if field.check() { Ok(()) } else { Err(field.error.unwrap_or_default()) };
+    }
+    let chosen = match field.kind {
+        FieldKind::Select(_) => vec![value.trim()],
+        _ => field.chosen(),
+    };
+    match chosen.into_iter().find(|choice| !choice.is_empty() && !field.options().iter().any(|option| option == choice)) {
+        Some(choice) => Err(format!("{} is not one of {}", choice, field.options().join(", "))),
+        None => Ok(()),
+    }
+}
+
+/// One page of a wizard: fields to fill in, or a review of what will be added to the script.
+pub struct Step {
+    title: String,
+    fields: Vec<Field>,
+}
+
+impl Step {
+    pub fn new(title: impl Into<String>, fields: Vec<Field>) -> Step {
+        Step { title: title.into(), fields }
+    }
+
+    /// A step without fields, showing the text passed to [`Wizard::draw`].
+    pub fn review(title: impl Into<String>) -> Step {
+        Step::new(title, Vec::new())
+    }
+
+    fn is_review(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// A lone select field is shown as a list, navigated with ↑↓.
+    fn is_list(&self) -> bool {
+        matches!(self.fields.as_slice(), [Field { kind: FieldKind::Select(_), .. }])
+    }
+}
+
+/// Multi-step form shared by the parameter popup and the item wizards: Enter validates the step
+/// and moves on, Esc goes back a step (or cancels on the first), and the last step finishes.
+pub struct Wizard {
+    title: String,
+    steps: Vec<Step>,
+    current: usize,
+    focus: usize,
+    /// What Enter does on a final review step.
+    finish: &'static str,
+    /// Text typed to jump through a list step, dropped by any other key.
+    typed: String,
+}
+
+impl Wizard {
+    pub fn new(title: impl Into<String>, steps: Vec<Step>) -> Wizard {
+        Wizard { title: title.into(), steps, current: 0, focus: 0, finish: "add these commands to the script", typed: String::new() }
+    }
+
+    /// Names what finishing does, for wizards whose review step does not add commands.
+    pub fn finishing(mut self, action: &'static str) -> Wizard {
+        self.finish = action;
+        self
+    }
+
+    /// A single-step form with one text field per item parameter.
+    pub fn for_params(item: &Rc<RefCell<MenuNode>>) -> Wizard {
+        let item = item.borrow();
+        let fields = match &*item {
+            MenuNode::Item { params, .. } => params.iter().map(Field::from_param).collect(),
+            MenuNode::Menu { .. } => Vec::new(),
+        };
+        Wizard::new(format!("Parameters: {}", item.name()), vec![Step::new("Edit values", fields)])
+    }
+
+    /// The proxy form; blank fields leave that variable unset.
+    pub fn for_proxy(proxy: &Proxy) -> Wizard {
+        Wizard::new("Proxy", vec![Step::new("Proxy for package downloads", vec![
+            Field::new("http_proxy", "HTTP proxy, e.g. http://proxy.example.com:3128", FieldKind::Text, proxy.http.as_str()).validate(validate::proxy_url),
+            Field::new("https_proxy", "HTTPS proxy", FieldKind::Text, proxy.https.as_str()).validate(validate::proxy_url),
+            Field::new("no_proxy", "Reached directly (no_proxy), e.g. localhost,.example.com", FieldKind::Text, proxy.no_proxy.as_str())
+                .validate(validate::no_proxy),
+        ])])
+    }
+
+    /// The proxy entered in a [`Wizard::for_proxy`] form.
+    pub fn proxy(&self) -> Proxy {
+        let value = |key: &str| self.value(key).trim().to_string();
+        Proxy { http: value("http_proxy"), https: value("https_proxy"), no_proxy: value("no_proxy") }
+    }
+
+    fn fields(&self) -> impl Iterator<Item = &Field> {
+        self.steps.iter().flat_map(|step| &step.fields)
+    }
+
+    pub fn value(&self, key: &str) -> &str {
+        self.fields().find(|field| field.key == key).map(|field| field.value.as_str()).unwrap_or("")
+    }
+
+    pub fn field_mut(&mut self, key: &str) -> Option<&mut Field> {
+        self.steps.iter_mut().flat_map(|step| &mut step.fields).find(|field| field.key == key)
+    }
+
+    /// Moves to the step holding `key` and focuses that field.
+    pub fn focus_field(&mut self, key: &str) {
+        for (s, step) in self.steps.iter().enumerate() {
+            if let Some(f) = step.fields.iter().position(|field| field.key == key) {
+                self.current = s;
+                self.focus = f;
+            }
+        }
+    }
+
+    pub fn on_review(&self) -> bool {
+        self.steps[self.current].is_review()
+    }
+
+    /// Copies the entered values into the item's parameters with matching keys.
+    pub fn apply(&self, item: &Rc<RefCell<MenuNode>>) {
+        if let MenuNode::Item { params, .. } = &mut *item.borrow_mut() {
+            for param in params.iter_mut() {
+                if let Some(field) = self.fields().find(|field| field.key == param.key) {
+                    param.value = field.value.clone();
+                }
+            }
+        }
+    }
+
+    /// The item's script rendered with the values entered so far.
+    pub fn preview(&self, item: &Rc<RefCell<MenuNode>>, backend: &dyn PackageBackend) -> String {
+        match &*item.borrow() {
+            MenuNode::Item { script_fn, params, .. } => {
+                let entered: Vec<Param> = params.iter()
+                    .map(|param| self.fields().find(|field| field.key == param.key).map_or_else(|| param.with_value(&param.value), |field| param.with_value(&field.value)))
+                    .collect();
+                script_fn(backend).for_distro(backend.distro()).with_params(&entered).render()
+            }
+            MenuNode::Menu { .. } => String::new(),
+        }
+    }
+
+    /// Checks every field of the current step, focusing the first rejected one.
+    fn validate_step(&mut self) -> bool {
+        let fields = &mut self.steps[self.current].fields;
+        // Every field is checked, so all of their problems show at once.
+        let accepted: Vec<bool> = fields.iter_mut().map(Field::check).collect();
+        match accepted.iter().position(|ok| !ok) {
+            Some(first) => {
+                self.focus = first;
+                false
+            }
+            None => true,
+        }
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> WizardOutcome {
+        let step = &mut self.steps[self.current];
+        let is_list = step.is_list();
+        let count = step.fields.len();
+        if !matches!(code, KeyCode::Char(_) | KeyCode::Backspace) {
+            self.typed.clear();
+        }
+        match code {
+            KeyCode::Esc => {
+                if self.current == 0 {
+                    return WizardOutcome::Cancel;
+                }
+                self.current -= 1;
+                self.focus = 0;
+            }
+            KeyCode::Backspace if step.is_review() => {
+                if self.current > 0 {
+                    self.current -= 1;
+                    self.focus = 0;
+                }
+            }
+            KeyCode::Enter => {
+                if !self.validate_step() {
+                    return WizardOutcome::Continue;
+                }
+                if self.current + 1 == self.steps.len() {
+                    return WizardOutcome::Finish;
+                }
+                self.current += 1;
+                self.focus = 0;
+            }
+            _ if step.is_review() => {}
+            KeyCode::Up | KeyCode::Down if is_list => step.fields[0].move_cursor(code == KeyCode::Down),
+            KeyCode::Char(c) if is_list => {
+                self.typed.push(c);
+                // Keep the last match while further letters match nothing.
+                if !step.fields[0].jump_to(&self.typed) {
+                    self.typed.pop();
+                }
+            }
+            KeyCode::Backspace if is_list => {
+                self.typed.pop();
+                if !self.typed.is_empty() {
+                    step.fields[0].jump_to(&self.typed);
+                }
+            }
+            KeyCode::Down | KeyCode::Tab => self.focus = (self.focus + 1) % count,
+            KeyCode::Up | KeyCode::BackTab => self.focus = (self.focus + count - 1) % count,
+            _ => {
+                let field = &mut step.fields[self.focus];
+                match code {
+                    KeyCode::Left | KeyCode::Right if !field.is_text() => field.move_cursor(code == KeyCode::Right),
+                    KeyCode::Char(' ') if matches!(field.kind, FieldKind::MultiSelect(_)) => field.toggle_choice(),
+                    KeyCode::Char(c) if field.is_text() => field.value.push(c),
+                    KeyCode::Backspace if field.is_text() => { field.value.pop(); },
+                    _ => {}
+                }
+                // Once a field has been rejected, its message follows the edits.
+                if field.error.is_some() {
+                    field.check();
+                }
+            }
+        }
+        WizardOutcome::Continue
+    }
+
+    /// Adds pasted text to the focused text field, without its line breaks.
+    pub fn paste(&mut self, text: &str) {
+        let Some(field) = self.steps[self.current].fields.get_mut(self.focus).filter(|field| field.is_text()) else { return };
+        field.value.extend(text.chars().filter(|c| !c.is_control()));
+        if field.error.is_some() {
+            field.check();
+        }
+    }
+
+    /// Key hint line for the current step, listing only the keys its fields use.
+    fn hint(&self) -> String {
+        let step = &self.steps[self.current];
+        let mut keys = Vec::new();
+        if step.is_list() {
+            keys.push("↑↓ or type to choose");
+        } else if step.fields.len() > 1 {
+            keys.push("↑↓/Tab to move");
+        }
+        if !step.is_list() && step.fields.iter().any(|field| !field.is_text()) {
+            keys.push("←→ to choose");
+        }
+        if step.fields.iter().any(|field| matches!(field.kind, FieldKind::MultiSelect(_))) {
+            keys.push("Space to toggle");
+        }
+        let finish = format!("Enter to {}", self.finish);
+        keys.push(match (step.is_review(), self.current + 1 == self.steps.len()) {
+            (true, _) => &finish,
+            (false, true) => "Enter to confirm",
+            (false, false) => "Enter to continue",
+        });
+        keys.push(if self.current == 0 { "Esc to cancel" } else { "Esc to go back" });
+        format!("{}:", keys.join(", "))
+    }
+
+    /// Draws the current step; `review` is the text shown on review steps.
+    pub fn draw(&self, f: &mut Frame, theme: &Theme, review: &str) {
+        let step = &self.steps[self.current];
+        let (title, area) = if self.steps.len() > 1 {
+            let title = format!("{} - Step {}/{}: {}", self.title, self.current + 1, self.steps.len(), step.title);
+            (title, centered_rect(70, 70, f.size()))
+        } else {
+            (self.title.clone(), centered_rect(60, 60, f.size()))
+        };
+        f.render_widget(Clear, area);
+        f.render_widget(Block::default().title(title).borders(Borders::ALL), area);
+
+        let hint = self.hint();
+        let hint_height = if hint.chars().count() > area.width.saturating_sub(4) as usize { 2 } else { 1 };
+        let chunks = Layout::default().direction(Direction::Vertical).margin(2)
+            .constraints([Constraint::Length(hint_height), Constraint::Min(0)].as_ref()).split(area);
+        f.render_widget(Paragraph::new(hint).wrap(Wrap { trim: true }), chunks[0]);
+
+        if step.is_review() {
+            f.render_widget(Paragraph::new(review).block(Block::default().borders(Borders::ALL)), chunks[1]);
+        } else if step.is_list() {
+            let field = &step.fields[0];
+            let items: Vec<ListItem> = field.options().iter().map(|option| ListItem::new(option.as_str())).collect();
+            let list = List::new(items)
+                .block(Block::default().borders(Borders::ALL).title(match self.typed.as_str() {
+                    "" => field.label.clone(),
+                    typed => format!("{} - {}", field.label, typed),
+                }))
+                .highlight_style(theme.highlight)
+                .highlight_symbol(">> ");
+            let mut state = ListState::default();
+            state.select(Some(field.cursor));
+            f.render_stateful_widget(list, chunks[1], &mut state);
+        } else {
+            let contents: Vec<Vec<Line>> = step.fields.iter().map(|field| field.display(chunks[1].width)).collect();
+            // Each field's box, plus a line under it for its error message.
+            let heights: Vec<u16> = step.fields.iter().zip(&contents)
+                .map(|(field, lines)| lines.len() as u16 + 2 + field.error.is_some() as u16)
+                .collect();
+            let field_chunks = Layout::default().direction(Direction::Vertical)
+                .constraints(heights.iter().map(|&height| Constraint::Length(height)).chain([Constraint::Min(0)]).collect::<Vec<_>>())
+                .split(chunks[1]);
+            for (i, (field, lines)) in step.fields.iter().zip(contents).enumerate() {
+                let rejected = field.is_missing() || field.error.is_some();
+                let style = match (i == self.focus, rejected) {
+                    (true, _) => theme.focused,
+                    (false, true) => theme.error,
+                    (false, false) => Style::default(),
+                };
+                let title = if field.is_missing() { format!("{} (required)", field.label) } else { field.label.clone() };
+                let [input, message] = *Layout::default().direction(Direction::Vertical)
+                    .constraints([Constraint::Length(lines.len() as u16 + 2), Constraint::Min(0)].as_ref())
+                    .split(field_chunks[i]) else { continue };
+                let widget = Paragraph::new(lines)
+                    .block(Block::default().borders(Borders::ALL).title(title).border_style(style));
+                f.render_widget(widget, input);
+                if let Some(error) = &field.error {
+                    f.render_widget(Paragraph::new(format!(" {}", error)).style(theme.error), message);
+                }
+            }
+        }
+    }
+}
+
+/// The start of numbered account names; the number is appended.
+fn account_prefix(value: &str) -> Result<(), String> {
+    match value.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-') {
+        true => Ok(()),
+        false => Err("must be lowercase letters, digits, '_' or '-'".to_string()),
+    }
+}
+
+fn account_count(value: &str) -> Result<(), String> {
+    match value.parse::<u32>() {
+        Ok(1..=999) => Ok(()),
+        _ => Err("must be between 1 and 999".to_string()),
+    }
+}
+
+fn initial_password(value: &str) -> Result<(), String> {
+    match value.len() >= 8 && !value.contains('\'') {
+        true => Ok(()),
+        false => Err("needs at least 8 characters and no single quotes".to_string()),
+    }
+}
+
+/// A Kubernetes minor release as pkgs.k8s.io names its repositories, such as `v1.34`.
+fn kubernetes_minor(value: &str) -> Result<(), String> {
+    let numbers = value.strip_prefix('v').and_then(|rest| rest.split_once('.'));
+    match numbers.is_some_and(|(major, minor)| [major, minor].iter().all(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))) {
+        true => Ok(()),
+        false => Err("must be a minor release such as v1.34".to_string()),
+    }
+}
+
+/// A fail2ban duration: seconds, or amounts with `s`, `m`, `h`, `d`, `w`, `mo` or `y` units such as
+/// `1h` or `1d12h`; `-1` bans for good.
+fn ban_time(value: &str) -> Result<(), String> {
+    if value == "-1" {
+        return Ok(());
+    }
+    let mut rest = value;
+    while !rest.is_empty() {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let unit = ["mo", "s", "m", "h", "d", "w", "y"].into_iter().find(|unit| rest[digits..].starts_with(unit)).unwrap_or("");
+        if digits == 0 || (unit.is_empty() && digits < rest.len()) {
+            return Err("must be a duration such as 600, 10m, 1h or 1d, or -1".to_string());
+        }
+        rest = &rest[digits + unit.len()..];
+    }
+    Ok(())
+}
+
+/// An organization name for login banners: letters, digits, spaces and `.,&'()-`.
+fn organization(value: &str) -> Result<(), String> {
+    match value.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '.' | ',' | '&' | '\'' | '(' | ')' | '-')) {
+        true => Ok(()),
+        false => Err("may only contain letters, digits, spaces and .,&'()-".to_string()),
+    }
+}
+
+/// A scheduled job's command line, which has to start with the absolute path of a program.
+fn job_command(value: &str) -> Result<(), String> {
+    let program = value.split_whitespace().next().unwrap_or_default();
+    validate::plain_path(program).map_err(|_| "must start with the absolute path of a program, without quotes or spaces".to_string())
+}
+
+/// A sudoers user, or a group written as `%group`.
+fn sudo_principal(value: &str) -> Result<(), String> {
+    validate::user_name(value.strip_prefix('%').unwrap_or(value))
+}
+
+/// A comma-separated sudoers command list: absolute paths with optional fixed arguments.
+fn sudo_commands(value: &str) -> Result<(), String> {
+    for command in value.split(',').map(str::trim) {
+        validate::absolute_path(command).map_err(|_| format!("'{}' must start with an absolute path", command))?;
+        if command.contains(['\'', '\\', ':', '=']) {
+            return Err(format!("'{}' must not contain quotes, \\, ':' or '='", command));
+        }
+    }
+    Ok(())
+}
+
+/// A schedule whose type is not known here: a crontab or an `OnCalendar=` expression.
+fn schedule(value: &str) -> Result<(), String> {
+    validate::cron(value)
+        .or_else(|_| validate::on_calendar(value))
+        .map_err(|_| "must be a cron expression such as 0 3 * * * or a calendar event such as *-*-* 03:00:00".to_string())
+}
+
+/// Time zones offered for `timezone` parameters, as `timedatectl list-timezones` names them; where
+/// it cannot run (no systemd, e.g. in a container), the zones and links of the tz database it reads.
+fn timezones() -> Vec<String> {
+    let listed = Command::new("timedatectl").arg("list-timezones").output().ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).lines().map(str::to_string).collect::<Vec<_>>())
+        .unwrap_or_default();
+    if !listed.is_empty() {
+        return listed;
+    }
+    let tzdata = fs::read_to_string("/usr/share/zoneinfo/tzdata.zi").unwrap_or_default();
+    let mut zones: Vec<String> = tzdata.lines()
+        .filter_map(|line| match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["Z", zone, ..] | ["L", _, zone] => Some(zone.to_string()),
+            _ => None,
+        })
+        .collect();
+    zones.sort();
+    zones.dedup();
+    zones
+}
@@ -0,0 +1,566 @@
+// src/generate.rs
+
+use crate::{backend::PackageBackend, menu::{find_item, ordered_selected_items, MenuNode}, script::Phase};
+use std::{
+    cell::RefCell,
+    env, fs,
+    rc::Rc,
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// How the generated script reacts when one of its commands fails.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ErrorMode {
+    /// Plain concatenation: a failing command is reported by bash and the next one runs.
+    Continue,
+    /// `set -euo pipefail`: the first failing command aborts the script.
+    Strict,
+    /// Every command block is wrapped so failures are reported and collected into a summary at the end.
+    Report,
+}
+
+impl ErrorMode {
+    pub const ALL: [ErrorMode; 3] = [ErrorMode::Continue, ErrorMode::Strict, ErrorMode::Report];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ErrorMode::Continue => "Continue on error",
+            ErrorMode::Strict => "Stop on first error (set -euo pipefail)",
+            ErrorMode::Report => "Report failed blocks and summarize at the end",
+        }
+    }
+}
+
+/// The selected items in the order the generated script runs them, each with its phase: grouped
+/// by [`Phase`], in [`ordered_selected_items`] order within a phase. A requirement runs in the
+/// earliest phase of the items requiring it, so it still comes first.
+pub fn script_order(tree: &Rc<RefCell<MenuNode>>, backend: &dyn PackageBackend) -> Vec<(Rc<RefCell<MenuNode>>, Phase)> {
+    let items = ordered_selected_items(tree);
+    let mut phases: Vec<Phase> = items.iter()
+        .map(|item| item.borrow().script(backend).map_or(Phase::Configuration, |script| script.run_phase()))
+        .collect();
+    // Requirements come before the items requiring them, so walking backwards sees every
+    // dependent's final phase before its requirements are adjusted.
+    for position in (0..items.len()).rev() {
+        for req in items[position].borrow().requires() {
+            if let Some(dep) = find_item(tree, req)
+                && let Some(dep_position) = items.iter().position(|item| Rc::ptr_eq(item, &dep))
+            {
+                phases[dep_position] = phases[dep_position].min(phases[position]);
+            }
+        }
+    }
+    let mut ordered: Vec<_> = items.into_iter().zip(phases).collect();
+    ordered.sort_by_key(|(_, phase)| *phase);
+    ordered
+}
+
+/// A port a selected item's service listens on once the script has run.
+pub struct PortClaim {
+    /// `port/protocol`, e.g. `9090/tcp`.
+    pub port: String,
+    /// The systemd unit that listens on it.
+    pub unit: String,
+    /// Name of the item whose script starts the unit.
+    pub item: String,
+}
+
+/// The ports the selected items' services listen on, in script order.
+pub fn port_claims(tree: &Rc<RefCell<MenuNode>>, backend: &dyn PackageBackend) -> Vec<PortClaim> {
+    let mut claims = Vec::new();
+    for (item, _) in script_order(tree, backend) {
+        let item = item.borrow();
+        let Some(script) = item.script(backend) else { continue };
+        claims.extend(script.listens.into_iter().map(|(port, unit)| PortClaim { port, unit, item: item.name().to_string() }));
+    }
+    claims
+}
+
+/// Ports claimed by more than one unit, each with the names of the items claiming it. Items
+/// starting the same unit (several Cockpit items enable `cockpit.socket`) share its port.
+pub fn port_conflicts(claims: &[PortClaim]) -> Vec<(String, Vec<String>)> {
+    let mut conflicts: Vec<(String, Vec<String>)> = Vec::new();
+    for (n, claim) in claims.iter().enumerate() {
+        if conflicts.iter().any(|(port, _)| *port == claim.port) {
+            continue;
+        }
+        let others: Vec<&PortClaim> = claims[n..].iter().filter(|other| other.port == claim.port).collect();
+        if others.iter().any(|other| other.unit != claim.unit) {
+            let mut items: Vec<String> = Vec::new();
+            for other in others {
+                if !items.contains(&other.item) {
+                    items.push(other.item.clone());
+                }
+            }
+            conflicts.push((claim.port.clone(), items));
+        }
+    }
+    conflicts
+}
+
+/// What a selected item puts on disk, for checking free space before the script runs.
+pub struct SpaceClaim {
+    /// Name of the item.
+    pub item: String,
+    /// Packages the item's script installs.
+    pub packages: Vec<String>,
+    /// The script's own estimate in MiB, see [`crate::Script::size_mib`]; when set it covers `packages`.
+    pub size_mib: Option<u64>,
+}
+
+/// The selected items that install packages or carry a size estimate, in script order.
+pub fn space_claims(tree: &Rc<RefCell<MenuNode>>, backend: &dyn PackageBackend) -> Vec<SpaceClaim> {
+    script_order(tree, backend).into_iter().filter_map(|(item, _)| {
+        let item = item.borrow();
+        let script = item.script(backend)?;
+        (script.size_mib.is_some() || !script.packages.is_empty())
+            .then(|| SpaceClaim { item: item.name().to_string(), packages: script.packages, size_mib: script.size_mib })
+    }).collect()
+}
+
+/// A stretch of the run between reboots.
+pub struct Stage {
+    /// Names of the items run in this stage, in script order.
+    pub items: Vec<String>,
+    /// Whether an item in this stage only takes full effect after a reboot.
+    pub reboot_after: bool,
+}
+
+/// Splits the selected items, in script order, into stages that each end with a reboot: a stage
+/// closes after its last consecutive item that requires one, so back-to-back items share a reboot.
+pub fn plan_stages(tree: &Rc<RefCell<MenuNode>>, backend: &dyn PackageBackend) -> Vec<Stage> {
+    let mut stages = vec![Stage { items: Vec::new(), reboot_after: false }];
+    for (item, _) in script_order(tree, backend) {
+        let item = item.borrow();
+        let Some(script) = item.script(backend) else { continue };
+        let current = stages.last_mut().unwrap();
+        if current.reboot_after && !script.requires_reboot {
+            stages.push(Stage { items: vec![item.name().to_string()], reboot_after: false });
+        } else {
+            current.items.push(item.name().to_string());
+            current.reboot_after |= script.requires_reboot;
+        }
+    }
+    stages
+}
+
+/// Opens every command block of a resumable script, see [`generate_resumable_script`]; followed by
+/// the quoted ID of the item the block belongs to. Scripts from before the ID was added end here.
+const CHECKPOINT_GUARD: &str = "if ! redcent_skip";
+
+/// Printed by resumable scripts as each command block starts, followed by `<block>: <item ID>`.
+pub const BLOCK_MARKER: &str = "==> Block ";
+
+/// Printed by resumable scripts for each verification command that succeeds after its item's
+/// commands ran, followed by `<item ID>: <command>`.
+pub const VERIFIED_MARKER: &str = "VERIFIED: ";
+/// Like [`VERIFIED_MARKER`], for verification commands that fail.
+pub const NOT_VERIFIED_MARKER: &str = "NOT VERIFIED: ";
+
+/// Helpers of resumable scripts. `$1` names the checkpoint file, which receives the number of the
+/// last block completed without an earlier failure; blocks up to `$2` are skipped.
+const CHECKPOINT_PREAMBLE: &str = r#"# Resumable run: completed blocks are recorded in the file named by $1; blocks up to $2 are skipped.
+redcent_checkpoint=${1:-}
+redcent_resume_after=${2:-0}
+redcent_block=0
+redcent_ok=1
+redcent_skip() {
+    redcent_block=$((redcent_block + 1))
+    if [ "$redcent_block" -le "$redcent_resume_after" ]; then
+        echo "Skipping block $redcent_block, completed by an earlier run"
+        return 0
+    fi
+    echo "==> Block $redcent_block: ${1:-}"
+    return 1
+}
+redcent_done() {
+    if [ "$1" -ne 0 ]; then
+        redcent_ok=0
+    fi
+    if [ "$redcent_ok" -eq 1 ] && [ -n "$redcent_checkpoint" ]; then
+        echo "$redcent_block" > "$redcent_checkpoint"
+    fi
+}
+# Runs verification command $2 of item $1, also for items whose blocks were skipped.
+redcent_verified=0
+redcent_unverified=0
+redcent_verify() {
+    if bash -c "$2" > /dev/null 2>&1; then
+        redcent_verified=$((redcent_verified + 1))
+        echo "VERIFIED: $1: $2"
+    else
+        redcent_unverified=$((redcent_unverified + 1))
+        echo "NOT VERIFIED: $1: $2"
+    fi
+}
+
+"#;
+
+/// Helpers of scripts with a transcript: all output is also appended to a log file under
+/// `/var/log` (the home directory when that is not writable), each phase starts with a timestamp,
+/// and a summary of the succeeded and failed command blocks ends the run, also when `set -e`
+/// stops it early.
+const TRANSCRIPT_PREAMBLE: &str = r#"# Transcript: the output is also appended to the log file named at the start and at the end.
+redcent_log=/var/log/redcent-setup-$(date +%Y%m%d-%H%M%S).log
+if ! (umask 077 && : >> "$redcent_log") 2> /dev/null; then
+    redcent_log=$HOME/redcent-setup-$(date +%Y%m%d-%H%M%S).log
+    (umask 077 && : >> "$redcent_log")
+fi
+exec > >(tee -a "$redcent_log") 2>&1
+echo "Transcript: $redcent_log"
+redcent_succeeded=0
+redcent_failed_blocks=()
+redcent_current=
+redcent_section() {
+    echo "[$(date '+%F %T')] ===== $1 ====="
+}
+redcent_result() {
+    if [ "$1" -eq 0 ]; then
+        redcent_succeeded=$((redcent_succeeded + 1))
+    else
+        redcent_failed_blocks+=("$redcent_current")
+    fi
+    redcent_current=
+}
+redcent_summary() {
+    # A block still marked as running stopped the script.
+    if [ -n "$redcent_current" ]; then
+        redcent_failed_blocks+=("$redcent_current")
+        redcent_current=
+    fi
+    echo "[$(date '+%F %T')] Summary: $redcent_succeeded block(s) succeeded, ${#redcent_failed_blocks[@]} failed"
+    if [ ${#redcent_failed_blocks[@]} -gt 0 ]; then
+        printf '  failed: %s\n' "${redcent_failed_blocks[@]}"
+    fi
+    echo "Transcript saved to $redcent_log"
+}
+trap redcent_summary EXIT
+
+"#;
+
+/// Generates a bash script from the selected items in `tree`, grouped by phase with a section
+/// comment opening each one, requirements first.
+/// `target` names the distribution in the header, `backend` renders package commands,
+/// `errors` selects the failure handling, and `reboot` appends a final reboot.
+pub fn generate_script(tree: &Rc<RefCell<MenuNode>>, target: &str, backend: &dyn PackageBackend, errors: ErrorMode, reboot: bool) -> String {
+    render_script(tree, target, backend, errors, reboot, false, false)
+}
+
+/// Like [`generate_script`], for scripts saved and run later without the TUI: the script keeps a
+/// transcript of its output in `/var/log/redcent-setup-<time>.log`, timestamps each phase, and
+/// ends with a summary of the command blocks that succeeded and failed.
+pub fn generate_logged_script(tree: &Rc<RefCell<MenuNode>>, target: &str, backend: &dyn PackageBackend, errors: ErrorMode, reboot: bool) -> String {
+    render_script(tree, target, backend, errors, reboot, false, true)
+}
+
+/// Like [`generate_script`], but every command block records a checkpoint when it completes, so
+/// an interrupted or failed run can be resumed: run it as `bash script.sh <checkpoint file>
+/// <blocks to skip>`. Blocks after a failed one do not advance the checkpoint. Each item's
+/// verification commands run after its commands and are summarized at the end; the script exits
+/// with status 1 before any final reboot if one of them failed.
+pub fn generate_resumable_script(tree: &Rc<RefCell<MenuNode>>, target: &str, backend: &dyn PackageBackend, errors: ErrorMode, reboot: bool) -> String {
+    render_script(tree, target, backend, errors, reboot, true, false)
+}
+
+/// The number of checkpointed blocks in a script from [`generate_resumable_script`]; zero for other scripts.
+pub fn checkpoint_blocks(script: &str) -> usize {
+    script.lines().filter(|line| line.starts_with(CHECKPOINT_GUARD) && line.ends_with("; then")).count()
+}
+
+fn render_script(
+    tree: &Rc<RefCell<MenuNode>>, target: &str, backend: &dyn PackageBackend, errors: ErrorMode, reboot: bool, checkpoints: bool, transcript: bool,
+) -> String {
+    let mut command_text = String::new();
+    command_text.push_str("#!/bin/bash\n");
+    command_text.push_str(&format!("# Commands generated for {} by RHEL/CentOS TUI Manager\n", target));
+    command_text.push_str("# Save this script and run it with sudo: sudo bash ./script.sh\n#\n");
+    command_text.push_str(&script_header(tree, target, backend));
+    command_text.push('\n');
+    match errors {
+        ErrorMode::Continue => {}
+        ErrorMode::Strict => command_text.push_str("set -euo pipefail\n\n"),
+        ErrorMode::Report => command_text.push_str("redcent_failed=()\n\n"),
+    }
+    if transcript {
+        command_text.push_str(TRANSCRIPT_PREAMBLE);
+    }
+    if checkpoints {
+        command_text.push_str(CHECKPOINT_PREAMBLE);
+    }
+
+    let scripts = script_order(tree, backend);
+
+    if scripts.is_empty() {
+         command_text.push_str("\n# No options selected.\n");
+    } else {
+        let mut needs_reboot = false;
+        let mut verifies = false;
+        let mut current_phase = None;
+        for (item, phase) in scripts {
+            let Some(script) = item.borrow().script(backend) else { continue };
+            if current_phase != Some(phase) {
+                command_text.push_str(&format!("# ===== {} =====\n\n", phase.label()));
+                if transcript {
+                    command_text.push_str(&format!("redcent_section '{}'\n", phase.label()));
+                }
+                current_phase = Some(phase);
+            }
+            command_text.push_str(&format!("# {}: {}\n", script.id, script.description));
+            match errors {
+                ErrorMode::Report => {
+                    for (n, cmd) in script.commands.iter().enumerate() {
+                        if cmd.is_comment() {
+                            command_text.push_str(&cmd.code);
+                            command_text.push('\n');
+                        } else {
+                            let label = format!("{} (block {})", script.id, n + 1);
+                            let (failed, succeeded) = match (checkpoints, transcript) {
+                                (true, _) => ("    redcent_done 1\n", "else\n    redcent_done 0\n"),
+                                (false, true) => ("    redcent_result 1\n", "else\n    redcent_result 0\n"),
+                                (false, false) => ("", ""),
+                            };
+                            let wrapped = format!(
+                                "if ! {{\n{}\n}}; then\n    echo 'FAILED: {}' >&2\n    redcent_failed+=('{}')\n{}{}fi\n",
+                                cmd.code, label, label, failed, succeeded,
+                            );
+                            let wrapped = if transcript { format!("redcent_current='{}'\n{}", label, wrapped) } else { wrapped };
+                            command_text.push_str(&checkpointed(&wrapped, script.id, checkpoints, false));
+                        }
+                    }
+                }
+                _ if checkpoints => {
+                    for cmd in &script.commands {
+                        let code = format!("{}\n", cmd.code);
+                        command_text.push_str(&if cmd.is_comment() { code } else { checkpointed(&code, script.id, true, true) });
+                    }
+                }
+                _ if transcript => {
+                    for (n, cmd) in script.commands.iter().enumerate() {
+                        if cmd.is_comment() {
+                            command_text.push_str(&format!("{}\n", cmd.code));
+                        } else {
+                            command_text.push_str(&format!("redcent_current='{} (block {})'\n{}\nredcent_result $?\n", script.id, n + 1, cmd.code));
+                        }
+                    }
+                    command_text.push('\n');
+                }
+                _ => {
+                    command_text.push_str(&script.render());
+                    command_text.push('\n');
+                }
+            }
+            if checkpoints {
+                for check in &script.verify {
+                    command_text.push_str(&format!("redcent_verify '{}' {}\n", script.id, shell_quote(check)));
+                }
+                verifies |= !script.verify.is_empty();
+            }
+            needs_reboot |= script.requires_reboot;
+        }
+        if verifies {
+            command_text.push_str("\necho \"Verification: $redcent_verified passed, $redcent_unverified failed\"\n");
+        }
+        if needs_reboot && !reboot {
+            command_text.push_str("\n# Some of the selected changes only take full effect after a reboot.\n");
+        }
+    }
+
+    if errors == ErrorMode::Report {
+        command_text.push_str("\nif [ ${#redcent_failed[@]} -gt 0 ]; then\n");
+        command_text.push_str("    echo \"${#redcent_failed[@]} block(s) failed:\" >&2\n");
+        command_text.push_str("    printf '  %s\\n' \"${redcent_failed[@]}\" >&2\n");
+        command_text.push_str("    exit 1\n");
+        command_text.push_str("fi\n");
+        command_text.push_str("echo 'All blocks succeeded.'\n");
+    }
+    if checkpoints {
+        command_text.push_str("\nif [ \"$redcent_unverified\" -gt 0 ]; then\n");
+        command_text.push_str("    echo \"$redcent_unverified verification(s) failed; see NOT VERIFIED above.\" >&2\n");
+        command_text.push_str("    exit 1\n");
+        command_text.push_str("fi\n");
+    }
+
+    if reboot {
+        if transcript {
+            // The summary would otherwise race the reboot.
+            command_text.push_str("\ntrap - EXIT\nredcent_summary\n");
+        }
+        command_text.push_str("\necho 'Installation complete. Rebooting now...'\n");
+        command_text.push_str("sudo reboot\n");
+    }
+
+    command_text
+}
+
+/// The `# redcent-<key>: <value>` comment lines opening every generated script, so a copy found on
+/// a server can be traced back to the selection it came from: the generator's version, the target,
+/// the generation time in UTC, who generated it on which OS, the selected item IDs in script
+/// order and a hash of their parameter values.
+fn script_header(tree: &Rc<RefCell<MenuNode>>, target: &str, backend: &dyn PackageBackend) -> String {
+    let author = Author::get();
+    let mut header = format!("# redcent-version: {}\n", env!("CARGO_PKG_VERSION"));
+    header.push_str(&format!("# redcent-target: {} ({})\n", target, backend.name()));
+    header.push_str(&format!("# redcent-generated: {}\n", iso_timestamp_now()));
+    header.push_str(&format!("# redcent-author: {}\n", author.login));
+    header.push_str(&format!("# redcent-host-os: {}\n", author.host_os));
+    // Long selections continue on further `redcent-items` lines.
+    let mut line = String::new();
+    for (item, _) in script_order(tree, backend) {
+        let id = item.borrow().id().to_string();
+        if !line.is_empty() && line.len() + id.len() > 80 {
+            header.push_str(&format!("# redcent-items: {}\n", line));
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&id);
+    }
+    header.push_str(&format!("# redcent-items: {}\n", if line.is_empty() { "(none)" } else { &line }));
+    header.push_str(&format!("# redcent-params: fnv1a64:{:016x}\n", params_hash(tree)));
+    header
+}
+
+/// FNV-1a over the selected items' parameter values in menu order, so two scripts from the same
+/// selection and values carry the same hash whichever build of the tool generated them.
+fn params_hash(tree: &Rc<RefCell<MenuNode>>) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for item in ordered_selected_items(tree) {
+        if let MenuNode::Item { id, params, .. } = &*item.borrow() {
+            for param in params {
+                for byte in format!("{}\0{}\0{}\n", id, param.key, param.value).bytes() {
+                    hash ^= u64::from(byte);
+                    hash = hash.wrapping_mul(0x0100_0000_01b3);
+                }
+            }
+        }
+    }
+    hash
+}
+
+/// The user and machine generating scripts, read once per process.
+struct Author {
+    /// `user@host`; the invoking user when run through `sudo`.
+    login: String,
+    /// `PRETTY_NAME` from `/etc/os-release`.
+    host_os: String,
+}
+
+impl Author {
+    fn get() -> &'static Author {
+        static AUTHOR: OnceLock<Author> = OnceLock::new();
+        AUTHOR.get_or_init(|| {
+            let user = ["SUDO_USER", "USER", "LOGNAME"].iter().find_map(|var| env::var(var).ok().filter(|user| !user.is_empty()))
+                .or_else(passwd_user)
+                .unwrap_or_else(|| "unknown".to_string());
+            let host = fs::read_to_string("/proc/sys/kernel/hostname").or_else(|_| fs::read_to_string("/etc/hostname"))
+                .map(|host| host.trim().to_string())
+                .unwrap_or_else(|_| "localhost".to_string());
+            let host_os = fs::read_to_string("/etc/os-release").ok()
+                .and_then(|text| text.lines().find_map(|line| line.strip_prefix("PRETTY_NAME=").map(|name| name.trim_matches('"').to_string())))
+                .unwrap_or_else(|| "unknown".to_string());
+            Author { login: format!("{}@{}", user, host), host_os }
+        })
+    }
+}
+
+/// The name of this process's user in `/etc/passwd`, for sessions that do not set `$USER`.
+fn passwd_user() -> Option<String> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let uid = status.lines().find_map(|line| line.strip_prefix("Uid:"))?.split_whitespace().next()?.to_string();
+    fs::read_to_string("/etc/passwd").ok()?.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split(':').collect();
+        (fields.get(2) == Some(&uid.as_str())).then(|| fields[0].to_string())
+    })
+}
+
+/// Current UTC time as `YYYY-MM-DDTHH:MM:SSZ`.
+fn iso_timestamp_now() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0);
+    let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+    let rem = secs.rem_euclid(86400);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+/// Converts days since the Unix epoch to a (year, month, day) civil date.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Guards a command `block` (ending in a newline) of item `id` so resumed runs skip it, recording
+/// the checkpoint after it when `record` is set; returns it unchanged without `checkpoints`.
+fn checkpointed(block: &str, id: &str, checkpoints: bool, record: bool) -> String {
+    match (checkpoints, record) {
+        (false, _) => block.to_string(),
+        (true, false) => format!("{} '{}'; then\n{}fi\n", CHECKPOINT_GUARD, id, block),
+        (true, true) => format!("{} '{}'; then\n{}redcent_done $?\nfi\n", CHECKPOINT_GUARD, id, block),
+    }
+}
+
+/// Generates a Markdown runbook from the selected items in `tree`, in script order: one section per
+/// item with its description, its commands in fenced code blocks and the steps that verify it took effect.
+pub fn generate_runbook(tree: &Rc<RefCell<MenuNode>>, target: &str, backend: &dyn PackageBackend) -> String {
+    let mut doc = format!("# {} Setup Runbook\n\n", target);
+    doc.push_str("Generated by RHEL/CentOS TUI Manager. Run each step in order with administrator rights, then run its verification commands.\n\n");
+
+    let items = script_order(tree, backend);
+    if items.is_empty() {
+        doc.push_str("No options selected.\n");
+        return doc;
+    }
+
+    let mut needs_reboot = false;
+    for (n, (item, _)) in items.iter().enumerate() {
+        let item = item.borrow();
+        let Some(script) = item.script(backend) else { continue };
+        doc.push_str(&format!("## {}. {} (`{}`)\n\n{}\n\n", n + 1, item.name(), script.id, script.description));
+        let notes: Vec<&str> = [
+            (script.requires_network, "downloads packages or other content"),
+            (script.requires_reboot, "takes full effect after a reboot"),
+            (script.idempotent, "safe to run again"),
+        ].into_iter().filter(|(set, _)| *set).map(|(_, note)| note).collect();
+        if !notes.is_empty() {
+            doc.push_str(&format!("_Notes: {}._\n\n", notes.join("; ")));
+        }
+
+        doc.push_str("### Commands\n\n");
+        doc.push_str(&fenced(&script.render()));
+        doc.push_str("### Verification\n\n");
+        if script.verify.is_empty() {
+            doc.push_str("No automated check; confirm the change manually.\n\n");
+        } else {
+            doc.push_str(&fenced(&script.verify.join("\n")));
+        }
+        needs_reboot |= script.requires_reboot;
+    }
+
+    if needs_reboot {
+        doc.push_str("## Reboot\n\nSome of the steps above only take full effect after a reboot:\n\n");
+        doc.push_str(&fenced("sudo reboot"));
+    }
+    doc
+}
+
+/// Quotes `text` as a single shell word.
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// Wraps `code` in a bash code fence, lengthening the fence if the code itself contains one.
+fn fenced(code: &str) -> String {
+    let mut fence = "```".to_string();
+    while code.contains(&fence) {
+        fence.push('`');
+    }
+    format!("{}bash\n{}\n{}\n\n", fence, code.trim_end(), fence)
+}
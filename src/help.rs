@@ -0,0 +1,433 @@
+// src/help.rs
+
+use crossterm::event::KeyCode;
+use crate::theme::Theme;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+/// The key bindings of one screen or pane, as (keys, action) pairs.
+pub struct Section {
+    pub title: &'static str,
+    pub bindings: &'static [(&'static str, &'static str)],
+}
+
+pub static MAIN_MENU: Section = Section {
+    title: "Main Menu",
+    bindings: &[
+        ("↑ ↓", "Move the highlight"),
+        ("→ Enter", "Open a menu, or select/deselect an item (opening its wizard or parameters first)"),
+        ("← Backspace", "Back to the parent menu"),
+        ("g 1-9", "Jump to a numbered level of the path in the menu title (or click it)"),
+        ("a / A", "Select / deselect everything under the highlighted or current menu"),
+        ("i", "Generate the script"),
+        ("r", "Generate the script with a reboot at the end"),
+        ("u", "Fill in the first missing parameter"),
+        ("t", "Switch the target distribution"),
+        ("F", "List of every item"),
+        ("O", "Review and reorder the selected items in script order"),
+        ("T", "List of every item, filtered by tag"),
+        ("*", "Add the highlighted item to Favorites, or remove it"),
+        ("Ctrl-P", "Command palette"),
+        ("/ PgUp PgDn", "Search or scroll the script preview"),
+        ("D", "Show what changed in the script since it was last viewed, in place of the preview"),
+        ("p", "Preflight checks"),
+        ("o", "Settings: privilege escalation, error handling, transcript, offline mode, color scheme"),
+        ("P", "Proxy for package downloads in generated scripts"),
+        ("h", "Run history"),
+        ("L", "Disk encryption status: LUKS volumes, /etc/crypttab, enrolled tokens"),
+        ("B", "Link aggregation status: bonds, bridges and their ports"),
+        ("W", "Firewall: firewalld zones with their services and ports"),
+        ("Tab", "Focus Selected Components, then the preview"),
+        ("q", "Quit"),
+    ],
+};
+
+pub static SELECTED_PANEL: Section = Section {
+    title: "Selected Components",
+    bindings: &[
+        ("↑ ↓", "Move the highlight"),
+        ("Enter", "Edit the item's parameters in its wizard or form"),
+        ("Tab", "Focus the preview"),
+        ("Esc", "Back to the menu"),
+        ("q", "Quit"),
+    ],
+};
+
+pub static SCRIPT_VIEWER: Section = Section {
+    title: "Script Viewer",
+    bindings: &[
+        ("↑ ↓ ← →", "Scroll"),
+        ("PgUp PgDn", "Scroll a page"),
+        ("Home End", "Jump to the top / bottom"),
+        ("/", "Search; Enter confirms, Esc cancels"),
+        ("n / N", "Next / previous match"),
+    ],
+};
+
+pub static PREVIEW: Section = Section {
+    title: "Preview Pane",
+    bindings: &[
+        ("↑ ↓ PgUp PgDn", "Move between section headings and lines"),
+        ("Home End", "Jump to the top / bottom"),
+        ("Enter", "Expand or collapse the highlighted section"),
+        ("→ / ←", "Expand / collapse the highlighted section"),
+        ("+ / -", "Expand / collapse every section"),
+        ("/", "Search; sections with a match are expanded"),
+        ("n / N", "Next / previous match, or in the diff pane the next / previous change"),
+        ("Tab Esc", "Back to the menu"),
+    ],
+};
+
+pub static SEARCH: Section = Section {
+    title: "Search",
+    bindings: &[
+        ("Type", "Search text"),
+        ("Enter", "Jump to the first match"),
+        ("Esc", "Cancel the search"),
+    ],
+};
+
+pub static FINISHED: Section = Section {
+    title: "Generated Script",
+    bindings: &[
+        ("s", "Save the script"),
+        ("m", "Save a Markdown runbook"),
+        ("c", "Copy the script to the clipboard (OSC 52, also over SSH; wl-copy or xclip locally)"),
+        ("e", "Export for Terraform, cloud-init, Packer or Vagrant"),
+        ("j", "Save the selection as JSON: item IDs, resolved commands, target and reboot policy"),
+        ("d", "Compare with a saved script"),
+        ("l", "Jump to the next bash -n / shellcheck finding"),
+        ("p", "Preflight checks"),
+        ("r", "Run the script in a terminal pane (asks for confirmation); only for targets using this host's package manager"),
+        ("R", "Run the script on remote hosts over SSH (back to the hosts table while they run)"),
+        ("Esc Backspace", "Back"),
+        ("q", "Quit"),
+    ],
+};
+
+pub static SAVING: Section = Section {
+    title: "Save Dialog",
+    bindings: &[
+        ("Type", "File name; a bare name gets .sh (.md for runbooks, .json for selections)"),
+        ("Tab", "Complete the name from the directory listing"),
+        ("↑ ↓", "Browse the directory; a highlighted file's name is taken"),
+        ("Enter", "Open the highlighted directory, or save (asking before overwriting)"),
+        ("Backspace", "With an empty name: go up a directory"),
+        ("Shift+Tab", "Next export format"),
+        ("Esc", "Cancel; n / Esc also declines an overwrite"),
+    ],
+};
+
+pub static PATH_INPUT: Section = Section {
+    title: "Compare with Saved Script",
+    bindings: &[
+        ("Type", "Path of the saved script"),
+        ("Enter", "Compare"),
+        ("Esc", "Cancel"),
+    ],
+};
+
+pub static DIFF: Section = Section {
+    title: "Script Diff",
+    bindings: &[
+        ("↑ ↓", "Scroll"),
+        ("PgUp PgDn", "Scroll a page"),
+        ("Home End", "Jump to the top / bottom"),
+        ("n / N", "Next / previous change"),
+        ("Esc d", "Back"),
+        ("q", "Quit"),
+    ],
+};
+
+pub static HISTORY: Section = Section {
+    title: "Run History",
+    bindings: &[
+        ("↑ ↓", "Move the highlight"),
+        ("Enter", "View the script and its output"),
+        ("r", "Run the script again"),
+        ("c", "Resume a failed or interrupted run from its failed block"),
+        ("Esc h", "Back (from the viewer: back to the list)"),
+        ("q", "Quit"),
+    ],
+};
+
+pub static INDEX: Section = Section {
+    title: "All Items",
+    bindings: &[
+        ("↑ ↓", "Move the highlight"),
+        ("Enter Space", "Select / deselect the item"),
+        ("/", "Filter by tag; prefix a tag with ! to exclude it"),
+        ("s", "Sort by name or category"),
+        ("Esc F", "Back"),
+        ("q", "Quit"),
+    ],
+};
+
+pub static ORDER: Section = Section {
+    title: "Script Order",
+    bindings: &[
+        ("↑ ↓", "Move the highlight"),
+        ("Shift+↑ Shift+↓ K J", "Move the item earlier / later within its phase; requirements always stay first"),
+        ("R", "Back to menu order"),
+        ("Enter i", "Generate the script"),
+        ("Esc O", "Back"),
+        ("q", "Quit"),
+    ],
+};
+
+pub static LUKS_STATUS: Section = Section {
+    title: "Disk Encryption Status",
+    bindings: &[
+        ("↑ ↓ PgUp PgDn", "Scroll"),
+        ("r", "Read the status again"),
+        ("Esc L", "Back"),
+        ("q", "Quit"),
+    ],
+};
+
+pub static FIREWALL: Section = Section {
+    title: "Firewall",
+    bindings: &[
+        ("↑ ↓", "Highlight a zone to list all its settings"),
+        ("r", "Read the zones again"),
+        ("Esc W", "Back"),
+        ("q", "Quit"),
+    ],
+};
+
+pub static LINK_STATUS: Section = Section {
+    title: "Link Aggregation Status",
+    bindings: &[
+        ("↑ ↓ PgUp PgDn", "Scroll"),
+        ("r", "Read the status again"),
+        ("Esc B", "Back"),
+        ("q", "Quit"),
+    ],
+};
+
+pub static REMOTE: Section = Section {
+    title: "Remote Run",
+    bindings: &[
+        ("↑ ↓", "Move the highlight"),
+        ("Enter", "Show the host's output; Esc or Enter returns to the table"),
+        ("↑ ↓ PgUp PgDn", "In the output: scroll back and forward"),
+        ("Esc", "Back; the hosts keep running and R on the script screen returns here"),
+        ("q", "Quit, once every host has finished"),
+    ],
+};
+
+pub static PREFLIGHT: Section = Section {
+    title: "Preflight",
+    bindings: &[
+        ("r", "Run the checks again"),
+        ("m", "Install from a local mirror when mirrors are unreachable"),
+        ("Esc p", "Back"),
+        ("q", "Quit"),
+    ],
+};
+
+pub static RUN_LOCKED: Section = Section {
+    title: "Run Locked",
+    bindings: &[
+        ("r", "Retry taking the lock"),
+        ("t", "Take over a stale lock and run"),
+        ("Esc", "Back"),
+        ("q", "Quit"),
+    ],
+};
+
+pub static FORM: Section = Section {
+    title: "Parameters and Wizards",
+    bindings: &[
+        ("Type", "Edit the focused field"),
+        ("↑ ↓ Tab", "Move between fields, or through a list of choices"),
+        ("← →", "Change a choice field, or move through multiple choices"),
+        ("Space", "Tick / untick the highlighted choice"),
+        ("Enter", "Check the step's fields, then confirm / continue to the next step"),
+        ("Esc", "Cancel / back to the previous step"),
+    ],
+};
+
+pub static CPU_PICKER: Section = Section {
+    title: "CPU Isolation Cores",
+    bindings: &[
+        ("← → ↑ ↓", "Move between cores; each NUMA node starts a new row"),
+        ("Space", "Isolate / release the core with its sibling threads; CPU 0 stays for housekeeping"),
+        ("Enter", "Review the commands for the isolated CPUs"),
+        ("Esc", "Cancel"),
+    ],
+};
+
+pub static SETTINGS: Section = Section {
+    title: "Settings",
+    bindings: &[
+        ("Tab", "Next section"),
+        ("↑ ↓ ← → Space", "Change the value"),
+        ("Type Backspace", "Edit the local repository base URL for offline mode"),
+        ("Enter", "Apply"),
+        ("Esc", "Cancel"),
+    ],
+};
+
+pub static PALETTE: Section = Section {
+    title: "Command Palette",
+    bindings: &[
+        ("select <id-glob>", "Select matching items (also: s)"),
+        ("deselect <id-glob>", "Deselect matching items"),
+        ("goto <id>", "Jump to a menu or item (also: g)"),
+        ("Enter", "Run"),
+        ("Esc", "Cancel"),
+    ],
+};
+
+pub static RESOLVE: Section = Section {
+    title: "Resolve Problems",
+    bindings: &[
+        ("↑↓", "Pick a problem"),
+        ("←→ Tab", "Pick a fix"),
+        ("Enter", "Apply the fix; the script is generated once no problems are left"),
+        ("Esc", "Back to the menu"),
+    ],
+};
+
+pub static CONFIRM: Section = Section {
+    title: "Confirm Run",
+    bindings: &[
+        ("yes Enter", "Run the script, unless the selection does not fit on the disk"),
+        ("Esc", "Back"),
+    ],
+};
+
+pub static TERMINAL: Section = Section {
+    title: "Script Terminal",
+    bindings: &[
+        ("Type", "While the script runs, keys go to it, e.g. to answer a password prompt"),
+        ("Ctrl-C", "Interrupt the script"),
+        ("Shift+PgUp Shift+PgDn", "Scroll back through the output"),
+        ("F2", "While the script has been quiet for 30 seconds: show or hide its last output lines"),
+        ("Enter Esc", "Once the script has finished: back to the menu"),
+        ("h", "Once finished: run history"),
+        ("t", "Once failed: troubleshoot the failed steps"),
+        ("c", "Once failed: resume from the failed block"),
+        ("q", "Once finished: quit"),
+    ],
+};
+
+pub static TROUBLESHOOT: Section = Section {
+    title: "Troubleshoot",
+    bindings: &[
+        ("↑↓", "Pick a check: re-run a failed step with tracing, a service's journal, SELinux denials"),
+        ("Enter", "Run the check; its output shows below"),
+        ("Type", "While a check runs, keys go to it, e.g. to answer a password prompt"),
+        ("Shift+PgUp Shift+PgDn", "Scroll back through the check's output"),
+        ("Esc q", "Back to the failed run"),
+    ],
+};
+
+pub static RESTORE_DRAFT: Section = Section {
+    title: "Restore Draft",
+    bindings: &[
+        ("y Enter", "Restore the selection of the last session"),
+        ("n Esc", "Discard it"),
+    ],
+};
+
+pub static RESTORE_SESSION: Section = Section {
+    title: "Restore Session",
+    bindings: &[
+        ("y Enter", "Restore the selection, open menus and cursor of the last session"),
+        ("n Esc", "Start with an empty selection"),
+    ],
+};
+
+pub static RESUME_RUN: Section = Section {
+    title: "Resume Last Run",
+    bindings: &[
+        ("y Enter", "Run the script again, skipping the blocks it completed"),
+        ("n Esc", "Not now; c in the run history resumes it later"),
+    ],
+};
+
+pub static HELP: Section = Section {
+    title: "Help",
+    bindings: &[
+        ("?", "Open this help where no text is being typed"),
+        ("F1", "Open this help anywhere"),
+        ("↑ ↓ PgUp PgDn Home End", "Scroll"),
+        ("Esc ? q", "Close"),
+    ],
+};
+
+/// Full-screen, scrollable key reference for the screen it was opened from.
+pub struct HelpOverlay {
+    title: String,
+    sections: Vec<&'static Section>,
+    scroll: usize,
+    viewport_height: usize,
+}
+
+impl HelpOverlay {
+    /// `sections` start with the bindings active right now, followed by the rest of the screen's.
+    pub fn new(title: impl Into<String>, sections: Vec<&'static Section>) -> HelpOverlay {
+        HelpOverlay { title: title.into(), sections, scroll: 0, viewport_height: 0 }
+    }
+
+    fn lines(&self, theme: &Theme) -> Vec<Line<'static>> {
+        let width = self.sections.iter().flat_map(|section| section.bindings).map(|(keys, _)| keys.chars().count()).max().unwrap_or(0);
+        let mut lines = Vec::new();
+        for section in &self.sections {
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::from(Span::styled(section.title, theme.title)));
+            for (keys, action) in section.bindings {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {:<width$}  ", keys, width = width), theme.focused),
+                    Span::raw(*action),
+                ]));
+            }
+        }
+        lines
+    }
+
+    fn max_scroll(&self, len: usize) -> usize {
+        len.saturating_sub(self.viewport_height.max(1))
+    }
+
+    /// Scrolls; returns `false` when the overlay should close.
+    pub fn handle_key(&mut self, code: KeyCode, theme: &Theme) -> bool {
+        let max = self.max_scroll(self.lines(theme).len());
+        let page = self.viewport_height.max(1);
+        match code {
+            KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') | KeyCode::F(1) => return false,
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = (self.scroll + 1).min(max),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(page),
+            KeyCode::PageDown => self.scroll = (self.scroll + page).min(max),
+            KeyCode::Home => self.scroll = 0,
+            KeyCode::End => self.scroll = max,
+            _ => {}
+        }
+        true
+    }
+
+    pub fn draw(&mut self, f: &mut Frame, theme: &Theme) {
+        let chunks = Layout::default().direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref()).split(f.size());
+        let lines = self.lines(theme);
+        self.viewport_height = chunks[0].height.saturating_sub(2) as usize;
+        self.scroll = self.scroll.min(self.max_scroll(lines.len()));
+        let body = Paragraph::new(lines)
+            .scroll((self.scroll as u16, 0))
+            .block(Block::default().borders(Borders::ALL).title(format!("Help: {}", self.title)));
+        f.render_widget(body, chunks[0]);
+        let footer = Paragraph::new("Scroll [↑↓ PgUp/PgDn Home/End] | [Esc/?/q] Close")
+            .style(theme.footer)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(footer, chunks[1]);
+    }
+}
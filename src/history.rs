@@ -0,0 +1,159 @@
+// src/history.rs
+
+use redcent_tui::civil_from_days;
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const SCRIPT_FILE: &str = "script.sh";
+const OUTPUT_FILE: &str = "output.log";
+const EXIT_CODE_FILE: &str = "exit_code";
+const CHECKPOINT_FILE: &str = "checkpoint";
+
+/// A generated or executed script stored under the runs directory.
+#[derive(Clone)]
+pub struct RunRecord {
+    pub dir: PathBuf,
+    /// Directory name, a UTC timestamp like `20261016-142501`.
+    pub name: String,
+    /// Exit code of the run, or `None` if the script was only generated (or is still running).
+    pub exit_code: Option<i32>,
+    pub has_output: bool,
+}
+
+impl RunRecord {
+    /// One-line description for the history list.
+    pub fn summary(&self) -> String {
+        let status = match (self.exit_code, self.has_output) {
+            (Some(0), _) => "executed, succeeded".to_string(),
+            (Some(code), _) => format!("executed, failed (exit {})", code),
+            (None, true) => "executed, no exit code recorded".to_string(),
+            (None, false) => "generated".to_string(),
+        };
+        format!("{}  {}", display_timestamp(&self.name), status)
+    }
+
+    pub fn script(&self) -> io::Result<String> {
+        fs::read_to_string(self.dir.join(SCRIPT_FILE))
+    }
+
+    pub fn output(&self) -> Option<String> {
+        fs::read_to_string(self.dir.join(OUTPUT_FILE)).ok()
+    }
+
+    /// Path the executor should tee the script's output into.
+    pub fn output_path(&self) -> PathBuf {
+        self.dir.join(OUTPUT_FILE)
+    }
+
+    /// File a resumable script records its last completed block in.
+    pub fn checkpoint_path(&self) -> PathBuf {
+        self.dir.join(CHECKPOINT_FILE)
+    }
+
+    /// Number of blocks the run completed, as recorded by a resumable script.
+    pub fn checkpoint(&self) -> usize {
+        fs::read_to_string(self.checkpoint_path()).ok().and_then(|n| n.trim().parse().ok()).unwrap_or(0)
+    }
+
+    /// How far an executed resumable run got, if it failed or was interrupted before its last block.
+    pub fn resume_point(&self) -> Option<ResumePoint> {
+        if !self.has_output || self.exit_code == Some(0) {
+            return None;
+        }
+        let total = redcent_tui::checkpoint_blocks(&self.script().ok()?);
+        let completed = self.checkpoint();
+        (completed < total).then_some(ResumePoint { completed, total })
+    }
+}
+
+/// Progress of an unfinished resumable run.
+pub struct ResumePoint {
+    /// Blocks completed, which a resumed run skips.
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// `$XDG_STATE_HOME/redcent-tui`, falling back to `~/.local/state/redcent-tui`.
+pub fn state_dir() -> Option<PathBuf> {
+    let state_home = env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/state")))?;
+    Some(state_home.join("redcent-tui"))
+}
+
+/// The `runs` directory under [`state_dir`].
+pub fn runs_dir() -> Option<PathBuf> {
+    Some(state_dir()?.join("runs"))
+}
+
+/// Stores `script` in a new timestamped run directory and returns its record.
+pub fn record_script(script: &str) -> io::Result<RunRecord> {
+    let base = runs_dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "cannot determine state directory"))?;
+    fs::create_dir_all(&base)?;
+    let stamp = timestamp_now();
+    let mut name = stamp.clone();
+    let mut suffix = 1;
+    while base.join(&name).exists() {
+        name = format!("{}-{}", stamp, suffix);
+        suffix += 1;
+    }
+    let dir = base.join(&name);
+    fs::create_dir(&dir)?;
+    fs::write(dir.join(SCRIPT_FILE), script)?;
+    Ok(RunRecord { dir, name, exit_code: None, has_output: false })
+}
+
+/// Records the exit code of an executed run.
+pub fn record_exit_code(record: &RunRecord, code: i32) -> io::Result<()> {
+    fs::write(record.dir.join(EXIT_CODE_FILE), format!("{}\n", code))
+}
+
+/// Records that a resumed run starts with `completed` blocks already done, so the checkpoint is
+/// right even if it stops before finishing another one.
+pub fn record_checkpoint(record: &RunRecord, completed: usize) -> io::Result<()> {
+    fs::write(record.checkpoint_path(), format!("{}\n", completed))
+}
+
+/// The most recently executed run, with its resume point, if it can be resumed.
+pub fn last_unfinished_run() -> Option<(RunRecord, ResumePoint)> {
+    let run = list_runs().into_iter().find(|run| run.has_output)?;
+    let point = run.resume_point()?;
+    Some((run, point))
+}
+
+/// Lists recorded runs, newest first.
+pub fn list_runs() -> Vec<RunRecord> {
+    let Some(base) = runs_dir() else { return Vec::new() };
+    let Ok(entries) = fs::read_dir(&base) else { return Vec::new() };
+    let mut runs: Vec<RunRecord> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join(SCRIPT_FILE).is_file())
+        .map(|entry| {
+            let dir = entry.path();
+            let exit_code = fs::read_to_string(dir.join(EXIT_CODE_FILE)).ok().and_then(|c| c.trim().parse().ok());
+            let has_output = dir.join(OUTPUT_FILE).is_file();
+            RunRecord { name: entry.file_name().to_string_lossy().into_owned(), dir, exit_code, has_output }
+        })
+        .collect();
+    runs.sort_by(|a, b| b.name.cmp(&a.name));
+    runs
+}
+
+/// Current UTC time formatted as `YYYYMMDD-HHMMSS`.
+pub fn timestamp_now() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0);
+    let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+    let rem = secs.rem_euclid(86400);
+    format!("{:04}{:02}{:02}-{:02}{:02}{:02}", year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+/// Turns `YYYYMMDD-HHMMSS[-n]` into `YYYY-MM-DD HH:MM:SS UTC`, leaving other names untouched.
+pub fn display_timestamp(name: &str) -> String {
+    if name.len() < 15 || !name.is_char_boundary(15) || name.as_bytes()[8] != b'-' {
+        return name.to_string();
+    }
+    format!("{}-{}-{} {}:{}:{} UTC", &name[0..4], &name[4..6], &name[6..8], &name[9..11], &name[11..13], &name[13..15])
+}
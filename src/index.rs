@@ -0,0 +1,183 @@
+// src/index.rs
+
+use crossterm::event::KeyCode;
+use redcent_tui::{item_paths, MenuNode, PackageBackend};
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use std::{cell::RefCell, rc::Rc};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum SortKey {
+    Name,
+    Category,
+}
+
+#[derive(Clone)]
+pub struct IndexEntry {
+    /// Category path, e.g. `Main Menu > Repositories > Add Repositories`.
+    pub path: String,
+    pub item: Rc<RefCell<MenuNode>>,
+    pub tags: Vec<&'static str>,
+}
+
+/// Whether `tags` satisfy a space-separated tag filter: every plain tag must be present
+/// and no `!tag` may be. An empty filter matches everything.
+pub fn tag_filter_matches(filter: &str, tags: &[&str]) -> bool {
+    filter.split_whitespace().all(|term| match term.strip_prefix('!') {
+        Some(excluded) => !tags.iter().any(|tag| tag.eq_ignore_ascii_case(excluded)),
+        None => tags.iter().any(|tag| tag.eq_ignore_ascii_case(term)),
+    })
+}
+
+/// Flat, sortable list of every item in the tree, for users who know what they are looking for.
+pub struct ItemIndex {
+    all: Vec<IndexEntry>,
+    /// Entries passing the tag filter, in display order.
+    pub entries: Vec<IndexEntry>,
+    pub sort: SortKey,
+    pub selected: usize,
+    /// Tag filter such as `kvm` or `!destructive`.
+    pub filter: String,
+    pub editing_filter: bool,
+}
+
+impl ItemIndex {
+    pub fn new(tree: &Rc<RefCell<MenuNode>>, backend: &dyn PackageBackend) -> ItemIndex {
+        let all: Vec<IndexEntry> = item_paths(tree).into_iter().map(|(path, item)| {
+            let tags = item.borrow().tags(backend);
+            IndexEntry { path, item, tags }
+        }).collect();
+        let mut index = ItemIndex {
+            entries: all.clone(), all, sort: SortKey::Name, selected: 0, filter: String::new(), editing_filter: false,
+        };
+        index.sort_entries();
+        index
+    }
+
+    /// Every tag used by some item, sorted.
+    pub fn known_tags(&self) -> Vec<&'static str> {
+        let mut tags: Vec<&'static str> = self.all.iter().flat_map(|entry| entry.tags.iter().copied()).collect();
+        tags.sort_unstable();
+        tags.dedup();
+        tags
+    }
+
+    /// Handles a key while the filter bar is being edited. Returns `true` if the key was consumed.
+    pub fn handle_filter_key(&mut self, code: KeyCode) -> bool {
+        if !self.editing_filter {
+            return false;
+        }
+        match code {
+            KeyCode::Char(c) => self.filter.push(c),
+            KeyCode::Backspace => { self.filter.pop(); },
+            KeyCode::Enter => self.editing_filter = false,
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.editing_filter = false;
+            }
+            _ => return true,
+        }
+        self.apply_filter();
+        true
+    }
+
+    fn apply_filter(&mut self) {
+        let current = self.current();
+        self.entries = self.all.iter().filter(|entry| tag_filter_matches(&self.filter, &entry.tags)).cloned().collect();
+        self.sort_entries();
+        self.selected = current
+            .and_then(|current| self.entries.iter().position(|entry| Rc::ptr_eq(&entry.item, &current)))
+            .unwrap_or(0);
+    }
+
+    pub fn toggle_sort(&mut self) {
+        let current = self.current();
+        self.sort = match self.sort {
+            SortKey::Name => SortKey::Category,
+            SortKey::Category => SortKey::Name,
+        };
+        self.sort_entries();
+        // Keep the highlighted item under the cursor after re-sorting.
+        if let Some(current) = current {
+            self.selected = self.entries.iter().position(|entry| Rc::ptr_eq(&entry.item, &current)).unwrap_or(0);
+        }
+    }
+
+    fn sort_entries(&mut self) {
+        let key = |entry: &IndexEntry| (entry.path.to_lowercase(), entry.item.borrow().name().to_lowercase());
+        match self.sort {
+            SortKey::Name => self.entries.sort_by_cached_key(|entry| { let (path, name) = key(entry); (name, path) }),
+            SortKey::Category => self.entries.sort_by_cached_key(key),
+        }
+    }
+
+    pub fn current(&self) -> Option<Rc<RefCell<MenuNode>>> {
+        self.entries.get(self.selected).map(|entry| entry.item.clone())
+    }
+
+    pub fn next(&mut self) {
+        if !self.entries.is_empty() {
+            self.selected = (self.selected + 1) % self.entries.len();
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.entries.is_empty() {
+            self.selected = (self.selected + self.entries.len() - 1) % self.entries.len();
+        }
+    }
+
+    pub fn draw(&self, f: &mut Frame) {
+        let chunks = Layout::default().direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0), Constraint::Length(3)].as_ref()).split(f.size());
+
+        let (filter_text, filter_style) = if self.editing_filter {
+            (format!("{}_   (known: {})", self.filter, self.known_tags().join(" ")), Style::default().fg(Color::Yellow))
+        } else if self.filter.is_empty() {
+            ("(none; press / to filter by tag, e.g. kvm or !destructive)".to_string(), Style::default())
+        } else {
+            (self.filter.clone(), Style::default())
+        };
+        f.render_widget(
+            Paragraph::new(filter_text).block(Block::default().borders(Borders::ALL).title("Tag Filter").border_style(filter_style)),
+            chunks[0],
+        );
+
+        let name_width = self.entries.iter().map(|entry| entry.item.borrow().name().chars().count()).max().unwrap_or(0);
+        let items: Vec<ListItem> = self.entries.iter().map(|entry| {
+            let item = entry.item.borrow();
+            let mark = if item.is_selected() { "[x]" } else { "[ ]" };
+            let tags = if entry.tags.is_empty() { String::new() } else { format!("  [{}]", entry.tags.join(", ")) };
+            let line = format!("{} {:<width$}  {}{}", mark, item.name(), entry.path, tags, width = name_width);
+            let style = if item.is_selected() { Style::default().fg(Color::Green) } else { Style::default() };
+            ListItem::new(line).style(style)
+        }).collect();
+        let sort = match self.sort {
+            SortKey::Name => "name",
+            SortKey::Category => "category",
+        };
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(format!("All Items ({} of {}, sorted by {})", self.entries.len(), self.all.len(), sort)))
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(Color::DarkGray))
+            .highlight_symbol(">> ");
+        let mut state = ListState::default();
+        if !self.entries.is_empty() {
+            state.select(Some(self.selected));
+        }
+        f.render_stateful_widget(list, chunks[1], &mut state);
+
+        let footer_text = if self.editing_filter {
+            "Type tags separated by spaces, prefix with ! to exclude | [Enter] Apply | [Esc] Clear"
+        } else {
+            "Navigate [↑↓] | [Enter/Space] Select | [/] Filter by Tag | [s] Sort by Name/Category | [Esc/F] Back | [q] Quit"
+        };
+        let footer = Paragraph::new(footer_text)
+            .style(Style::default().fg(Color::Cyan))
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(footer, chunks[2]);
+    }
+}
@@ -0,0 +1,86 @@
+// src/jobwizard.rs
+
+use crate::{
+    form::{Field, Step, Validator, Wizard, WizardOutcome, SCHEDULE_TYPES},
+    theme::Theme,
+};
+use redcent_tui::{validate, MenuNode, PackageBackend};
+use crossterm::event::KeyCode;
+use ratatui::Frame;
+use std::{cell::RefCell, rc::Rc};
+
+/// ID of the item configured through this wizard.
+pub const SCHEDULED_JOB_ITEM: &str = "maint.scheduled-job";
+
+/// Parameter keys edited on the job step, in display order.
+const JOB_PARAMS: [&str; 3] = ["job_name", "command", "job_user"];
+
+/// How the schedule is asked for per entry of [`SCHEDULE_TYPES`]: field label, check and the
+/// example filled in when the type changes.
+const SCHEDULES: [(&str, Validator, &str); 2] = [
+    ("OnCalendar= event, e.g. Mon..Fri *-*-* 03:00:00 or daily", validate::on_calendar, "*-*-* 03:00:00"),
+    ("Cron expression: minute hour day month weekday, or @daily", validate::cron, "0 3 * * *"),
+];
+
+/// Wizard turning a command and a schedule into a crontab entry or a systemd service and timer.
+pub struct JobWizard {
+    pub item: Rc<RefCell<MenuNode>>,
+    pub wizard: Wizard,
+    /// Index into [`SCHEDULE_TYPES`] the schedule field is currently checked for.
+    kind: Option<usize>,
+}
+
+impl JobWizard {
+    pub fn new(item: &Rc<RefCell<MenuNode>>) -> JobWizard {
+        let (kind, job, schedule) = match &*item.borrow() {
+            MenuNode::Item { params, .. } => {
+                let field = |key: &str| params.iter().find(|p| p.key == key).map(|p| Field::from_param(p).required());
+                let job = JOB_PARAMS.iter().filter_map(|key| field(key)).collect();
+                (field("schedule_type"), job, field("schedule"))
+            }
+            MenuNode::Menu { .. } => (None, Vec::new(), None),
+        };
+        let steps = vec![
+            Step::new("How to schedule the job", kind.into_iter().collect()),
+            Step::new("Job", job),
+            Step::new("Schedule", schedule.into_iter().collect()),
+            Step::review("Review commands"),
+        ];
+        let mut wizard = JobWizard { item: item.clone(), wizard: Wizard::new("Scheduled Job", steps), kind: None };
+        wizard.sync_schedule();
+        wizard
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> WizardOutcome {
+        let outcome = self.wizard.handle_key(code);
+        self.sync_schedule();
+        outcome
+    }
+
+    /// Once the type changes, checks the schedule against it instead, swapping in the type's
+    /// example while the field still holds the other's.
+    fn sync_schedule(&mut self) {
+        let kind = SCHEDULE_TYPES.iter().position(|kind| *kind == self.wizard.value("schedule_type")).unwrap_or(0);
+        if self.kind == Some(kind) {
+            return;
+        }
+        self.kind = Some(kind);
+        let (label, check, example) = SCHEDULES[kind];
+        if let Some(field) = self.wizard.field_mut("schedule") {
+            if field.value.trim().is_empty() || SCHEDULES.iter().any(|(_, _, other)| *other == field.value) {
+                field.value = example.to_string();
+            }
+            field.set_check(label, check);
+        }
+    }
+
+    /// Writes the wizard's values back to the item.
+    pub fn apply(&self) {
+        self.wizard.apply(&self.item);
+    }
+
+    pub fn draw(&self, f: &mut Frame, theme: &Theme, backend: &dyn PackageBackend) {
+        let review = if self.wizard.on_review() { self.wizard.preview(&self.item, backend) } else { String::new() };
+        self.wizard.draw(f, theme, &review);
+    }
+}
@@ -0,0 +1,71 @@
+// src/labwizard.rs
+
+use crate::{
+    form::{Field, FieldKind, Step, Wizard, WizardOutcome},
+    theme::Theme,
+};
+use redcent_tui::{MenuNode, PackageBackend};
+use crossterm::event::KeyCode;
+use ratatui::Frame;
+use std::{cell::RefCell, rc::Rc};
+
+/// ID of the item configured through this wizard.
+pub const LAB_ACCOUNTS_ITEM: &str = "lab.accounts";
+
+/// Desktop presets offered by the wizard, as (label, item ID to select).
+pub const DESKTOP_PRESETS: [(&str, Option<&str>); 3] = [
+    ("GNOME (minimal)", Some("gnome.minimal")),
+    ("GNOME (full Workstation)", Some("gnome.full")),
+    ("Keep current desktop", None),
+];
+
+/// Parameter keys edited on the accounts step, in display order.
+const ACCOUNT_PARAMS: [&str; 4] = ["prefix", "count", "password", "max_days"];
+
+/// Wizard for lab/classroom workstations: numbered accounts, password policy and a desktop preset.
+pub struct LabWizard {
+    pub item: Rc<RefCell<MenuNode>>,
+    pub wizard: Wizard,
+}
+
+impl LabWizard {
+    pub fn new(item: &Rc<RefCell<MenuNode>>) -> LabWizard {
+        let (accounts, desktop) = match &*item.borrow() {
+            MenuNode::Item { params, .. } => {
+                let accounts = ACCOUNT_PARAMS.iter()
+                    .filter_map(|key| params.iter().find(|p| p.key == *key).map(|p| Field::from_param(p).required()))
+                    .collect();
+                let desktop = params.iter().find(|p| p.key == "desktop").map(|p| p.value.clone()).unwrap_or_default();
+                (accounts, desktop)
+            }
+            MenuNode::Menu { .. } => (Vec::new(), String::new()),
+        };
+        let presets = DESKTOP_PRESETS.iter().map(|(label, _)| label.to_string()).collect();
+        let steps = vec![
+            Step::new("Accounts & password policy", accounts),
+            Step::new("Desktop preset", vec![Field::new("desktop", "Desktop applied to every workstation", FieldKind::Select(presets), desktop)]),
+            Step::review("Review commands"),
+        ];
+        LabWizard { item: item.clone(), wizard: Wizard::new("Lab Workstations", steps) }
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> WizardOutcome {
+        self.wizard.handle_key(code)
+    }
+
+    /// Writes the wizard's values back to the item.
+    pub fn apply(&self) {
+        self.wizard.apply(&self.item);
+    }
+
+    /// The desktop item the caller should select alongside the accounts, if any.
+    pub fn desktop_item(&self) -> Option<&'static str> {
+        let desktop = self.wizard.value("desktop");
+        DESKTOP_PRESETS.iter().find(|(label, _)| *label == desktop).and_then(|(_, id)| *id)
+    }
+
+    pub fn draw(&self, f: &mut Frame, theme: &Theme, backend: &dyn PackageBackend) {
+        let review = if self.wizard.on_review() { self.wizard.preview(&self.item, backend) } else { String::new() };
+        self.wizard.draw(f, theme, &review);
+    }
+}
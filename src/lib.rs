@@ -0,0 +1,155 @@
+// src/lib.rs
+
+//! Menu engine behind redcent-tui.
+//!
+//! The crate exposes the menu tree model ([`MenuNode`], [`Param`]), the dependency
+//! metadata handling ([`toggle_item`]), the package manager abstraction
+//! ([`PackageBackend`]), the structured item scripts ([`Script`]), the bash script generator ([`generate_script`]),
+//! the terminal-independent front-end state ([`Model`]),
+//! the Markdown runbook exporter ([`generate_runbook`]) and wrappers embedding a script in Terraform,
+//! cloud-init, Packer or Vagrant ([`terraform_provisioner`], [`cloud_init`], [`packer_provisioner`], [`vagrantfile`]) and
+//! validators for typed form input ([`validate`]), so
+//! distro-specific front ends can define their own trees with the [`item!`] and
+//! [`menu!`] macros and reuse the rest.
+//!
+//! ```
+//! use redcent_tui::{find_item, generate_script, item, menu, toggle_item, Dnf, ErrorMode, PackageBackend, Script};
+//!
+//! fn install_epel(pm: &dyn PackageBackend) -> Script { Script::new("Install EPEL").install(pm, &["epel-release"]) }
+//! fn install_htop(pm: &dyn PackageBackend) -> Script { Script::new("Install htop").install(pm, &["htop"]) }
+//!
+//! let tree = menu!("Main Menu",
+//!     menu!("Repositories", item!("repos.epel", "EPEL", install_epel)),
+//!     menu!("Tools", item!("tools.htop", "htop", install_htop, requires: ["repos.epel"]))
+//! );
+//! let htop = find_item(&tree, "tools.htop").unwrap();
+//! toggle_item(&tree, &htop).unwrap();
+//!
+//! let script = generate_script(&tree, "Rhel", &Dnf, ErrorMode::Continue, false);
+//! assert!(script.find("install -y epel-release").unwrap() < script.find("install -y htop").unwrap());
+//! ```
+
+mod backend;
+pub mod dconf;
+mod export;
+mod generate;
+mod menu;
+mod model;
+mod script;
+mod template;
+pub mod validate;
+
+pub use backend::{offline_repo_setup, Apt, Dnf, Offline, PackageBackend, Proxy, Targeted, DEFAULT_OFFLINE_BASEURL};
+pub use export::{cloud_init, packer_provisioner, terraform_provisioner, vagrantfile};
+pub use generate::{
+    checkpoint_blocks, civil_from_days, generate_logged_script, generate_resumable_script, generate_runbook, generate_script, plan_stages, port_claims, port_conflicts,
+    script_order, space_claims, ErrorMode, PortClaim, SpaceClaim, Stage, BLOCK_MARKER, NOT_VERIFIED_MARKER, VERIFIED_MARKER,
+};
+pub use menu::{
+    all_items, deselect_all, deselect_items, find_item, has_script_order, item_paths, ordered_selected_items, path_to,
+    render_params, reset_script_order, select_all, select_items, set_script_order, swap_items, toggle_item,
+    unresolved_placeholders, MenuNode, Param, ToggleOutcome,
+};
+pub use model::{Fix, Model, Problem};
+pub use script::{Cmd, Phase, Script};
+pub use template::render_conditionals;
+
+use std::fs;
+
+/// Creates a leaf node (an item), optionally with dependency metadata referencing other item IDs, tags,
+/// parameters given as `(key, label, default)` tuples (append `optional` to a tuple for
+/// parameters that may be left blank) and a long-form description.
+#[macro_export]
+macro_rules! item {
+    ($id:expr, $name:expr, $func:expr
+        $(, requires: [$($req:expr),*])?
+        $(, conflicts: [$($con:expr),*])?
+        $(, tags: [$($tag:expr),*])?
+        $(, params: [$(($key:expr, $label:expr, $default:expr $(, $optional:ident)?)),*])?
+        $(, description: $description:expr)?) => {
+        ::std::rc::Rc::new(::std::cell::RefCell::new($crate::MenuNode::Item {
+            id: $id,
+            name: $name.to_string(),
+            script_fn: $func,
+            selected: false,
+            rank: None,
+            requires: &[$($($req),*)?],
+            conflicts: &[$($($con),*)?],
+            tags: &[$($($tag),*)?],
+            params: vec![$($($crate::Param::new($key, $label, $default)$(.$optional())?),*)?],
+            description: None$(.or(Some($description)))?,
+        }))
+    };
+}
+
+/// Creates a branch node (a sub-menu).
+#[macro_export]
+macro_rules! menu {
+    ($name:expr, $($child:expr),*) => {
+        ::std::rc::Rc::new(::std::cell::RefCell::new($crate::MenuNode::Menu {
+            name: $name.to_string(),
+            children: vec![$($child),*],
+        }))
+    };
+}
+
+/// Renders a file template as a heredoc that writes it to `$path`. Both arguments must be literals.
+#[macro_export]
+macro_rules! write_file {
+    ($path:expr, $content:expr) => {
+        concat!("sudo tee ", $path, " > /dev/null <<'REDCENT_EOF'\n", $content, "REDCENT_EOF")
+    };
+}
+
+/// Enum to represent the detected Linux distribution.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OsDistribution {
+    Rhel,
+    Centos,
+    Debian,
+    Ubuntu,
+    Unknown,
+}
+
+impl OsDistribution {
+    /// The package backend scripts for this distribution are rendered with, resolving their
+    /// distribution conditionals for it.
+    pub fn package_backend(self) -> Box<dyn PackageBackend> {
+        let backend: Box<dyn PackageBackend> = match self {
+            OsDistribution::Debian | OsDistribution::Ubuntu => Box::new(Apt),
+            OsDistribution::Rhel | OsDistribution::Centos | OsDistribution::Unknown => Box::new(Dnf),
+        };
+        Box::new(Targeted(self, backend))
+    }
+
+    /// The distribution's `ID` in `/etc/os-release`, as tested by `{{#if <id>}}` in item scripts;
+    /// `None` for distributions this crate does not know, which only get `{{else}}` branches.
+    pub fn id(self) -> Option<&'static str> {
+        match self {
+            OsDistribution::Rhel => Some("rhel"),
+            OsDistribution::Centos => Some("centos"),
+            OsDistribution::Debian => Some("debian"),
+            OsDistribution::Ubuntu => Some("ubuntu"),
+            OsDistribution::Unknown => None,
+        }
+    }
+}
+
+/// Reads the distribution ID from /etc/os-release.
+pub fn detect_os() -> OsDistribution {
+    if let Ok(content) = fs::read_to_string("/etc/os-release") {
+        for line in content.lines() {
+            if line.starts_with("ID=") {
+                let id = line.trim_start_matches("ID=").trim_matches('"');
+                return match id {
+                    "rhel" => OsDistribution::Rhel,
+                    "centos" => OsDistribution::Centos,
+                    "debian" => OsDistribution::Debian,
+                    "ubuntu" => OsDistribution::Ubuntu,
+                    _ => OsDistribution::Unknown,
+                };
+            }
+        }
+    }
+    OsDistribution::Unknown
+}
@@ -0,0 +1,184 @@
+// src/links.rs
+
+use crate::theme::Theme;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::{fs, path::Path, process::Command};
+
+const BONDING_DIR: &str = "/proc/net/bonding";
+const SYS_NET: &str = "/sys/class/net";
+
+/// A bond as the kernel reports it in `/proc/net/bonding/<bond>`.
+struct Bond {
+    name: String,
+    mode: String,
+    status: String,
+    active: Option<String>,
+    /// Each port's name, MII status, speed and link failure count.
+    ports: Vec<(String, String, String, String)>,
+}
+
+/// Parses a `/proc/net/bonding` file: bond-wide settings first, then a block per
+/// `Slave Interface:`.
+fn parse_bond(name: &str, content: &str) -> Bond {
+    let mut bond = Bond { name: name.to_string(), mode: String::new(), status: String::new(), active: None, ports: Vec::new() };
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let value = value.trim().to_string();
+        match (key.trim(), bond.ports.last_mut()) {
+            ("Slave Interface", _) => bond.ports.push((value, String::new(), String::new(), String::new())),
+            ("Bonding Mode", None) => bond.mode = value,
+            ("MII Status", None) => bond.status = value,
+            ("Currently Active Slave", None) => bond.active = Some(value),
+            ("MII Status", Some(port)) => port.1 = value,
+            ("Speed", Some(port)) => port.2 = value,
+            ("Link Failure Count", Some(port)) => port.3 = value,
+            _ => {}
+        }
+    }
+    bond
+}
+
+fn list_dir(dir: &str) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir).into_iter().flatten().filter_map(Result::ok)
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+/// The interface's `operstate` (`up`, `down`, `lowerlayerdown`...).
+fn operstate(iface: &str) -> String {
+    fs::read_to_string(Path::new(SYS_NET).join(iface).join("operstate")).map(|state| state.trim().to_string()).unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Splits a line of `nmcli -t` output on the colons that `\:` does not escape.
+fn terse_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => fields.last_mut().unwrap().extend(chars.next()),
+            ':' => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// Read-only view of the link aggregates: bonds from `/proc/net/bonding`, bridges and their
+/// ports from sysfs, and the NetworkManager connections behind them, to check that generated
+/// bond and bridge changes came up.
+pub struct LinkStatus {
+    lines: Vec<Line<'static>>,
+    scroll: usize,
+}
+
+impl LinkStatus {
+    pub fn probe(theme: &Theme) -> LinkStatus {
+        let state_style = |state: &str| match state {
+            "up" | "activated" => theme.added,
+            "down" | "lowerlayerdown" | "deactivated" => theme.error,
+            _ => theme.warning,
+        };
+        let mut lines = vec![Line::styled(format!("Bonds ({})", BONDING_DIR), theme.title)];
+        let bonds = list_dir(BONDING_DIR);
+        if bonds.is_empty() {
+            lines.push(Line::styled("  none (no bond is configured or the bonding module is not loaded)", theme.muted));
+        }
+        for name in &bonds {
+            let Ok(content) = fs::read_to_string(Path::new(BONDING_DIR).join(name)) else { continue };
+            let bond = parse_bond(name, &content);
+            let mut spans = vec![
+                Span::raw(format!("  {}  ", bond.name)),
+                Span::styled(bond.status.clone(), state_style(&bond.status)),
+                Span::styled(format!("  {}", bond.mode), theme.muted),
+            ];
+            if let Some(active) = bond.active.filter(|active| active != "None") {
+                spans.push(Span::raw(format!("  active port: {}", active)));
+            }
+            lines.push(Line::from(spans));
+            if bond.ports.is_empty() {
+                lines.push(Line::styled("    no ports", theme.warning));
+            }
+            for (port, status, speed, failures) in bond.ports {
+                lines.push(Line::from(vec![
+                    Span::raw(format!("    {:<12} ", port)),
+                    Span::styled(format!("{:<6}", status), state_style(&status)),
+                    Span::styled(format!("  {}  link failures: {}", speed, failures), theme.muted),
+                ]));
+            }
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::styled(format!("Bridges ({})", SYS_NET), theme.title));
+        let bridges: Vec<String> = list_dir(SYS_NET).into_iter().filter(|iface| Path::new(SYS_NET).join(iface).join("bridge").is_dir()).collect();
+        if bridges.is_empty() {
+            lines.push(Line::styled("  none", theme.muted));
+        }
+        for bridge in &bridges {
+            let state = operstate(bridge);
+            lines.push(Line::from(vec![Span::raw(format!("  {}  ", bridge)), Span::styled(state.clone(), state_style(&state))]));
+            let ports = list_dir(&format!("{}/{}/brif", SYS_NET, bridge));
+            if ports.is_empty() {
+                lines.push(Line::styled("    no ports", theme.warning));
+            }
+            for port in ports {
+                let state = operstate(&port);
+                lines.push(Line::from(vec![Span::raw(format!("    {:<12} ", port)), Span::styled(state.clone(), state_style(&state))]));
+            }
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::styled("NetworkManager connections (nmcli)", theme.title));
+        match Command::new("nmcli").args(["-t", "-f", "NAME,TYPE,SLAVE,DEVICE,STATE", "connection", "show"]).output() {
+            Ok(output) if output.status.success() => {
+                let connections: Vec<Vec<String>> = String::from_utf8_lossy(&output.stdout).lines()
+                    .map(terse_fields)
+                    .filter(|fields| fields.len() == 5 && (["bond", "bridge", "team"].contains(&fields[1].as_str()) || !fields[2].is_empty()))
+                    .collect();
+                if connections.is_empty() {
+                    lines.push(Line::styled("  no bond, bridge or port connections", theme.muted));
+                }
+                let width = connections.iter().map(|fields| fields[0].chars().count()).max().unwrap_or(0);
+                for fields in connections {
+                    let kind = if fields[2].is_empty() { fields[1].clone() } else { format!("{} port", fields[2]) };
+                    let (state, style) = if fields[4].is_empty() { ("inactive".to_string(), theme.muted) } else { (fields[4].clone(), state_style(&fields[4])) };
+                    lines.push(Line::from(vec![
+                        Span::raw(format!("  {:<width$}  ", fields[0])),
+                        Span::styled(format!("{:<12}", kind), theme.muted),
+                        Span::styled(state, style),
+                        Span::raw(if fields[3].is_empty() { String::new() } else { format!(" on {}", fields[3]) }),
+                    ]));
+                }
+            }
+            Ok(output) => {
+                let error = String::from_utf8_lossy(&output.stderr).lines().next().unwrap_or("failed").to_string();
+                lines.push(Line::styled(format!("  {}", error), theme.warning));
+            }
+            Err(e) => lines.push(Line::styled(format!("  nmcli not available: {}", e), theme.warning)),
+        }
+        LinkStatus { lines, scroll: 0 }
+    }
+
+    pub fn scroll(&mut self, lines: isize) {
+        self.scroll = self.scroll.saturating_add_signed(lines).min(self.lines.len().saturating_sub(1));
+    }
+
+    pub fn draw(&self, f: &mut Frame, theme: &Theme) {
+        let chunks = Layout::default().direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref()).split(f.size());
+        let body = Paragraph::new(self.lines.clone())
+            .scroll((self.scroll as u16, 0))
+            .block(Block::default().borders(Borders::ALL).title("Link Aggregation Status"));
+        f.render_widget(body, chunks[0]);
+        let footer = Paragraph::new("Scroll [↑↓ PgUp/PgDn] | [r] Refresh | [Esc/B] Back | [q] Quit")
+            .style(theme.footer)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(footer, chunks[1]);
+    }
+}
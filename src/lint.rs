@@ -0,0 +1,135 @@
+// src/lint.rs
+
+use crate::theme::Theme;
+use ratatui::{
+    layout::Rect,
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem `bash -n` or `shellcheck` reported in the generated script.
+pub struct Finding {
+    /// 1-based line in the script, when the tool names one.
+    pub line: Option<usize>,
+    pub severity: Severity,
+    /// `bash -n`, or the ShellCheck code such as `SC2086`.
+    pub source: String,
+    pub message: String,
+}
+
+/// Findings of a syntax check with `bash -n` and, when it is installed, `shellcheck` at warning
+/// severity, shown below the generated script before it is saved or run.
+pub struct Lint {
+    pub findings: Vec<Finding>,
+    shellcheck: bool,
+    /// Finding the viewer last jumped to with [`Lint::next`].
+    current: Option<usize>,
+}
+
+impl Lint {
+    pub fn run(script: &str) -> Lint {
+        let mut findings = Vec::new();
+        if let Ok((_, stderr)) = pipe("bash", &["-n"], script) {
+            findings.extend(stderr.lines().map(parse_bash_error));
+        }
+        // shellcheck exits non-zero when it finds something, so only a failure to start counts.
+        let shellcheck = match pipe("shellcheck", &["--format=gcc", "--severity=warning", "--shell=bash", "-"], script) {
+            Ok((stdout, _)) => {
+                findings.extend(stdout.lines().filter_map(parse_shellcheck_line));
+                true
+            }
+            Err(_) => false,
+        };
+        findings.sort_by_key(|finding| finding.line);
+        Lint { findings, shellcheck, current: None }
+    }
+
+    pub fn errors(&self) -> usize {
+        self.findings.iter().filter(|finding| finding.severity == Severity::Error).count()
+    }
+
+    /// Moves to the next finding with a line number and returns that line, wrapping around.
+    pub fn next(&mut self) -> Option<usize> {
+        let start = self.current.map_or(0, |current| current + 1);
+        let count = self.findings.len();
+        let index = (0..count).map(|n| (start + n) % count).find(|&n| self.findings[n].line.is_some())?;
+        self.current = Some(index);
+        self.findings[index].line
+    }
+
+    /// Rows the panel takes: none when the script is clean.
+    pub fn height(&self) -> u16 {
+        if self.findings.is_empty() { 0 } else { self.findings.len().min(5) as u16 + 2 }
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let errors = self.errors();
+        let tools = if self.shellcheck { "bash -n, shellcheck" } else { "bash -n; shellcheck is not installed" };
+        let title = format!("Lint: {} error(s), {} warning(s) ({})", errors, self.findings.len() - errors, tools);
+        let items: Vec<ListItem> = self.findings.iter().map(|finding| {
+            let (label, style) = match finding.severity {
+                Severity::Error => ("error", theme.error),
+                Severity::Warning => ("warning", theme.warning),
+            };
+            let line = finding.line.map_or(String::new(), |line| format!("line {}: ", line));
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:<8}", label), style),
+                Span::styled(format!("{:<10}", finding.source), theme.muted),
+                Span::raw(format!("{}{}", line, finding.message)),
+            ]))
+        }).collect();
+        let border = if errors > 0 { theme.error } else { theme.warning };
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).border_style(border).title(title))
+            .highlight_style(theme.highlight);
+        let mut state = ListState::default();
+        state.select(self.current);
+        f.render_stateful_widget(list, area, &mut state);
+    }
+}
+
+/// Runs `program` with `script` on stdin and returns its stdout and stderr.
+fn pipe(program: &str, args: &[&str], script: &str) -> io::Result<(String, String)> {
+    let mut child = Command::new(program).args(args)
+        .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped())
+        .spawn()?;
+    // A tool that exits before reading all of its input closes the pipe; its output still counts.
+    let _ = child.stdin.take().expect("stdin is piped").write_all(script.as_bytes());
+    let output = child.wait_with_output()?;
+    Ok((String::from_utf8_lossy(&output.stdout).into_owned(), String::from_utf8_lossy(&output.stderr).into_owned()))
+}
+
+/// Parses a `bash -n` message such as ``bash: line 12: syntax error near unexpected token `fi'``.
+fn parse_bash_error(text: &str) -> Finding {
+    let rest = text.strip_prefix("bash: ").unwrap_or(text);
+    let (line, message) = match rest.strip_prefix("line ").and_then(|rest| rest.split_once(": ")) {
+        Some((number, message)) if number.parse::<usize>().is_ok() => (number.parse().ok(), message),
+        _ => (None, rest),
+    };
+    Finding { line, severity: Severity::Error, source: "bash -n".to_string(), message: message.to_string() }
+}
+
+/// Parses a line of `shellcheck --format=gcc` output: `-:12:5: warning: message [SC2086]`.
+fn parse_shellcheck_line(text: &str) -> Option<Finding> {
+    let mut parts = text.strip_prefix("-:")?.splitn(3, ':');
+    let line = parts.next()?.parse().ok()?;
+    parts.next()?;
+    let (severity, message) = parts.next()?.trim_start().split_once(": ")?;
+    let (message, code) = match message.rsplit_once(" [") {
+        Some((message, code)) => (message, code.trim_end_matches(']')),
+        None => (message, "shellcheck"),
+    };
+    let severity = if severity == "error" { Severity::Error } else { Severity::Warning };
+    Some(Finding { line: Some(line), severity, source: code.to_string(), message: message.to_string() })
+}
@@ -0,0 +1,172 @@
+// src/luks.rs
+
+use crate::{
+    form::{Field, FieldKind, Step, Wizard, WizardOutcome},
+    theme::Theme,
+};
+use redcent_tui::{validate, MenuNode, PackageBackend};
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::{cell::RefCell, fs, process::Command, rc::Rc};
+
+/// Parameter key that marks an item as needing the LUKS volume picker instead of the plain form.
+pub const LUKS_DEVICE_PARAM: &str = "luks_device";
+
+const CRYPTTAB: &str = "/etc/crypttab";
+
+/// Whether the item is configured through the LUKS wizard rather than the plain parameter form.
+pub fn needs_luks_wizard(node: &MenuNode) -> bool {
+    matches!(node, MenuNode::Item { params, .. } if params.iter().any(|p| p.key == LUKS_DEVICE_PARAM))
+}
+
+/// A LUKS-formatted block device found by `lsblk`.
+pub struct LuksVolume {
+    pub path: String,
+    pub uuid: String,
+    /// The opened `/dev/mapper` device and where it is mounted, if anywhere.
+    pub opened: Option<(String, String)>,
+}
+
+/// Lists the LUKS volumes from `lsblk`, with the mapper devices they are opened as.
+pub fn list_volumes() -> Vec<LuksVolume> {
+    let Ok(output) = Command::new("lsblk").args(["-rpno", "NAME,FSTYPE,UUID,PKNAME,TYPE,MOUNTPOINT"]).output() else {
+        return Vec::new();
+    };
+    // Raw output separates columns with single spaces and escapes spaces inside values.
+    let rows: Vec<Vec<String>> = String::from_utf8_lossy(&output.stdout).lines()
+        .map(|line| line.split(' ').map(|column| column.replace("\\x20", " ")).collect())
+        .filter(|row: &Vec<String>| row.len() >= 6)
+        .collect();
+    rows.iter().filter(|row| row[1] == "crypto_LUKS").map(|row| LuksVolume {
+        path: row[0].clone(),
+        uuid: row[2].clone(),
+        opened: rows.iter().find(|child| child[4] == "crypt" && child[3] == row[0]).map(|child| (child[0].clone(), child[5].clone())),
+    }).collect()
+}
+
+/// Wizard for the disk encryption items: pick a LUKS volume, fill the remaining parameters, review the commands.
+pub struct LuksWizard {
+    pub item: Rc<RefCell<MenuNode>>,
+    pub wizard: Wizard,
+}
+
+impl LuksWizard {
+    pub fn new(item: &Rc<RefCell<MenuNode>>) -> LuksWizard {
+        let node = item.borrow();
+        let MenuNode::Item { params, .. } = &*node else {
+            return LuksWizard { item: item.clone(), wizard: Wizard::new(node.name(), Vec::new()) };
+        };
+        let volumes: Vec<String> = list_volumes().into_iter().map(|volume| volume.path).collect();
+        let mut steps = Vec::new();
+        if let Some(param) = params.iter().find(|p| p.key == LUKS_DEVICE_PARAM) {
+            // Without detected volumes the path is typed in by hand.
+            let device = if volumes.is_empty() {
+                let label = "LUKS device path (none detected via `lsblk`)";
+                Field::new(LUKS_DEVICE_PARAM, label, FieldKind::Text, param.value.clone()).required().validate(validate::block_device)
+            } else {
+                Field::new(LUKS_DEVICE_PARAM, param.label, FieldKind::Select(volumes), param.value.clone())
+            };
+            steps.push(Step::new("Select LUKS volume", vec![device]));
+        }
+        let settings: Vec<Field> = params.iter().filter(|p| p.key != LUKS_DEVICE_PARAM).map(Field::from_param).collect();
+        if !settings.is_empty() {
+            steps.push(Step::new("Settings", settings));
+        }
+        steps.push(Step::review("Review commands"));
+        LuksWizard { item: item.clone(), wizard: Wizard::new(node.name(), steps) }
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> WizardOutcome {
+        self.wizard.handle_key(code)
+    }
+
+    /// Writes the wizard's values back to the item.
+    pub fn apply(&self) {
+        self.wizard.apply(&self.item);
+    }
+
+    pub fn draw(&self, f: &mut Frame, theme: &Theme, backend: &dyn PackageBackend) {
+        let review = if self.wizard.on_review() { self.wizard.preview(&self.item, backend) } else { String::new() };
+        self.wizard.draw(f, theme, &review);
+    }
+}
+
+/// Read-only view of the host's disk encryption: LUKS volumes, `/etc/crypttab` and the tokens
+/// enrolled in each volume.
+pub struct LuksStatus {
+    lines: Vec<Line<'static>>,
+    scroll: usize,
+}
+
+impl LuksStatus {
+    pub fn probe(theme: &Theme) -> LuksStatus {
+        let mut lines = vec![Line::styled("LUKS volumes (lsblk)", theme.title)];
+        let volumes = list_volumes();
+        if volumes.is_empty() {
+            lines.push(Line::styled("  none found", theme.muted));
+        }
+        for volume in &volumes {
+            let state = match &volume.opened {
+                Some((mapper, mount)) if !mount.is_empty() => format!("open as {}, mounted on {}", mapper, mount),
+                Some((mapper, _)) => format!("open as {}", mapper),
+                None => "closed".to_string(),
+            };
+            lines.push(Line::from(vec![
+                Span::raw(format!("  {}  ", volume.path)),
+                Span::styled(format!("UUID={}  ", volume.uuid), theme.muted),
+                Span::raw(state),
+            ]));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::styled(CRYPTTAB, theme.title));
+        match fs::read_to_string(CRYPTTAB) {
+            Ok(content) => {
+                let entries: Vec<&str> = content.lines().filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#')).collect();
+                if entries.is_empty() {
+                    lines.push(Line::styled("  no entries", theme.muted));
+                }
+                lines.extend(entries.into_iter().map(|entry| Line::raw(format!("  {}", entry.split_whitespace().collect::<Vec<_>>().join("  ")))));
+            }
+            Err(e) => lines.push(Line::styled(format!("  not readable: {}", e), theme.warning)),
+        }
+
+        for volume in &volumes {
+            lines.push(Line::from(""));
+            lines.push(Line::styled(format!("Key slots and tokens of {} (systemd-cryptenroll)", volume.path), theme.title));
+            match Command::new("systemd-cryptenroll").arg(&volume.path).output() {
+                Ok(output) if output.status.success() => {
+                    lines.extend(String::from_utf8_lossy(&output.stdout).lines().map(|line| Line::raw(format!("  {}", line))));
+                }
+                Ok(output) => {
+                    let error = String::from_utf8_lossy(&output.stderr).lines().next().unwrap_or("failed").to_string();
+                    lines.push(Line::styled(format!("  {} (listing needs root)", error), theme.warning));
+                }
+                Err(e) => lines.push(Line::styled(format!("  systemd-cryptenroll not available: {}", e), theme.warning)),
+            }
+        }
+        LuksStatus { lines, scroll: 0 }
+    }
+
+    pub fn scroll(&mut self, lines: isize) {
+        self.scroll = self.scroll.saturating_add_signed(lines).min(self.lines.len().saturating_sub(1));
+    }
+
+    pub fn draw(&self, f: &mut Frame, theme: &Theme) {
+        let chunks = Layout::default().direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref()).split(f.size());
+        let body = Paragraph::new(self.lines.clone())
+            .scroll((self.scroll as u16, 0))
+            .block(Block::default().borders(Borders::ALL).title("Disk Encryption Status"));
+        f.render_widget(body, chunks[0]);
+        let footer = Paragraph::new("Scroll [↑↓ PgUp/PgDn] | [r] Refresh | [Esc/L] Back | [q] Quit")
+            .style(theme.footer)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(footer, chunks[1]);
+    }
+}
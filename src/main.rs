@@ -1,115 +1,414 @@
 // src/main.rs
+mod archive;
+mod clipboard;
+mod cockpit;
+mod confirm;
+mod console;
+mod cpuwizard;
+mod diff;
+mod draft;
+mod dump;
+mod events;
+mod favorites;
+mod firewall;
+mod form;
+mod help;
+mod history;
+mod index;
+mod jobwizard;
+mod labwizard;
+mod links;
+mod lint;
+mod luks;
+mod netwizard;
+mod order;
+mod outline;
+mod palette;
+mod preflight;
+mod progress;
+mod privilege;
+mod recent;
+mod remote;
+mod resolve;
+mod runfile;
+mod runlock;
+mod savedialog;
 mod scripts;
+mod session;
+mod settings;
+mod space;
+mod terminal;
+#[cfg(test)]
+mod tests;
+mod theme;
+mod troubleshoot;
+mod tty;
+mod viewer;
+mod watchdog;
 
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
+use crossterm::event::{Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind};
 use ratatui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::Line,
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
-    Frame, Terminal,
+    Frame, Terminal, TerminalOptions, Viewport,
 };
-use std::{cell::RefCell, error::Error, io, fs, process::Command, os::unix::fs::PermissionsExt, rc::Rc};
-
-/// Represents a node in the menu tree. It can be a selectable item or a sub-menu.
-pub enum MenuNode {
-    Item {
-        name: String,
-        script_fn: fn() -> &'static str,
-        selected: bool,
-    },
-    Menu {
-        name: String,
-        children: Vec<Rc<RefCell<MenuNode>>>,
-    },
-}
-
-impl MenuNode {
-    /// Recursively collects all selected script functions.
-    fn get_selected_scripts(&self, scripts: &mut Vec<fn() -> &'static str>) {
+use redcent_tui::{cloud_init, detect_os, generate_runbook, ordered_selected_items, packer_provisioner, plan_stages, space_claims, terraform_provisioner, vagrantfile, Fix, MenuNode, Model, OsDistribution, ToggleOutcome};
+use std::{cell::RefCell, error::Error, io, fs, path::{Path, PathBuf}, process::{self, Command}, os::unix::fs::PermissionsExt, rc::Rc, sync::mpsc::{self, Receiver}, thread};
+use cockpit::RunManifest;
+use confirm::ConfirmRun;
+use cpuwizard::{CpuWizard, CPU_ISOLATION_ITEM};
+use diff::DiffView;
+use draft::{Autosave, Draft};
+use events::{AppEvent, Events, Waker};
+use firewall::FirewallStatus;
+use help::HelpOverlay;
+use history::{ResumePoint, RunRecord};
+use index::ItemIndex;
+use jobwizard::{JobWizard, SCHEDULED_JOB_ITEM};
+use labwizard::{LabWizard, LAB_ACCOUNTS_ITEM};
+use links::LinkStatus;
+use luks::{LuksStatus, LuksWizard};
+use form::{Wizard, WizardOutcome};
+use netwizard::NetWizard;
+use order::OrderView;
+use remote::{RemoteRun, RemoteSetup};
+use resolve::{ResolveDialog, ResolveOutcome};
+use savedialog::{SaveDialog, SaveOutcome};
+use session::Session;
+use outline::ScriptOutline;
+use palette::{Palette, PaletteCommand};
+use lint::Lint;
+use preflight::Preflight;
+use progress::RunProgress;
+use privilege::{Escalation, PrivilegeInfo};
+use runfile::RunFile;
+use runlock::{LockError, RunLock};
+use settings::SettingsPopup;
+use space::SpaceCheck;
+use terminal::TerminalPane;
+use theme::Theme;
+use troubleshoot::{FailureLog, Troubleshooter};
+use tty::TerminalGuard;
+use viewer::ScriptViewer;
+use watchdog::Watchdog;
+
+/// Enum to manage the overall state of the application.
+#[derive(PartialEq, Clone, Copy)]
+enum AppState {
+    Running,
+    Finished,
+    Saving,
+    EditingParams,
+    NetWizard,
+    LabWizard,
+    JobWizard,
+    LuksWizard,
+    LuksStatus,
+    LinkStatus,
+    Firewall,
+    CpuWizard,
+    History,
+    Settings,
+    ProxyForm,
+    Index,
+    Order,
+    Palette,
+    Resolve,
+    ConfirmRun,
+    Preflight,
+    RunLocked,
+    DiffInput,
+    Diff,
+    RestoreDraft,
+    RestoreSession,
+    ResumeRun,
+    Terminal,
+    Troubleshoot,
+    RemoteSetup,
+    Remote,
+    Help,
+}
+
+/// What the save popup writes.
+#[derive(PartialEq, Clone, Copy)]
+enum SaveFormat {
+    Script,
+    Runbook,
+    Terraform,
+    CloudInit,
+    Packer,
+    Vagrant,
+    /// The selection as JSON for image build pipelines, see [`dump::selection_json`].
+    Json,
+}
+
+impl SaveFormat {
+    /// Provisioning formats the export popup cycles through with Tab.
+    const EXPORTS: [SaveFormat; 4] = [SaveFormat::Terraform, SaveFormat::CloudInit, SaveFormat::Packer, SaveFormat::Vagrant];
+
+    fn title(self) -> &'static str {
         match self {
-            MenuNode::Item { selected, script_fn, .. } => {
-                if *selected {
-                    scripts.push(*script_fn);
-                }
-            }
-            MenuNode::Menu { children, .. } => {
-                for child in children {
-                    child.borrow().get_selected_scripts(scripts);
-                }
-            }
+            SaveFormat::Script => "Save Script",
+            SaveFormat::Runbook => "Save Markdown Runbook",
+            SaveFormat::Terraform => "Export: Terraform/OpenTofu provisioners",
+            SaveFormat::CloudInit => "Export: cloud-init user data",
+            SaveFormat::Packer => "Export: Packer shell provisioner + script",
+            SaveFormat::Vagrant => "Export: Vagrantfile",
+            SaveFormat::Json => "Save Selection as JSON",
         }
     }
-    
-    /// Recursively collects the names of all selected items.
-    fn get_selected_item_names(&self, names: &mut Vec<String>) {
+
+    /// Extension the save dialog adds to a file name typed without one.
+    fn extension(self) -> Option<&'static str> {
         match self {
-            MenuNode::Item { name, selected, .. } => {
-                if *selected {
-                    names.push(name.clone());
+            SaveFormat::Script => Some(".sh"),
+            SaveFormat::Runbook => Some(".md"),
+            SaveFormat::Json => Some(".json"),
+            _ => None,
+        }
+    }
+
+    /// The next provisioning format, or `self` for formats outside [`SaveFormat::EXPORTS`].
+    fn next_export(self) -> SaveFormat {
+        match SaveFormat::EXPORTS.iter().position(|&f| f == self) {
+            Some(i) => SaveFormat::EXPORTS[(i + 1) % SaveFormat::EXPORTS.len()],
+            None => self,
+        }
+    }
+}
+
+/// Enum to tell the main function what to do after the TUI exits.
+enum ActionAfterExit {
+    Quit,
+    /// Run the prepared script, escalating with the given strategy, while holding the run lock;
+    /// used when no pseudo-terminal is available to run it inside the TUI.
+    RunScript(PreparedRun, RunManifest, Escalation, RunLock),
+}
+
+/// The smallest terminal the screens are laid out for; below it only a notice is drawn, since
+/// the panels would overlap into garbage.
+const MIN_WIDTH: u16 = 60;
+const MIN_HEIGHT: u16 = 16;
+/// Below this many rows the script preview on the main screen shrinks to a one-line stub, so the
+/// menu keeps enough room; focusing it shows it in place of the menu.
+const PREVIEW_MIN_HEIGHT: u16 = 24;
+
+/// A script written to a private temporary file and recorded in the run history, ready to execute.
+struct PreparedRun {
+    /// The script as run; removed when the run finishes or the run is dropped.
+    file: RunFile,
+    /// The run's history entry; `None` if it could not be recorded.
+    record: Option<RunRecord>,
+    /// Number of blocks a resumed run skips.
+    resume_after: usize,
+    /// Problems that do not stop the run, e.g. a history entry that could not be written.
+    warnings: Vec<String>,
+}
+
+impl PreparedRun {
+    fn new(script: &str, manifest: &RunManifest, resume_after: usize) -> io::Result<PreparedRun> {
+        let file = RunFile::create(script)?;
+        let mut warnings = Vec::new();
+        let record = match history::record_script(script) {
+            Ok(record) => {
+                if let Err(e) = cockpit::write_metadata(&record, manifest, None) {
+                    warnings.push(format!("could not write run metadata: {}", e));
                 }
-            }
-            MenuNode::Menu { children, .. } => {
-                for child in children {
-                    child.borrow().get_selected_item_names(names);
+                if resume_after > 0
+                    && let Err(e) = history::record_checkpoint(&record, resume_after)
+                {
+                    warnings.push(format!("could not record the resume point: {}", e));
                 }
+                Some(record)
             }
-        }
+            Err(e) => {
+                warnings.push(format!("could not record run history: {}", e));
+                None
+            }
+        };
+        Ok(PreparedRun { file, record, resume_after, warnings })
     }
-}
 
+    /// The command line running the script with `escalation`, once the script file is found
+    /// untouched.
+    fn argv(&self, escalation: Escalation) -> io::Result<Vec<String>> {
+        self.file.verify()?;
+        let script = self.file.path().display().to_string();
+        let mut argv = vec!["bash".to_string(), "-c".to_string()];
+        match &self.record {
+            // Tee the output into the run directory while still showing it live; resumable scripts
+            // take their checkpoint file and the number of blocks to skip as arguments.
+            Some(record) => argv.extend([
+                format!("{}bash \"$0\" \"$2\" \"$3\" 2>&1 | tee \"$1\"; exit \"${{PIPESTATUS[0]}}\"", escalation.prefix()),
+                script,
+                record.output_path().display().to_string(),
+                record.checkpoint_path().display().to_string(),
+                self.resume_after.to_string(),
+            ]),
+            None => argv.extend([
+                format!("{}bash \"$0\" '' \"$1\"", escalation.prefix()),
+                script,
+                self.resume_after.to_string(),
+            ]),
+        }
+        Ok(argv)
+    }
 
-/// Enum to represent the detected Linux distribution.
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub enum OsDistribution {
-    Rhel,
-    Centos,
-    Unknown,
+    /// Records the exit code and removes the script file.
+    fn finish(&mut self, manifest: &RunManifest, code: Option<i32>) -> io::Result<()> {
+        if let (Some(record), Some(code)) = (&mut self.record, code) {
+            history::record_exit_code(record, code)?;
+            if let Err(e) = cockpit::write_metadata(record, manifest, Some(code)) {
+                self.warnings.push(format!("could not write run metadata: {}", e));
+            }
+            record.exit_code = Some(code);
+            record.has_output = record.output_path().is_file();
+        }
+        self.file.remove()
+    }
 }
 
-/// Enum to manage the overall state of the application.
-enum AppState {
-    Running,
-    Finished,
-    Saving,
+/// A script running in the terminal pane.
+struct LiveRun {
+    pane: TerminalPane,
+    /// Block and overall progress, estimated from the output.
+    progress: RunProgress,
+    /// Notices when the script goes quiet.
+    watchdog: Watchdog,
+    /// What the script reports about failures, for troubleshooting.
+    failures: FailureLog,
+    prepared: PreparedRun,
+    manifest: RunManifest,
+    /// Held until the script exits.
+    lock: Option<RunLock>,
+    /// Why recording the finished run failed, if it did.
+    error: Option<String>,
 }
 
-/// Enum to tell the main function what to do after the TUI exits.
-pub enum ActionAfterExit {
-    Quit,
-    RunScript(String),
+/// A run that could not start because the run lock was unavailable.
+struct PendingRun {
+    script: String,
+    manifest: RunManifest,
+    resume_after: usize,
+    /// Screen to return to when the run is abandoned.
+    parent: AppState,
+    error: LockError,
 }
 
 /// Holds the application's state.
 struct App {
     state: AppState,
-    menu_tree: Rc<RefCell<MenuNode>>,
-    nav_path: Vec<Rc<RefCell<MenuNode>>>,
-    selected_index: usize,
-    os_distro: OsDistribution,
-    reboot_requested: bool,
+    /// The menu tree, navigation, target and generation settings, apart from the screens.
+    model: Model,
+    privilege: PrivilegeInfo,
+    /// How `sudo ` prefixes are rewritten and how the script is launched.
+    escalation: Escalation,
+    settings: Option<SettingsPopup>,
+    theme: Theme,
+    /// Screen a parameter form, wizard or settings popup returns to when closed.
+    popup_parent: AppState,
+    index: Option<ItemIndex>,
+    order: Option<OrderView>,
+    palette: Option<Palette>,
+    confirm: Option<ConfirmRun>,
+    /// Problems found when generating, with the fixes on offer.
+    resolve: Option<ResolveDialog>,
+    /// `bash -n`/shellcheck findings for the script on the finished screen.
+    lint: Option<Lint>,
+    /// Results of the last preflight run; `None` while the checks are running.
+    preflight: Option<Preflight>,
+    /// The checks running in the background, until their results arrive.
+    preflight_task: Option<Receiver<Preflight>>,
+    /// Screen the preflight pane returns to; kept apart from `popup_parent`, which the finished screen relies on.
+    preflight_parent: AppState,
+    pending_run: Option<PendingRun>,
+    /// Whether the Selected Components panel has focus, and which entry is highlighted there.
+    selected_focused: bool,
+    selected_panel_index: usize,
+    /// IDs of items selected in this and earlier sessions, most recent first.
+    recent: Vec<String>,
+    /// "Recently Used" menu shown at the top of the root, built from earlier sessions.
+    recent_menu: Option<Rc<RefCell<MenuNode>>>,
+    /// IDs of the items marked with `*`, kept across sessions.
+    favorites: Vec<String>,
+    /// "Favorites" menu shown at the very top of the root.
+    favorites_menu: Option<Rc<RefCell<MenuNode>>>,
     filename_input: String,
+    save_format: SaveFormat,
+    /// Comparison of a saved script with the generated one, opened from the finished screen.
+    diff: Option<DiffView>,
+    /// The preview as it was when the generated script was last viewed (and so possibly saved);
+    /// the diff pane compares the preview against it.
+    viewed_script: Option<String>,
+    /// Whether the main screen shows the diff pane in place of the preview (`D`).
+    diff_pane: bool,
+    /// The diff pane, with the preview it was built for; rebuilt once the preview changes.
+    preview_diff: Option<(String, DiffView)>,
     save_status_message: Option<String>,
+    save_dialog: Option<SaveDialog>,
+    viewer: ScriptViewer,
+    /// The script preview on the main screen, in collapsible sections by menu.
+    outline: ScriptOutline,
+    /// Short-lived message explaining automatic selections or blocked toggles; cleared on the next key press.
+    toast: Option<String>,
+    param_form: Option<ParamForm>,
+    net_wizard: Option<NetWizard>,
+    lab_wizard: Option<LabWizard>,
+    job_wizard: Option<JobWizard>,
+    proxy_form: Option<Wizard>,
+    luks_wizard: Option<LuksWizard>,
+    luks_status: Option<LuksStatus>,
+    link_status: Option<LinkStatus>,
+    firewall: Option<FirewallStatus>,
+    cpu_wizard: Option<CpuWizard>,
+    remote_setup: Option<RemoteSetup>,
+    /// The last run on remote hosts; its hosts keep running while other screens are shown.
+    remote: Option<RemoteRun>,
+    history: Vec<RunRecord>,
+    history_index: usize,
+    /// Whether the selected history entry is open in the viewer.
+    history_viewing: bool,
+    autosave: Autosave,
+    /// Selection left by a session that did not exit cleanly, offered for restore at startup.
+    draft: Option<Draft>,
+    /// Where the last session quit, offered for restore at startup.
+    session: Option<Session>,
+    /// The last executed run, if it stopped before its last block; offered for resuming at startup.
+    unfinished: Option<(RunRecord, ResumePoint)>,
+    /// The script running (or last run) in the terminal pane.
+    live_run: Option<LiveRun>,
+    /// Checks on the failed run in the terminal pane.
+    troubleshoot: Option<Troubleshooter>,
+    help: Option<HelpOverlay>,
+    /// Screen the help overlay returns to; popups keep their own parent, so it cannot share `popup_parent`.
+    help_parent: AppState,
+    /// Whether `g` was pressed and the next digit picks a breadcrumb level.
+    jump_pending: bool,
+    /// Screen row and column ranges of the breadcrumb segments as last drawn, for mouse clicks.
+    breadcrumbs: Vec<(u16, u16, u16)>,
+    /// Wakes the event loop when a background task has news; `None` outside of it, e.g. in tests.
+    waker: Option<Waker>,
 }
 
-fn detect_os() -> OsDistribution {
-    if let Ok(content) = fs::read_to_string("/etc/os-release") {
-        for line in content.lines() {
-            if line.starts_with("ID=") {
-                let id = line.trim_start_matches("ID=").trim_matches('"');
-                return match id {
-                    "rhel" => OsDistribution::Rhel,
-                    "centos" => OsDistribution::Centos,
-                    _ => OsDistribution::Unknown,
-                };
-            }
-        }
+/// Popup state for entering an item's parameters before it gets selected.
+struct ParamForm {
+    item: Rc<RefCell<MenuNode>>,
+    wizard: Wizard,
+}
+
+impl ParamForm {
+    /// Opens a form pre-filled with the item's current parameter values.
+    fn new(item: &Rc<RefCell<MenuNode>>) -> ParamForm {
+        ParamForm { item: item.clone(), wizard: Wizard::for_params(item) }
+    }
+
+    /// Writes the edited values back to the item.
+    fn apply(&self) {
+        self.wizard.apply(&self.item);
     }
-    OsDistribution::Unknown
 }
 
 impl App {
@@ -117,169 +416,1810 @@ impl App {
     fn new() -> App {
         let os_distro = detect_os();
         let menu_tree = scripts::build_menu_tree(os_distro);
-        let nav_path = vec![menu_tree.clone()];
+        let recent = recent::load();
+        let recent_menu = recent::recent_menu(&menu_tree, &recent);
+        let favorites = favorites::load();
+        let favorites_menu = favorites::favorites_menu(&menu_tree, &favorites);
+        let privilege = PrivilegeInfo::detect();
+        let escalation = privilege.default_escalation();
+        let toast = privilege.warning(escalation);
+        let theme = Theme::new(theme::load_scheme());
+        let draft = Draft::load();
+        // A draft is only left behind by a session that died, so it is newer than the session file.
+        let session = if draft.is_some() { None } else { Session::load() };
+        let unfinished = history::last_unfinished_run();
+        let autosave = Autosave::new(draft::snapshot(&menu_tree, &format!("{:?}", os_distro)));
 
         App {
-            state: AppState::Running,
-            menu_tree,
-            nav_path,
-            selected_index: 0,
-            os_distro,
-            reboot_requested: false,
+            state: match (&draft, &session, &unfinished) {
+                (Some(_), _, _) => AppState::RestoreDraft,
+                (None, Some(_), _) => AppState::RestoreSession,
+                (None, None, Some(_)) => AppState::ResumeRun,
+                (None, None, None) => AppState::Running,
+            },
+            model: Model::new(menu_tree, os_distro),
+            privilege,
+            escalation,
+            settings: None,
+            theme,
+            popup_parent: AppState::Running,
+            index: None,
+            order: None,
+            palette: None,
+            confirm: None,
+            resolve: None,
+            lint: None,
+            preflight: None,
+            preflight_task: None,
+            preflight_parent: AppState::Running,
+            pending_run: None,
+            selected_focused: false,
+            selected_panel_index: 0,
+            recent,
+            recent_menu,
+            favorites,
+            favorites_menu,
             filename_input: String::new(),
+            save_format: SaveFormat::Script,
+            diff: None,
+            viewed_script: None,
+            diff_pane: false,
+            preview_diff: None,
             save_status_message: None,
+            save_dialog: None,
+            viewer: ScriptViewer::new(theme),
+            outline: ScriptOutline::new(theme),
+            toast,
+            param_form: None,
+            net_wizard: None,
+            luks_wizard: None,
+            luks_status: None,
+            link_status: None,
+            firewall: None,
+            cpu_wizard: None,
+            remote_setup: None,
+            remote: None,
+            lab_wizard: None,
+            job_wizard: None,
+            proxy_form: None,
+            history: Vec::new(),
+            history_index: 0,
+            history_viewing: false,
+            autosave,
+            draft,
+            session,
+            unfinished,
+            live_run: None,
+            troubleshoot: None,
+            help: None,
+            help_parent: AppState::Running,
+            jump_pending: false,
+            breadcrumbs: Vec::new(),
+            waker: None,
         }
     }
 
     /// Generates the shell commands based on the user's selections.
     fn generate_commands(&self, reboot: bool) -> String {
-        let mut command_text = String::new();
-        command_text.push_str("#!/bin/bash\n");
-        command_text.push_str(&format!("# Commands generated for {:?} by RHEL/CentOS TUI Manager\n", self.os_distro));
-        command_text.push_str("# Save this script and run it with sudo: sudo bash ./script.sh\n\n");
-
-        let mut scripts = Vec::new();
-        self.menu_tree.borrow().get_selected_scripts(&mut scripts);
-        
-        if scripts.is_empty() {
-             command_text.push_str("\n# No options selected.\n");
+        self.escalation.apply(&self.model.generate_commands(reboot))
+    }
+
+    /// The commands as run from the TUI: like [`App::generate_commands`], with checkpoints so the
+    /// run can be resumed if it fails or is interrupted.
+    fn generate_run_commands(&self, reboot: bool) -> String {
+        self.escalation.apply(&self.model.generate_run_commands(reboot))
+    }
+
+    /// Describes the current selection for the run metadata the Cockpit companion page reads.
+    fn run_manifest(&self) -> RunManifest {
+        RunManifest::from_selection(&self.model.menu_tree, &self.model.target_name(), self.model.backend.as_ref())
+    }
+
+    /// Renders the selection in `format` as the files to write for a save to `path`: the file
+    /// itself, plus the script next to it for Packer templates, which reference it by name.
+    fn save_files(&self, format: SaveFormat, path: &Path) -> Vec<(PathBuf, String)> {
+        let target = self.model.target_name();
+        let script = self.generate_commands(self.model.reboot_requested);
+        let content = match format {
+            SaveFormat::Script => script,
+            SaveFormat::Runbook => self.escalation.apply(&generate_runbook(&self.model.menu_tree, &target, self.model.backend.as_ref())),
+            SaveFormat::Terraform => terraform_provisioner(&script, &target),
+            SaveFormat::CloudInit => cloud_init(&script, &target),
+            SaveFormat::Vagrant => vagrantfile(&script, &target),
+            SaveFormat::Json => dump::selection_json(&self.model, self.escalation, self.model.reboot_requested),
+            SaveFormat::Packer => {
+                let stem = path.file_name().and_then(|name| name.to_str()).and_then(|name| name.split('.').next()).unwrap_or("");
+                let script_file = format!("{}.sh", if stem.is_empty() { "redcent-setup" } else { stem });
+                let script_path = path.with_file_name(&script_file);
+                let template = packer_provisioner(&script_file, &target, self.model.reboot_requested);
+                return vec![(path.to_path_buf(), template), (script_path, script)];
+            }
+        };
+        vec![(path.to_path_buf(), content)]
+    }
+
+    /// Switches the authoring target to the next distribution, so one selection can be
+    /// rendered for every family in a mixed fleet.
+    fn cycle_target(&mut self) {
+        self.model.cycle_target();
+        self.toast = Some(format!("Authoring for {:?} ({})", self.model.target, self.model.backend.name()));
+    }
+
+    fn find_item(&self, id: &str) -> Option<Rc<RefCell<MenuNode>>> {
+        self.model.find_item(id)
+    }
+
+    /// The Favorites and Recently Used menus shown above the tree at the root, those that have items.
+    fn shortcut_menus(&self) -> Vec<Rc<RefCell<MenuNode>>> {
+        self.favorites_menu.iter().chain(&self.recent_menu).cloned().collect()
+    }
+
+    /// Adds the highlighted item to the favorites or removes it, rebuilding the Favorites menu.
+    fn toggle_favorite(&mut self, node: &Rc<RefCell<MenuNode>>) {
+        let name = node.borrow().name().to_string();
+        let added = match favorites::toggle(&mut self.favorites, node.borrow().id()) {
+            Ok(added) => added,
+            Err(e) => {
+                self.toast = Some(format!("Could not save the favorites: {}", e));
+                return;
+            }
+        };
+        // The highlight stays on the same entry although the root list grows or shrinks above it.
+        let highlighted = get_visible_nodes(&self.model.nav_path, &self.shortcut_menus()).get(self.model.selected_index).map(|(_, node)| node.clone());
+        self.favorites_menu = favorites::favorites_menu(&self.model.menu_tree, &self.favorites);
+        if self.model.nav_path.len() == 1
+            && let Some(highlighted) = highlighted
+        {
+            let visible = get_visible_nodes(&self.model.nav_path, &self.shortcut_menus());
+            let before = self.model.selected_index;
+            // A node listed twice (in Favorites and its own menu) keeps the occurrence below the shortcuts.
+            let occurrences: Vec<usize> = visible.iter().enumerate().filter(|(_, (_, node))| Rc::ptr_eq(node, &highlighted)).map(|(n, _)| n).collect();
+            self.model.selected_index = occurrences.iter().copied().find(|&n| n >= before).or(occurrences.last().copied()).unwrap_or(0);
+        }
+        self.toast = Some(if added { format!("Added {} to Favorites", name) } else { format!("Removed {} from Favorites", name) });
+    }
+
+    /// Toggles an item through the dependency rules, explaining automatic or refused changes in a toast.
+    fn toggle_item(&mut self, node: &Rc<RefCell<MenuNode>>) {
+        match self.model.toggle_item(node) {
+            Ok(ToggleOutcome::Selected { auto_selected }) => {
+                // Usage tracking is best-effort; a read-only state directory must not block selection.
+                let _ = recent::record(&mut self.recent, node.borrow().id());
+                if !auto_selected.is_empty() {
+                    self.toast = Some(format!("{} requires {}; selected automatically", node.borrow().name(), auto_selected.join(", ")));
+                }
+            }
+            Ok(ToggleOutcome::Deselected) => {}
+            Err(reason) => self.toast = Some(reason),
+        }
+    }
+
+    /// Recursively selects (or deselects) every item under `menu` and reports what was refused.
+    fn set_all(&mut self, menu: &Rc<RefCell<MenuNode>>, select: bool) {
+        let (changed, refused) = if select {
+            let (changed, refused) = redcent_tui::select_all(&self.model.menu_tree, menu);
+            self.drop_internet_items(changed, refused)
+        } else {
+            redcent_tui::deselect_all(&self.model.menu_tree, menu)
+        };
+        let verb = if select { "Selected" } else { "Deselected" };
+        self.toast = Some(bulk_summary(&format!("{} under {}:", verb, menu.borrow().name()), changed, &refused));
+    }
+
+    /// Takes back, in offline mode, what a bulk selection selected that needs the internet, and
+    /// adjusts its count of selected and list of refused items.
+    fn drop_internet_items(&mut self, changed: usize, mut refused: Vec<String>) -> (usize, Vec<String>) {
+        let dropped = self.model.deselect_internet_items();
+        refused.extend(dropped.iter().map(|name| format!("{} needs the internet (offline mode)", name)));
+        (changed.saturating_sub(dropped.len()), refused)
+    }
+
+    /// Executes a command palette line and reports the result in a toast.
+    fn run_palette_command(&mut self, input: &str) {
+        let command = match PaletteCommand::parse(input) {
+            Ok(command) => command,
+            Err(usage) => {
+                self.toast = Some(usage);
+                return;
+            }
+        };
+        match command {
+            PaletteCommand::Select(pattern) | PaletteCommand::Deselect(pattern) if palette::matching_items(&self.model.menu_tree, &pattern).is_empty() => {
+                self.toast = Some(format!("No item IDs match '{}'", pattern));
+            }
+            PaletteCommand::Select(pattern) => {
+                let (changed, refused) = redcent_tui::select_items(&self.model.menu_tree, &palette::matching_items(&self.model.menu_tree, &pattern));
+                let (changed, refused) = self.drop_internet_items(changed, refused);
+                self.toast = Some(bulk_summary("Selected", changed, &refused));
+            }
+            PaletteCommand::Deselect(pattern) => {
+                let (changed, refused) = redcent_tui::deselect_items(&self.model.menu_tree, &palette::matching_items(&self.model.menu_tree, &pattern));
+                self.toast = Some(bulk_summary("Deselected", changed, &refused));
+            }
+            PaletteCommand::Goto(query) => {
+                let Some(path) = palette::goto_target(&self.model.menu_tree, &query).and_then(|node| redcent_tui::path_to(&self.model.menu_tree, &node)) else {
+                    self.toast = Some(format!("Nothing matches '{}'", query));
+                    return;
+                };
+                let target = path.last().unwrap().clone();
+                if matches!(&*target.borrow(), MenuNode::Menu { .. }) {
+                    self.model.nav_path = path;
+                    self.model.selected_index = 0;
+                } else {
+                    // Open the item's menu with the item highlighted.
+                    self.model.nav_path = path[..path.len() - 1].to_vec();
+                    self.model.selected_index = get_visible_nodes(&self.model.nav_path, &self.shortcut_menus()).iter()
+                        .position(|(_, node)| Rc::ptr_eq(node, &target))
+                        .unwrap_or(0);
+                }
+            }
+        }
+    }
+
+    /// Selects or deselects an item, opening its wizard or parameter form first when it needs one.
+    fn activate_item(&mut self, node: &Rc<RefCell<MenuNode>>) {
+        if node.borrow().is_selected() || !self.edit_item(node) {
+            self.toggle_item(node);
+        }
+    }
+
+    /// Opens the item's wizard, or else its parameter form, returning to the current screen when
+    /// it is done; false if the item has no parameters.
+    fn edit_item(&mut self, node: &Rc<RefCell<MenuNode>>) -> bool {
+        let id = node.borrow().id();
+        self.popup_parent = self.state;
+        if id == LAB_ACCOUNTS_ITEM {
+            self.lab_wizard = Some(LabWizard::new(node));
+            self.state = AppState::LabWizard;
+        } else if id == SCHEDULED_JOB_ITEM {
+            self.job_wizard = Some(JobWizard::new(node));
+            self.state = AppState::JobWizard;
+        } else if id == CPU_ISOLATION_ITEM {
+            self.cpu_wizard = Some(CpuWizard::new(node));
+            self.state = AppState::CpuWizard;
+        } else if netwizard::needs_net_wizard(&node.borrow()) {
+            self.net_wizard = Some(NetWizard::new(node));
+            self.state = AppState::NetWizard;
+        } else if luks::needs_luks_wizard(&node.borrow()) {
+            self.luks_wizard = Some(LuksWizard::new(node));
+            self.state = AppState::LuksWizard;
+        } else if node.borrow().has_params() {
+            self.param_form = Some(ParamForm::new(node));
+            self.state = AppState::EditingParams;
         } else {
-            for script_fn in scripts {
-                command_text.push_str(script_fn());
-                command_text.push('\n');
+            return false;
+        }
+        true
+    }
+
+    /// Opens the item's editor like [`App::edit_item`], on the step holding the `key` field.
+    fn edit_item_field(&mut self, node: &Rc<RefCell<MenuNode>>, key: &str) {
+        if !self.edit_item(node) {
+            return;
+        }
+        let wizard = match self.state {
+            AppState::LabWizard => self.lab_wizard.as_mut().map(|w| &mut w.wizard),
+            AppState::JobWizard => self.job_wizard.as_mut().map(|w| &mut w.wizard),
+            AppState::CpuWizard => self.cpu_wizard.as_mut().map(|w| &mut w.wizard),
+            AppState::NetWizard => self.net_wizard.as_mut().map(|w| &mut w.wizard),
+            AppState::LuksWizard => self.luks_wizard.as_mut().map(|w| &mut w.wizard),
+            _ => self.param_form.as_mut().map(|form| &mut form.wizard),
+        };
+        if let Some(wizard) = wizard {
+            wizard.focus_field(key);
+        }
+    }
+
+    /// Selects the item an editor was finished for, unless it was already selected and only had
+    /// its values changed, and checks again when the editor was opened from the resolution dialog.
+    fn finish_editing(&mut self, item: &Rc<RefCell<MenuNode>>) {
+        if !item.borrow().is_selected() {
+            self.toggle_item(item);
+        }
+        if self.state == AppState::Resolve {
+            self.refresh_resolve();
+        }
+    }
+
+    /// Switches to the finished screen with the viewer focused at the top of the script.
+    /// Refuses while a selected item still has an unresolved `{{placeholder}}`.
+    fn enter_finished(&mut self, reboot: bool) {
+        let problems = self.model.problems();
+        if !problems.is_empty() {
+            self.resolve = Some(ResolveDialog::new(problems, reboot));
+            self.state = AppState::Resolve;
+            return;
+        }
+        self.popup_parent = AppState::Running;
+        self.state = AppState::Finished;
+        self.model.reboot_requested = reboot;
+        self.lint = Some(Lint::run(&self.generate_commands(reboot)));
+        self.viewer.home();
+        self.viewer.focused = true;
+        self.viewed_script = Some(self.generate_commands(false));
+    }
+
+    /// Looks for problems again after a fix, generating the script once none are left.
+    fn refresh_resolve(&mut self) {
+        let Some(dialog) = self.resolve.as_mut() else { return };
+        let problems = self.model.problems();
+        if problems.is_empty() {
+            let reboot = dialog.reboot;
+            self.resolve = None;
+            self.enter_finished(reboot);
+        } else {
+            dialog.refresh(problems);
+        }
+    }
+
+    /// Opens the parameter form of the first item with an unresolved placeholder, focused on that field.
+    fn jump_to_unresolved(&mut self) {
+        let Some((item, key)) = self.model.first_unresolved() else {
+            self.toast = Some("Every selected item has its parameters filled in".to_string());
+            return;
+        };
+        let has_param = match &*item.borrow() {
+            MenuNode::Item { params, .. } => params.iter().any(|p| p.key == key),
+            MenuNode::Menu { .. } => false,
+        };
+        if !has_param {
+            self.toast = Some(format!("{} uses {{{{{}}}}} but has no parameter for it", item.borrow().name(), key));
+            return;
+        }
+        self.edit_item_field(&item, &key);
+    }
+
+    /// Takes the run lock (optionally replacing a stale one) and runs `script` in the terminal
+    /// pane, skipping its first `resume_after` blocks. When the lock is unavailable the run is
+    /// parked on the lock screen instead; without a pseudo-terminal it is handed over to run after
+    /// leaving the TUI.
+    fn start_run(&mut self, script: String, manifest: RunManifest, resume_after: usize, take_over: bool) -> Option<ActionAfterExit> {
+        let lock = if take_over { runlock::take_over() } else { runlock::acquire() };
+        match lock {
+            Ok(lock) => {
+                let prepared = match PreparedRun::new(&script, &manifest, resume_after) {
+                    Ok(prepared) => prepared,
+                    Err(e) => {
+                        self.toast = Some(format!("Could not write the script to run: {}", e));
+                        return None;
+                    }
+                };
+                let argv = match prepared.argv(self.escalation) {
+                    Ok(argv) => argv,
+                    Err(e) => {
+                        self.toast = Some(format!("Not running the script: {}", e));
+                        return None;
+                    }
+                };
+                let (cols, rows) = crossterm::terminal::size().unwrap_or((80, 24));
+                let progress = RunProgress::new(&script);
+                let rows = rows.saturating_sub(5 + progress.height()).max(1);
+                match TerminalPane::spawn(&argv, rows, cols.saturating_sub(2).max(1), self.waker.clone()) {
+                    Ok(pane) => {
+                        self.live_run = Some(LiveRun { pane, progress, watchdog: Watchdog::start(), failures: FailureLog::start(), prepared, manifest, lock: Some(lock), error: None });
+                        self.state = AppState::Terminal;
+                        None
+                    }
+                    Err(_) => Some(ActionAfterExit::RunScript(prepared, manifest, self.escalation, lock)),
+                }
+            }
+            Err(error) => {
+                let parent = match (&self.pending_run, self.state) {
+                    (Some(pending), AppState::RunLocked) => pending.parent,
+                    _ => self.state,
+                };
+                self.pending_run = Some(PendingRun { script, manifest, resume_after, parent, error });
+                self.state = AppState::RunLocked;
+                None
+            }
+        }
+    }
+
+    /// Asks for confirmation before running `script` on this host, unless it is authored for a
+    /// target with another package manager.
+    fn confirm_run(&mut self, script: &str) {
+        if let Some(reason) = self.model.foreign_target() {
+            self.toast = Some(format!("Not running here: {}. Save it, copy it or run it on remote hosts ([R]).", reason));
+            return;
+        }
+        self.confirm = Some(ConfirmRun::new(
+            plan_stages(&self.model.menu_tree, self.model.backend.as_ref()), script, self.model.backend.name(), self.model.reboot_requested, self.escalation.label(),
+            self.space_check(),
+        ));
+        self.state = AppState::ConfirmRun;
+    }
+
+    /// Runs the preflight checks against the repositories the current selection downloads from.
+    fn run_preflight(&mut self) {
+        let selected = ordered_selected_items(&self.model.menu_tree);
+        let ids: Vec<&str> = selected.iter().map(|item| item.borrow().id()).collect();
+        // Offline mode installs from the local repository and refuses items needing the internet.
+        let needs_network = self.model.offline.is_none() && selected.iter()
+            .any(|item| item.borrow().script(self.model.backend.as_ref()).is_some_and(|script| script.requires_network));
+        let claims = space_claims(&self.model.menu_tree, self.model.backend.as_ref());
+        let space_package_manager = self.space_package_manager();
+        let mut repos = preflight::repo_urls(&ids, self.model.target);
+        // On a host of the target's family, the repositories it has enabled stand in for the default mirror.
+        let package_manager = self.model.os_distro.package_backend().name();
+        if package_manager == self.model.target.package_backend().name() {
+            let enabled = preflight::enabled_repos(package_manager);
+            if !enabled.is_empty() {
+                repos.splice(0..1, enabled);
             }
         }
+        // COPR projects are checked for the host's release when it is an Enterprise Linux host.
+        let scripts: Vec<String> = selected.iter().map(|item| item.borrow().rendered_script(self.model.backend.as_ref())).collect();
+        let release = if package_manager == "dnf" { preflight::host_release() } else { String::new() };
+        repos.extend(preflight::copr_urls(&scripts, &release));
+        let host = self.model.os_distro;
+        self.spawn_preflight(move || Preflight::run(&repos, needs_network, host, &SpaceCheck::run(claims, space_package_manager)));
+    }
+
+    /// Runs `checks` on a thread of their own and waits for the results in [`App::pump_preflight`].
+    fn spawn_preflight(&mut self, checks: impl FnOnce() -> Preflight + Send + 'static) {
+        let (sender, results) = mpsc::channel();
+        let waker = self.waker.clone();
+        // The checks wait on the network and the package manager; the screen stays responsive meanwhile.
+        thread::spawn(move || {
+            let _ = sender.send(checks());
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        });
+        self.preflight = None;
+        self.preflight_task = Some(results);
+    }
 
-        if reboot {
-            command_text.push_str("\necho 'Installation complete. Rebooting now...'\n");
-            command_text.push_str("sudo reboot\n");
+    /// Takes in the preflight results once the background checks are done.
+    fn pump_preflight(&mut self) {
+        if let Some(task) = &self.preflight_task
+            && let Ok(preflight) = task.try_recv()
+        {
+            self.preflight = Some(preflight);
+            self.preflight_task = None;
         }
+    }
 
-        command_text
+    /// Opens the preflight pane and starts the checks; it shows a progress note until they are done.
+    fn start_preflight(&mut self) {
+        self.preflight_parent = self.state;
+        self.state = AppState::Preflight;
+        self.run_preflight();
     }
-    
-    fn get_selected_items(&self) -> Vec<String> {
-        let mut names = Vec::new();
-        self.menu_tree.borrow().get_selected_item_names(&mut names);
-        names
+
+    /// Estimates the selection's disk use against this host's free space. Packages are only sized
+    /// when the script targets this host's package manager.
+    fn space_check(&self) -> SpaceCheck {
+        SpaceCheck::run(space_claims(&self.model.menu_tree, self.model.backend.as_ref()), self.space_package_manager())
+    }
+
+    fn space_package_manager(&self) -> Option<&'static str> {
+        let host = self.model.os_distro.package_backend();
+        (host.name() == self.model.backend.name()).then(|| host.name())
     }
+
+    fn enter_history(&mut self) {
+        self.history = history::list_runs();
+        self.history_index = 0;
+        self.history_viewing = false;
+        self.state = AppState::History;
+    }
+
+    /// The selected run's script followed by its captured output, for the history viewer.
+    fn history_content(&self) -> String {
+        let Some(record) = self.history.get(self.history_index) else { return String::new() };
+        let mut content = record.script().unwrap_or_else(|e| format!("# Could not read script: {}\n", e));
+        if let Some(output) = record.output() {
+            content.push_str(&format!("\n# ----- Output (exit code: {}) -----\n", record.exit_code.map(|c| c.to_string()).unwrap_or_else(|| "unknown".to_string())));
+            content.push_str(&output);
+        }
+        content
+    }
+
+    /// Selected items in script order, as listed in the Selected Components panel.
+    fn selected_nodes(&self) -> Vec<Rc<RefCell<MenuNode>>> {
+        self.model.selected_nodes()
+    }
+
+    /// Returns to the `level`th entry (1-based) of the navigation path, highlighting the menu it was left through.
+    fn jump_to_level(&mut self, level: usize) {
+        if level == 0 || level >= self.model.nav_path.len() {
+            return;
+        }
+        let left_through = self.model.nav_path[level].clone();
+        self.model.nav_path.truncate(level);
+        self.model.selected_index = get_visible_nodes(&self.model.nav_path, &self.shortcut_menus()).iter()
+            .position(|(_, node)| Rc::ptr_eq(node, &left_through))
+            .unwrap_or(0);
+    }
+
+    /// Whether the current screen takes text input, where `?` has to be typed rather than open the help.
+    fn typing(&self) -> bool {
+        match self.state {
+            AppState::Saving | AppState::DiffInput | AppState::EditingParams | AppState::NetWizard
+            | AppState::LabWizard | AppState::JobWizard | AppState::LuksWizard | AppState::CpuWizard | AppState::RemoteSetup | AppState::ProxyForm
+            | AppState::Palette | AppState::ConfirmRun => true,
+            AppState::Index => self.index.as_ref().is_some_and(|index| index.editing_filter),
+            AppState::Running => self.outline.searching,
+            AppState::Finished | AppState::History => self.viewer.searching,
+            // Keys go to the script until it exits.
+            AppState::Terminal => self.live_run.as_ref().is_some_and(|run| run.lock.is_some()),
+            AppState::Troubleshoot => self.troubleshoot.as_ref().is_some_and(Troubleshooter::is_running),
+            _ => false,
+        }
+    }
+
+    /// Opens the key reference for the current screen, the bindings active right now first.
+    fn open_help(&mut self) {
+        let (title, mut sections) = match self.state {
+            AppState::Running if self.outline.searching => ("Main Menu", vec![&help::SEARCH, &help::MAIN_MENU, &help::PREVIEW]),
+            AppState::Running if self.selected_focused => ("Main Menu", vec![&help::SELECTED_PANEL, &help::MAIN_MENU, &help::PREVIEW]),
+            AppState::Running if self.outline.focused => ("Main Menu", vec![&help::PREVIEW, &help::MAIN_MENU, &help::SELECTED_PANEL]),
+            AppState::Running => ("Main Menu", vec![&help::MAIN_MENU, &help::SELECTED_PANEL, &help::PREVIEW]),
+            AppState::Finished if self.viewer.searching => ("Generated Script", vec![&help::SEARCH, &help::FINISHED, &help::SCRIPT_VIEWER]),
+            AppState::Finished => ("Generated Script", vec![&help::FINISHED, &help::SCRIPT_VIEWER]),
+            AppState::Saving => ("Save", vec![&help::SAVING, &help::FINISHED]),
+            AppState::DiffInput => ("Compare", vec![&help::PATH_INPUT, &help::FINISHED]),
+            AppState::Diff => ("Script Diff", vec![&help::DIFF]),
+            AppState::History if self.history_viewing => ("Run History", vec![&help::SCRIPT_VIEWER, &help::HISTORY]),
+            AppState::History => ("Run History", vec![&help::HISTORY, &help::SCRIPT_VIEWER]),
+            AppState::Index => ("All Items", vec![&help::INDEX]),
+            AppState::Order => ("Script Order", vec![&help::ORDER]),
+            AppState::Preflight => ("Preflight", vec![&help::PREFLIGHT]),
+            AppState::RunLocked => ("Run Locked", vec![&help::RUN_LOCKED]),
+            AppState::EditingParams | AppState::NetWizard | AppState::LabWizard | AppState::JobWizard | AppState::LuksWizard | AppState::RemoteSetup => {
+                ("Parameters", vec![&help::FORM])
+            }
+            AppState::ProxyForm => ("Proxy", vec![&help::FORM]),
+            AppState::Remote => ("Remote Run", vec![&help::REMOTE]),
+            AppState::CpuWizard => ("CPU Isolation", vec![&help::CPU_PICKER, &help::FORM]),
+            AppState::LuksStatus => ("Disk Encryption Status", vec![&help::LUKS_STATUS]),
+            AppState::LinkStatus => ("Link Aggregation Status", vec![&help::LINK_STATUS]),
+            AppState::Firewall => ("Firewall", vec![&help::FIREWALL]),
+            AppState::Settings => ("Settings", vec![&help::SETTINGS]),
+            AppState::Palette => ("Command Palette", vec![&help::PALETTE]),
+            AppState::ConfirmRun => ("Confirm Run", vec![&help::CONFIRM]),
+            AppState::Resolve => ("Resolve Problems", vec![&help::RESOLVE]),
+            AppState::RestoreDraft => ("Restore Draft", vec![&help::RESTORE_DRAFT]),
+            AppState::RestoreSession => ("Restore Session", vec![&help::RESTORE_SESSION]),
+            AppState::ResumeRun => ("Resume Last Run", vec![&help::RESUME_RUN]),
+            AppState::Terminal => ("Script Terminal", vec![&help::TERMINAL]),
+            AppState::Troubleshoot => ("Troubleshoot", vec![&help::TROUBLESHOOT]),
+            AppState::Help => return,
+        };
+        sections.push(&help::HELP);
+        self.help = Some(HelpOverlay::new(title, sections));
+        self.help_parent = self.state;
+        self.state = AppState::Help;
+    }
+
+    /// The screen after the startup prompts already answered: the session prompt, then the resume
+    /// prompt while an unfinished run is pending, otherwise the main menu.
+    fn startup_state(&self) -> AppState {
+        if self.session.is_some() {
+            AppState::RestoreSession
+        } else if self.unfinished.is_some() {
+            AppState::ResumeRun
+        } else {
+            AppState::Running
+        }
+    }
+
+    /// Feeds the terminal pane its script's output, and records the run once the script exits.
+    fn pump_run(&mut self) {
+        let Some(run) = self.live_run.as_mut() else { return };
+        let output = run.pane.pump();
+        run.progress.feed(&output);
+        run.watchdog.feed(&output);
+        run.failures.feed(&output);
+        if run.lock.is_some()
+            && let Some(code) = run.pane.exit_code()
+        {
+            run.progress.finish(code);
+            if let Err(e) = run.prepared.finish(&run.manifest, Some(code)) {
+                run.error = Some(format!("could not record the exit code: {}", e));
+            }
+            run.lock = None;
+        }
+    }
+
+    /// Runs `record`'s script again, skipping the blocks it completed.
+    fn resume_run(&mut self, record: &RunRecord) -> Option<ActionAfterExit> {
+        let Some(point) = record.resume_point() else {
+            self.toast = Some(format!("The run of {} completed or cannot be resumed", history::display_timestamp(&record.name)));
+            return None;
+        };
+        let script = record.script().ok()?;
+        self.start_run(script, RunManifest::from_record(record), point.completed, false)
+    }
+
+    /// Applies the crash-recovery draft: its target, parameter values and selection.
+    fn restore_draft(&mut self) {
+        let Some(draft) = self.draft.take() else { return };
+        self.restore_selection(&draft, "Restored");
+    }
+
+    /// Applies the session saved at the last quit: its selection, then the menus that were open
+    /// and the highlighted row, as far as they still exist.
+    fn restore_session(&mut self) {
+        let Some(session) = self.session.take() else { return };
+        self.restore_selection(&session.selection, "Restored");
+        self.model.nav_path.truncate(1);
+        for name in &session.nav_path {
+            let current = self.model.nav_path.last().unwrap().clone();
+            let next = match &*current.borrow() {
+                MenuNode::Menu { children, .. } => children.iter()
+                    .find(|child| matches!(&*child.borrow(), MenuNode::Menu { name: menu, .. } if menu == name))
+                    .cloned(),
+                MenuNode::Item { .. } => None,
+            };
+            let Some(next) = next else { break };
+            self.model.nav_path.push(next);
+        }
+        let visible = get_visible_nodes(&self.model.nav_path, &self.shortcut_menus()).len();
+        self.model.selected_index = session.selected_index.min(visible.saturating_sub(1));
+    }
+
+    /// Switches to the draft's target and selects its items, summing up in a toast what `verb`
+    /// (e.g. `Restored`) changed. Returns the reasons for items left out.
+    fn restore_selection(&mut self, draft: &Draft, verb: &str) -> Vec<String> {
+        let targets = [OsDistribution::Rhel, OsDistribution::Centos, OsDistribution::Debian, OsDistribution::Ubuntu];
+        if let Some(target) = targets.into_iter().find(|target| format!("{:?}", target) == draft.target) {
+            self.model.set_target(target);
+        }
+        let (changed, refused) = draft.apply(&self.model.menu_tree);
+        self.toast = Some(bulk_summary(verb, changed, &refused));
+        refused
+    }
+
+    /// Opens the menu with a selection given by `--apply` instead of offering to restore the
+    /// last session or draft.
+    fn apply_selection(&mut self, selection: &Draft) {
+        self.draft = None;
+        self.session = None;
+        self.unfinished = None;
+        self.state = AppState::Running;
+        self.restore_selection(selection, "Applied");
+    }
+}
+
+/// Reads the selection for `--apply` from `path`, or from standard input when it is `-`.
+fn load_selection(path: &str) -> Result<Draft, String> {
+    let text = if path == "-" {
+        io::read_to_string(io::stdin())
+    } else {
+        fs::read_to_string(path)
+    };
+    text.map_err(|e| format!("could not read {}: {}", path, e))
+        .and_then(|text| dump::parse_selection(&text).map_err(|e| format!("could not apply {}: {}", path, e)))
+}
+
+/// Applies `selection` and runs the resulting script without opening the menu, for `--apply
+/// FILE --yes`. Anything short of the exact selection, a problem left for the resolution
+/// dialog or a run already in progress stops it before the script is written.
+fn apply_headless(selection: &Draft) -> Result<(), Box<dyn Error>> {
+    let mut app = App::new();
+    let refused = app.restore_selection(selection, "Applied");
+    console::heading(&app.toast.take().unwrap_or_default());
+    if !refused.is_empty() {
+        console::error("not running: the selection could not be applied as given.");
+        process::exit(1);
+    }
+    if let Some(reason) = app.model.foreign_target() {
+        console::error(&format!("not running: {}; save it with --apply FILE without --yes instead.", reason));
+        process::exit(1);
+    }
+    let problems = app.model.problems();
+    if !problems.is_empty() {
+        for problem in &problems {
+            console::warning(&problem.describe());
+        }
+        console::error("not running: resolve these problems in the menu (redcent-tui --apply FILE without --yes).");
+        process::exit(1);
+    }
+    let _lock = match runlock::acquire() {
+        Ok(lock) => lock,
+        Err(LockError::Busy(holder)) => {
+            console::error(&format!("another run is in progress ({}).", holder.describe()));
+            process::exit(1);
+        }
+        Err(LockError::Io(e)) => return Err(e.into()),
+    };
+    let manifest = app.run_manifest();
+    let prepared = PreparedRun::new(&app.generate_run_commands(false), &manifest, 0)?;
+    if !run_prepared(prepared, &manifest, app.escalation)? {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// Runs a prepared script in this terminal, outside the TUI, and reports how it went. Returns
+/// whether it succeeded.
+fn run_prepared(mut prepared: PreparedRun, manifest: &RunManifest, escalation: Escalation) -> Result<bool, Box<dyn Error>> {
+    if prepared.resume_after > 0 {
+        console::note(&format!("Resuming: skipping the {} block(s) completed by the earlier run.", prepared.resume_after));
+    }
+    console::listing(&format!("SCRIPT ({})", prepared.file.path().display()), &prepared.file.read()?);
+    match escalation {
+        Escalation::None => console::heading("Running the script as root"),
+        other => console::heading(&format!("Running the script with {}", other.label())),
+    }
+
+    let argv = prepared.argv(escalation)?;
+    let status = Command::new(&argv[0]).args(&argv[1..]).status()?;
+    prepared.finish(manifest, status.code())?;
+    for warning in &prepared.warnings {
+        console::warning(warning);
+    }
+    if let (Some(record), Some(_)) = (&prepared.record, status.code()) {
+        console::note(&format!("Run recorded in {}", record.dir.display()));
+    }
+
+    println!();
+    if status.success() {
+        console::success("Script executed successfully.");
+    } else {
+        console::failure("Script execution failed. Please check the output above.");
+        if let Some(output) = prepared.record.as_ref().and_then(RunRecord::output) {
+            let mut failures = FailureLog::start();
+            failures.feed(output.as_bytes());
+            for (id, block) in failures.steps() {
+                console::note(&match block {
+                    Some(n) => format!("Failed: {}, command {}", id, n),
+                    None => format!("Failed in {}", id),
+                });
+            }
+            for hint in failures.hints() {
+                console::note(&format!("• {}", hint));
+            }
+        }
+    }
+    Ok(status.success())
+}
+
+/// `export-config [FILE]` and `import-config FILE`: move the configuration, session and recently
+/// used items to another workstation as one archive.
+fn transfer_config(command: &str, file: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let Some(dirs) = archive::Dirs::current() else {
+        console::error("cannot determine the configuration directory; is HOME set?");
+        process::exit(1);
+    };
+    let result = if command == "export-config" {
+        let file = file.map(String::from).unwrap_or_else(|| format!("redcent-tui-config-{}.tar.gz", history::timestamp_now()));
+        archive::export(&dirs, Path::new(&file)).map(|files| (format!("Exported to {}", file), files))
+    } else {
+        let Some(file) = file else {
+            console::error("import-config needs the archive to import");
+            process::exit(1);
+        };
+        archive::import(&dirs, Path::new(file)).map(|files| (format!("Imported {}", file), files))
+    };
+    match result {
+        Ok((summary, files)) => {
+            console::heading(&summary);
+            for file in files {
+                console::note(&format!("  {}", file.display()));
+            }
+            Ok(())
+        }
+        Err(e) => {
+            console::error(&format!("{} failed: {}", command, e));
+            process::exit(1);
+        }
+    }
+}
+
+/// Prints the selection saved by the last session (or left in a draft) as JSON, for build
+/// pipelines that run `redcent-tui --dump-json` instead of the interactive menu.
+fn dump_json() -> Result<(), Box<dyn Error>> {
+    let mut app = App::new();
+    match app.state {
+        AppState::RestoreDraft => app.restore_draft(),
+        AppState::RestoreSession => app.restore_session(),
+        _ => {}
+    }
+    if ordered_selected_items(&app.model.menu_tree).is_empty() {
+        console::error("no saved selection to describe; select items in the menu and quit with q to save one.");
+        process::exit(1);
+    }
+    print!("{}", dump::selection_json(&app.model, app.escalation, false));
+    Ok(())
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--no-color") {
+        console::disable_color();
+    }
+    if let Some(command @ ("export-config" | "import-config")) = args.first().map(String::as_str) {
+        return transfer_config(command, args.get(1).map(String::as_str));
+    }
+    if args.iter().any(|arg| arg == "--dump-json") {
+        return dump_json();
+    }
+    let selection = match args.iter().position(|arg| arg == "--apply") {
+        Some(n) => match args.get(n + 1).map(|path| load_selection(path)) {
+            Some(Ok(selection)) => Some(selection),
+            Some(Err(e)) => {
+                console::error(&e);
+                process::exit(1);
+            }
+            None => {
+                console::error("--apply needs a file name, or - to read the selection from standard input.");
+                process::exit(1);
+            }
+        },
+        None => None,
+    };
+    if args.iter().any(|arg| arg == "--yes") {
+        let Some(selection) = &selection else {
+            console::error("--yes runs a selection without asking; give one with --apply FILE.");
+            process::exit(1);
+        };
+        return apply_headless(selection);
+    }
+
+    let mut app = App::new();
+    if let Some(selection) = &selection {
+        app.apply_selection(selection);
+    }
+    // Inline mode draws in the normal screen buffer, so the last screen stays in the scrollback.
+    let inline = args.iter().any(|arg| arg == "--inline");
+    tty::install_panic_hook();
+    let guard = TerminalGuard::enter(inline)?;
+    let backend = CrosstermBackend::new(io::stdout());
+    let viewport = if inline { Viewport::Inline(crossterm::terminal::size()?.1) } else { Viewport::Fullscreen };
+    // An inline viewport starts at the cursor, which terminals that do not report its position
+    // leave unknown; the guard gives the terminal back before that is reported.
+    let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport })
+        .map_err(|e| format!("could not set up the inline viewport: {}", e))?;
 
-    let app = App::new();
-    let res = run_app(&mut terminal, app);
+    let res = run_app(&mut terminal, &mut app);
+    let mut session_error = None;
+    if res.is_ok() {
+        // A clean exit; the draft only matters when the session dies.
+        draft::discard();
+        session_error = session::save(&app.model.menu_tree, &app.model.target_name(), &app.model.nav_path, app.model.selected_index).err();
+    }
 
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
+    if inline {
+        // Continue below the last screen drawn.
+        let bottom = terminal.get_frame().size().bottom();
+        terminal.set_cursor(0, bottom.saturating_sub(1))?;
+    }
+    guard.restore()?;
+    if inline {
+        println!();
+    }
     terminal.show_cursor()?;
+    if let Some(e) = session_error {
+        console::warning(&format!("could not save the session: {}", e));
+    }
 
-    if let Ok(ActionAfterExit::RunScript(script_content)) = res {
-        let script_path = "/tmp/tui_install_script.sh";
-        println!("Saving temporary script to {}...", script_path);
-        fs::write(script_path, &script_content)?;
-        fs::set_permissions(script_path, fs::Permissions::from_mode(0o755))?;
-
-        println!("Exited TUI. Now attempting to run the script with sudo...");
-        println!("--- SCRIPT ---");
-        println!("{}", script_content);
-        println!("--------------");
-        
-        let status = Command::new("sudo").arg("bash").arg(script_path).status()?;
-
-        if status.success() {
-            println!("\nScript executed successfully.");
-        } else {
-            println!("\nScript execution failed. Please check the output above.");
-        }
-        fs::remove_file(script_path)?;
+    if let Ok(ActionAfterExit::RunScript(prepared, manifest, escalation, _lock)) = res {
+        console::note("No pseudo-terminal is available to run the script inside the TUI; running it here instead.");
+        run_prepared(prepared, &manifest, escalation)?;
     } else if let Err(err) = res {
-        println!("{:?}", err)
+        console::error(&format!("{:?}", err));
     }
 
     Ok(())
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<ActionAfterExit> {
+fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<ActionAfterExit> {
+    // Input and background tasks both wake the loop; dropping `events` on return stops reading
+    // the terminal before anything else uses it.
+    let events = Events::start();
+    app.waker = Some(events.waker());
     loop {
-        terminal.draw(|f| ui(f, &mut app))?;
-
-        if let Event::Key(key) = event::read()? {
+        app.pump_run();
+        app.pump_preflight();
+        if let Some(remote) = app.remote.as_mut() {
+            remote.pump();
+        }
+        if let Some(troubleshoot) = app.troubleshoot.as_mut() {
+            troubleshoot.pump();
+        }
+        terminal.draw(|f| ui(f, app))?;
+        // Best-effort, like the usage tracking; a read-only state directory must not interrupt editing.
+        let _ = app.autosave.tick(draft::snapshot(&app.model.menu_tree, &app.model.target_name()));
+        // A running script's output, local or remote, is shown as it arrives.
+        let running = app.live_run.as_ref().is_some_and(|run| run.lock.is_some())
+            || app.remote.as_ref().is_some_and(|remote| !remote.is_finished())
+            || app.troubleshoot.as_ref().is_some_and(Troubleshooter::is_running);
+        let tick = if running { terminal::REFRESH } else { draft::TICK };
+        let event = match events.next(tick)? {
+            AppEvent::Input(event) => event,
+            AppEvent::Wake | AppEvent::Tick => continue,
+        };
+        // Resizing the buffers clears the screen, so nothing of the old layout is left behind
+        // where the new one draws nothing; the next pass redraws at the new size.
+        if let Event::Resize(width, height) = event {
+            terminal.resize(Rect::new(0, 0, width, height))?;
+            continue;
+        }
+        // Keys are not acted on blind while the screen only shows the size notice; a running
+        // script keeps its input until the terminal is large enough to watch it again.
+        if let Event::Key(key) = event && too_small(terminal.size()?) {
+            if key.code == KeyCode::Char('q') && !running {
+                return Ok(ActionAfterExit::Quit);
+            }
+            continue;
+        }
+        if let Event::Mouse(mouse) = event {
+            if app.state == AppState::Running && mouse.kind == MouseEventKind::Down(MouseButton::Left)
+                && let Some(level) = app.breadcrumbs.iter().position(|&(row, start, end)| mouse.row == row && (start..end).contains(&mouse.column))
+            {
+                app.jump_to_level(level + 1);
+            }
+            continue;
+        }
+        // A paste, e.g. an SSH public key, arrives whole instead of as keys, so its line break
+        // does not submit the form.
+        if let Event::Paste(text) = &event {
+            if app.state == AppState::EditingParams && let Some(form) = app.param_form.as_mut() {
+                form.wizard.paste(text);
+            }
+            continue;
+        }
+        if let Event::Key(key) = event {
+            if app.state != AppState::Help && (key.code == KeyCode::F(1) || (key.code == KeyCode::Char('?') && !app.typing())) {
+                app.open_help();
+                continue;
+            }
             match app.state {
                 AppState::Running => {
-                    let visible_nodes = get_visible_nodes(&app.nav_path);
+                    app.toast = None;
+                    // Tab cycles focus: menu -> Selected Components -> preview -> menu.
+                    if key.code == KeyCode::Tab {
+                        if app.outline.focused {
+                            app.outline.focused = false;
+                        } else if app.selected_focused {
+                            app.selected_focused = false;
+                            app.outline.focused = true;
+                        } else {
+                            app.selected_focused = true;
+                        }
+                        continue;
+                    }
+                    if app.selected_focused {
+                        let selected = app.selected_nodes();
+                        app.selected_panel_index = app.selected_panel_index.min(selected.len().saturating_sub(1));
+                        match key.code {
+                            KeyCode::Esc => app.selected_focused = false,
+                            KeyCode::Down if !selected.is_empty() => app.selected_panel_index = (app.selected_panel_index + 1) % selected.len(),
+                            KeyCode::Up if !selected.is_empty() => {
+                                app.selected_panel_index = (app.selected_panel_index + selected.len() - 1) % selected.len();
+                            }
+                            KeyCode::Enter => match selected.get(app.selected_panel_index) {
+                                Some(item) if !app.edit_item(item) => app.toast = Some(format!("{} has no parameters to edit", item.borrow().name())),
+                                _ => {}
+                            },
+                            KeyCode::Char('q') => return Ok(ActionAfterExit::Quit),
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    if app.outline.focused && !app.outline.searching && key.code == KeyCode::Esc {
+                        app.outline.focused = false;
+                        continue;
+                    }
+                    if app.diff_pane && app.outline.focused
+                        && let Some((_, diff)) = app.preview_diff.as_mut()
+                        && diff.handle_key(key.code)
+                    {
+                        continue;
+                    }
+                    if app.outline.handle_key(key.code) {
+                        continue;
+                    }
+
+                    let visible_nodes = get_visible_nodes(&app.model.nav_path, &app.shortcut_menus());
                     let visible_len = visible_nodes.len();
 
                     if visible_len > 0 {
-                        app.selected_index = app.selected_index.min(visible_len - 1);
+                        app.model.selected_index = app.model.selected_index.min(visible_len - 1);
                     } else {
-                        app.selected_index = 0;
+                        app.model.selected_index = 0;
+                    }
+
+                    if app.jump_pending {
+                        app.jump_pending = false;
+                        if let KeyCode::Char(c) = key.code
+                            && let Some(level) = c.to_digit(10)
+                        {
+                            app.jump_to_level(level as usize);
+                            continue;
+                        }
+                    }
+
+                    match key.code {
+                        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            app.popup_parent = AppState::Running;
+                            app.palette = Some(Palette::default());
+                            app.state = AppState::Palette;
+                        }
+                        KeyCode::Char('q') => return Ok(ActionAfterExit::Quit),
+                        KeyCode::Char('i') => { app.enter_finished(false); },
+                        KeyCode::Char('h') => app.enter_history(),
+                        KeyCode::Char('r') => { app.enter_finished(true); },
+                        KeyCode::Char('u') => app.jump_to_unresolved(),
+                        KeyCode::Char('p') => app.start_preflight(),
+                        KeyCode::Char('t') => app.cycle_target(),
+                        KeyCode::Char('g') if app.model.nav_path.len() > 1 => {
+                            app.jump_pending = true;
+                            app.toast = Some(format!("Jump to level 1-{} of the path", app.model.nav_path.len() - 1));
+                        }
+                        KeyCode::Char('a') | KeyCode::Char('A') => {
+                            // Applies to the highlighted menu, or to the current one when an item is highlighted.
+                            let target = visible_nodes.get(app.model.selected_index)
+                                .map(|(_, node)| node.clone())
+                                .filter(|node| matches!(&*node.borrow(), MenuNode::Menu { .. }))
+                                .unwrap_or_else(|| app.model.nav_path.last().unwrap().clone());
+                            app.set_all(&target, key.code == KeyCode::Char('a'));
+                        }
+                        KeyCode::Char('*') => {
+                            if let Some((_, node)) = visible_nodes.get(app.model.selected_index)
+                                && matches!(&*node.borrow(), MenuNode::Item { .. })
+                            {
+                                app.toggle_favorite(node);
+                            }
+                        }
+                        KeyCode::Char('F') => {
+                            app.index = Some(ItemIndex::new(&app.model.menu_tree, app.model.backend.as_ref()));
+                            app.state = AppState::Index;
+                        }
+                        KeyCode::Char('L') => {
+                            app.luks_status = Some(LuksStatus::probe(&app.theme));
+                            app.state = AppState::LuksStatus;
+                        }
+                        KeyCode::Char('B') => {
+                            app.link_status = Some(LinkStatus::probe(&app.theme));
+                            app.state = AppState::LinkStatus;
+                        }
+                        KeyCode::Char('D') => {
+                            app.diff_pane = !app.diff_pane;
+                            app.preview_diff = None;
+                        }
+                        KeyCode::Char('W') => {
+                            let changing = app.selected_nodes().iter()
+                                .filter(|item| item.borrow().rendered_script(app.model.backend.as_ref()).contains("firewall-cmd"))
+                                .map(|item| item.borrow().name().to_string())
+                                .collect();
+                            app.firewall = Some(FirewallStatus::probe(changing));
+                            app.state = AppState::Firewall;
+                        }
+                        KeyCode::Char('O') => {
+                            app.order = Some(OrderView::new(&app.model.menu_tree, app.model.backend.as_ref()));
+                            app.state = AppState::Order;
+                        }
+                        KeyCode::Char('T') => {
+                            let mut index = ItemIndex::new(&app.model.menu_tree, app.model.backend.as_ref());
+                            index.editing_filter = true;
+                            app.index = Some(index);
+                            app.state = AppState::Index;
+                        }
+                        KeyCode::Char('P') => {
+                            app.popup_parent = AppState::Running;
+                            app.proxy_form = Some(Wizard::for_proxy(&app.model.proxy));
+                            app.state = AppState::ProxyForm;
+                        }
+                        KeyCode::Char('o') => {
+                            app.popup_parent = AppState::Running;
+                            app.settings = Some(SettingsPopup::new(app.escalation, app.model.errors, app.model.transcript, app.model.offline.as_deref(), app.theme.scheme));
+                            app.state = AppState::Settings;
+                        }
+                        KeyCode::Down if !visible_nodes.is_empty() => {
+                            app.model.selected_index = (app.model.selected_index + 1) % visible_nodes.len();
+                        }
+                        KeyCode::Up if !visible_nodes.is_empty() => {
+                            app.model.selected_index = (app.model.selected_index + visible_nodes.len() - 1) % visible_nodes.len();
+                        }
+                        KeyCode::Right | KeyCode::Enter => {
+                            if let Some((_, selected_rc)) = visible_nodes.get(app.model.selected_index) {
+                                let is_menu = matches!(&*selected_rc.borrow(), MenuNode::Menu { .. });
+                                if is_menu {
+                                    app.model.nav_path.push(selected_rc.clone());
+                                    app.model.selected_index = 0;
+                                } else {
+                                    app.activate_item(selected_rc);
+                                }
+                            }
+                        }
+                        KeyCode::Left | KeyCode::Backspace if app.model.nav_path.len() > 1 => {
+                            app.model.nav_path.pop();
+                            app.model.selected_index = 0;
+                        }
+                        _ => {}
+                    }
+                },
+                AppState::Finished => {
+                    let script = app.generate_commands(app.model.reboot_requested);
+                    if app.viewer.handle_key(key.code, &script) {
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Char('q') => return Ok(ActionAfterExit::Quit),
+                        KeyCode::Char(c @ ('s' | 'm' | 'e' | 'j')) => {
+                            app.save_format = match c {
+                                's' => SaveFormat::Script,
+                                'm' => SaveFormat::Runbook,
+                                'j' => SaveFormat::Json,
+                                _ => SaveFormat::EXPORTS[0],
+                            };
+                            app.save_dialog = Some(SaveDialog::new(app.save_format.extension()));
+                            app.state = AppState::Saving;
+                        }
+                        KeyCode::Char('d') => app.state = AppState::DiffInput,
+                        KeyCode::Char('l') => match app.lint.as_mut().and_then(Lint::next) {
+                            Some(line) => app.viewer.jump_to_line(line - 1),
+                            None => app.toast = Some("bash -n and shellcheck found nothing to show".to_string()),
+                        },
+                        KeyCode::Char('c') => {
+                            app.save_status_message = Some(match clipboard::copy(&script) {
+                                Ok(how) => format!("Copied {} lines to the clipboard ({})", script.lines().count(), how),
+                                Err(e) => format!("Error: {}", e),
+                            });
+                        }
+                        KeyCode::Char('p') => app.start_preflight(),
+                        KeyCode::Char('R') if app.remote.as_ref().is_some_and(|remote| !remote.is_finished()) => app.state = AppState::Remote,
+                        KeyCode::Char('R') => {
+                            app.remote_setup = Some(RemoteSetup::new(script));
+                            app.state = AppState::RemoteSetup;
+                        }
+                        KeyCode::Char('r') => app.confirm_run(&script),
+                        KeyCode::Esc | KeyCode::Backspace => {
+                            app.state = app.popup_parent;
+                            app.viewer.focused = false;
+                        }
+                        _ => {}
+                    }
+                },
+                AppState::EditingParams => {
+                    let Some(form) = app.param_form.as_mut() else {
+                        app.state = app.popup_parent;
+                        continue;
+                    };
+                    match form.wizard.handle_key(key.code) {
+                        WizardOutcome::Continue => {}
+                        WizardOutcome::Cancel => {
+                            app.param_form = None;
+                            app.state = app.popup_parent;
+                        }
+                        WizardOutcome::Finish => {
+                            form.apply();
+                            let item = form.item.clone();
+                            app.param_form = None;
+                            app.state = app.popup_parent;
+                            app.finish_editing(&item);
+                        }
+                    }
+                },
+                AppState::NetWizard => {
+                    let Some(wizard) = app.net_wizard.as_mut() else {
+                        app.state = app.popup_parent;
+                        continue;
+                    };
+                    match wizard.handle_key(key.code) {
+                        WizardOutcome::Continue => {}
+                        WizardOutcome::Cancel => {
+                            app.net_wizard = None;
+                            app.state = app.popup_parent;
+                        }
+                        WizardOutcome::Finish => {
+                            wizard.apply();
+                            let item = wizard.item.clone();
+                            app.net_wizard = None;
+                            app.state = app.popup_parent;
+                            app.finish_editing(&item);
+                        }
+                    }
+                },
+                AppState::LuksWizard => {
+                    let Some(wizard) = app.luks_wizard.as_mut() else {
+                        app.state = app.popup_parent;
+                        continue;
+                    };
+                    match wizard.handle_key(key.code) {
+                        WizardOutcome::Continue => {}
+                        WizardOutcome::Cancel => {
+                            app.luks_wizard = None;
+                            app.state = app.popup_parent;
+                        }
+                        WizardOutcome::Finish => {
+                            wizard.apply();
+                            let item = wizard.item.clone();
+                            app.luks_wizard = None;
+                            app.state = app.popup_parent;
+                            app.finish_editing(&item);
+                        }
+                    }
+                },
+                AppState::CpuWizard => {
+                    let Some(wizard) = app.cpu_wizard.as_mut() else {
+                        app.state = app.popup_parent;
+                        continue;
+                    };
+                    match wizard.handle_key(key.code) {
+                        WizardOutcome::Continue => {}
+                        WizardOutcome::Cancel => {
+                            app.cpu_wizard = None;
+                            app.state = app.popup_parent;
+                        }
+                        WizardOutcome::Finish => {
+                            wizard.apply();
+                            let item = wizard.item.clone();
+                            app.cpu_wizard = None;
+                            app.state = app.popup_parent;
+                            app.finish_editing(&item);
+                        }
+                    }
+                },
+                AppState::RemoteSetup => {
+                    let Some(setup) = app.remote_setup.as_mut() else {
+                        app.state = AppState::Finished;
+                        continue;
+                    };
+                    match setup.handle_key(key.code) {
+                        WizardOutcome::Continue => {}
+                        WizardOutcome::Cancel => {
+                            app.remote_setup = None;
+                            app.state = AppState::Finished;
+                        }
+                        WizardOutcome::Finish => {
+                            match setup.start(app.waker.clone()) {
+                                Ok(run) => {
+                                    app.remote = Some(run);
+                                    app.state = AppState::Remote;
+                                }
+                                Err(e) => {
+                                    app.save_status_message = Some(format!("Error: {}", e));
+                                    app.state = AppState::Finished;
+                                }
+                            }
+                            app.remote_setup = None;
+                        }
+                    }
+                },
+                AppState::Remote => {
+                    let Some(remote) = app.remote.as_mut() else {
+                        app.state = AppState::Finished;
+                        continue;
+                    };
+                    if key.code == KeyCode::Char('q') && remote.is_finished() {
+                        return Ok(ActionAfterExit::Quit);
+                    }
+                    if !remote.handle_key(key.code) {
+                        app.state = AppState::Finished;
+                    }
+                },
+                AppState::LuksStatus => {
+                    let Some(status) = app.luks_status.as_mut() else {
+                        app.state = AppState::Running;
+                        continue;
+                    };
+                    match key.code {
+                        KeyCode::Up => status.scroll(-1),
+                        KeyCode::Down => status.scroll(1),
+                        KeyCode::PageUp => status.scroll(-10),
+                        KeyCode::PageDown => status.scroll(10),
+                        KeyCode::Char('r') => app.luks_status = Some(LuksStatus::probe(&app.theme)),
+                        KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('L') => {
+                            app.luks_status = None;
+                            app.state = AppState::Running;
+                        }
+                        KeyCode::Char('q') => return Ok(ActionAfterExit::Quit),
+                        _ => {}
+                    }
+                },
+                AppState::Firewall => {
+                    let Some(firewall) = app.firewall.as_mut() else {
+                        app.state = AppState::Running;
+                        continue;
+                    };
+                    if key.code == KeyCode::Char('q') {
+                        return Ok(ActionAfterExit::Quit);
+                    }
+                    if !firewall.handle_key(key.code) {
+                        app.firewall = None;
+                        app.state = AppState::Running;
+                    }
+                },
+                AppState::LinkStatus => {
+                    let Some(status) = app.link_status.as_mut() else {
+                        app.state = AppState::Running;
+                        continue;
+                    };
+                    match key.code {
+                        KeyCode::Up => status.scroll(-1),
+                        KeyCode::Down => status.scroll(1),
+                        KeyCode::PageUp => status.scroll(-10),
+                        KeyCode::PageDown => status.scroll(10),
+                        KeyCode::Char('r') => app.link_status = Some(LinkStatus::probe(&app.theme)),
+                        KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('B') => {
+                            app.link_status = None;
+                            app.state = AppState::Running;
+                        }
+                        KeyCode::Char('q') => return Ok(ActionAfterExit::Quit),
+                        _ => {}
+                    }
+                },
+                AppState::LabWizard => {
+                    let Some(wizard) = app.lab_wizard.as_mut() else {
+                        app.state = app.popup_parent;
+                        continue;
+                    };
+                    match wizard.handle_key(key.code) {
+                        WizardOutcome::Continue => {}
+                        WizardOutcome::Cancel => {
+                            app.lab_wizard = None;
+                            app.state = app.popup_parent;
+                        }
+                        WizardOutcome::Finish => {
+                            wizard.apply();
+                            let item = wizard.item.clone();
+                            let desktop = wizard.desktop_item().and_then(|id| app.find_item(id));
+                            app.lab_wizard = None;
+                            app.state = app.popup_parent;
+                            app.finish_editing(&item);
+                            if let Some(desktop) = desktop.filter(|d| !d.borrow().is_selected()) {
+                                app.toggle_item(&desktop);
+                            }
+                        }
+                    }
+                },
+                AppState::JobWizard => {
+                    let Some(wizard) = app.job_wizard.as_mut() else {
+                        app.state = app.popup_parent;
+                        continue;
+                    };
+                    match wizard.handle_key(key.code) {
+                        WizardOutcome::Continue => {}
+                        WizardOutcome::Cancel => {
+                            app.job_wizard = None;
+                            app.state = app.popup_parent;
+                        }
+                        WizardOutcome::Finish => {
+                            wizard.apply();
+                            let item = wizard.item.clone();
+                            app.job_wizard = None;
+                            app.state = app.popup_parent;
+                            app.finish_editing(&item);
+                        }
+                    }
+                },
+                AppState::ProxyForm => {
+                    let Some(form) = app.proxy_form.as_mut() else {
+                        app.state = app.popup_parent;
+                        continue;
+                    };
+                    match form.handle_key(key.code) {
+                        WizardOutcome::Continue => {}
+                        WizardOutcome::Cancel => {
+                            app.proxy_form = None;
+                            app.state = app.popup_parent;
+                        }
+                        WizardOutcome::Finish => {
+                            app.model.proxy = form.proxy();
+                            app.toast = Some(if app.model.proxy.is_set() {
+                                "Generated scripts now set up the proxy before installing anything".to_string()
+                            } else {
+                                "Proxy cleared".to_string()
+                            });
+                            app.proxy_form = None;
+                            app.state = app.popup_parent;
+                        }
+                    }
+                },
+                AppState::Settings => {
+                    let Some(popup) = app.settings.as_mut() else {
+                        app.state = app.popup_parent;
+                        continue;
+                    };
+                    match popup.handle_key(key.code) {
+                        WizardOutcome::Continue => {}
+                        WizardOutcome::Cancel => {
+                            app.settings = None;
+                            app.state = app.popup_parent;
+                        }
+                        WizardOutcome::Finish => {
+                            app.escalation = popup.escalation;
+                            app.model.errors = popup.errors;
+                            app.model.transcript = popup.transcript;
+                            app.toast = app.privilege.warning(app.escalation);
+                            let offline = popup.offline_baseurl();
+                            // Checks opened from preflight are stale once the packages come from elsewhere.
+                            let rerun_preflight = offline != app.model.offline && app.popup_parent == AppState::Preflight;
+                            if offline != app.model.offline {
+                                let dropped = app.model.set_offline(offline);
+                                if !dropped.is_empty() {
+                                    app.toast = Some(format!("Offline mode: deselected {}, which need the internet", dropped.join(", ")));
+                                }
+                            }
+                            if popup.scheme != app.theme.scheme {
+                                app.theme = Theme::new(popup.scheme);
+                                app.viewer.theme = app.theme;
+                                app.outline.theme = app.theme;
+                                if let Err(e) = theme::save_scheme(popup.scheme) {
+                                    app.toast = Some(format!("Could not save the color scheme: {}", e));
+                                }
+                            }
+                            app.settings = None;
+                            app.state = app.popup_parent;
+                            if rerun_preflight {
+                                app.run_preflight();
+                            }
+                        }
+                    }
+                },
+                AppState::Resolve => {
+                    let Some(dialog) = app.resolve.as_mut() else {
+                        app.state = AppState::Running;
+                        continue;
+                    };
+                    match dialog.handle_key(key.code) {
+                        ResolveOutcome::Continue => {}
+                        ResolveOutcome::Cancel => {
+                            app.resolve = None;
+                            app.state = AppState::Running;
+                        }
+                        // The form returns to the dialog, which then checks again.
+                        ResolveOutcome::Apply(Fix::FillIn(item, key)) => app.edit_item_field(&item, &key),
+                        ResolveOutcome::Apply(fix) => match app.model.apply_fix(&fix) {
+                            Ok(()) => app.refresh_resolve(),
+                            Err(reason) => dialog.message = Some(reason),
+                        },
+                    }
+                },
+                AppState::ConfirmRun => {
+                    let Some(confirm) = app.confirm.as_mut() else {
+                        app.state = AppState::Finished;
+                        continue;
+                    };
+                    match confirm.handle_key(key.code) {
+                        WizardOutcome::Continue => {}
+                        WizardOutcome::Cancel => {
+                            app.confirm = None;
+                            app.state = AppState::Finished;
+                        }
+                        WizardOutcome::Finish => {
+                            app.confirm = None;
+                            app.state = AppState::Finished;
+                            if let Some(action) = app.start_run(app.generate_run_commands(app.model.reboot_requested), app.run_manifest(), 0, false) {
+                                return Ok(action);
+                            }
+                        }
+                    }
+                },
+                AppState::Palette => {
+                    let Some(palette) = app.palette.as_mut() else {
+                        app.state = AppState::Running;
+                        continue;
+                    };
+                    match palette.handle_key(key.code) {
+                        WizardOutcome::Continue => {}
+                        WizardOutcome::Cancel => {
+                            app.palette = None;
+                            app.state = app.popup_parent;
+                        }
+                        WizardOutcome::Finish => {
+                            let input = palette.input.clone();
+                            app.palette = None;
+                            app.state = app.popup_parent;
+                            app.run_palette_command(&input);
+                        }
+                    }
+                },
+                AppState::Order => {
+                    app.toast = None;
+                    let len = app.selected_nodes().len();
+                    let Some(order) = app.order.as_mut() else {
+                        app.state = AppState::Running;
+                        continue;
+                    };
+                    let shifted = key.modifiers.contains(KeyModifiers::SHIFT);
+                    let moved = match key.code {
+                        KeyCode::Char('q') => return Ok(ActionAfterExit::Quit),
+                        KeyCode::Esc | KeyCode::Char('O') => {
+                            app.order = None;
+                            app.state = AppState::Running;
+                            Ok(())
+                        }
+                        KeyCode::Up if shifted => order.move_highlighted(&app.model.menu_tree, app.model.backend.as_ref(), true),
+                        KeyCode::Down if shifted => order.move_highlighted(&app.model.menu_tree, app.model.backend.as_ref(), false),
+                        KeyCode::Char('K') => order.move_highlighted(&app.model.menu_tree, app.model.backend.as_ref(), true),
+                        KeyCode::Char('J') => order.move_highlighted(&app.model.menu_tree, app.model.backend.as_ref(), false),
+                        KeyCode::Up => { order.previous(len); Ok(()) }
+                        KeyCode::Down => { order.next(len); Ok(()) }
+                        KeyCode::Char('R') => {
+                            redcent_tui::reset_script_order(&app.model.menu_tree);
+                            app.toast = Some("Script order follows the menu again".to_string());
+                            Ok(())
+                        }
+                        KeyCode::Enter | KeyCode::Char('i') => {
+                            app.order = None;
+                            app.state = AppState::Running;
+                            app.enter_finished(false);
+                            Ok(())
+                        }
+                        _ => Ok(()),
+                    };
+                    if let Err(reason) = moved {
+                        app.toast = Some(reason);
+                    }
+                }
+                AppState::Index => {
+                    app.toast = None;
+                    let Some(index) = app.index.as_mut() else {
+                        app.state = AppState::Running;
+                        continue;
+                    };
+                    if index.handle_filter_key(key.code) {
+                        continue;
                     }
-
                     match key.code {
                         KeyCode::Char('q') => return Ok(ActionAfterExit::Quit),
-                        KeyCode::Char('i') => { app.state = AppState::Finished; app.reboot_requested = false; },
-                        KeyCode::Char('r') => { app.state = AppState::Finished; app.reboot_requested = true; },
-                        KeyCode::Down => {
-                            if !visible_nodes.is_empty() {
-                                app.selected_index = (app.selected_index + 1) % visible_nodes.len();
-                            }
+                        KeyCode::Char('/') => index.editing_filter = true,
+                        KeyCode::Esc | KeyCode::Char('F') => {
+                            app.index = None;
+                            app.state = AppState::Running;
                         }
-                        KeyCode::Up => {
-                            if !visible_nodes.is_empty() {
-                                app.selected_index = (app.selected_index + visible_nodes.len() - 1) % visible_nodes.len();
+                        KeyCode::Down => index.next(),
+                        KeyCode::Up => index.previous(),
+                        KeyCode::Char('s') => index.toggle_sort(),
+                        KeyCode::Enter | KeyCode::Char(' ') => {
+                            if let Some(item) = index.current() {
+                                app.activate_item(&item);
                             }
                         }
-                        KeyCode::Right | KeyCode::Enter => {
-                            if let Some((_, selected_rc)) = visible_nodes.get(app.selected_index) {
-                                let mut node_mut = selected_rc.borrow_mut();
-                                match &mut *node_mut {
-                                    MenuNode::Menu { .. } => {
-                                        drop(node_mut);
-                                        app.nav_path.push(selected_rc.clone());
-                                        app.selected_index = 0;
-                                    }
-                                    MenuNode::Item { selected, .. } => {
-                                        *selected = !*selected;
-                                    }
-                                }
-                            }
+                        _ => {}
+                    }
+                },
+                AppState::RunLocked => {
+                    let Some(pending) = app.pending_run.take() else {
+                        app.state = AppState::Running;
+                        continue;
+                    };
+                    let stale = matches!(&pending.error, LockError::Busy(holder) if holder.is_stale());
+                    let take_over = match key.code {
+                        KeyCode::Char('r') => Some(false),
+                        KeyCode::Char('t') if stale => Some(true),
+                        _ => None,
+                    };
+                    if let Some(take_over) = take_over {
+                        if let Some(action) = app.start_run(pending.script, pending.manifest, pending.resume_after, take_over) {
+                            return Ok(action);
+                        }
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Backspace => app.state = pending.parent,
+                        KeyCode::Char('q') => return Ok(ActionAfterExit::Quit),
+                        _ => app.pending_run = Some(pending),
+                    }
+                },
+                AppState::Help => {
+                    if !app.help.as_mut().is_some_and(|help| help.handle_key(key.code, &app.theme)) {
+                        app.help = None;
+                        app.state = app.help_parent;
+                    }
+                },
+                AppState::RestoreSession => match key.code {
+                    KeyCode::Char('y') | KeyCode::Enter => {
+                        app.restore_session();
+                        app.state = app.startup_state();
+                    }
+                    KeyCode::Char('n') | KeyCode::Esc => {
+                        app.session = None;
+                        session::discard();
+                        app.state = app.startup_state();
+                    }
+                    _ => {}
+                },
+                AppState::RestoreDraft => match key.code {
+                    KeyCode::Char('y') | KeyCode::Enter => {
+                        app.restore_draft();
+                        app.state = app.startup_state();
+                    }
+                    KeyCode::Char('n') | KeyCode::Esc => {
+                        app.draft = None;
+                        draft::discard();
+                        app.state = app.startup_state();
+                    }
+                    _ => {}
+                },
+                AppState::Terminal => {
+                    let Some(run) = app.live_run.as_mut() else {
+                        app.state = AppState::Running;
+                        continue;
+                    };
+                    let resumable = run.prepared.record.as_ref().filter(|record| record.resume_point().is_some()).cloned();
+                    match key.code {
+                        KeyCode::PageUp if key.modifiers.contains(KeyModifiers::SHIFT) => run.pane.scroll(10),
+                        KeyCode::PageDown if key.modifiers.contains(KeyModifiers::SHIFT) => run.pane.scroll(-10),
+                        KeyCode::F(2) if run.lock.is_some() => run.watchdog.expanded = !run.watchdog.expanded,
+                        _ if run.lock.is_some() => run.pane.send_key(key),
+                        KeyCode::Enter | KeyCode::Esc => {
+                            app.live_run = None;
+                            app.state = AppState::Running;
+                        }
+                        KeyCode::Char('h') => {
+                            app.live_run = None;
+                            app.enter_history();
                         }
-                        KeyCode::Left | KeyCode::Backspace => {
-                            if app.nav_path.len() > 1 {
-                                app.nav_path.pop();
-                                app.selected_index = 0;
+                        KeyCode::Char('t') if run.pane.exit_code().is_some_and(|code| code != 0) => {
+                            app.troubleshoot = Some(Troubleshooter::new(&run.failures, &app.model.menu_tree, app.model.backend.as_ref(), app.waker.clone()));
+                            app.state = AppState::Troubleshoot;
+                        }
+                        KeyCode::Char('c') => {
+                            if let Some(record) = resumable {
+                                app.live_run = None;
+                                app.state = AppState::Running;
+                                if let Some(action) = app.resume_run(&record) {
+                                    return Ok(action);
+                                }
                             }
                         }
+                        KeyCode::Char('q') => return Ok(ActionAfterExit::Quit),
                         _ => {}
                     }
                 },
-                AppState::Finished => match key.code {
+                AppState::Troubleshoot => {
+                    let open = app.troubleshoot.as_mut().is_some_and(|troubleshoot| troubleshoot.handle_key(key, app.escalation));
+                    if !open {
+                        app.troubleshoot = None;
+                        app.state = AppState::Terminal;
+                    }
+                }
+                AppState::ResumeRun => match key.code {
+                    KeyCode::Char('y') | KeyCode::Enter => {
+                        app.state = AppState::Running;
+                        if let Some((record, _)) = app.unfinished.take()
+                            && let Some(action) = app.resume_run(&record) {
+                            return Ok(action);
+                        }
+                    }
+                    // The run stays in the history, where `c` resumes it later.
+                    KeyCode::Char('n') | KeyCode::Esc => {
+                        app.unfinished = None;
+                        app.state = AppState::Running;
+                    }
+                    _ => {}
+                },
+                AppState::Preflight => match key.code {
+                    KeyCode::Char('r') if app.preflight_task.is_none() => app.run_preflight(),
+                    KeyCode::Char('m') if app.preflight.as_ref().is_some_and(|preflight| preflight.unreachable > 0) => {
+                        let mut popup = SettingsPopup::new(app.escalation, app.model.errors, app.model.transcript, app.model.offline.as_deref(), app.theme.scheme);
+                        popup.focus_mirror();
+                        app.settings = Some(popup);
+                        app.popup_parent = AppState::Preflight;
+                        app.state = AppState::Settings;
+                    }
+                    KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('p') => app.state = app.preflight_parent,
                     KeyCode::Char('q') => return Ok(ActionAfterExit::Quit),
-                    KeyCode::Char('s') => app.state = AppState::Saving,
-                    KeyCode::Char('r') => return Ok(ActionAfterExit::RunScript(app.generate_commands(app.reboot_requested))),
-                    KeyCode::Esc | KeyCode::Backspace => app.state = AppState::Running,
                     _ => {}
                 },
-                AppState::Saving => match key.code {
+                AppState::History => {
+                    if app.history_viewing {
+                        let content = app.history_content();
+                        if app.viewer.handle_key(key.code, &content) {
+                            continue;
+                        }
+                    }
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Backspace if app.history_viewing => {
+                            app.history_viewing = false;
+                            app.viewer.focused = false;
+                        }
+                        KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('h') => app.state = AppState::Running,
+                        KeyCode::Down if !app.history.is_empty() => {
+                            app.history_index = (app.history_index + 1) % app.history.len();
+                        }
+                        KeyCode::Up if !app.history.is_empty() => {
+                            app.history_index = (app.history_index + app.history.len() - 1) % app.history.len();
+                        }
+                        KeyCode::Enter if !app.history.is_empty() => {
+                            app.history_viewing = true;
+                            app.viewer.home();
+                            app.viewer.focused = true;
+                        }
+                        KeyCode::Char('r') => {
+                            if let Some(record) = app.history.get(app.history_index)
+                                && let Ok(script) = record.script()
+                                && let Some(action) = app.start_run(script, RunManifest::from_record(record), 0, false) {
+                                return Ok(action);
+                            }
+                        }
+                        KeyCode::Char('c') => {
+                            if let Some(record) = app.history.get(app.history_index).cloned()
+                                && let Some(action) = app.resume_run(&record) {
+                                return Ok(action);
+                            }
+                        }
+                        KeyCode::Char('q') => return Ok(ActionAfterExit::Quit),
+                        _ => {}
+                    }
+                },
+                AppState::Saving => {
+                    let Some(dialog) = app.save_dialog.as_mut() else {
+                        app.state = AppState::Finished;
+                        continue;
+                    };
+                    if key.code == KeyCode::BackTab {
+                        app.save_format = app.save_format.next_export();
+                        dialog.set_extension(app.save_format.extension());
+                        continue;
+                    }
+                    let path = match dialog.handle_key(key.code) {
+                        SaveOutcome::Continue => continue,
+                        SaveOutcome::Cancel => {
+                            app.save_dialog = None;
+                            app.state = AppState::Finished;
+                            app.save_status_message = None;
+                            continue;
+                        }
+                        SaveOutcome::Save(path) => path,
+                    };
+                    let confirmed = dialog.overwrite_confirmed;
+                    let files = app.save_files(app.save_format, &path);
+                    let existing: Vec<PathBuf> = files.iter().map(|(path, _)| path.clone()).filter(|path| path.exists()).collect();
+                    if !existing.is_empty() && !confirmed {
+                        if let Some(dialog) = app.save_dialog.as_mut() {
+                            dialog.confirm_overwrite(existing);
+                        }
+                        continue;
+                    }
+                    match files.iter().try_for_each(|(path, content)| save_file(path, content)) {
+                            Ok(_) => {
+                                let paths: Vec<String> = files.iter().map(|(path, _)| path.display().to_string()).collect();
+                                app.save_status_message = Some(format!("Saved to {}", paths.join(" and ")));
+                                // History is best-effort; a failure here must not mask the successful save.
+                                // Runbooks and exports wrap the script rather than being one, so they are not recorded.
+                                if app.save_format == SaveFormat::Script {
+                                    let _ = history::record_script(&files[0].1);
+                                }
+                            }
+                            Err(e) => app.save_status_message = Some(format!("Error: {}", e)),
+                    }
+                    app.save_dialog = None;
+                    app.state = AppState::Finished;
+                },
+                AppState::DiffInput => match key.code {
                     KeyCode::Char(c) => app.filename_input.push(c),
                     KeyCode::Backspace => { app.filename_input.pop(); },
-                    KeyCode::Esc => { app.state = AppState::Finished; app.filename_input.clear(); app.save_status_message = None; },
+                    KeyCode::Esc => { app.state = AppState::Finished; app.filename_input.clear(); },
                     KeyCode::Enter => {
-                        let script = app.generate_commands(app.reboot_requested);
-                        match fs::write(&app.filename_input, script) {
-                            Ok(_) => app.save_status_message = Some(format!("Saved to {}", app.filename_input)),
-                            Err(e) => app.save_status_message = Some(format!("Error: {}", e)),
+                        match fs::read_to_string(&app.filename_input) {
+                            Ok(saved) => {
+                                let current = app.generate_commands(app.model.reboot_requested);
+                                app.diff = Some(DiffView::new(app.filename_input.as_str(), &saved, "Current script", &current));
+                                app.state = AppState::Diff;
+                            }
+                            Err(e) => {
+                                app.save_status_message = Some(format!("Error reading {}: {}", app.filename_input, e));
+                                app.state = AppState::Finished;
+                            }
                         }
-                        app.state = AppState::Finished;
                         app.filename_input.clear();
                     }
                     _ => {}
+                },
+                AppState::Diff => {
+                    let Some(diff) = app.diff.as_mut() else {
+                        app.state = AppState::Finished;
+                        continue;
+                    };
+                    if diff.handle_key(key.code) {
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('d') => {
+                            app.diff = None;
+                            app.state = AppState::Finished;
+                        }
+                        KeyCode::Char('q') => return Ok(ActionAfterExit::Quit),
+                        _ => {}
+                    }
                 }
             }
         }
@@ -287,42 +2227,229 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<A
 }
 
 fn ui(f: &mut Frame, app: &mut App) {
+    if too_small(f.size()) {
+        draw_too_small(f, app);
+        return;
+    }
     match app.state {
-        AppState::Finished | AppState::Saving => {
+        AppState::Finished | AppState::Saving | AppState::DiffInput => {
             draw_finished_screen(f, app);
-            if let AppState::Saving = app.state {
-                draw_saving_popup(f, &app.filename_input);
+            if let (AppState::Saving, Some(dialog)) = (app.state, &app.save_dialog) {
+                let hint = if SaveFormat::EXPORTS.contains(&app.save_format) {
+                    "Type a file name, Tab to complete it, ↑↓ to browse, Enter to open a directory or save, Shift+Tab for the next format, Esc to cancel:"
+                } else {
+                    "Type a file name, Tab to complete it, ↑↓ to browse, Enter to open a directory or save, Esc to cancel:"
+                };
+                dialog.draw(f, app.save_format.title(), hint, &app.theme);
+            } else if let AppState::DiffInput = app.state {
+                draw_input_popup(f, "Compare with Saved Script", "Path of a saved script (Enter to compare, Esc to cancel):", &app.filename_input);
             }
         },
+        AppState::Diff => {
+            let chunks = Layout::default().direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref()).split(f.size());
+            if let Some(diff) = app.diff.as_mut() {
+                diff.draw(f, chunks[0], &app.theme);
+            }
+            let footer = Paragraph::new("Scroll [↑↓ PgUp/PgDn Home/End] | [n/N] Next/Prev Change | [Esc/d] Back | [q] Quit")
+                .style(app.theme.footer)
+                .block(Block::default().borders(Borders::ALL));
+            f.render_widget(footer, chunks[1]);
+        }
         AppState::Running => {
             draw_main_ui(f, app);
         }
+        AppState::EditingParams => {
+            draw_popup_parent(f, app);
+            if let Some(form) = &app.param_form {
+                form.wizard.draw(f, &app.theme, "");
+            }
+        }
+        AppState::NetWizard => {
+            draw_popup_parent(f, app);
+            if let Some(wizard) = &app.net_wizard {
+                wizard.draw(f, &app.theme, app.model.backend.as_ref());
+            }
+        }
+        AppState::LuksWizard => {
+            draw_popup_parent(f, app);
+            if let Some(wizard) = &app.luks_wizard {
+                wizard.draw(f, &app.theme, app.model.backend.as_ref());
+            }
+        }
+        AppState::CpuWizard => {
+            draw_popup_parent(f, app);
+            if let Some(wizard) = &app.cpu_wizard {
+                wizard.draw(f, &app.theme, app.model.backend.as_ref());
+            }
+        }
+        AppState::RemoteSetup => {
+            draw_finished_screen(f, app);
+            if let Some(setup) = &app.remote_setup {
+                setup.draw(f, &app.theme);
+            }
+        }
+        AppState::Remote => {
+            if let Some(remote) = &app.remote {
+                remote.draw(f, &app.theme);
+            }
+        }
+        AppState::LuksStatus => {
+            if let Some(status) = &app.luks_status {
+                status.draw(f, &app.theme);
+            }
+        }
+        AppState::LinkStatus => {
+            if let Some(status) = &app.link_status {
+                status.draw(f, &app.theme);
+            }
+        }
+        AppState::Firewall => {
+            if let Some(firewall) = &app.firewall {
+                firewall.draw(f, &app.theme);
+            }
+        }
+        AppState::LabWizard => {
+            draw_popup_parent(f, app);
+            if let Some(wizard) = &app.lab_wizard {
+                wizard.draw(f, &app.theme, app.model.backend.as_ref());
+            }
+        }
+        AppState::JobWizard => {
+            draw_popup_parent(f, app);
+            if let Some(wizard) = &app.job_wizard {
+                wizard.draw(f, &app.theme, app.model.backend.as_ref());
+            }
+        }
+        AppState::History => draw_history_screen(f, app),
+        AppState::Help => {
+            if let Some(help) = app.help.as_mut() {
+                help.draw(f, &app.theme);
+            }
+        }
+        AppState::RestoreDraft => {
+            draw_main_ui(f, app);
+            if let Some(draft) = &app.draft {
+                draw_restore_draft(f, draft, &app.theme);
+            }
+        }
+        AppState::RestoreSession => {
+            draw_main_ui(f, app);
+            if let Some(session) = &app.session {
+                draw_restore_session(f, session, &app.theme);
+            }
+        }
+        AppState::Terminal => draw_terminal_screen(f, app),
+        AppState::Troubleshoot => {
+            draw_terminal_screen(f, app);
+            if let Some(troubleshoot) = app.troubleshoot.as_mut() {
+                troubleshoot.draw(f, &app.theme);
+            }
+        }
+        AppState::ResumeRun => {
+            draw_main_ui(f, app);
+            if let Some((record, point)) = &app.unfinished {
+                draw_resume_run(f, record, point, &app.theme);
+            }
+        }
+        AppState::RunLocked => {
+            if let Some(pending) = &app.pending_run {
+                draw_run_locked(f, pending, &app.theme);
+            }
+        }
+        AppState::Preflight => match &app.preflight {
+            Some(preflight) => preflight.draw(f),
+            None => {
+                let area = centered_rect(50, 10, f.size());
+                f.render_widget(Paragraph::new("Running preflight checks...").block(Block::default().borders(Borders::ALL).title("Preflight")), area);
+            }
+        },
+        AppState::Resolve => {
+            draw_main_ui(f, app);
+            if let Some(dialog) = &app.resolve {
+                dialog.draw(f, &app.theme);
+            }
+        }
+        AppState::ConfirmRun => {
+            draw_finished_screen(f, app);
+            if let Some(confirm) = &app.confirm {
+                confirm.draw(f);
+            }
+        }
+        AppState::Palette => {
+            draw_popup_parent(f, app);
+            if let Some(palette) = &app.palette {
+                palette.draw(f, &palette.hint(&app.model.menu_tree));
+            }
+        }
+        AppState::Order => {
+            if let Some(order) = app.order.as_mut() {
+                order.draw(f, &app.model.menu_tree, app.model.backend.as_ref(), &app.theme);
+            }
+            if let Some(toast) = &app.toast {
+                draw_toast(f, toast, &app.theme);
+            }
+        }
+        AppState::Index => {
+            if let Some(index) = &app.index {
+                index.draw(f);
+            }
+            if let Some(toast) = &app.toast {
+                draw_toast(f, toast, &app.theme);
+            }
+        }
+        AppState::Settings => {
+            draw_popup_parent(f, app);
+            if let Some(popup) = &app.settings {
+                popup.draw(f, &app.privilege);
+            }
+        }
+        AppState::ProxyForm => {
+            draw_popup_parent(f, app);
+            if let Some(form) = &app.proxy_form {
+                form.draw(f, &app.theme, "");
+            }
+        }
+    }
+}
+
+/// Draws the screen a popup was opened from underneath it.
+fn draw_popup_parent(f: &mut Frame, app: &mut App) {
+    match (app.popup_parent, &app.index, &app.preflight) {
+        (AppState::Index, Some(index), _) => index.draw(f),
+        (AppState::Preflight, _, Some(preflight)) => preflight.draw(f),
+        _ => draw_main_ui(f, app),
     }
 }
 
 fn draw_main_ui(f: &mut Frame, app: &mut App) {
+    let collapsed = f.size().height < PREVIEW_MIN_HEIGHT;
+    let (content, preview) = match (collapsed, app.outline.focused) {
+        (false, _) => (Constraint::Min(0), Constraint::Percentage(40)),
+        (true, false) => (Constraint::Min(0), Constraint::Length(3)),
+        (true, true) => (Constraint::Length(0), Constraint::Min(0)),
+    };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3), // Title
-            Constraint::Min(0), // Main content area
-            Constraint::Percentage(40), // Script preview
+            content, // Main content area
+            preview, // Script preview
             Constraint::Length(3), // Footer
         ].as_ref())
         .split(f.size());
 
-    let path_str = {
-        app.nav_path.iter().map(|node_rc| {
-            let node = node_rc.borrow();
-            match &*node {
-                MenuNode::Menu { name, .. } => name.clone(),
-                MenuNode::Item { name, .. } => name.clone(),
-            }
-        }).collect::<Vec<_>>().join(" > ")
-    };
+    // Ancestors are numbered for `g` + digit; the current level is the menu being shown.
+    let last = app.model.nav_path.len() - 1;
+    let segments: Vec<String> = app.model.nav_path.iter().enumerate().map(|(i, node_rc)| {
+        let name = node_rc.borrow().name().to_string();
+        if i < last { format!("[{}] {}", i + 1, name) } else { name }
+    }).collect();
+    let path_str = segments.join(" > ");
 
-    let title_text = format!("RHEL/CentOS 10 TUI Manager (Detected: {:?})", app.os_distro);
-    let title = Paragraph::new(title_text).style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+    let offline = if app.model.offline.is_some() { ", Offline" } else { "" };
+    let title_text = format!("RHEL/CentOS 10 TUI Manager (Detected: {:?}, Target: {:?}{})", app.model.os_distro, app.model.target, offline);
+    let title = Paragraph::new(title_text).style(app.theme.title)
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(title, chunks[0]);
 
@@ -330,44 +2457,177 @@ fn draw_main_ui(f: &mut Frame, app: &mut App) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
         .split(chunks[1]);
 
-    let visible_nodes = get_visible_nodes(&app.nav_path);
-    let menu_items: Vec<ListItem> = visible_nodes.iter().map(|(text, _)| ListItem::new(text.clone())).collect();
+    // The block title starts right after the top-left corner and is cut off before the top-right one.
+    let title_end = main_chunks[0].right().saturating_sub(1);
+    let mut column = main_chunks[0].x + 1;
+    app.breadcrumbs = segments[..last].iter().map(|segment| {
+        let width = segment.chars().count() as u16;
+        let range = (main_chunks[0].y, column.min(title_end), (column + width).min(title_end));
+        column += width + " > ".len() as u16;
+        range
+    }).collect();
+
+    let visible_nodes = get_visible_nodes(&app.model.nav_path, &app.shortcut_menus());
+    let menu_items: Vec<ListItem> = visible_nodes.iter().map(|(text, node)| {
+        if app.model.offline.is_some() && app.model.needs_internet(node) {
+            ListItem::new(format!("{}  (needs internet)", text)).style(app.theme.muted)
+        } else {
+            ListItem::new(text.clone())
+        }
+    }).collect();
 
     if !visible_nodes.is_empty() {
-        app.selected_index = app.selected_index.min(visible_nodes.len() - 1);
+        app.model.selected_index = app.model.selected_index.min(visible_nodes.len() - 1);
     } else {
-        app.selected_index = 0;
+        app.model.selected_index = 0;
     }
 
-    let menu_block = Block::default().title(path_str).borders(Borders::ALL).style(Style::default().fg(Color::Yellow));
+    let menu_block = Block::default().title(path_str).borders(Borders::ALL).style(app.theme.menu);
     let list = List::new(menu_items)
         .block(menu_block)
-        .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(Color::DarkGray))
+        .highlight_style(app.theme.highlight)
         .highlight_symbol(">> ");
     
     let mut list_state = ratatui::widgets::ListState::default();
     if !visible_nodes.is_empty() {
-        list_state.select(Some(app.selected_index));
+        list_state.select(Some(app.model.selected_index));
     }
     f.render_stateful_widget(list, main_chunks[0], &mut list_state);
 
-    let selected_items: Vec<ListItem> = app.get_selected_items().iter().map(|s| ListItem::new(s.clone())).collect();
-    let selected_list = List::new(selected_items).block(Block::default().borders(Borders::ALL).title("Selected Components"));
-    f.render_widget(selected_list, main_chunks[1]);
+    let side_chunks = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+        .split(main_chunks[1]);
+    let selected_nodes = app.selected_nodes();
+    let highlighted = if app.selected_focused {
+        selected_nodes.get(app.selected_panel_index).cloned()
+    } else {
+        visible_nodes.get(app.model.selected_index).map(|(_, node)| node.clone())
+    };
+    draw_details(f, side_chunks[0], highlighted.as_ref(), app);
+
+    let selected_items: Vec<ListItem> = selected_nodes.iter().map(|node| {
+        let node = node.borrow();
+        let mut lines = vec![Line::from(node.name().to_string())];
+        if let MenuNode::Item { params, .. } = &*node {
+            lines.extend(params.iter().map(|p| if p.is_resolved() {
+                Line::styled(format!("    {} = {}", p.label, p.value), app.theme.muted)
+            } else {
+                Line::styled(format!("    {} = (required)", p.label), app.theme.error)
+            }));
+        }
+        ListItem::new(lines)
+    }).collect();
+    let (selected_title, selected_style) = if app.selected_focused {
+        ("Selected Components [Enter] Edit Parameters", app.theme.focused)
+    } else {
+        ("Selected Components", Style::default())
+    };
+    let mut selected_list = List::new(selected_items)
+        .block(Block::default().borders(Borders::ALL).title(selected_title).border_style(selected_style));
+    let mut selected_state = ratatui::widgets::ListState::default();
+    if app.selected_focused && !selected_nodes.is_empty() {
+        selected_list = selected_list.highlight_style(app.theme.highlight);
+        selected_state.select(Some(app.selected_panel_index.min(selected_nodes.len() - 1)));
+    }
+    f.render_stateful_widget(selected_list, side_chunks[1], &mut selected_state);
 
     let script_content = app.generate_commands(false);
-    let script_preview = Paragraph::new(script_content)
-        .wrap(Wrap { trim: true })
-        .block(Block::default().borders(Borders::ALL).title("Generated Script Preview"));
-    f.render_widget(script_preview, chunks[2]);
+    if collapsed && !app.outline.focused {
+        let title = if app.diff_pane { "Changes Since Last Viewed" } else { "Generated Script Preview" };
+        let stub = Paragraph::new(format!("Collapsed below {} rows; [Tab] shows it in place of the menu.", PREVIEW_MIN_HEIGHT))
+            .style(app.theme.muted)
+            .block(Block::default().title(title).borders(Borders::ALL));
+        f.render_widget(stub, chunks[2]);
+    } else if app.diff_pane {
+        if app.preview_diff.as_ref().is_none_or(|(built_for, _)| *built_for != script_content) {
+            let viewed = app.viewed_script.as_deref().map(|viewed| same_generation_time(viewed, &script_content));
+            let title = if viewed.is_some() { "Last viewed" } else { "Not viewed yet" };
+            let diff = DiffView::new(title, viewed.as_deref().unwrap_or(""), "Current preview", &script_content);
+            app.preview_diff = Some((script_content.clone(), diff));
+        }
+        if let Some((_, diff)) = app.preview_diff.as_mut() {
+            diff.draw(f, chunks[2], &app.theme);
+        }
+    } else {
+        let sections = outline::sections(&script_content, &app.model.menu_tree, app.model.backend.as_ref());
+        app.outline.render(f, chunks[2], sections, "Generated Script Preview");
+    }
 
-    let footer_text = "Navigate [←→↑↓] | Select [Enter] | [i] Generate Script | [q] Quit";
-    let footer = Paragraph::new(footer_text).style(Style::default().fg(Color::Cyan))
+    let footer_text = if app.outline.focused && app.diff_pane {
+        "Diff: Scroll [↑↓ PgUp/PgDn Home/End] | [n/N] Next/Prev Change | [D] Back to Preview | [Tab/Esc] Back to Menu"
+    } else if app.outline.focused {
+        "Preview: Move [↑↓ PgUp/PgDn Home/End] | [Enter] Expand/Collapse | [→/←] Expand/Collapse | [+/-] All Sections | [/] Search | [n/N] Next/Prev Match | [Tab/Esc] Back to Menu"
+    } else {
+        "Navigate [←→↑↓] | Select [Enter] | [Tab] Focus Selected/Preview | [/] Search | [i] Generate Script | [u] Fill Missing | [p] Preflight | [a/A] Select/Deselect All | [Ctrl-P] Commands | [F] All Items | [O] Script Order | [T] Tags | [*] Favorite | [t] Target | [g1-9] Jump Up | [o] Settings | [P] Proxy | [D] Diff | [h] History | [L] Encryption | [B] Links | [W] Firewall | [?] Help | [q] Quit"
+    };
+    let footer = Paragraph::new(footer_text).style(app.theme.footer)
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(footer, chunks[3]);
+
+    if let Some(toast) = &app.toast {
+        draw_toast(f, toast, &app.theme);
+    }
+}
+
+fn too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+/// Stands in for every screen while the terminal is smaller than [`MIN_WIDTH`] x [`MIN_HEIGHT`].
+fn draw_too_small(f: &mut Frame, app: &App) {
+    let area = f.size();
+    let running = app.live_run.as_ref().is_some_and(|run| run.lock.is_some())
+        || app.remote.as_ref().is_some_and(|remote| !remote.is_finished());
+    let lines = vec![
+        Line::styled("Terminal too small", app.theme.warning),
+        Line::from(format!("Need {} x {}, have {} x {}.", MIN_WIDTH, MIN_HEIGHT, area.width, area.height)),
+        Line::styled(if running { "Enlarge it to continue." } else { "Enlarge it to continue, or press q to quit." }, app.theme.muted),
+    ];
+    let top = area.height.saturating_sub(lines.len() as u16) / 2;
+    let rect = Rect { y: area.y + top, height: area.height - top, ..area };
+    f.render_widget(Paragraph::new(lines).alignment(Alignment::Center).wrap(Wrap { trim: true }), rect);
 }
 
-fn get_visible_nodes(nav_path: &[Rc<RefCell<MenuNode>>]) -> Vec<(String, Rc<RefCell<MenuNode>>)> {
+/// Draws a one-line notification box just above the footer.
+fn draw_toast(f: &mut Frame, message: &str, theme: &Theme) {
+    let size = f.size();
+    let width = (message.chars().count() as u16 + 4).min(size.width);
+    let area = Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + size.height.saturating_sub(6),
+        width,
+        height: 3.min(size.height),
+    };
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(message).style(theme.warning)
+            .block(Block::default().borders(Borders::ALL).title("Notice")),
+        area,
+    );
+}
+
+/// Toast text for a bulk selection change, e.g. `Selected 3 item(s); 1 skipped: ...`.
+fn bulk_summary(verb: &str, changed: usize, refused: &[String]) -> String {
+    let mut message = format!("{} {} item(s)", verb, changed);
+    if !refused.is_empty() {
+        message.push_str(&format!("; {} skipped: {}", refused.len(), refused.join("; ")));
+    }
+    message
+}
+
+/// A menu's list entry: `[x]` when every item under it is selected, `[~]` when only some are,
+/// `[ ]` otherwise, followed by its name and a `(selected/total)` badge counting all items below it.
+fn menu_label(menu: &MenuNode) -> String {
+    let (selected, total) = menu.selection_counts();
+    let checkbox = match (selected, total) {
+        (0, _) => "[ ]",
+        (selected, total) if selected == total => "[x]",
+        _ => "[~]",
+    };
+    format!("{} {} ({}/{}) >", checkbox, menu.name(), selected, total)
+}
+
+fn get_visible_nodes(nav_path: &[Rc<RefCell<MenuNode>>], shortcuts: &[Rc<RefCell<MenuNode>>]) -> Vec<(String, Rc<RefCell<MenuNode>>)> {
     let mut items = Vec::new();
     let current_menu = nav_path.last().unwrap();
     
@@ -375,8 +2635,8 @@ fn get_visible_nodes(nav_path: &[Rc<RefCell<MenuNode>>]) -> Vec<(String, Rc<RefC
         let node_borrow = node.borrow();
         let indent = "  ".repeat(depth);
         match &*node_borrow {
-            MenuNode::Menu { name, children } => {
-                items.push((format!("{}{} >", indent, name), node.clone()));
+            MenuNode::Menu { children, .. } => {
+                items.push((format!("{}{}", indent, menu_label(&node_borrow)), node.clone()));
                 for child in children {
                     build_display_list(items, child, depth + 1);
                 }
@@ -390,6 +2650,9 @@ fn get_visible_nodes(nav_path: &[Rc<RefCell<MenuNode>>]) -> Vec<(String, Rc<RefC
 
     if let MenuNode::Menu { children, .. } = &*current_menu.borrow() {
         if nav_path.len() == 1 { // Root, show full tree
+            for shortcut in shortcuts {
+                build_display_list(&mut items, shortcut, 0);
+            }
             for child in children {
                 build_display_list(&mut items, child, 0);
             }
@@ -397,7 +2660,7 @@ fn get_visible_nodes(nav_path: &[Rc<RefCell<MenuNode>>]) -> Vec<(String, Rc<RefC
              for child in children {
                 let node_borrow = child.borrow();
                  match &*node_borrow {
-                    MenuNode::Menu { name, .. } => items.push((format!("{} >", name), child.clone())),
+                    MenuNode::Menu { .. } => items.push((menu_label(&node_borrow), child.clone())),
                     MenuNode::Item { name, selected, .. } => {
                         let prefix = if *selected { "[x]" } else { "[ ]" };
                         items.push((format!("{} {}", prefix, name), child.clone()));
@@ -411,16 +2674,18 @@ fn get_visible_nodes(nav_path: &[Rc<RefCell<MenuNode>>]) -> Vec<(String, Rc<RefC
 
 
 fn draw_finished_screen(f: &mut Frame, app: &mut App) {
+    let lint_height = app.lint.as_ref().map_or(0, Lint::height);
     let chunks = Layout::default().direction(Direction::Vertical)
-        .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref()).split(f.size());
-    let script_content = app.generate_commands(app.reboot_requested);
-    let title = if app.reboot_requested { "Installation Script (with Reboot)" } else { "Installation Script" };
-    let paragraph = Paragraph::new(script_content).wrap(Wrap { trim: true })
-        .block(Block::default().title(title).borders(Borders::ALL));
-    f.render_widget(paragraph, chunks[0]);
+        .constraints([Constraint::Min(0), Constraint::Length(lint_height), Constraint::Length(3)].as_ref()).split(f.size());
+    let script_content = app.generate_commands(app.model.reboot_requested);
+    let title = if app.model.reboot_requested { "Installation Script (with Reboot)" } else { "Installation Script" };
+    app.viewer.render(f, chunks[0], &script_content, title);
+    if let Some(lint) = app.lint.as_ref().filter(|lint| !lint.findings.is_empty()) {
+        lint.draw(f, chunks[1], &app.theme);
+    }
 
     if let Some(msg) = &app.save_status_message {
-        let msg_p = Paragraph::new(msg.as_str()).style(Style::default().fg(Color::Yellow));
+        let msg_p = Paragraph::new(msg.as_str()).style(app.theme.warning);
         let area = centered_rect(50, 10, f.size());
         f.render_widget(Clear, area);
         f.render_widget(msg_p.block(Block::default().borders(Borders::ALL).title("Status")), area);
@@ -429,27 +2694,223 @@ fn draw_finished_screen(f: &mut Frame, app: &mut App) {
         }
     }
 
-    let footer_text = "Review Script | [s] Save to File | [r] Run Directly | [q] Quit | [Esc/Backspace] Go Back";
-    let footer = Paragraph::new(footer_text).style(Style::default().fg(Color::Cyan))
+    let run_here = if app.model.target_runs_here() { "[r] Run Directly | " } else { "" };
+    let footer_text = format!(
+        "Scroll [↑↓ PgUp/PgDn] | [/] Search | [s] Save to File | [m] Save Runbook | [c] Copy | [e] Export (Terraform/cloud-init/Packer/Vagrant) | [j] Save Selection JSON | [d] Diff with Saved | [l] Next Lint Finding | [p] Preflight | {}[R] Run on Remote Hosts | [?] Help | [q] Quit | [Esc/Backspace] Go Back",
+        run_here,
+    );
+    let footer = Paragraph::new(footer_text).style(app.theme.footer)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(footer, chunks[2]);
+}
+
+/// `viewed` with its `# redcent-generated:` time taken from `current`, so the diff pane does not
+/// report the time the preview was generated as a change.
+fn same_generation_time(viewed: &str, current: &str) -> String {
+    const GENERATED: &str = "# redcent-generated: ";
+    let Some(now) = current.lines().find(|line| line.starts_with(GENERATED)) else { return viewed.to_string() };
+    viewed.lines().map(|line| if line.starts_with(GENERATED) { now } else { line }).collect::<Vec<_>>().join("\n")
+}
+
+/// Describes the highlighted node: an item's long-form description (or its script's summary),
+/// tags and dependencies, or how much of a menu is selected.
+fn draw_details(f: &mut Frame, area: Rect, node: Option<&Rc<RefCell<MenuNode>>>, app: &App) {
+    let mut lines = Vec::new();
+    if let Some(node) = node {
+        let node = node.borrow();
+        lines.push(Line::styled(node.name().to_string(), app.theme.title));
+        match &*node {
+            MenuNode::Item { id, requires, conflicts, .. } => {
+                let tags = node.tags(app.model.backend.as_ref());
+                let mut meta = format!("ID: {}", id);
+                if !tags.is_empty() {
+                    meta.push_str(&format!("   Tags: {}", tags.join(", ")));
+                }
+                if app.favorites.iter().any(|favorite| favorite == id) {
+                    meta.push_str("   ★ Favorite");
+                }
+                lines.push(Line::styled(meta, app.theme.muted));
+                lines.push(Line::from(""));
+                let summary = node.script(app.model.backend.as_ref()).map(|script| script.description).unwrap_or_default();
+                lines.extend(node.description().unwrap_or(&summary).lines().map(|line| Line::from(line.to_string())));
+                if !requires.is_empty() {
+                    lines.push(Line::from(""));
+                    lines.push(Line::styled(format!("Requires: {}", requires.join(", ")), app.theme.muted));
+                }
+                if !conflicts.is_empty() {
+                    lines.push(Line::styled(format!("Conflicts with: {}", conflicts.join(", ")), app.theme.muted));
+                }
+            }
+            MenuNode::Menu { .. } => {
+                let (selected, total) = node.selection_counts();
+                lines.push(Line::styled(format!("{} of {} items selected", selected, total), app.theme.muted));
+            }
+        }
+    }
+    let details = Paragraph::new(lines).wrap(Wrap { trim: false }).block(Block::default().borders(Borders::ALL).title("Details"));
+    f.render_widget(details, area);
+}
+
+fn draw_history_screen(f: &mut Frame, app: &mut App) {
+    let chunks = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref()).split(f.size());
+
+    let footer_text = if app.history_viewing {
+        let content = app.history_content();
+        let title = app.history.get(app.history_index).map(|r| r.summary()).unwrap_or_default();
+        app.viewer.render(f, chunks[0], &content, &title);
+        "Scroll [↑↓ PgUp/PgDn] | [/] Search | [r] Re-run | [Esc/Backspace] Back to List"
+    } else {
+        let title = history::runs_dir().map(|d| format!("Run History ({})", d.display())).unwrap_or_else(|| "Run History".to_string());
+        let items: Vec<ListItem> = if app.history.is_empty() {
+            vec![ListItem::new("No recorded runs yet. Saved and executed scripts appear here.")]
+        } else {
+            app.history.iter().map(|record| ListItem::new(record.summary())).collect()
+        };
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(app.theme.highlight)
+            .highlight_symbol(">> ");
+        let mut list_state = ratatui::widgets::ListState::default();
+        if !app.history.is_empty() {
+            list_state.select(Some(app.history_index));
+        }
+        f.render_stateful_widget(list, chunks[0], &mut list_state);
+        "Navigate [↑↓] | [Enter] View Script & Output | [r] Re-run | [c] Resume | [Esc/h] Back | [q] Quit"
+    };
+
+    let footer = Paragraph::new(footer_text).style(app.theme.footer)
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(footer, chunks[1]);
 }
 
-fn draw_saving_popup(f: &mut Frame, input: &str) {
+/// A one-line text prompt over the current screen, used for file paths.
+/// Writes a saved file, making scripts (anything starting with a shebang) executable.
+fn save_file(path: &Path, content: &str) -> io::Result<()> {
+    fs::write(path, content)?;
+    if content.starts_with("#!") {
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+fn draw_input_popup(f: &mut Frame, title: &str, hint: &str, input: &str) {
     let area = centered_rect(60, 20, f.size());
-    let block = Block::default().title("Save Script").borders(Borders::ALL);
+    let block = Block::default().title(title).borders(Borders::ALL);
     f.render_widget(Clear, area);
     f.render_widget(block, area);
 
     let popup_chunks = Layout::default().direction(Direction::Vertical).margin(2)
         .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Min(1)].as_ref()).split(area);
     
-    let p1 = Paragraph::new("Enter filename (press Enter to save, Esc to cancel):");
+    let p1 = Paragraph::new(hint);
     let p2 = Paragraph::new(input).block(Block::default().borders(Borders::ALL));
     f.render_widget(p1, popup_chunks[0]);
     f.render_widget(p2, popup_chunks[1]);
 }
 
+fn draw_run_locked(f: &mut Frame, pending: &PendingRun, theme: &Theme) {
+    let area = centered_rect(60, 40, f.size());
+    f.render_widget(Clear, area);
+    let (message, keys) = match &pending.error {
+        LockError::Busy(holder) if holder.is_stale() => (
+            format!("A previous run left a stale lock ({}); that process no longer exists.", holder.describe()),
+            "[t] Take Over Lock and Run | [r] Retry | [Esc] Back",
+        ),
+        LockError::Busy(holder) => (
+            format!("Another run is in progress ({}). Wait for it to finish, then retry.", holder.describe()),
+            "[r] Retry | [Esc] Back",
+        ),
+        LockError::Io(e) => (format!("Could not take the run lock: {}", e), "[r] Retry | [Esc] Back"),
+    };
+    let text = format!("{}\n\n{}", message, keys);
+    let block = Block::default().title("Run Locked").borders(Borders::ALL).border_style(theme.error);
+    f.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }).block(block), area);
+}
+
+fn draw_restore_draft(f: &mut Frame, draft: &Draft, theme: &Theme) {
+    let area = centered_rect(60, 40, f.size());
+    f.render_widget(Clear, area);
+    let mut ids = draft.items.iter().take(5).cloned().collect::<Vec<_>>().join(", ");
+    if draft.items.len() > 5 {
+        ids.push_str(&format!(" and {} more", draft.items.len() - 5));
+    }
+    let text = format!(
+        "The last session did not exit cleanly. Its selection was saved at {}: {} item(s) for {} ({}).\n\n[y] Restore | [n] Discard",
+        history::display_timestamp(&draft.saved), draft.items.len(), draft.target, ids,
+    );
+    let block = Block::default().title("Restore Draft").borders(Borders::ALL).border_style(theme.warning);
+    f.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }).block(block), area);
+}
+
+fn draw_terminal_screen(f: &mut Frame, app: &mut App) {
+    let Some(run) = app.live_run.as_mut() else { return };
+    let chunks = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Length(run.progress.height()), Constraint::Min(0), Constraint::Length(3)].as_ref()).split(f.size());
+    let (title, footer) = match run.pane.exit_code() {
+        None => (
+            format!("Running script with {}", app.escalation.label()),
+            "Keys go to the script | [Ctrl-C] Interrupt | [Shift+PgUp/PgDn] Scroll | [F2] Quiet-Step Tail | [F1] Help".to_string(),
+        ),
+        Some(code) => {
+            let resume = run.prepared.record.as_ref().and_then(|record| record.resume_point())
+                .map(|point| format!(" | [c] Resume from block {}", point.completed + 1))
+                .unwrap_or_default();
+            let status = if code == 0 { "Script finished successfully".to_string() } else { format!("Script failed with exit code {}", code) };
+            let status = match run.failures.verification() {
+                Some((passed, 0)) => format!("{}, {} check(s) verified", status, passed),
+                Some((passed, failed)) => format!("{}, {} of {} check(s) failed verification", status, failed, passed + failed),
+                None => status,
+            };
+            let troubleshoot = if code == 0 { "" } else { " | [t] Troubleshoot" };
+            let problems: Vec<&str> = run.prepared.warnings.iter().chain(&run.error).map(String::as_str).collect();
+            let status = if problems.is_empty() { status } else { format!("{} ({})", status, problems.join("; ")) };
+            (status, format!("[Enter/Esc] Back to the menu | [h] Run History{}{} | [Shift+PgUp/PgDn] Scroll | [q] Quit", troubleshoot, resume))
+        }
+    };
+    if run.progress.is_tracked() {
+        run.progress.draw(f, chunks[0], &app.theme);
+    }
+    run.pane.draw(f, chunks[1], &title, &app.theme);
+    if run.lock.is_some() {
+        run.watchdog.draw(f, chunks[1], &app.theme);
+    }
+    let footer = Paragraph::new(footer).style(app.theme.footer).block(Block::default().borders(Borders::ALL));
+    f.render_widget(footer, chunks[2]);
+}
+
+fn draw_restore_session(f: &mut Frame, session: &Session, theme: &Theme) {
+    let area = centered_rect(60, 40, f.size());
+    f.render_widget(Clear, area);
+    let selection = &session.selection;
+    let mut ids = selection.items.iter().take(5).cloned().collect::<Vec<_>>().join(", ");
+    if selection.items.len() > 5 {
+        ids.push_str(&format!(" and {} more", selection.items.len() - 5));
+    }
+    let place = if session.nav_path.is_empty() { "the main menu".to_string() } else { session.nav_path.join(" > ") };
+    let text = format!(
+        "Continue where you left off at {}? {} item(s) were selected for {} ({}), in {}.\n\n[y] Restore | [n] Start fresh",
+        history::display_timestamp(&selection.saved), selection.items.len(), selection.target, ids, place,
+    );
+    let block = Block::default().title("Restore Session").borders(Borders::ALL).border_style(theme.title);
+    f.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }).block(block), area);
+}
+
+fn draw_resume_run(f: &mut Frame, record: &RunRecord, point: &ResumePoint, theme: &Theme) {
+    let area = centered_rect(60, 40, f.size());
+    f.render_widget(Clear, area);
+    let how = match record.exit_code {
+        Some(code) => format!("failed with exit code {}", code),
+        None => "was interrupted".to_string(),
+    };
+    let text = format!(
+        "The last run, started {}, {} after completing {} of {} blocks. Resuming runs its script again from block {}, skipping the completed ones.\n\n[y] Resume | [n] Not now (resume later from the history with [c])",
+        history::display_timestamp(&record.name), how, point.completed, point.total, point.completed + 1,
+    );
+    let block = Block::default().title("Resume Last Run").borders(Borders::ALL).border_style(theme.warning);
+    f.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }).block(block), area);
+}
+
 /// Helper function to create a centered rectangle for popups
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default().direction(Direction::Vertical)
@@ -0,0 +1,453 @@
+// src/menu.rs
+
+use crate::{backend::PackageBackend, script::Script};
+use std::{cell::RefCell, rc::Rc};
+
+/// A user-supplied value substituted for `{{key}}` placeholders in an item's script.
+pub struct Param {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub value: String,
+    /// Whether the script is broken without a value; optional parameters may be left blank.
+    pub required: bool,
+}
+
+impl Param {
+    pub fn new(key: &'static str, label: &'static str, default: &str) -> Param {
+        Param { key, label, value: default.to_string(), required: true }
+    }
+
+    /// Marks the parameter as optional, so a blank value is substituted instead of left unresolved.
+    pub fn optional(mut self) -> Param {
+        self.required = false;
+        self
+    }
+
+    /// A copy of the parameter holding `value`, e.g. for previewing values entered in a form.
+    pub fn with_value(&self, value: &str) -> Param {
+        Param { key: self.key, label: self.label, value: value.to_string(), required: self.required }
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        !self.required || !self.value.trim().is_empty()
+    }
+}
+
+/// Replaces every `{{key}}` placeholder in `script` with the matching parameter value.
+/// Required parameters without a value are left as `{{key}}` so they show up as unresolved.
+pub fn render_params(script: &str, params: &[Param]) -> String {
+    params.iter().filter(|param| param.is_resolved()).fold(script.to_string(), |text, param| {
+        text.replace(&format!("{{{{{}}}}}", param.key), &param.value)
+    })
+}
+
+/// Keys of the `{{key}}` placeholders left in a rendered script, in order of first appearance.
+pub fn unresolved_placeholders(script: &str) -> Vec<&str> {
+    let mut keys: Vec<&str> = Vec::new();
+    let mut rest = script;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else { break };
+        let key = &after[..end];
+        if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') && !keys.contains(&key) {
+            keys.push(key);
+        }
+        rest = &after[end + 2..];
+    }
+    keys
+}
+
+/// Represents a node in the menu tree. It can be a selectable item or a sub-menu.
+pub enum MenuNode {
+    Item {
+        /// Stable identifier, e.g. `repos.epel`, used by dependency metadata.
+        id: &'static str,
+        name: String,
+        /// Renders the item's commands through the target's package backend.
+        script_fn: fn(&dyn PackageBackend) -> Script,
+        selected: bool,
+        /// Position in the script order arranged by the user; unarranged items run after the
+        /// arranged ones, in tree order. Cleared when the item is deselected.
+        rank: Option<usize>,
+        /// IDs of items that are auto-selected together with this one.
+        requires: &'static [&'static str],
+        /// IDs of items that cannot be selected together with this one.
+        conflicts: &'static [&'static str],
+        /// Free-form labels for filtering, e.g. `gui`, `kvm`, `destructive`.
+        tags: &'static [&'static str],
+        /// Values prompted for when the item is selected.
+        params: Vec<Param>,
+        /// Long-form help for the details pane: what the component is, what selecting it does, links.
+        description: Option<&'static str>,
+    },
+    Menu {
+        name: String,
+        children: Vec<Rc<RefCell<MenuNode>>>,
+    },
+}
+
+impl MenuNode {
+    /// Recursively collects every item node in the tree, in tree order.
+    pub fn collect_items(node: &Rc<RefCell<MenuNode>>, items: &mut Vec<Rc<RefCell<MenuNode>>>) {
+        match &*node.borrow() {
+            MenuNode::Item { .. } => items.push(node.clone()),
+            MenuNode::Menu { children, .. } => {
+                for child in children {
+                    MenuNode::collect_items(child, items);
+                }
+            }
+        }
+    }
+
+    /// Returns the item ID, or an empty string for menus.
+    pub fn id(&self) -> &'static str {
+        match self {
+            MenuNode::Item { id, .. } => id,
+            MenuNode::Menu { .. } => "",
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            MenuNode::Item { name, .. } | MenuNode::Menu { name, .. } => name,
+        }
+    }
+
+    /// The item's long-form description; `None` for menus and items without one.
+    pub fn description(&self) -> Option<&'static str> {
+        match self {
+            MenuNode::Item { description, .. } => *description,
+            MenuNode::Menu { .. } => None,
+        }
+    }
+
+    pub fn has_params(&self) -> bool {
+        matches!(self, MenuNode::Item { params, .. } if !params.is_empty())
+    }
+
+    pub fn is_selected(&self) -> bool {
+        matches!(self, MenuNode::Item { selected: true, .. })
+    }
+
+    pub fn requires(&self) -> &'static [&'static str] {
+        match self {
+            MenuNode::Item { requires, .. } => requires,
+            MenuNode::Menu { .. } => &[],
+        }
+    }
+
+    /// Whether either item declares a conflict with the other.
+    pub fn conflicts_with(&self, other: &MenuNode) -> bool {
+        match (self, other) {
+            (MenuNode::Item { id, conflicts, .. }, MenuNode::Item { id: other_id, conflicts: other_conflicts, .. }) => {
+                conflicts.contains(other_id) || other_conflicts.contains(id)
+            }
+            _ => false,
+        }
+    }
+
+    /// Builds the item's script for `backend` with its distribution conditionals resolved for the
+    /// backend's target and its current parameter values; `None` for menus.
+    pub fn script(&self, backend: &dyn PackageBackend) -> Option<Script> {
+        match self {
+            MenuNode::Item { id, script_fn, params, .. } => {
+                let mut script = script_fn(backend).for_distro(backend.distro()).with_params(params);
+                script.id = id;
+                Some(script)
+            }
+            MenuNode::Menu { .. } => None,
+        }
+    }
+
+    /// The item's tags plus `network` and `reboot` when its script for `backend` downloads
+    /// content or needs a reboot; menus have no tags.
+    pub fn tags(&self, backend: &dyn PackageBackend) -> Vec<&'static str> {
+        let MenuNode::Item { tags, .. } = self else { return Vec::new() };
+        let mut all = tags.to_vec();
+        if let Some(script) = self.script(backend) {
+            if script.requires_network && !all.contains(&"network") {
+                all.push("network");
+            }
+            if script.requires_reboot && !all.contains(&"reboot") {
+                all.push("reboot");
+            }
+            if !script.listens.is_empty() && !all.contains(&"server") {
+                all.push("server");
+            }
+        }
+        all
+    }
+
+    /// Renders the item's script with its current parameter values; menus render to an empty string.
+    pub fn rendered_script(&self, backend: &dyn PackageBackend) -> String {
+        self.script(backend).map(|script| script.render()).unwrap_or_default()
+    }
+
+    /// Counts the selected items and all items under this node, for tri-state menu checkboxes.
+    pub fn selection_counts(&self) -> (usize, usize) {
+        match self {
+            MenuNode::Item { selected, .. } => (usize::from(*selected), 1),
+            MenuNode::Menu { children, .. } => children.iter()
+                .map(|child| child.borrow().selection_counts())
+                .fold((0, 0), |(s, t), (cs, ct)| (s + cs, t + ct)),
+        }
+    }
+
+    /// Recursively collects the names of all selected items.
+    pub fn get_selected_item_names(&self, names: &mut Vec<String>) {
+        match self {
+            MenuNode::Item { name, selected, .. } => {
+                if *selected {
+                    names.push(name.clone());
+                }
+            }
+            MenuNode::Menu { children, .. } => {
+                for child in children {
+                    child.borrow().get_selected_item_names(names);
+                }
+            }
+        }
+    }
+}
+
+/// Returns every item in the tree, in tree order.
+pub fn all_items(tree: &Rc<RefCell<MenuNode>>) -> Vec<Rc<RefCell<MenuNode>>> {
+    let mut items = Vec::new();
+    MenuNode::collect_items(tree, &mut items);
+    items
+}
+
+/// Returns the chain of nodes from `tree` down to `target`, both included.
+pub fn path_to(tree: &Rc<RefCell<MenuNode>>, target: &Rc<RefCell<MenuNode>>) -> Option<Vec<Rc<RefCell<MenuNode>>>> {
+    if Rc::ptr_eq(tree, target) {
+        return Some(vec![tree.clone()]);
+    }
+    if let MenuNode::Menu { children, .. } = &*tree.borrow() {
+        for child in children {
+            if let Some(mut path) = path_to(child, target) {
+                path.insert(0, tree.clone());
+                return Some(path);
+            }
+        }
+    }
+    None
+}
+
+/// Returns every item with its category path (menu names below the root joined by ` > `), in tree order.
+pub fn item_paths(tree: &Rc<RefCell<MenuNode>>) -> Vec<(String, Rc<RefCell<MenuNode>>)> {
+    fn visit(node: &Rc<RefCell<MenuNode>>, path: &mut Vec<String>, out: &mut Vec<(String, Rc<RefCell<MenuNode>>)>) {
+        match &*node.borrow() {
+            MenuNode::Item { .. } => out.push((path.join(" > "), node.clone())),
+            MenuNode::Menu { children, .. } => {
+                for child in children {
+                    if let MenuNode::Menu { name, .. } = &*child.borrow() {
+                        path.push(name.clone());
+                        visit(child, path, out);
+                        path.pop();
+                    } else {
+                        visit(child, path, out);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    visit(tree, &mut Vec::new(), &mut out);
+    out
+}
+
+pub fn find_item(tree: &Rc<RefCell<MenuNode>>, id: &str) -> Option<Rc<RefCell<MenuNode>>> {
+    all_items(tree).into_iter().find(|item| item.borrow().id() == id)
+}
+
+/// Returns the selected items in script order: the order arranged with [`swap_items`], then the
+/// remaining items in tree order, except that every item's requirements are emitted before it.
+pub fn ordered_selected_items(tree: &Rc<RefCell<MenuNode>>) -> Vec<Rc<RefCell<MenuNode>>> {
+    fn visit(tree: &Rc<RefCell<MenuNode>>, item: &Rc<RefCell<MenuNode>>, ordered: &mut Vec<Rc<RefCell<MenuNode>>>) {
+        if ordered.iter().any(|done| Rc::ptr_eq(done, item)) {
+            return;
+        }
+        ordered.push(item.clone());
+        let position = ordered.len() - 1;
+        for req in item.borrow().requires() {
+            if let Some(dep) = find_item(tree, req).filter(|dep| dep.borrow().is_selected()) {
+                visit(tree, &dep, ordered);
+            }
+        }
+        // Move the item behind any requirements that were just added.
+        let item = ordered.remove(position);
+        ordered.push(item);
+    }
+
+    let mut selected: Vec<_> = all_items(tree).into_iter().filter(|item| item.borrow().is_selected()).collect();
+    selected.sort_by_key(|item| match &*item.borrow() {
+        MenuNode::Item { rank, .. } => rank.unwrap_or(usize::MAX),
+        MenuNode::Menu { .. } => usize::MAX,
+    });
+    let mut ordered = Vec::new();
+    for item in &selected {
+        visit(tree, item, &mut ordered);
+    }
+    ordered
+}
+
+/// Arranges the script order: `items` run first, in this order.
+pub fn set_script_order(items: &[Rc<RefCell<MenuNode>>]) {
+    for (position, item) in items.iter().enumerate() {
+        if let MenuNode::Item { rank, .. } = &mut *item.borrow_mut() {
+            *rank = Some(position);
+        }
+    }
+}
+
+/// Whether the user has arranged the script order, as opposed to it following the tree.
+pub fn has_script_order(tree: &Rc<RefCell<MenuNode>>) -> bool {
+    all_items(tree).iter().any(|item| matches!(&*item.borrow(), MenuNode::Item { rank: Some(_), .. }))
+}
+
+/// Returns the script order to tree order.
+pub fn reset_script_order(tree: &Rc<RefCell<MenuNode>>) {
+    for item in all_items(tree) {
+        if let MenuNode::Item { rank, .. } = &mut *item.borrow_mut() {
+            *rank = None;
+        }
+    }
+}
+
+/// Swaps two selected items in the script order, `first` being the earlier one. Swapping an item
+/// with its own requirement is refused with an explanation.
+pub fn swap_items(tree: &Rc<RefCell<MenuNode>>, first: &Rc<RefCell<MenuNode>>, second: &Rc<RefCell<MenuNode>>) -> Result<(), String> {
+    {
+        let (first, second) = (first.borrow(), second.borrow());
+        if second.requires().contains(&first.id()) {
+            return Err(format!("{} has to run before {}, which requires it", first.name(), second.name()));
+        }
+    }
+    let mut order = ordered_selected_items(tree);
+    let (Some(a), Some(b)) = (order.iter().position(|item| Rc::ptr_eq(item, first)), order.iter().position(|item| Rc::ptr_eq(item, second))) else {
+        return Ok(());
+    };
+    order.swap(a, b);
+    set_script_order(&order);
+    Ok(())
+}
+
+/// What a successful [`toggle_item`] changed.
+pub enum ToggleOutcome {
+    /// The item was selected; `auto_selected` names the requirements selected along with it.
+    Selected { auto_selected: Vec<String> },
+    Deselected,
+}
+
+/// Toggles an item, auto-selecting its requirements. Conflicting selections and deselecting
+/// an item that selected items still require are refused with an explanation.
+pub fn toggle_item(tree: &Rc<RefCell<MenuNode>>, node: &Rc<RefCell<MenuNode>>) -> Result<ToggleOutcome, String> {
+    let items = all_items(tree);
+    let (id, name, selected) = {
+        let node = node.borrow();
+        (node.id(), node.name().to_string(), node.is_selected())
+    };
+
+    if selected {
+        let dependents: Vec<String> = items.iter()
+            .map(|item| item.borrow())
+            .filter(|item| item.is_selected() && item.requires().contains(&id))
+            .map(|item| item.name().to_string())
+            .collect();
+        if !dependents.is_empty() {
+            return Err(format!("Cannot deselect {}: required by {}", name, dependents.join(", ")));
+        }
+        if let MenuNode::Item { selected, rank, .. } = &mut *node.borrow_mut() {
+            *selected = false;
+            *rank = None;
+        }
+        return Ok(ToggleOutcome::Deselected);
+    }
+
+    // Transitively gather the item and every unselected requirement.
+    let mut to_select = vec![node.clone()];
+    let mut i = 0;
+    while i < to_select.len() {
+        let requires = to_select[i].borrow().requires();
+        for req in requires {
+            if let Some(dep) = items.iter().find(|item| item.borrow().id() == *req)
+                && !dep.borrow().is_selected()
+                && !to_select.iter().any(|pending| Rc::ptr_eq(pending, dep))
+            {
+                to_select.push(dep.clone());
+            }
+        }
+        i += 1;
+    }
+
+    for candidate in &to_select {
+        let others = items.iter().filter(|item| item.borrow().is_selected()).chain(to_select.iter());
+        for other in others {
+            if !Rc::ptr_eq(candidate, other) && candidate.borrow().conflicts_with(&other.borrow()) {
+                return Err(if Rc::ptr_eq(candidate, node) {
+                    format!("Cannot select {}: conflicts with {}", name, other.borrow().name())
+                } else {
+                    format!("Cannot select {}: its requirement {} conflicts with {}", name, candidate.borrow().name(), other.borrow().name())
+                });
+            }
+        }
+    }
+
+    for item in &to_select {
+        if let MenuNode::Item { selected, .. } = &mut *item.borrow_mut() {
+            *selected = true;
+        }
+    }
+    let auto_selected = to_select[1..].iter().map(|item| item.borrow().name().to_string()).collect();
+    Ok(ToggleOutcome::Selected { auto_selected })
+}
+
+/// Selects every unselected item under `menu`; see [`select_items`].
+pub fn select_all(tree: &Rc<RefCell<MenuNode>>, menu: &Rc<RefCell<MenuNode>>) -> (usize, Vec<String>) {
+    select_items(tree, &all_items(menu))
+}
+
+/// Deselects every selected item under `menu`; see [`deselect_items`].
+pub fn deselect_all(tree: &Rc<RefCell<MenuNode>>, menu: &Rc<RefCell<MenuNode>>) -> (usize, Vec<String>) {
+    deselect_items(tree, &all_items(menu))
+}
+
+/// Selects each unselected item in `items` through [`toggle_item`], so requirements are pulled in
+/// and conflicts are refused. Returns how many items were selected (requirements included) and the
+/// refusal messages.
+pub fn select_items(tree: &Rc<RefCell<MenuNode>>, items: &[Rc<RefCell<MenuNode>>]) -> (usize, Vec<String>) {
+    let before = tree.borrow().selection_counts().0;
+    let mut refused = Vec::new();
+    for item in items {
+        if !item.borrow().is_selected()
+            && let Err(reason) = toggle_item(tree, item)
+        {
+            refused.push(reason);
+        }
+    }
+    (tree.borrow().selection_counts().0 - before, refused)
+}
+
+/// Deselects each selected item in `items`. Items still required by other selections stay selected;
+/// returns how many items were deselected and the refusal messages.
+pub fn deselect_items(tree: &Rc<RefCell<MenuNode>>, items: &[Rc<RefCell<MenuNode>>]) -> (usize, Vec<String>) {
+    let mut changed = 0;
+    // Dependents may come after their requirements in tree order, so retry until nothing changes.
+    loop {
+        let mut refused = Vec::new();
+        let mut progress = false;
+        for item in items.iter().filter(|item| item.borrow().is_selected()) {
+            match toggle_item(tree, item) {
+                Ok(_) => {
+                    changed += 1;
+                    progress = true;
+                }
+                Err(reason) => refused.push(reason),
+            }
+        }
+        if !progress {
+            return (changed, refused);
+        }
+    }
+}
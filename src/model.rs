@@ -0,0 +1,619 @@
+// src/model.rs
+
+use crate::{
+    backend::{offline_repo_setup, Offline, PackageBackend, Proxy},
+    generate::{generate_logged_script, generate_resumable_script, generate_script, script_order, ErrorMode},
+    menu::{all_items, find_item, toggle_item, unresolved_placeholders, MenuNode, ToggleOutcome},
+    OsDistribution,
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// The state of a front end that does not depend on the terminal: the menu tree with its
+/// selection, where the user is in it, the distribution the script is authored for and how it is
+/// generated. A front end wraps it with its screens and widgets.
+pub struct Model {
+    pub menu_tree: Rc<RefCell<MenuNode>>,
+    /// Menus opened from the root, the root first.
+    pub nav_path: Vec<Rc<RefCell<MenuNode>>>,
+    /// Highlighted row of the current menu.
+    pub selected_index: usize,
+    /// Distribution of the host the front end runs on.
+    pub os_distro: OsDistribution,
+    /// Distribution the script is authored for; defaults to the detected one.
+    pub target: OsDistribution,
+    /// Renders package-management commands for `target`.
+    pub backend: Box<dyn PackageBackend>,
+    /// Failure handling of generated scripts.
+    pub errors: ErrorMode,
+    /// Whether generated scripts keep a transcript; see [`generate_logged_script`]. Runs inside the
+    /// front end record their output themselves, so their scripts never do.
+    pub transcript: bool,
+    pub reboot_requested: bool,
+    /// Base URL of the local repository packages come from on hosts without internet access, e.g.
+    /// a mounted DVD; `None` uses the host's configured repositories. Set with [`Model::set_offline`].
+    pub offline: Option<String>,
+    /// Written into generated scripts when set.
+    pub proxy: Proxy,
+}
+
+impl Model {
+    pub fn new(menu_tree: Rc<RefCell<MenuNode>>, os_distro: OsDistribution) -> Model {
+        Model {
+            nav_path: vec![menu_tree.clone()],
+            menu_tree,
+            selected_index: 0,
+            os_distro,
+            target: os_distro,
+            backend: os_distro.package_backend(),
+            errors: ErrorMode::Continue,
+            transcript: false,
+            reboot_requested: false,
+            offline: None,
+            proxy: Proxy::default(),
+        }
+    }
+
+    /// The target as written into generated scripts, e.g. `Rhel`.
+    pub fn target_name(&self) -> String {
+        format!("{:?}", self.target)
+    }
+
+    /// Authors for `target` from now on.
+    pub fn set_target(&mut self, target: OsDistribution) {
+        self.target = target;
+        self.backend = match self.offline {
+            Some(_) => Box::new(Offline(target.package_backend())),
+            None => target.package_backend(),
+        };
+    }
+
+    /// Turns offline mode on with the repository at `baseurl`, or off with `None`. Turning it on
+    /// deselects the items that need the internet, and those requiring them; returns their names.
+    pub fn set_offline(&mut self, baseurl: Option<String>) -> Vec<String> {
+        self.offline = baseurl;
+        self.set_target(self.target);
+        self.deselect_internet_items()
+    }
+
+    /// Whether the item's script downloads from the internet; see [`crate::Script::needs_internet`].
+    pub fn needs_internet(&self, node: &Rc<RefCell<MenuNode>>) -> bool {
+        node.borrow().script(self.backend.as_ref()).is_some_and(|script| script.needs_internet())
+    }
+
+    /// In offline mode, deselects the selected items that need the internet together with the
+    /// selected items requiring them, and returns their names. Called after bulk selections,
+    /// which do not go through [`Model::toggle_item`].
+    pub fn deselect_internet_items(&mut self) -> Vec<String> {
+        if self.offline.is_none() {
+            return Vec::new();
+        }
+        let selected: Vec<_> = all_items(&self.menu_tree).into_iter().filter(|item| item.borrow().is_selected()).collect();
+        let mut dropped: Vec<_> = selected.iter().filter(|item| self.needs_internet(item)).cloned().collect();
+        let mut n = 0;
+        while n < dropped.len() {
+            let id = dropped[n].borrow().id();
+            for item in &selected {
+                if item.borrow().requires().contains(&id) && !dropped.iter().any(|other| Rc::ptr_eq(other, item)) {
+                    dropped.push(item.clone());
+                }
+            }
+            n += 1;
+        }
+        dropped.iter().map(|item| {
+            if let MenuNode::Item { selected, rank, .. } = &mut *item.borrow_mut() {
+                *selected = false;
+                *rank = None;
+            }
+            item.borrow().name().to_string()
+        }).collect()
+    }
+
+    /// Moves on to the next distribution: RHEL, CentOS Stream, Debian, Ubuntu.
+    pub fn cycle_target(&mut self) {
+        self.set_target(match self.target {
+            OsDistribution::Rhel => OsDistribution::Centos,
+            OsDistribution::Centos => OsDistribution::Debian,
+            OsDistribution::Debian => OsDistribution::Ubuntu,
+            OsDistribution::Ubuntu | OsDistribution::Unknown => OsDistribution::Rhel,
+        });
+    }
+
+    /// Whether scripts for the target can run on this host: the two share a package manager.
+    /// Scripts for any other target are only authored here, to be saved or run on remote hosts.
+    pub fn target_runs_here(&self) -> bool {
+        self.target.package_backend().name() == self.os_distro.package_backend().name()
+    }
+
+    /// Why scripts for the target are not run on this host, when they are not.
+    pub fn foreign_target(&self) -> Option<String> {
+        (!self.target_runs_here()).then(|| format!(
+            "the script is authored for {:?} ({}) and this host uses {}",
+            self.target, self.backend.name(), self.os_distro.package_backend().name(),
+        ))
+    }
+
+    pub fn find_item(&self, id: &str) -> Option<Rc<RefCell<MenuNode>>> {
+        find_item(&self.menu_tree, id)
+    }
+
+    /// Toggles an item through the dependency rules; see [`toggle_item`]. In offline mode, items
+    /// that need the internet, or whose requirements do, cannot be selected.
+    pub fn toggle_item(&mut self, node: &Rc<RefCell<MenuNode>>) -> Result<ToggleOutcome, String> {
+        if self.offline.is_some() && !node.borrow().is_selected() {
+            let mut pending = vec![node.clone()];
+            while let Some(item) = pending.pop() {
+                if self.needs_internet(&item) {
+                    let name = node.borrow().name().to_string();
+                    return Err(if Rc::ptr_eq(&item, node) {
+                        format!("Cannot select {} in offline mode: it downloads from the internet", name)
+                    } else {
+                        format!("Cannot select {} in offline mode: its requirement {} downloads from the internet", name, item.borrow().name())
+                    });
+                }
+                pending.extend(item.borrow().requires().iter().filter_map(|id| self.find_item(id)).filter(|dep| !dep.borrow().is_selected()));
+            }
+        }
+        toggle_item(&self.menu_tree, node)
+    }
+
+    /// Selected items in script order.
+    pub fn selected_nodes(&self) -> Vec<Rc<RefCell<MenuNode>>> {
+        script_order(&self.menu_tree, self.backend.as_ref()).into_iter().map(|(item, _)| item).collect()
+    }
+
+    /// The first selected item, in script order, whose rendered commands still contain a placeholder, and that placeholder's key.
+    pub fn first_unresolved(&self) -> Option<(Rc<RefCell<MenuNode>>, String)> {
+        script_order(&self.menu_tree, self.backend.as_ref()).into_iter().find_map(|(item, _)| {
+            let script = item.borrow().rendered_script(self.backend.as_ref());
+            let key = unresolved_placeholders(&script).first()?.to_string();
+            Some((item, key))
+        })
+    }
+
+    /// The script for the current selection, before privilege escalation is applied.
+    pub fn generate_commands(&self, reboot: bool) -> String {
+        let generate = if self.transcript { generate_logged_script } else { generate_script };
+        self.with_preamble(generate(&self.menu_tree, &self.target_name(), self.backend.as_ref(), self.errors, reboot))
+    }
+
+    /// Like [`Model::generate_commands`], with checkpoints so a run can be resumed.
+    pub fn generate_run_commands(&self, reboot: bool) -> String {
+        self.with_preamble(generate_resumable_script(&self.menu_tree, &self.target_name(), self.backend.as_ref(), self.errors, reboot))
+    }
+
+    /// Sets up the proxy and, in offline mode, the local repository ahead of the first phase of `script`.
+    fn with_preamble(&self, mut script: String) -> String {
+        let mut preamble = String::new();
+        if self.proxy.is_set() {
+            preamble.push_str(&format!("# ===== Proxy =====\n\n{}\n\n", self.proxy.setup(self.target)));
+        }
+        if let Some(baseurl) = &self.offline {
+            preamble.push_str(&format!("# ===== Offline repository =====\n\n{}\n\n", offline_repo_setup(self.target, baseurl)));
+        }
+        if let Some(start) = script.find("# ===== ") {
+            script.insert_str(start, &preamble);
+        }
+        script
+    }
+
+    /// What keeps the selection from generating a working script, in script order: requirements
+    /// that are not selected (or missing from the tree), conflicting pairs of selected items, pairs
+    /// whose scripts collide, and parameters still left blank. Toggling keeps the first two out of
+    /// the selection, but bulk changes and front ends setting the flags directly may not.
+    pub fn problems(&self) -> Vec<Problem> {
+        let selected = self.selected_nodes();
+        let mut problems = Vec::new();
+        for item in &selected {
+            for id in item.borrow().requires() {
+                let requirement = self.find_item(id);
+                if !requirement.as_ref().is_some_and(|requirement| requirement.borrow().is_selected()) {
+                    problems.push(Problem::MissingRequirement { item: item.clone(), id: id.to_string(), requirement });
+                }
+            }
+        }
+        for (n, item) in selected.iter().enumerate() {
+            for other in &selected[n + 1..] {
+                if item.borrow().conflicts_with(&other.borrow()) {
+                    problems.push(Problem::Conflict { first: item.clone(), second: other.clone() });
+                } else if let Some(what) = self.collision(item, other) {
+                    problems.push(Problem::Collision { first: item.clone(), second: other.clone(), what });
+                }
+            }
+        }
+        for item in &selected {
+            let script = item.borrow().rendered_script(self.backend.as_ref());
+            if let Some(key) = unresolved_placeholders(&script).first() {
+                problems.push(Problem::UnresolvedParam { item: item.clone(), key: key.to_string() });
+            }
+        }
+        problems
+    }
+
+    /// What the scripts of `first` and `second` both do differently: set an [`crate::Script::exclusive`]
+    /// setting, or start different services on one port. `None` when they can run side by side.
+    fn collision(&self, first: &Rc<RefCell<MenuNode>>, second: &Rc<RefCell<MenuNode>>) -> Option<String> {
+        let first = first.borrow().script(self.backend.as_ref())?;
+        let second = second.borrow().script(self.backend.as_ref())?;
+        for (setting, value) in &first.exclusive {
+            if let Some((_, other)) = second.exclusive.iter().find(|(other, other_value)| other == setting && other_value != value) {
+                return Some(format!("set the {} ({} and {})", setting, value, other));
+            }
+        }
+        for (port, unit) in &first.listens {
+            if let Some((_, other)) = second.listens.iter().find(|(other, other_unit)| other == port && other_unit != unit) {
+                return Some(format!("listen on port {} ({} and {})", port, unit, other));
+            }
+        }
+        None
+    }
+
+    /// Applies `fix`. Selecting goes through [`Model::toggle_item`] and may be refused; deselecting
+    /// always succeeds, leaving any item that required the deselected one to [`Model::problems`].
+    /// [`Fix::FillIn`] is the front end's to carry out and changes nothing here.
+    pub fn apply_fix(&mut self, fix: &Fix) -> Result<(), String> {
+        match fix {
+            Fix::Select(item) => self.toggle_item(item).map(|_| ()),
+            Fix::Deselect(item) | Fix::Keep { drop: item, .. } => {
+                if let MenuNode::Item { selected, rank, .. } = &mut *item.borrow_mut() {
+                    *selected = false;
+                    *rank = None;
+                }
+                Ok(())
+            }
+            Fix::FillIn(..) => Ok(()),
+        }
+    }
+}
+
+/// A reason the selection would generate a broken script; see [`Model::problems`].
+#[derive(Clone)]
+pub enum Problem {
+    /// `item` requires the item `id`, which is not selected; `requirement` is `None` when no
+    /// item in the tree has that ID, e.g. a repository this tree does not offer.
+    MissingRequirement { item: Rc<RefCell<MenuNode>>, id: String, requirement: Option<Rc<RefCell<MenuNode>>> },
+    /// Two selected items that conflict.
+    Conflict { first: Rc<RefCell<MenuNode>>, second: Rc<RefCell<MenuNode>> },
+    /// Two selected items whose scripts both do `what`, e.g. `set the display manager (sddm and
+    /// lightdm)`, so the one running last would silently win.
+    Collision { first: Rc<RefCell<MenuNode>>, second: Rc<RefCell<MenuNode>>, what: String },
+    /// `item`'s commands still contain `{{key}}`.
+    UnresolvedParam { item: Rc<RefCell<MenuNode>>, key: String },
+}
+
+impl Problem {
+    pub fn describe(&self) -> String {
+        match self {
+            Problem::MissingRequirement { item, id, requirement: Some(requirement) } => {
+                format!("{} requires {} ({}), which is not selected", item.borrow().name(), requirement.borrow().name(), id)
+            }
+            Problem::MissingRequirement { item, id, requirement: None } => {
+                format!("{} requires {}, which this menu does not offer", item.borrow().name(), id)
+            }
+            Problem::Conflict { first, second } => format!("{} conflicts with {}", first.borrow().name(), second.borrow().name()),
+            Problem::Collision { first, second, what } => {
+                format!("{} and {} both {}; pick the one to keep", first.borrow().name(), second.borrow().name(), what)
+            }
+            Problem::UnresolvedParam { item, key } => format!("{} needs a value for {{{{{}}}}}", item.borrow().name(), key),
+        }
+    }
+
+    /// The ways to resolve the problem, the least disruptive first.
+    pub fn fixes(&self) -> Vec<Fix> {
+        match self {
+            Problem::MissingRequirement { item, requirement, .. } => {
+                requirement.iter().map(|requirement| Fix::Select(requirement.clone())).chain([Fix::Deselect(item.clone())]).collect()
+            }
+            Problem::Conflict { first, second } => vec![Fix::Deselect(second.clone()), Fix::Deselect(first.clone())],
+            Problem::Collision { first, second, .. } => vec![Fix::Keep { keep: first.clone(), drop: second.clone() }, Fix::Keep { keep: second.clone(), drop: first.clone() }],
+            Problem::UnresolvedParam { item, key } => vec![Fix::FillIn(item.clone(), key.clone()), Fix::Deselect(item.clone())],
+        }
+    }
+}
+
+/// A way to resolve a [`Problem`], applied with [`Model::apply_fix`].
+#[derive(Clone)]
+pub enum Fix {
+    Select(Rc<RefCell<MenuNode>>),
+    Deselect(Rc<RefCell<MenuNode>>),
+    /// Keep `keep` of a colliding pair by deselecting `drop`.
+    Keep { keep: Rc<RefCell<MenuNode>>, drop: Rc<RefCell<MenuNode>> },
+    /// Fill in the item's parameter `key`.
+    FillIn(Rc<RefCell<MenuNode>>, String),
+}
+
+impl Fix {
+    pub fn label(&self) -> String {
+        match self {
+            Fix::Select(item) => format!("Select {}", item.borrow().name()),
+            Fix::Deselect(item) => format!("Deselect {}", item.borrow().name()),
+            Fix::Keep { keep, .. } => format!("Keep {}", keep.borrow().name()),
+            Fix::FillIn(_, key) => format!("Fill in {{{{{}}}}}", key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{item, menu, render_conditionals, space_claims, Script};
+
+    fn install_epel(pm: &dyn PackageBackend) -> Script {
+        Script::new("Install EPEL").install(pm, &["epel-release"])
+    }
+    fn install_htop(pm: &dyn PackageBackend) -> Script {
+        Script::new("Install htop").install(pm, &["htop"])
+    }
+    fn hostname(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Set the hostname").cmd("sudo hostnamectl set-hostname {{hostname}}")
+    }
+    fn firmware(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Update firmware").cmd("sudo fwupdmgr update -y").reboot()
+    }
+    fn rustup(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Install rustup").cmd("curl -sSf https://sh.rustup.rs | sh -s -- -y")
+    }
+
+    fn model(target: OsDistribution) -> Model {
+        let tree = menu!("Main Menu",
+            menu!("Repositories", item!("repos.epel", "EPEL", install_epel)),
+            menu!("Tools",
+                item!("tools.htop", "htop", install_htop, requires: ["repos.epel"]),
+                item!("tools.hostname", "Hostname", hostname, params: [("hostname", "Hostname", "")]),
+                item!("tools.firmware", "Firmware", firmware, conflicts: ["tools.rustup"]),
+                item!("tools.rustup", "rustup", rustup, requires: ["tools.htop"]))
+        );
+        Model::new(tree, target)
+    }
+
+    fn select(model: &mut Model, id: &str) {
+        let item = model.find_item(id).unwrap();
+        model.toggle_item(&item).unwrap();
+    }
+
+    #[test]
+    fn nothing_selected() {
+        let script = model(OsDistribution::Rhel).generate_commands(false);
+        assert!(script.starts_with("#!/bin/bash"));
+        assert!(script.contains("# No options selected."));
+    }
+
+    #[test]
+    fn requirements_run_first() {
+        let mut model = model(OsDistribution::Rhel);
+        select(&mut model, "tools.htop");
+        assert!(model.find_item("repos.epel").unwrap().borrow().is_selected());
+        let script = model.generate_commands(false);
+        assert!(script.find("install -y epel-release").unwrap() < script.find("install -y htop").unwrap());
+    }
+
+    #[test]
+    fn target_picks_the_package_manager() {
+        let mut model = model(OsDistribution::Rhel);
+        select(&mut model, "tools.htop");
+        assert!(model.generate_commands(false).contains("dnf install -y htop"));
+        model.set_target(OsDistribution::Debian);
+        let script = model.generate_commands(false);
+        assert!(script.contains("apt-get install -y htop"));
+        assert!(!script.contains("dnf "));
+    }
+
+    #[test]
+    fn cycle_target_wraps_around() {
+        let mut model = model(OsDistribution::Ubuntu);
+        model.cycle_target();
+        assert_eq!(model.target, OsDistribution::Rhel);
+        assert_eq!(model.backend.name(), "dnf");
+    }
+
+    #[test]
+    fn parameters_are_substituted() {
+        let mut model = model(OsDistribution::Rhel);
+        select(&mut model, "tools.hostname");
+        let (item, key) = model.first_unresolved().unwrap();
+        assert_eq!((item.borrow().id(), key.as_str()), ("tools.hostname", "hostname"));
+        if let MenuNode::Item { params, .. } = &mut *item.borrow_mut() {
+            params[0].value = "web01".to_string();
+        }
+        assert!(model.first_unresolved().is_none());
+        assert!(model.generate_commands(false).contains("sudo hostnamectl set-hostname web01"));
+    }
+
+    #[test]
+    fn header_traces_the_selection() {
+        let mut model = model(OsDistribution::Rhel);
+        select(&mut model, "tools.htop");
+        select(&mut model, "tools.hostname");
+        let header = |model: &Model| -> Vec<String> {
+            model.generate_commands(false).lines().filter(|line| line.starts_with("# redcent-")).map(str::to_string).collect()
+        };
+        let before = header(&model);
+        assert!(before.contains(&"# redcent-target: Rhel (dnf)".to_string()));
+        assert!(before.contains(&"# redcent-items: repos.epel tools.htop tools.hostname".to_string()));
+        let hash = |lines: &[String]| lines.iter().find(|line| line.starts_with("# redcent-params: fnv1a64:")).cloned();
+        assert_eq!(hash(&before), hash(&header(&model)));
+        let item = model.find_item("tools.hostname").unwrap();
+        if let MenuNode::Item { params, .. } = &mut *item.borrow_mut() {
+            params[0].value = "web01".to_string();
+        }
+        assert_ne!(hash(&before), hash(&header(&model)));
+    }
+
+    #[test]
+    fn problems_offer_fixes() {
+        let mut model = model(OsDistribution::Rhel);
+        select(&mut model, "tools.hostname");
+        // Flags set directly bypass the dependency rules, as bulk changes from other front ends may.
+        for id in ["tools.htop", "tools.firmware", "tools.rustup"] {
+            if let MenuNode::Item { selected, .. } = &mut *model.find_item(id).unwrap().borrow_mut() {
+                *selected = true;
+            }
+        }
+        let problems = model.problems();
+        let descriptions: Vec<String> = problems.iter().map(Problem::describe).collect();
+        assert_eq!(descriptions, vec![
+            "htop requires EPEL (repos.epel), which is not selected",
+            "rustup conflicts with Firmware",
+            "Hostname needs a value for {{hostname}}",
+        ]);
+        let labels: Vec<String> = problems[0].fixes().iter().map(Fix::label).collect();
+        assert_eq!(labels, vec!["Select EPEL", "Deselect htop"]);
+        model.apply_fix(&problems[0].fixes()[0]).unwrap();
+        model.apply_fix(&problems[1].fixes()[0]).unwrap();
+        model.apply_fix(&problems[2].fixes()[1]).unwrap();
+        assert!(model.problems().is_empty());
+        assert!(!model.find_item("tools.firmware").unwrap().borrow().is_selected());
+    }
+
+    #[test]
+    fn colliding_scripts_let_the_user_keep_one() {
+        fn sddm(_pm: &dyn PackageBackend) -> Script {
+            Script::new("SDDM").cmd("sudo systemctl enable --force sddm").exclusive("display manager", "sddm")
+        }
+        fn sway_sddm(_pm: &dyn PackageBackend) -> Script {
+            Script::new("SDDM for Sway").cmd("sudo systemctl enable --force sddm").exclusive("display manager", "sddm")
+        }
+        fn lightdm(_pm: &dyn PackageBackend) -> Script {
+            Script::new("LightDM").cmd("sudo systemctl enable --force lightdm").exclusive("display manager", "lightdm")
+        }
+        fn cockpit(_pm: &dyn PackageBackend) -> Script {
+            Script::new("Cockpit").cmd("sudo systemctl enable --now cockpit.socket").listens("9090/tcp", "cockpit.socket")
+        }
+        fn dashboard(_pm: &dyn PackageBackend) -> Script {
+            Script::new("Dashboard").cmd("sudo systemctl enable --now dashboard").listens("9090/tcp", "dashboard.service")
+        }
+        let tree = menu!("Main Menu",
+            item!("dm.sddm", "SDDM", sddm),
+            item!("sway.sddm", "Sway SDDM", sway_sddm),
+            item!("dm.lightdm", "LightDM", lightdm),
+            item!("web.cockpit", "Cockpit", cockpit),
+            item!("web.dashboard", "Dashboard", dashboard)
+        );
+        let mut model = Model::new(tree, OsDistribution::Rhel);
+        for id in ["dm.sddm", "sway.sddm", "web.cockpit"] {
+            select(&mut model, id);
+        }
+        assert!(model.problems().is_empty());
+        select(&mut model, "dm.lightdm");
+        select(&mut model, "web.dashboard");
+        let problems = model.problems();
+        let descriptions: Vec<String> = problems.iter().map(Problem::describe).collect();
+        assert_eq!(descriptions, vec![
+            "SDDM and LightDM both set the display manager (sddm and lightdm); pick the one to keep",
+            "Sway SDDM and LightDM both set the display manager (sddm and lightdm); pick the one to keep",
+            "Cockpit and Dashboard both listen on port 9090/tcp (cockpit.socket and dashboard.service); pick the one to keep",
+        ]);
+        let labels: Vec<String> = problems[0].fixes().iter().map(Fix::label).collect();
+        assert_eq!(labels, vec!["Keep SDDM", "Keep LightDM"]);
+        model.apply_fix(&problems[0].fixes()[0]).unwrap();
+        model.apply_fix(&problems[2].fixes()[1]).unwrap();
+        assert!(model.problems().is_empty());
+        assert!(!model.find_item("dm.lightdm").unwrap().borrow().is_selected());
+        assert!(!model.find_item("web.cockpit").unwrap().borrow().is_selected());
+    }
+
+    #[test]
+    fn strict_mode_stops_on_errors() {
+        let mut model = model(OsDistribution::Rhel);
+        select(&mut model, "tools.htop");
+        model.errors = ErrorMode::Strict;
+        assert!(model.generate_commands(false).contains("set -euo pipefail"));
+    }
+
+    #[test]
+    fn transcript_logs_timestamps_phases_and_summarizes_blocks() {
+        let mut model = model(OsDistribution::Rhel);
+        select(&mut model, "tools.htop");
+        model.transcript = true;
+        let script = model.generate_commands(true);
+        assert!(script.contains("exec > >(tee -a \"$redcent_log\") 2>&1"));
+        assert!(script.contains("# ===== Package installation =====\n\nredcent_section 'Package installation'\n"));
+        assert!(script.contains("redcent_current='tools.htop (block 1)'\nsudo dnf install -y htop\nredcent_result $?\n"));
+        // The summary is printed before the reboot rather than by the exit trap.
+        assert!(script.find("trap - EXIT\nredcent_summary").unwrap() < script.find("sudo reboot").unwrap());
+        model.errors = ErrorMode::Report;
+        assert!(model.generate_commands(false).contains("redcent_failed+=('tools.htop (block 1)')\n    redcent_result 1\nelse\n    redcent_result 0\nfi"));
+        // Runs inside the front end record their output themselves.
+        assert!(!model.generate_run_commands(false).contains("redcent_log"));
+    }
+
+    #[test]
+    fn runs_verify_each_item_before_the_final_reboot() {
+        let mut model = model(OsDistribution::Rhel);
+        select(&mut model, "tools.htop");
+        let script = model.generate_run_commands(true);
+        let verify = script.find("redcent_verify 'tools.htop' 'rpm -q htop'").unwrap();
+        assert!(script.find("install -y htop").unwrap() < verify);
+        assert!(verify < script.find("exit 1").unwrap());
+        assert!(script.find("exit 1").unwrap() < script.find("sudo reboot").unwrap());
+        assert!(!model.generate_commands(true).contains("redcent_verify"));
+    }
+
+    #[test]
+    fn reboot_items_run_last() {
+        let mut model = model(OsDistribution::Rhel);
+        select(&mut model, "tools.firmware");
+        select(&mut model, "tools.htop");
+        let order: Vec<String> = model.selected_nodes().iter().map(|item| item.borrow().id().to_string()).collect();
+        assert_eq!(order, ["repos.epel", "tools.htop", "tools.firmware"]);
+        assert!(model.generate_commands(true).trim_end().ends_with("reboot"));
+    }
+
+    #[test]
+    fn space_claims_list_packages_and_estimates() {
+        let mut model = model(OsDistribution::Rhel);
+        select(&mut model, "tools.htop");
+        select(&mut model, "tools.hostname");
+        let claims = space_claims(&model.menu_tree, model.backend.as_ref());
+        let packages: Vec<(&str, Vec<String>)> = claims.iter().map(|claim| (claim.item.as_str(), claim.packages.clone())).collect();
+        assert_eq!(packages, [("EPEL", vec!["epel-release".to_string()]), ("htop", vec!["htop".to_string()])]);
+        assert!(claims.iter().all(|claim| claim.size_mib.is_none()));
+    }
+
+    #[test]
+    fn offline_mode_uses_the_local_repository() {
+        let mut model = model(OsDistribution::Rhel);
+        select(&mut model, "tools.rustup");
+        assert_eq!(model.set_offline(Some("http://mirror.example/rhel10".to_string())), ["rustup"]);
+        let rustup = model.find_item("tools.rustup").unwrap();
+        assert!(model.toggle_item(&rustup).is_err_and(|reason| reason.contains("downloads from the internet")));
+        let script = model.generate_commands(false);
+        assert!(script.contains("redcent_baseurl=\"${REDCENT_BASEURL:-http://mirror.example/rhel10}\""));
+        assert!(script.contains("baseurl=${redcent_baseurl}/AppStream"));
+        assert!(script.contains("sudo dnf --disablerepo='*' --enablerepo='redcent-offline-*' install -y htop"));
+        assert!(script.find("Offline repository").unwrap() < script.find("epel-release").unwrap());
+        model.set_offline(None);
+        assert!(!model.generate_commands(false).contains("redcent-offline"));
+    }
+
+    #[test]
+    fn proxy_is_configured_before_installing() {
+        let mut model = model(OsDistribution::Rhel);
+        select(&mut model, "tools.htop");
+        model.proxy = Proxy { http: "http://proxy.example:3128".to_string(), https: String::new(), no_proxy: "localhost,.example".to_string() };
+        let script = model.generate_commands(false);
+        assert!(script.contains("export http_proxy='http://proxy.example:3128' HTTP_PROXY=\"$http_proxy\""));
+        assert!(script.contains("export no_proxy='localhost,.example'"));
+        assert!(script.contains("echo 'proxy=http://proxy.example:3128' | sudo tee -a /etc/dnf/dnf.conf"));
+        assert!(script.find("# ===== Proxy").unwrap() < script.find("epel-release").unwrap());
+        model.set_target(OsDistribution::Ubuntu);
+        assert!(model.generate_commands(false).contains("Acquire::http::Proxy \"http://proxy.example:3128\";"));
+    }
+
+    #[test]
+    fn distribution_conditionals_resolve_for_the_target() {
+        let inline = "sudo {{#if rhel}}subscription-manager{{else}}dnf{{/if}} repos {{repo}}";
+        assert_eq!(render_conditionals(inline, OsDistribution::Rhel), "sudo subscription-manager repos {{repo}}");
+        assert_eq!(render_conditionals(inline, OsDistribution::Centos), "sudo dnf repos {{repo}}");
+
+        let block = "echo start\n{{#if debian ubuntu}}\napt-get update\n{{#if ubuntu}}\nsnap refresh\n{{/if}}\n{{else}}\ndnf makecache\n{{/if}}\necho done";
+        assert_eq!(render_conditionals(block, OsDistribution::Ubuntu), "echo start\napt-get update\nsnap refresh\necho done");
+        assert_eq!(render_conditionals(block, OsDistribution::Debian), "echo start\napt-get update\necho done");
+        assert_eq!(render_conditionals(block, OsDistribution::Unknown), "echo start\ndnf makecache\necho done");
+
+        // A command left empty for the target is dropped rather than rendered as a blank block.
+        let script = Script::new("Refresh").cmd("{{#if rhel}}\nsudo subscription-manager refresh\n{{/if}}").cmd("sudo dnf makecache")
+            .for_distro(OsDistribution::Centos);
+        assert_eq!(script.render(), "sudo dnf makecache");
+        assert!(OsDistribution::Centos.package_backend().distro() == OsDistribution::Centos);
+    }
+}
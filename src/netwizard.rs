@@ -0,0 +1,92 @@
+// src/netwizard.rs
+
+use crate::{
+    form::{Field, FieldKind, Step, Wizard, WizardOutcome},
+    theme::Theme,
+};
+use redcent_tui::{validate, MenuNode, PackageBackend};
+use crossterm::event::KeyCode;
+use ratatui::Frame;
+use std::{cell::RefCell, process::Command, rc::Rc};
+
+/// Parameter key that marks an item as needing the interface-picking wizard instead of the plain form.
+pub const IFACE_PARAM: &str = "iface";
+
+/// Whether the item is configured through the network wizard rather than the plain parameter form.
+pub fn needs_net_wizard(node: &MenuNode) -> bool {
+    matches!(node, MenuNode::Item { params, .. } if params.iter().any(|p| p.key == IFACE_PARAM))
+}
+
+/// Multi-step wizard for network items: pick a host interface, fill the remaining parameters, review the commands.
+pub struct NetWizard {
+    pub item: Rc<RefCell<MenuNode>>,
+    pub wizard: Wizard,
+}
+
+impl NetWizard {
+    pub fn new(item: &Rc<RefCell<MenuNode>>) -> NetWizard {
+        let node = item.borrow();
+        let MenuNode::Item { params, .. } = &*node else {
+            return NetWizard { item: item.clone(), wizard: Wizard::new(node.name(), Vec::new()) };
+        };
+        let index = iface_index(item);
+        let interfaces = list_interfaces();
+        let current = params[index].value.clone();
+        // Without detected interfaces the name is typed in by hand.
+        let iface = if interfaces.is_empty() {
+            let label = "Interface name (none detected via `ip -j link`)";
+            Field::new(IFACE_PARAM, label, FieldKind::Text, current).required().validate(validate::interface_name)
+        } else {
+            Field::new(IFACE_PARAM, params[index].label, FieldKind::Select(interfaces), current)
+        };
+        let mut steps = vec![Step::new("Select host interface", vec![iface])];
+        let settings: Vec<Field> = params.iter().filter(|p| p.key != IFACE_PARAM).map(Field::from_param).collect();
+        if !settings.is_empty() {
+            steps.push(Step::new("Settings", settings));
+        }
+        steps.push(Step::review("Review commands"));
+        NetWizard { item: item.clone(), wizard: Wizard::new(node.name(), steps) }
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> WizardOutcome {
+        self.wizard.handle_key(code)
+    }
+
+    /// Writes the wizard's values back to the item.
+    pub fn apply(&self) {
+        self.wizard.apply(&self.item);
+    }
+
+    pub fn draw(&self, f: &mut Frame, theme: &Theme, backend: &dyn PackageBackend) {
+        let review = if self.wizard.on_review() { self.wizard.preview(&self.item, backend) } else { String::new() };
+        self.wizard.draw(f, theme, &review);
+    }
+}
+
+fn iface_index(item: &Rc<RefCell<MenuNode>>) -> usize {
+    match &*item.borrow() {
+        MenuNode::Item { params, .. } => params.iter().position(|p| p.key == IFACE_PARAM).unwrap_or(0),
+        MenuNode::Menu { .. } => 0,
+    }
+}
+
+/// Lists physical-looking Ethernet interfaces from `ip -j link`, skipping loopback and virtual devices.
+fn list_interfaces() -> Vec<String> {
+    let Ok(output) = Command::new("ip").args(["-j", "link"]).output() else {
+        return Vec::new();
+    };
+    let json = String::from_utf8_lossy(&output.stdout);
+    json.split("{\"ifindex\":")
+        .filter(|entry| json_field(entry, "link_type").as_deref() == Some("ether"))
+        .filter_map(|entry| json_field(entry, "ifname"))
+        .filter(|name| !["virbr", "vnet", "veth", "docker", "podman", "macvtap", "ifb"].iter().any(|prefix| name.starts_with(prefix)))
+        .collect()
+}
+
+/// Extracts a top-level string field (`"key":"value"`) from a flat JSON object fragment.
+fn json_field(entry: &str, key: &str) -> Option<String> {
+    let marker = format!("\"{}\":\"", key);
+    let start = entry.find(&marker)? + marker.len();
+    let end = entry[start..].find('"')?;
+    Some(entry[start..start + end].to_string())
+}
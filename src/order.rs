@@ -0,0 +1,148 @@
+// src/order.rs
+
+use crate::theme::Theme;
+use redcent_tui::{has_script_order, item_paths, port_claims, port_conflicts, script_order, swap_items, MenuNode, PackageBackend};
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use std::{cell::RefCell, process::{Command, Stdio}, rc::Rc};
+
+/// The selected items as a numbered list in script order, with the phase each runs in, for
+/// changing the order of items within a phase. Ports the items' services would listen on are
+/// checked against each other and against the sockets listening on this host.
+pub struct OrderView {
+    pub selected: usize,
+    conflicts: Vec<String>,
+}
+
+impl OrderView {
+    pub fn new(tree: &Rc<RefCell<MenuNode>>, backend: &dyn PackageBackend) -> OrderView {
+        let claims = port_claims(tree, backend);
+        let mut conflicts: Vec<String> = port_conflicts(&claims).into_iter()
+            .map(|(port, items)| format!("{} is claimed by {}", port, join_names(&items)))
+            .collect();
+        let listening = if claims.is_empty() { Vec::new() } else { listening_ports() };
+        let mut checked: Vec<(&str, &str)> = Vec::new();
+        for claim in &claims {
+            if checked.contains(&(claim.port.as_str(), claim.unit.as_str())) {
+                continue;
+            }
+            checked.push((&claim.port, &claim.unit));
+            let Some((_, process)) = listening.iter().find(|(port, _)| *port == claim.port) else { continue };
+            // The unit itself listening there, e.g. on a host the script already ran on.
+            let own = Command::new("systemctl").args(["is-active", "--quiet", &claim.unit])
+                .stdout(Stdio::null()).stderr(Stdio::null()).status()
+                .is_ok_and(|status| status.success());
+            if !own {
+                let items: Vec<String> = claims.iter().filter(|other| other.port == claim.port && other.unit == claim.unit).map(|other| other.item.clone()).collect();
+                let process = if process.is_empty() { "another process" } else { process };
+                conflicts.push(format!("{} is in use on this host by {}; {} would start {} on it", claim.port, process, join_names(&items), claim.unit));
+            }
+        }
+        OrderView { selected: 0, conflicts }
+    }
+
+    pub fn next(&mut self, len: usize) {
+        if len > 0 {
+            self.selected = (self.selected + 1) % len;
+        }
+    }
+
+    pub fn previous(&mut self, len: usize) {
+        if len > 0 {
+            self.selected = (self.selected + len - 1) % len;
+        }
+    }
+
+    /// Moves the highlighted item one place up or down within its phase, keeping it highlighted.
+    pub fn move_highlighted(&mut self, tree: &Rc<RefCell<MenuNode>>, backend: &dyn PackageBackend, up: bool) -> Result<(), String> {
+        let order = script_order(tree, backend);
+        let Some((item, phase)) = order.get(self.selected).cloned() else { return Ok(()) };
+        let other = if up { self.selected.checked_sub(1) } else { Some(self.selected + 1) };
+        let Some((other, other_phase)) = other.and_then(|other| order.get(other).cloned()) else { return Ok(()) };
+        if other_phase != phase {
+            return Err(format!("{} belongs to {}; items move only within their phase", item.borrow().name(), phase.label()));
+        }
+        if up { swap_items(tree, &other, &item)? } else { swap_items(tree, &item, &other)? }
+        self.selected = script_order(tree, backend).iter().position(|(other, _)| Rc::ptr_eq(other, &item)).unwrap_or(0);
+        Ok(())
+    }
+
+    pub fn draw(&mut self, f: &mut Frame, tree: &Rc<RefCell<MenuNode>>, backend: &dyn PackageBackend, theme: &Theme) {
+        let width = f.size().width.saturating_sub(2).max(1) as usize;
+        let conflict_lines: usize = self.conflicts.iter().map(|conflict| conflict.chars().count().div_ceil(width)).sum();
+        let conflicts_height = if self.conflicts.is_empty() { 0 } else { conflict_lines.min(8) as u16 + 2 };
+        let chunks = Layout::default().direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(conflicts_height), Constraint::Length(3)].as_ref()).split(f.size());
+        let ordered = script_order(tree, backend);
+        self.selected = self.selected.min(ordered.len().saturating_sub(1));
+        let paths = item_paths(tree);
+        let name_width = ordered.iter().map(|(item, _)| item.borrow().name().chars().count()).max().unwrap_or(0);
+        let phase_width = ordered.iter().map(|(_, phase)| phase.label().chars().count()).max().unwrap_or(0);
+        let number_width = ordered.len().to_string().len();
+        let items: Vec<ListItem> = ordered.iter().enumerate().map(|(position, (item, phase))| {
+            let path = paths.iter().find(|(_, other)| Rc::ptr_eq(other, item)).map(|(path, _)| path.as_str()).unwrap_or("");
+            let node = item.borrow();
+            let mut spans = vec![
+                Span::raw(format!("{:>number_width$}. {:<name_width$}  ", position + 1, node.name())),
+                Span::styled(format!("{:<phase_width$}  ", phase.label()), theme.title),
+                Span::styled(path.to_string(), theme.muted),
+            ];
+            let after: Vec<String> = ordered[..position].iter()
+                .map(|(earlier, _)| earlier.borrow())
+                .filter(|earlier| node.requires().contains(&earlier.id()))
+                .map(|earlier| earlier.name().to_string())
+                .collect();
+            if !after.is_empty() {
+                spans.push(Span::styled(format!("  (after {})", after.join(", ")), theme.muted));
+            }
+            ListItem::new(Line::from(spans))
+        }).collect();
+        let title = if has_script_order(tree) { "Script Order (arranged)" } else { "Script Order (menu order)" };
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title).border_style(theme.focused))
+            .highlight_style(theme.highlight)
+            .highlight_symbol(">> ");
+        let mut state = ListState::default();
+        if !ordered.is_empty() {
+            state.select(Some(self.selected));
+        }
+        f.render_stateful_widget(list, chunks[0], &mut state);
+
+        if !self.conflicts.is_empty() {
+            let lines: Vec<Line> = self.conflicts.iter().map(|conflict| Line::styled(conflict.clone(), theme.warning)).collect();
+            let block = Block::default().borders(Borders::ALL).title("Port Conflicts").border_style(theme.warning);
+            f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }).block(block), chunks[1]);
+        }
+
+        let footer = Paragraph::new("Navigate [↑↓] | Move [Shift+↑↓ / K J] | [Enter/i] Generate Script | [R] Menu Order | [Esc/O] Back | [q] Quit")
+            .style(theme.footer)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(footer, chunks[2]);
+    }
+}
+
+/// `A`, `A and B`, `A, B and C`.
+fn join_names(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+/// Listening TCP and UDP sockets on this host as (`port/protocol`, process), from `ss -Hltunp`.
+/// The process is empty when `ss` may not show it, i.e. for other users' sockets without root.
+fn listening_ports() -> Vec<(String, String)> {
+    let Ok(output) = Command::new("ss").arg("-Hltunp").output() else { return Vec::new() };
+    String::from_utf8_lossy(&output.stdout).lines().filter_map(|line| {
+        // Netid State Recv-Q Send-Q Local:Port Peer:Port [users:(("name",pid=N,fd=N))]
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        let port = columns.get(4)?.rsplit(':').next()?;
+        let process = columns.get(6).and_then(|users| users.split('"').nth(1)).unwrap_or("");
+        Some((format!("{}/{}", port, columns[0]), process.to_string()))
+    }).collect()
+}
@@ -0,0 +1,371 @@
+// src/outline.rs
+
+use crate::{theme::Theme, viewer::{highlight_line, highlight_placeholders}};
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use redcent_tui::{path_to, script_order, MenuNode, PackageBackend};
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
+
+const HEADER_TITLE: &str = "Script header";
+const CLOSING_TITLE: &str = "End of script";
+
+/// How the lines the generator writes after the last item start; they make up the closing section.
+const CLOSING_OPENERS: [&str; 6] = [
+    "echo \"Verification: ",
+    "# Some of the selected changes only take full effect after a reboot.",
+    "if [ ${#redcent_failed[@]} -gt 0 ]; then",
+    "if [ \"$redcent_unverified\" -gt 0 ]; then",
+    "trap - EXIT",
+    "echo 'Installation complete. Rebooting now...'",
+];
+
+/// A part of the generated script: its header, the blocks of the selected items under one
+/// top-level menu, or the lines closing it.
+pub struct Section {
+    pub title: String,
+    /// Items whose blocks the section holds; none for the header and the closing lines.
+    pub items: usize,
+    pub lines: Vec<String>,
+}
+
+impl Section {
+    fn new(title: &str) -> Section {
+        Section { title: title.to_string(), items: 0, lines: Vec::new() }
+    }
+
+    fn summary(&self) -> String {
+        let lines = format!("{} line{}", self.lines.len(), if self.lines.len() == 1 { "" } else { "s" });
+        match self.items {
+            0 => lines,
+            1 => format!("1 item, {}", lines),
+            n => format!("{} items, {}", n, lines),
+        }
+    }
+
+    /// Drops trailing blank lines and the phase heading that would have opened the next block.
+    fn trim_end(&mut self) {
+        while self.lines.last().is_some_and(|line| line.trim().is_empty() || line.starts_with("# ===== ") || line.starts_with("redcent_section ")) {
+            self.lines.pop();
+        }
+    }
+}
+
+/// Splits `script` into sections by the top-level menu of each item, in menu order, between the
+/// script's header and its closing lines. Each item's block starts at its `# <id>: <description>`
+/// comment; the phase headings between blocks are left out, as the sections no longer follow the
+/// order the script runs in.
+pub fn sections(script: &str, tree: &Rc<RefCell<MenuNode>>, backend: &dyn PackageBackend) -> Vec<Section> {
+    let menus: Vec<String> = match &*tree.borrow() {
+        MenuNode::Menu { children, .. } => children.iter().map(|child| child.borrow().name().to_string()).collect(),
+        MenuNode::Item { .. } => Vec::new(),
+    };
+    let markers: Vec<(String, String)> = script_order(tree, backend).into_iter().filter_map(|(item, _)| {
+        let script = item.borrow().script(backend)?;
+        let menu = path_to(tree, &item).and_then(|path| path.get(1).map(|menu| menu.borrow().name().to_string())).unwrap_or_default();
+        Some((format!("# {}: {}", script.id, script.description), menu))
+    }).collect();
+
+    let mut header = Section::new(HEADER_TITLE);
+    let mut categories: Vec<Section> = Vec::new();
+    let mut closing = Section::new(CLOSING_TITLE);
+    let mut current: Option<usize> = None;
+    let mut next = 0;
+    for line in script.lines() {
+        if let Some((marker, menu)) = markers.get(next)
+            && line == marker
+        {
+            next += 1;
+            match current {
+                Some(index) => categories[index].trim_end(),
+                None => header.trim_end(),
+            }
+            let index = categories.iter().position(|section| section.title == *menu).unwrap_or_else(|| {
+                categories.push(Section::new(menu));
+                categories.len() - 1
+            });
+            let section = &mut categories[index];
+            if !section.lines.is_empty() {
+                section.lines.push(String::new());
+            }
+            section.items += 1;
+            current = Some(index);
+        } else if let Some(index) = current
+            && closing.lines.is_empty()
+            && next == markers.len()
+            && CLOSING_OPENERS.iter().any(|opener| line.starts_with(opener))
+        {
+            categories[index].trim_end();
+            current = None;
+        }
+        let section = match current {
+            Some(index) => &mut categories[index],
+            None if next > 0 => &mut closing,
+            None => &mut header,
+        };
+        section.lines.push(line.to_string());
+    }
+    if let Some(index) = current {
+        categories[index].trim_end();
+    }
+    closing.trim_end();
+    categories.sort_by_key(|section| menus.iter().position(|menu| *menu == section.title).unwrap_or(usize::MAX));
+
+    let mut sections = vec![header];
+    sections.extend(categories);
+    if !closing.lines.is_empty() {
+        sections.push(closing);
+    }
+    sections
+}
+
+/// State for the structured script preview on the main screen: the script as collapsible
+/// sections with a cursor, searchable like the [`crate::viewer::ScriptViewer`].
+#[derive(Default)]
+pub struct ScriptOutline {
+    pub focused: bool,
+    pub searching: bool,
+    pub query: String,
+    sections: Vec<Section>,
+    /// Titles of the expanded sections; sections start collapsed, unless the script has only one.
+    expanded: HashSet<String>,
+    cursor: usize,
+    scroll: usize,
+    viewport_height: usize,
+    /// Matching lines as (section, line) pairs.
+    matches: Vec<(usize, usize)>,
+    current_match: usize,
+    pub theme: Theme,
+}
+
+impl ScriptOutline {
+    pub fn new(theme: Theme) -> ScriptOutline {
+        ScriptOutline { theme, ..ScriptOutline::default() }
+    }
+
+    /// Replaces the sections shown, keeping which ones are expanded.
+    pub fn update(&mut self, sections: Vec<Section>) {
+        self.sections = sections;
+        self.matches.retain(|&(section, line)| self.sections.get(section).is_some_and(|s| line < s.lines.len()));
+        self.cursor = self.cursor.min(self.rows().len().saturating_sub(1));
+    }
+
+    fn is_expanded(&self, section: usize) -> bool {
+        self.sections.len() == 1 || self.expanded.contains(&self.sections[section].title)
+    }
+
+    /// The visible rows: each section's heading, followed by its lines when expanded.
+    fn rows(&self) -> Vec<(usize, Option<usize>)> {
+        let mut rows = Vec::new();
+        for (n, section) in self.sections.iter().enumerate() {
+            rows.push((n, None));
+            if self.is_expanded(n) {
+                rows.extend((0..section.lines.len()).map(|line| (n, Some(line))));
+            }
+        }
+        rows
+    }
+
+    fn cursor_section(&self) -> Option<usize> {
+        self.rows().get(self.cursor).map(|&(section, _)| section)
+    }
+
+    /// Expands or collapses `section`, leaving the cursor on its heading.
+    fn set_expanded(&mut self, section: usize, expanded: bool) {
+        let Some(title) = self.sections.get(section).map(|s| s.title.clone()) else { return };
+        if expanded {
+            self.expanded.insert(title);
+        } else {
+            self.expanded.remove(&title);
+        }
+        if let Some(row) = self.rows().iter().position(|&row| row == (section, None)) {
+            self.cursor = row;
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        if let Some(section) = self.cursor_section() {
+            self.set_expanded(section, !self.is_expanded(section));
+        }
+    }
+
+    pub fn expand_all(&mut self) {
+        self.expanded = self.sections.iter().map(|section| section.title.clone()).collect();
+    }
+
+    pub fn collapse_all(&mut self) {
+        let section = self.cursor_section().unwrap_or(0);
+        self.expanded.clear();
+        self.cursor = section;
+    }
+
+    fn move_cursor(&mut self, rows: isize) {
+        let last = self.rows().len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(rows).min(last);
+    }
+
+    /// Enters search input mode, clearing any previous query.
+    pub fn start_search(&mut self) {
+        self.focused = true;
+        self.searching = true;
+        self.query.clear();
+        self.matches.clear();
+    }
+
+    /// Finds all lines containing the query (case-insensitive), expands their sections and moves
+    /// to the first match.
+    pub fn commit_search(&mut self) {
+        self.searching = false;
+        let needle = self.query.to_lowercase();
+        self.matches = if needle.is_empty() {
+            Vec::new()
+        } else {
+            self.sections.iter().enumerate()
+                .flat_map(|(n, section)| section.lines.iter().enumerate().map(move |(line, text)| (n, line, text)))
+                .filter(|(_, _, text)| text.to_lowercase().contains(&needle))
+                .map(|(n, line, _)| (n, line))
+                .collect()
+        };
+        for &(section, _) in &self.matches {
+            self.expanded.insert(self.sections[section].title.clone());
+        }
+        self.current_match = 0;
+        self.jump_to_current_match();
+    }
+
+    pub fn cancel_search(&mut self) {
+        self.searching = false;
+        self.query.clear();
+        self.matches.clear();
+    }
+
+    pub fn next_match(&mut self) {
+        if !self.matches.is_empty() {
+            self.current_match = (self.current_match + 1) % self.matches.len();
+            self.jump_to_current_match();
+        }
+    }
+
+    pub fn prev_match(&mut self) {
+        if !self.matches.is_empty() {
+            self.current_match = (self.current_match + self.matches.len() - 1) % self.matches.len();
+            self.jump_to_current_match();
+        }
+    }
+
+    /// Moves to the current match, expanding its section again if it was collapsed since.
+    fn jump_to_current_match(&mut self) {
+        let Some(&(section, line)) = self.matches.get(self.current_match) else { return };
+        self.expanded.insert(self.sections[section].title.clone());
+        if let Some(row) = self.rows().iter().position(|&row| row == (section, Some(line))) {
+            self.cursor = row;
+            self.scroll = row.saturating_sub(self.viewport_height / 3);
+        }
+    }
+
+    /// Handles a key press aimed at the preview. Returns `true` if the key was consumed.
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        if self.searching {
+            match code {
+                KeyCode::Char(c) => self.query.push(c),
+                KeyCode::Backspace => { self.query.pop(); },
+                KeyCode::Enter => self.commit_search(),
+                KeyCode::Esc => self.cancel_search(),
+                _ => {}
+            }
+            return true;
+        }
+        let page = self.viewport_height.max(1) as isize;
+        match code {
+            KeyCode::PageUp => self.move_cursor(-page),
+            KeyCode::PageDown => self.move_cursor(page),
+            KeyCode::Char('/') => self.start_search(),
+            _ if !self.focused => return false,
+            KeyCode::Up => self.move_cursor(-1),
+            KeyCode::Down => self.move_cursor(1),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.move_cursor(isize::MAX),
+            KeyCode::Enter => self.toggle(),
+            KeyCode::Right => if let Some(section) = self.cursor_section() { self.set_expanded(section, true) },
+            KeyCode::Left => if let Some(section) = self.cursor_section() { self.set_expanded(section, false) },
+            KeyCode::Char('+') => self.expand_all(),
+            KeyCode::Char('-') => self.collapse_all(),
+            KeyCode::Char('n') => self.next_match(),
+            KeyCode::Char('N') => self.prev_match(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Describes the cursor position and search state for the block title.
+    fn status(&self, rows: usize) -> String {
+        let mut status = format!("{} sections, {}/{}", self.sections.len(), (self.cursor + 1).min(rows), rows);
+        if self.searching {
+            status.push_str(&format!(" | /{}_", self.query));
+        } else if !self.query.is_empty() {
+            if self.matches.is_empty() {
+                status.push_str(&format!(" | '{}' not found", self.query));
+            } else {
+                status.push_str(&format!(" | '{}' {}/{}", self.query, self.current_match + 1, self.matches.len()));
+            }
+        }
+        status
+    }
+
+    /// Renders `sections` inside a bordered block: a heading per section with its size, and the
+    /// lines of the expanded ones, highlighting search matches and unresolved placeholders.
+    pub fn render(&mut self, f: &mut Frame, area: Rect, sections: Vec<Section>, title: &str) {
+        self.update(sections);
+        self.viewport_height = area.height.saturating_sub(2) as usize;
+        let rows = self.rows();
+        let height = self.viewport_height.max(1);
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + height {
+            self.scroll = self.cursor + 1 - height;
+        }
+        self.scroll = self.scroll.min(rows.len().saturating_sub(height));
+
+        let needle = self.query.to_lowercase();
+        let current = self.matches.get(self.current_match).copied();
+        let lines: Vec<Line> = rows.iter().enumerate().skip(self.scroll).take(height).map(|(row, &(section, line))| {
+            let mut shown = match line {
+                None => {
+                    let marker = if self.is_expanded(section) { "▾" } else { "▸" };
+                    let section = &self.sections[section];
+                    Line::from(vec![
+                        Span::styled(format!("{} {}", marker, section.title), self.theme.title),
+                        Span::styled(format!("  ({})", section.summary()), self.theme.muted),
+                    ])
+                }
+                Some(line) => {
+                    let text = self.sections[section].lines[line].as_str();
+                    let mut spans = vec![Span::raw("  ")];
+                    spans.extend(if needle.is_empty() || self.searching || !self.matches.contains(&(section, line)) {
+                        highlight_placeholders(text, self.theme.placeholder).spans
+                    } else {
+                        let style = if current == Some((section, line)) { self.theme.current_match } else { self.theme.other_match };
+                        highlight_line(text, &needle, style).spans
+                    });
+                    Line::from(spans)
+                }
+            };
+            if self.focused && row == self.cursor {
+                shown.patch_style(self.theme.highlight);
+            }
+            shown
+        }).collect();
+
+        let border_style = if self.focused { self.theme.focused } else { Style::default() };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(format!("{} [{}]", title, self.status(rows.len())));
+        f.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
@@ -0,0 +1,145 @@
+// src/palette.rs
+
+use crate::{centered_rect, form::WizardOutcome};
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use redcent_tui::{all_items, MenuNode};
+use std::{cell::RefCell, rc::Rc};
+
+pub const USAGE: &str = "select <id-glob> | deselect <id-glob> | goto <menu or item id>";
+
+/// A parsed palette command.
+pub enum PaletteCommand {
+    Select(String),
+    Deselect(String),
+    Goto(String),
+}
+
+impl PaletteCommand {
+    pub fn parse(input: &str) -> Result<PaletteCommand, String> {
+        let input = input.trim();
+        let (verb, arg) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        let arg = arg.trim().to_string();
+        if arg.is_empty() {
+            return Err(format!("Usage: {}", USAGE));
+        }
+        match verb {
+            "select" | "s" => Ok(PaletteCommand::Select(arg)),
+            "deselect" | "d" => Ok(PaletteCommand::Deselect(arg)),
+            "goto" | "g" => Ok(PaletteCommand::Goto(arg)),
+            other => Err(format!("Unknown command '{}'. Usage: {}", other, USAGE)),
+        }
+    }
+}
+
+/// Case-insensitive glob match supporting `*` (any run of characters) and `?` (one character).
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text index it was tried against, for backtracking.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Items whose ID matches `pattern`, in tree order.
+pub fn matching_items(tree: &Rc<RefCell<MenuNode>>, pattern: &str) -> Vec<Rc<RefCell<MenuNode>>> {
+    all_items(tree).into_iter().filter(|item| glob_match(pattern, item.borrow().id())).collect()
+}
+
+/// Resolves a `goto` target: an exact menu name or item ID first, then the first menu whose
+/// name starts with, and finally contains, the query (all case-insensitive).
+pub fn goto_target(tree: &Rc<RefCell<MenuNode>>, query: &str) -> Option<Rc<RefCell<MenuNode>>> {
+    fn menus(node: &Rc<RefCell<MenuNode>>, out: &mut Vec<Rc<RefCell<MenuNode>>>) {
+        if let MenuNode::Menu { children, .. } = &*node.borrow() {
+            out.push(node.clone());
+            for child in children {
+                menus(child, out);
+            }
+        }
+    }
+
+    let query = query.to_lowercase();
+    let mut all_menus = Vec::new();
+    menus(tree, &mut all_menus);
+    let name = |node: &Rc<RefCell<MenuNode>>| node.borrow().name().to_lowercase();
+    all_menus.iter().find(|menu| name(menu) == query).cloned()
+        .or_else(|| all_items(tree).into_iter().find(|item| item.borrow().id() == query))
+        .or_else(|| all_menus.iter().find(|menu| name(menu).starts_with(&query)).cloned())
+        .or_else(|| all_menus.iter().find(|menu| name(menu).contains(&query)).cloned())
+}
+
+/// Ctrl-P command line for manipulating selections by ID pattern and jumping around the tree.
+#[derive(Default)]
+pub struct Palette {
+    pub input: String,
+}
+
+impl Palette {
+    pub fn handle_key(&mut self, code: KeyCode) -> WizardOutcome {
+        match code {
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => { self.input.pop(); },
+            KeyCode::Enter => return WizardOutcome::Finish,
+            KeyCode::Esc => return WizardOutcome::Cancel,
+            _ => {}
+        }
+        WizardOutcome::Continue
+    }
+
+    /// Live feedback for the current input: what the command would affect, or why it is invalid.
+    pub fn hint(&self, tree: &Rc<RefCell<MenuNode>>) -> String {
+        if self.input.trim().is_empty() {
+            return USAGE.to_string();
+        }
+        match PaletteCommand::parse(&self.input) {
+            Ok(PaletteCommand::Select(pattern)) | Ok(PaletteCommand::Deselect(pattern)) => {
+                let ids: Vec<&str> = matching_items(tree, &pattern).iter().map(|item| item.borrow().id()).collect();
+                match ids.len() {
+                    0 => format!("No item IDs match '{}'", pattern),
+                    n => format!("{} item(s): {}", n, ids.join(", ")),
+                }
+            }
+            Ok(PaletteCommand::Goto(query)) => match goto_target(tree, &query) {
+                Some(node) => format!("Go to {}", node.borrow().name()),
+                None => format!("Nothing matches '{}'", query),
+            },
+            Err(usage) => usage,
+        }
+    }
+
+    pub fn draw(&self, f: &mut Frame, hint: &str) {
+        let area = centered_rect(70, 30, f.size());
+        f.render_widget(Clear, area);
+        f.render_widget(Block::default().title("Command Palette").borders(Borders::ALL), area);
+        let chunks = Layout::default().direction(Direction::Vertical).margin(1)
+            .constraints([Constraint::Length(3), Constraint::Min(1), Constraint::Length(1)])
+            .split(area);
+        f.render_widget(
+            Paragraph::new(format!("> {}", self.input)).block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Yellow))),
+            chunks[0],
+        );
+        f.render_widget(Paragraph::new(hint).wrap(Wrap { trim: true }), chunks[1]);
+        f.render_widget(Paragraph::new("Enter run | Esc cancel").style(Style::default().fg(Color::Cyan)), chunks[2]);
+    }
+}
@@ -0,0 +1,416 @@
+// src/preflight.rs
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+use crate::space::{format_mib, SpaceCheck, Verdict, RESERVE_MIB};
+use redcent_tui::{validate, OsDistribution};
+use std::{
+    fs,
+    net::{TcpStream, ToSocketAddrs},
+    process::Command,
+    thread,
+    time::{Duration, Instant},
+};
+
+const TIMEOUT_SECS: u64 = 8;
+/// Mirrors answering slower than this are flagged in the latency column.
+const SLOW_MS: u128 = 1500;
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Status {
+    Pass,
+    Fail,
+    Skip,
+}
+
+pub struct CheckResult {
+    pub name: String,
+    pub status: Status,
+    pub detail: String,
+    /// Round trip of a reachability check.
+    pub latency: Option<Duration>,
+}
+
+impl CheckResult {
+    fn new(name: impl Into<String>, status: Status, detail: impl Into<String>) -> CheckResult {
+        CheckResult { name: name.into(), status, detail: detail.into(), latency: None }
+    }
+}
+
+/// Repositories the selection will download from, as `(label, url)` pairs: the target's
+/// package CDN plus any third-party source a selected item pulls in.
+pub fn repo_urls(selected_ids: &[&str], target: OsDistribution) -> Vec<(String, String)> {
+    let mut urls = vec![match target {
+        OsDistribution::Rhel => ("Red Hat CDN", "https://cdn.redhat.com/"),
+        OsDistribution::Debian => ("Debian mirror", "https://deb.debian.org/debian/"),
+        OsDistribution::Ubuntu => ("Ubuntu archive", "http://archive.ubuntu.com/ubuntu/"),
+        OsDistribution::Centos | OsDistribution::Unknown => ("CentOS Stream mirror", "https://mirror.stream.centos.org/"),
+    }];
+    if selected_ids.contains(&"repos.epel") {
+        urls.push(("EPEL", "https://dl.fedoraproject.org/pub/epel/"));
+    }
+    if selected_ids.contains(&"repos.flathub") {
+        urls.push(("Flathub", "https://dl.flathub.org/repo/"));
+    }
+    if selected_ids.iter().any(|id| id.starts_with("gnome.ext-")) {
+        urls.push(("GNOME Extensions", "https://extensions.gnome.org/"));
+    }
+    if selected_ids.contains(&"monitoring.node-exporter-bin") {
+        urls.push(("GitHub releases", "https://github.com/"));
+    }
+    if selected_ids.contains(&"monitoring.netdata") {
+        urls.push(("Netdata", "https://get.netdata.cloud/"));
+    }
+    urls.into_iter().map(|(label, url)| (label.to_string(), url.to_string())).collect()
+}
+
+/// Enterprise Linux release COPR projects are checked for when this host does not tell.
+const DEFAULT_EL_RELEASE: &str = "10";
+
+/// The repository files of the COPR projects the scripts enable (`dnf copr enable -y owner/project`),
+/// as `(label, url)` pairs for the `epel-<release>` chroot. The COPR server answers 404 for a
+/// project that does not exist or does not build for that release, which fails the check.
+/// `release` is the host's major version, or empty to assume [`DEFAULT_EL_RELEASE`].
+pub fn copr_urls(scripts: &[String], release: &str) -> Vec<(String, String)> {
+    let chroot = format!("epel-{}", if release.is_empty() { DEFAULT_EL_RELEASE } else { release });
+    let mut urls: Vec<(String, String)> = Vec::new();
+    for line in scripts.iter().flat_map(|script| script.lines()) {
+        let Some(rest) = line.split_once("dnf copr enable").map(|(_, rest)| rest) else { continue };
+        let Some(project) = rest.split_whitespace().last().map(|project| project.trim_matches(['\'', '"'])) else { continue };
+        if validate::copr_project(project).is_err() {
+            continue;
+        }
+        let (owner, name) = project.split_once('/').unwrap_or((project, ""));
+        // Group projects live under /coprs/g/<group>/.
+        let owner = owner.strip_prefix('@').map_or(owner.to_string(), |group| format!("g/{}", group));
+        let url = format!("https://copr.fedorainfracloud.org/coprs/{}/{}/repo/{}/dnf.repo", owner, name, chroot);
+        if !urls.iter().any(|(_, known)| *known == url) {
+            urls.push((format!("COPR {} ({})", project, chroot), url));
+        }
+    }
+    urls
+}
+
+/// This host's major release from `/etc/os-release`, e.g. `9`; empty when it does not say.
+pub fn host_release() -> String {
+    let version = fs::read_to_string("/etc/os-release").ok().and_then(|text| text.lines()
+        .find_map(|line| line.strip_prefix("VERSION_ID=").map(|value| value.trim_matches('"').to_string())))
+        .unwrap_or_default();
+    version.split('.').next().unwrap_or_default().to_string()
+}
+
+/// Repositories enabled on this host for `package_manager` (`dnf` or `apt`), as `(label, url)`
+/// pairs whose URL is the repository's metadata, metalink or mirrorlist. Local (`file://`)
+/// repositories are left out.
+pub fn enabled_repos(package_manager: &str) -> Vec<(String, String)> {
+    let files = |dir: &str, extensions: &[&str]| -> Vec<(String, String)> {
+        let mut paths: Vec<_> = fs::read_dir(dir).into_iter().flatten().flatten().map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| extensions.iter().any(|wanted| ext == *wanted)))
+            .collect();
+        paths.sort();
+        paths.into_iter().filter_map(|path| {
+            let ext = path.extension()?.to_string_lossy().into_owned();
+            Some((ext, fs::read_to_string(path).ok()?))
+        }).collect()
+    };
+    if package_manager == "apt" {
+        let mut sources: Vec<(String, String)> = fs::read_to_string("/etc/apt/sources.list").into_iter()
+            .map(|text| ("list".to_string(), text)).collect();
+        sources.extend(files("/etc/apt/sources.list.d", &["list", "sources"]));
+        sources.iter().flat_map(|(ext, text)| parse_apt_sources(text, ext == "sources")).collect()
+    } else {
+        let vars = dnf_vars();
+        files("/etc/yum.repos.d", &["repo"]).iter().flat_map(|(_, text)| parse_repo_file(text, &vars)).collect()
+    }
+}
+
+/// Enabled sections of a `.repo` file, with `$releasever`, `$basearch` and the like expanded.
+pub fn parse_repo_file(text: &str, vars: &[(String, String)]) -> Vec<(String, String)> {
+    struct Repo {
+        id: String,
+        enabled: bool,
+        baseurl: Option<String>,
+        mirror: Option<String>,
+    }
+    let mut repos: Vec<Repo> = Vec::new();
+    // `baseurl` may continue on indented lines; only its first URL is checked.
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(id) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            repos.push(Repo { id: id.to_string(), enabled: true, baseurl: None, mirror: None });
+            continue;
+        }
+        let (Some(repo), Some((key, value))) = (repos.last_mut(), line.split_once('=')) else { continue };
+        let value = value.trim();
+        match key.trim() {
+            "enabled" => repo.enabled = !matches!(value, "0" | "false" | "no"),
+            "baseurl" => repo.baseurl = value.split([',', ' ']).find(|url| !url.is_empty()).map(str::to_string),
+            "metalink" | "mirrorlist" if repo.mirror.is_none() => repo.mirror = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    repos.into_iter().filter(|repo| repo.enabled).filter_map(|repo| {
+        let url = match (repo.baseurl, repo.mirror) {
+            (Some(baseurl), _) => format!("{}/repodata/repomd.xml", baseurl.trim_end_matches('/')),
+            (None, Some(mirror)) => mirror,
+            (None, None) => return None,
+        };
+        let url = expand_vars(&url, vars);
+        url.starts_with("http").then_some((repo.id, url))
+    }).collect()
+}
+
+/// `deb` entries of a one-line `.list` file or a deb822 `.sources` file, pointing at each suite's `InRelease`.
+pub fn parse_apt_sources(text: &str, deb822: bool) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+    if deb822 {
+        for stanza in text.split("\n\n") {
+            let field = |name: &str| stanza.lines()
+                .find_map(|line| line.strip_prefix(name).and_then(|rest| rest.strip_prefix(':')))
+                .map(str::trim)
+                .unwrap_or("");
+            if !field("Types").split_whitespace().any(|kind| kind == "deb") || field("Enabled") == "no" {
+                continue;
+            }
+            for uri in field("URIs").split_whitespace() {
+                entries.extend(field("Suites").split_whitespace().map(|suite| (uri.to_string(), suite.to_string())));
+            }
+        }
+    } else {
+        for line in text.lines() {
+            let Some(rest) = line.trim().strip_prefix("deb ") else { continue };
+            // Options such as `[signed-by=...]` come before the URI.
+            let rest = match rest.trim_start().strip_prefix('[') {
+                Some(options) => options.split_once(']').map_or("", |(_, rest)| rest),
+                None => rest,
+            };
+            let mut words = rest.split_whitespace();
+            if let (Some(uri), Some(suite)) = (words.next(), words.next()) {
+                entries.push((uri.to_string(), suite.to_string()));
+            }
+        }
+    }
+    entries.into_iter().filter(|(uri, _)| uri.starts_with("http")).map(|(uri, suite)| {
+        let uri = uri.trim_end_matches('/');
+        // A suite ending in `/` is a flat repository with no `dists/` tree.
+        let url = match suite.strip_suffix('/') {
+            Some("" | ".") => format!("{}/InRelease", uri),
+            Some(path) => format!("{}/{}/InRelease", uri, path),
+            None => format!("{}/dists/{}/InRelease", uri, suite),
+        };
+        (format!("{} {}", host_of(uri), suite), url)
+    }).collect()
+}
+
+/// The variables dnf expands in repository URLs: `releasever` and `basearch` from this host, and
+/// any set in `/etc/dnf/vars`, such as CentOS Stream's `stream`.
+fn dnf_vars() -> Vec<(String, String)> {
+    let major = host_release();
+    let mut vars = vec![
+        ("releasever".to_string(), major.clone()),
+        ("releasever_major".to_string(), major),
+        ("basearch".to_string(), std::env::consts::ARCH.to_string()),
+        ("arch".to_string(), std::env::consts::ARCH.to_string()),
+    ];
+    for entry in fs::read_dir("/etc/dnf/vars").into_iter().flatten().flatten() {
+        if let Ok(value) = fs::read_to_string(entry.path()) {
+            vars.push((entry.file_name().to_string_lossy().into_owned(), value.trim().to_string()));
+        }
+    }
+    vars
+}
+
+fn expand_vars(url: &str, vars: &[(String, String)]) -> String {
+    let mut vars: Vec<&(String, String)> = vars.iter().collect();
+    // Longest first, so `$releasever_major` is not read as `$releasever` followed by `_major`.
+    vars.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+    let mut url = url.to_string();
+    for (name, value) in vars {
+        url = url.replace(&format!("${{{}}}", name), value).replace(&format!("${}", name), value);
+    }
+    url
+}
+
+/// Checks run on this host before the generated script is saved or executed.
+pub struct Preflight {
+    pub results: Vec<CheckResult>,
+    /// Repositories whose reachability check failed.
+    pub unreachable: usize,
+}
+
+impl Preflight {
+    /// Runs every check. `needs_network` is false when nothing is downloaded from the internet, in which
+    /// case the DNS and reachability checks are skipped; `host` is the detected OS of this machine
+    /// and `space` the selection's estimated size against its free disk space. The mirrors are
+    /// checked in parallel, so a run takes as long as the slowest of them.
+    pub fn run(repos: &[(String, String)], needs_network: bool, host: OsDistribution, space: &SpaceCheck) -> Preflight {
+        let mut results = Vec::new();
+        let mut unreachable = 0;
+        if needs_network {
+            let mut hosts: Vec<&str> = repos.iter().map(|(_, url)| host_of(url)).collect();
+            hosts.sort();
+            hosts.dedup();
+            thread::scope(|scope| {
+                let dns: Vec<_> = hosts.iter().map(|host| scope.spawn(move || check_dns(host))).collect();
+                let reachable: Vec<_> = repos.iter().map(|(label, url)| scope.spawn(move || check_reachable(label, url))).collect();
+                results.extend(dns.into_iter().map(|check| check.join().expect("DNS check panicked")));
+                for check in reachable {
+                    let result = check.join().expect("reachability check panicked");
+                    unreachable += usize::from(result.status == Status::Fail);
+                    results.push(result);
+                }
+            });
+        } else {
+            for (label, _) in repos {
+                results.push(CheckResult::new(format!("{} reachability", label), Status::Skip, "nothing is downloaded from the internet"));
+            }
+        }
+        if host == OsDistribution::Rhel {
+            results.push(check_subscription());
+        }
+        results.push(check_disk(space));
+        Preflight { results, unreachable }
+    }
+
+    pub fn failures(&self) -> usize {
+        self.results.iter().filter(|r| r.status == Status::Fail).count()
+    }
+
+    pub fn draw(&self, f: &mut Frame) {
+        let note_height = if self.unreachable > 0 { 3 } else { 0 };
+        let chunks = Layout::default().direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(note_height), Constraint::Length(3)].as_ref()).split(f.size());
+
+        let name_width = self.results.iter().map(|r| r.name.chars().count()).max().unwrap_or(0);
+        let items: Vec<ListItem> = self.results.iter().map(|result| {
+            let (mark, color) = match result.status {
+                Status::Pass => ("PASS", Color::Green),
+                Status::Fail => ("FAIL", Color::Red),
+                Status::Skip => ("SKIP", Color::DarkGray),
+            };
+            let (latency, latency_color) = match result.latency {
+                Some(latency) if latency.as_millis() > SLOW_MS => (format!("{} ms", latency.as_millis()), Color::Yellow),
+                Some(latency) => (format!("{} ms", latency.as_millis()), Color::Gray),
+                None => (String::new(), Color::Gray),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("[{}] ", mark), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                Span::raw(format!("{:<width$}  ", result.name, width = name_width)),
+                Span::styled(format!("{:>8}  ", latency), Style::default().fg(latency_color)),
+                Span::styled(result.detail.clone(), Style::default().fg(Color::Gray)),
+            ]))
+        }).collect();
+        let passed = self.results.iter().filter(|r| r.status == Status::Pass).count();
+        let title = format!("Preflight Checks ({} passed, {} failed)", passed, self.failures());
+        f.render_widget(List::new(items).block(Block::default().borders(Borders::ALL).title(title)), chunks[0]);
+
+        if self.unreachable > 0 {
+            let note = format!(
+                "{} repository mirror(s) unreachable. Press [m] to install from a local mirror instead: offline mode with its base URL.",
+                self.unreachable,
+            );
+            f.render_widget(Paragraph::new(note).style(Style::default().fg(Color::Red)).block(Block::default().borders(Borders::ALL)), chunks[1]);
+        }
+        let keys = if self.unreachable > 0 { "[r] Re-run Checks | [m] Use a Local Mirror | [Esc/p] Back | [q] Quit" } else { "[r] Re-run Checks | [Esc/p] Back | [q] Quit" };
+        let footer = Paragraph::new(keys)
+            .style(Style::default().fg(Color::Cyan))
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(footer, chunks[2]);
+    }
+}
+
+/// Host part of an `http(s)://host[:port]/...` URL.
+fn host_of(url: &str) -> &str {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let authority = rest.split(['/', '?']).next().unwrap_or(rest);
+    authority.rsplit_once(':').map_or(authority, |(host, _)| host)
+}
+
+fn check_dns(host: &str) -> CheckResult {
+    let name = format!("{} DNS", host);
+    match (host, 443).to_socket_addrs() {
+        Ok(mut addrs) => match addrs.next() {
+            Some(addr) => CheckResult::new(name, Status::Pass, format!("{} resolves to {}", host, addr.ip())),
+            None => CheckResult::new(name, Status::Fail, format!("{} has no addresses", host)),
+        },
+        Err(e) => CheckResult::new(name, Status::Fail, format!("{}: {}", host, e)),
+    }
+}
+
+/// Fetches the URL's headers with curl, falling back to a plain TCP connect when curl is missing.
+/// Server errors fail the check, as does a missing metadata file; a bare mirror root may answer
+/// anything, since the Red Hat CDN refuses requests without an entitlement certificate.
+fn check_reachable(label: &str, url: &str) -> CheckResult {
+    let name = format!("{} reachability", label);
+    let curl = Command::new("curl")
+        .args(["-sS", "-o", "/dev/null", "--head", "--max-time", &TIMEOUT_SECS.to_string(), "-w", "%{http_code} %{time_total}", url])
+        .output();
+    match curl {
+        Ok(out) if out.status.success() => {
+            let stdout = String::from_utf8_lossy(&out.stdout);
+            let (code, seconds) = stdout.trim().split_once(' ').unwrap_or((stdout.trim(), ""));
+            let code: u16 = code.parse().unwrap_or(0);
+            let status = if code >= 500 || (code == 404 && !url.ends_with('/')) { Status::Fail } else { Status::Pass };
+            let mut result = CheckResult::new(name, status, format!("{} answered HTTP {}", url, code));
+            result.latency = seconds.parse::<f64>().ok().map(Duration::from_secs_f64);
+            result
+        }
+        Ok(out) => CheckResult::new(name, Status::Fail, format!("{}: {}", url, String::from_utf8_lossy(&out.stderr).trim())),
+        Err(_) => {
+            let port = if url.starts_with("https") { 443 } else { 80 };
+            let addr = (host_of(url), port).to_socket_addrs().ok().and_then(|mut addrs| addrs.next());
+            let started = Instant::now();
+            match addr.map(|addr| TcpStream::connect_timeout(&addr, Duration::from_secs(TIMEOUT_SECS))) {
+                Some(Ok(_)) => {
+                    let mut result = CheckResult::new(name, Status::Pass, format!("connected to {}:{} (curl not installed)", host_of(url), port));
+                    result.latency = Some(started.elapsed());
+                    result
+                }
+                Some(Err(e)) => CheckResult::new(name, Status::Fail, format!("{}:{}: {}", host_of(url), port, e)),
+                None => CheckResult::new(name, Status::Fail, format!("{} does not resolve", host_of(url))),
+            }
+        }
+    }
+}
+
+fn check_subscription() -> CheckResult {
+    let name = "Subscription";
+    match Command::new("subscription-manager").arg("status").output() {
+        Ok(out) => {
+            let text = String::from_utf8_lossy(&out.stdout);
+            let status_line = text.lines().find(|line| line.contains("Overall Status") || line.contains("Content Access Mode"))
+                .or_else(|| text.lines().find(|line| !line.trim().is_empty() && !line.starts_with('+')))
+                .unwrap_or("")
+                .trim()
+                .to_string();
+            if out.status.success() {
+                CheckResult::new(name, Status::Pass, status_line)
+            } else {
+                let stderr = String::from_utf8_lossy(&out.stderr).trim().to_string();
+                CheckResult::new(name, Status::Fail, if status_line.is_empty() { stderr } else { status_line })
+            }
+        }
+        Err(_) => CheckResult::new(name, Status::Fail, "subscription-manager is not installed"),
+    }
+}
+
+fn check_disk(space: &SpaceCheck) -> CheckResult {
+    let name = match &space.free {
+        Some((mount, _)) => format!("Free disk space on {}", mount),
+        None => "Free disk space".to_string(),
+    };
+    let status = match space.verdict() {
+        Verdict::Fits => Status::Pass,
+        Verdict::Tight | Verdict::DoesNotFit => Status::Fail,
+        Verdict::Unknown => Status::Skip,
+    };
+    CheckResult::new(name, status, format!("{}; {} should remain free", space.summary(), format_mib(RESERVE_MIB)))
+}
@@ -0,0 +1,161 @@
+// src/privilege.rs
+
+use std::process::{Command, Stdio};
+
+/// How the generated script gains root privileges.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Escalation {
+    Sudo,
+    Doas,
+    Pkexec,
+    /// Already running as root: `sudo ` prefixes are stripped.
+    None,
+}
+
+impl Escalation {
+    pub const ALL: [Escalation; 4] = [Escalation::Sudo, Escalation::Doas, Escalation::Pkexec, Escalation::None];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Escalation::Sudo => "sudo",
+            Escalation::Doas => "doas",
+            Escalation::Pkexec => "pkexec",
+            Escalation::None => "none (run as root)",
+        }
+    }
+
+    /// Prefix for commands that need root, including the trailing space; empty when running as root.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Escalation::Sudo => "sudo ",
+            Escalation::Doas => "doas ",
+            Escalation::Pkexec => "pkexec ",
+            Escalation::None => "",
+        }
+    }
+
+    /// Rewrites the `sudo ` prefixes of a generated script for this strategy. Comment lines and
+    /// `sudo -u ...` invocations (which drop privileges to the invoking user) are left alone.
+    pub fn apply(self, script: &str) -> String {
+        if self == Escalation::Sudo {
+            return script.to_string();
+        }
+        script
+            .lines()
+            .map(|line| if line.trim_start().starts_with('#') { line.to_string() } else { self.rewrite_line(line) })
+            .collect::<Vec<_>>()
+            .join("\n")
+            + if script.ends_with('\n') { "\n" } else { "" }
+    }
+
+    fn rewrite_line(self, line: &str) -> String {
+        let mut out = String::with_capacity(line.len());
+        let mut rest = line;
+        while let Some(pos) = rest.find("sudo ") {
+            let prev = rest[..pos].chars().next_back().or_else(|| out.chars().next_back());
+            let at_word_start = prev.is_none_or(|c| c.is_whitespace() || "(;&|".contains(c));
+            let drops_privileges = rest[pos + 5..].starts_with('-');
+            out.push_str(&rest[..pos]);
+            if at_word_start && !drops_privileges {
+                out.push_str(self.prefix());
+            } else {
+                out.push_str("sudo ");
+            }
+            rest = &rest[pos + 5..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// What the environment offers for gaining root, probed once at startup.
+pub struct PrivilegeInfo {
+    pub is_root: bool,
+    pub has_sudo: bool,
+    /// `sudo -n true` succeeded, so sudo will not prompt for a password.
+    pub passwordless_sudo: bool,
+    pub has_doas: bool,
+    pub has_pkexec: bool,
+}
+
+impl PrivilegeInfo {
+    pub fn detect() -> PrivilegeInfo {
+        let is_root = Command::new("id").arg("-u").output()
+            .map(|out| String::from_utf8_lossy(&out.stdout).trim() == "0")
+            .unwrap_or(false);
+        let has_sudo = command_exists("sudo");
+        let passwordless_sudo = has_sudo && Command::new("sudo").args(["-n", "true"])
+            .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false);
+        PrivilegeInfo { is_root, has_sudo, passwordless_sudo, has_doas: command_exists("doas"), has_pkexec: command_exists("pkexec") }
+    }
+
+    /// The strategy to start with: strip sudo as root, otherwise the first available tool.
+    pub fn default_escalation(&self) -> Escalation {
+        if self.is_root {
+            Escalation::None
+        } else if self.has_sudo {
+            Escalation::Sudo
+        } else if self.has_doas {
+            Escalation::Doas
+        } else if self.has_pkexec {
+            Escalation::Pkexec
+        } else {
+            Escalation::Sudo
+        }
+    }
+
+    /// Whether `escalation` can work in this environment.
+    pub fn supports(&self, escalation: Escalation) -> bool {
+        match escalation {
+            Escalation::Sudo => self.has_sudo,
+            Escalation::Doas => self.has_doas,
+            Escalation::Pkexec => self.has_pkexec,
+            Escalation::None => self.is_root,
+        }
+    }
+
+    /// One-line description of the environment for the settings popup.
+    pub fn summary(&self) -> String {
+        if self.is_root {
+            return "Running as root.".to_string();
+        }
+        let sudo = match (self.has_sudo, self.passwordless_sudo) {
+            (false, _) => "sudo not installed",
+            (true, false) => "sudo asks for a password",
+            (true, true) => "passwordless sudo",
+        };
+        let mut tools = Vec::new();
+        if self.has_doas {
+            tools.push("doas");
+        }
+        if self.has_pkexec {
+            tools.push("pkexec");
+        }
+        if tools.is_empty() {
+            format!("Not root; {}.", sudo)
+        } else {
+            format!("Not root; {}; also available: {}.", sudo, tools.join(", "))
+        }
+    }
+
+    /// Explains why running the script directly with `escalation` will fail, if it will.
+    pub fn warning(&self, escalation: Escalation) -> Option<String> {
+        if self.supports(escalation) {
+            return None;
+        }
+        Some(match escalation {
+            Escalation::None => "Not running as root: [r] Run Directly will fail without an escalation tool.".to_string(),
+            other => format!("{} is not installed: [r] Run Directly will not work; save the script instead.", other.label()),
+        })
+    }
+}
+
+fn command_exists(name: &str) -> bool {
+    Command::new("sh").arg("-c").arg(format!("command -v {} >/dev/null", name))
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
@@ -0,0 +1,222 @@
+// src/progress.rs
+
+use crate::theme::Theme;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::{Block, Borders, Gauge},
+    Frame,
+};
+use redcent_tui::{checkpoint_blocks, BLOCK_MARKER};
+
+/// Share of a package-installing block taken by downloading; the transaction and its verification
+/// take the rest.
+const DOWNLOAD_PERCENT: u16 = 40;
+const VERIFY_FROM_PERCENT: u16 = 90;
+
+/// Progress of a script running in the terminal pane, estimated from its output: resumable scripts
+/// print a [`BLOCK_MARKER`] line as each command block starts, and within a block dnf's download
+/// and transaction counters (`(3/25): ...`, `Installing : ... 3/25`, dnf5's `[ 3/25] ...`) and
+/// apt's `Progress: [ 45%]` move the block's gauge.
+pub struct RunProgress {
+    /// Command blocks in the script.
+    total: usize,
+    /// Current block number and the ID of its item.
+    block: Option<(usize, String)>,
+    /// Estimated completion of the current block, 0 to 100.
+    percent: u16,
+    /// What the package manager reports doing, e.g. `Installing 3/25`.
+    activity: String,
+    /// Set once the script has exited successfully.
+    done: bool,
+    /// Output after the last line break.
+    partial: String,
+}
+
+impl RunProgress {
+    pub fn new(script: &str) -> RunProgress {
+        RunProgress { total: checkpoint_blocks(script), block: None, percent: 0, activity: String::new(), done: false, partial: String::new() }
+    }
+
+    /// Whether there are blocks to show progress for.
+    pub fn is_tracked(&self) -> bool {
+        self.total > 0
+    }
+
+    /// Reads new output; carriage returns end a line too, since progress bars redraw in place.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.partial.push_str(&String::from_utf8_lossy(bytes));
+        while let Some(end) = self.partial.find(['\n', '\r']) {
+            let line = strip_escapes(&self.partial[..end]);
+            self.partial.drain(..=end);
+            self.line(&line);
+        }
+    }
+
+    /// Marks the run as complete when the script exited successfully.
+    pub fn finish(&mut self, code: i32) {
+        self.done = code == 0;
+    }
+
+    fn line(&mut self, line: &str) {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix(BLOCK_MARKER) {
+            let (number, id) = rest.split_once(": ").unwrap_or((rest, ""));
+            if let Ok(number) = number.parse() {
+                self.block = Some((number, id.to_string()));
+                self.percent = 0;
+                self.activity.clear();
+            }
+            return;
+        }
+        if let Some(rest) = line.strip_prefix("Skipping block")
+            && let Some(number) = rest.split(',').next().and_then(|number| number.trim().parse().ok())
+        {
+            self.block = Some((number, String::new()));
+            self.percent = 100;
+            self.activity = "completed by an earlier run".to_string();
+            return;
+        }
+        if let Some((percent, activity)) = package_progress(line) {
+            self.percent = self.percent.max(percent.min(100));
+            self.activity = activity;
+        }
+    }
+
+    /// Estimated completion of the whole script, 0 to 100.
+    pub fn overall_percent(&self) -> u16 {
+        if self.done || self.total == 0 {
+            return 100;
+        }
+        let Some((number, _)) = &self.block else { return 0 };
+        let completed = number.saturating_sub(1) as f64 + f64::from(self.percent) / 100.0;
+        ((completed / self.total as f64) * 100.0).min(100.0) as u16
+    }
+
+    /// Rows the gauges take: none when the script has no blocks to track.
+    pub fn height(&self) -> u16 {
+        if self.is_tracked() { 4 } else { 0 }
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let block = Block::default().borders(Borders::ALL).title("Progress");
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let rows = Layout::default().direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(1)].as_ref()).split(inner);
+        let (block_label, block_percent) = match (&self.block, self.done) {
+            (_, true) => ("All blocks done".to_string(), 100),
+            (None, false) => ("Starting".to_string(), 0),
+            (Some((number, id)), false) => {
+                let mut label = format!("Block {}/{}", number, self.total);
+                if !id.is_empty() {
+                    label.push_str(&format!(" {}", id));
+                }
+                if !self.activity.is_empty() {
+                    label.push_str(&format!(": {}", self.activity));
+                }
+                (format!("{} ({}%)", label, self.percent), self.percent)
+            }
+        };
+        f.render_widget(Gauge::default().gauge_style(theme.focused).percent(block_percent).label(block_label), rows[0]);
+        let overall = self.overall_percent();
+        let label = format!("Overall {}%", overall);
+        f.render_widget(Gauge::default().gauge_style(theme.highlight).percent(overall).label(label), rows[1]);
+    }
+}
+
+/// The block's estimated completion and a short activity from a line of dnf or apt output.
+fn package_progress(line: &str) -> Option<(u16, String)> {
+    if line.starts_with("Downloading Packages") {
+        return Some((0, "Downloading".to_string()));
+    }
+    if line.starts_with("Running transaction") {
+        return Some((DOWNLOAD_PERCENT, "Running transaction".to_string()));
+    }
+    // apt with Dpkg::Progress-Fancy, which it turns on for terminals: `Progress: [ 45%]`.
+    if let Some(rest) = line.strip_prefix("Progress: [") {
+        let percent = rest.trim_start().split('%').next()?.trim().parse().ok()?;
+        return Some((percent, "Installing".to_string()));
+    }
+    // dnf5 prefixes both downloads and transaction steps with `[ 3/25]`.
+    if let Some(rest) = line.strip_prefix('[')
+        && let Some((counter, rest)) = rest.split_once(']')
+        && let Some((done, total)) = count(counter.trim())
+    {
+        let verb = rest.split_whitespace().next().unwrap_or("");
+        return Some(match transaction_step(verb) {
+            Some(verify) => step_progress(verb, verify, done, total),
+            None => (scale(0, DOWNLOAD_PERCENT, done, total), format!("Downloading {}/{}", done, total)),
+        });
+    }
+    // dnf4 downloads: `(3/25): name.rpm  1.2 MB/s | 300 kB  00:00`.
+    if let Some(rest) = line.strip_prefix('(')
+        && let Some((counter, _)) = rest.split_once("):")
+        && let Some((done, total)) = count(counter)
+    {
+        return Some((scale(0, DOWNLOAD_PERCENT, done, total), format!("Downloading {}/{}", done, total)));
+    }
+    // dnf4 transaction steps: `Installing       : name-1.0.x86_64      3/25`.
+    let (verb, rest) = line.split_once(" : ").or_else(|| line.split_once(':'))?;
+    let verb = verb.trim();
+    let verify = transaction_step(verb.split_whitespace().next()?)?;
+    let (done, total) = count(rest.split_whitespace().last()?)?;
+    Some(step_progress(verb, verify, done, total))
+}
+
+/// Whether `verb` starts a dnf transaction step, and whether that step is verification.
+fn transaction_step(verb: &str) -> Option<bool> {
+    match verb {
+        "Verifying" => Some(true),
+        "Installing" | "Upgrading" | "Reinstalling" | "Downgrading" | "Cleanup" | "Erasing" | "Removing" | "Obsoleting" | "Running" | "Preparing" => Some(false),
+        _ => None,
+    }
+}
+
+fn step_progress(verb: &str, verify: bool, done: u32, total: u32) -> (u16, String) {
+    let percent = if verify { scale(VERIFY_FROM_PERCENT, 100, done, total) } else { scale(DOWNLOAD_PERCENT, VERIFY_FROM_PERCENT, done, total) };
+    (percent, format!("{} {}/{}", verb, done, total))
+}
+
+/// Parses an `n/total` counter.
+fn count(text: &str) -> Option<(u32, u32)> {
+    let (done, total) = text.split_once('/')?;
+    let (done, total) = (done.trim().parse().ok()?, total.trim().parse().ok()?);
+    (total > 0 && done <= total).then_some((done, total))
+}
+
+/// Maps `done` of `total` onto the range `from..to`.
+fn scale(from: u16, to: u16, done: u32, total: u32) -> u16 {
+    from + ((u32::from(to - from) * done) / total) as u16
+}
+
+/// Drops ANSI escape sequences (colors, cursor movement) from a line of terminal output.
+pub fn strip_escapes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters up to a final byte in `@`..`~`.
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: up to BEL or ST.
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\u{7}' || (c == '\u{1b}' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
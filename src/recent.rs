@@ -0,0 +1,36 @@
+// src/recent.rs
+
+use crate::history::state_dir;
+use redcent_tui::{find_item, MenuNode};
+use std::{cell::RefCell, fs, io, rc::Rc};
+
+pub const RECENT_FILE: &str = "recent_items";
+const MAX_RECENT: usize = 8;
+
+/// IDs of recently selected items, most recent first.
+pub fn load() -> Vec<String> {
+    let Some(path) = state_dir().map(|dir| dir.join(RECENT_FILE)) else { return Vec::new() };
+    fs::read_to_string(path)
+        .map(|content| content.lines().map(str::trim).filter(|id| !id.is_empty()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Moves `id` to the front of the recently used list and writes it back to the state file.
+pub fn record(recent: &mut Vec<String>, id: &str) -> io::Result<()> {
+    recent.retain(|existing| existing != id);
+    recent.insert(0, id.to_string());
+    recent.truncate(MAX_RECENT);
+    let dir = state_dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "cannot determine state directory"))?;
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(RECENT_FILE), recent.join("\n") + "\n")
+}
+
+/// Builds the "Recently Used" menu from the items in `tree` matching `recent`. The menu is not part
+/// of the tree: its children are shared with their real parents, so toggling them shows in both places.
+pub fn recent_menu(tree: &Rc<RefCell<MenuNode>>, recent: &[String]) -> Option<Rc<RefCell<MenuNode>>> {
+    let children: Vec<_> = recent.iter().filter_map(|id| find_item(tree, id)).collect();
+    if children.is_empty() {
+        return None;
+    }
+    Some(Rc::new(RefCell::new(MenuNode::Menu { name: "Recently Used".to_string(), children })))
+}
@@ -0,0 +1,408 @@
+// src/remote.rs
+
+use crate::{
+    events::Waker,
+    form::{Field, FieldKind, Step, Wizard, WizardOutcome},
+    theme::Theme,
+};
+use redcent_tui::validate;
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+    Frame,
+};
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{BufRead, BufReader, Write},
+    process::{Command, Stdio},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Hosts the script runs on at the same time.
+const MAX_PARALLEL: usize = 8;
+
+/// Options for every `ssh` call: keys only, since no password prompt can be answered from a worker.
+const SSH_OPTIONS: [&str; 4] = ["-o", "BatchMode=yes", "-o", "ConnectTimeout=10"];
+
+/// An SSH destination as entered: `[user@]host[:port]`.
+#[derive(Clone)]
+pub struct Target {
+    /// `user@host` or `host`, as passed to `ssh`.
+    pub destination: String,
+    pub port: Option<u16>,
+}
+
+impl Target {
+    fn parse(entry: &str) -> Result<Target, String> {
+        let (user, address) = match entry.rsplit_once('@') {
+            Some((user, address)) => (Some(user), address),
+            None => (None, entry),
+        };
+        if let Some(user) = user {
+            validate::user_name(user).map_err(|e| format!("{}: {}", entry, e))?;
+        }
+        // A single colon separates the port; IPv6 addresses carry several and no port.
+        let (host, port) = match address.split_once(':') {
+            Some((host, port)) if !port.contains(':') => {
+                let port = port.parse::<u16>().ok().filter(|port| *port > 0).ok_or_else(|| format!("{}: '{}' is not a port", entry, port))?;
+                (host, Some(port))
+            }
+            _ => (address, None),
+        };
+        validate::host(host).map_err(|e| format!("{}: {}", entry, e))?;
+        let destination = match user {
+            Some(user) => format!("{}@{}", user, host),
+            None => host.to_string(),
+        };
+        Ok(Target { destination, port })
+    }
+
+    fn label(&self) -> String {
+        match self.port {
+            Some(port) => format!("{}:{}", self.destination, port),
+            None => self.destination.clone(),
+        }
+    }
+
+    fn ssh(&self, remote_command: &str) -> Command {
+        let mut command = Command::new("ssh");
+        command.args(SSH_OPTIONS);
+        if let Some(port) = self.port {
+            command.args(["-p", &port.to_string()]);
+        }
+        command.arg(&self.destination).arg(remote_command);
+        command
+    }
+}
+
+/// Reads the hosts field: entries separated by spaces or commas, or `@path` for a file with one
+/// host per line and `#` comments.
+pub fn parse_hosts(value: &str) -> Result<Vec<Target>, String> {
+    let list = match value.trim().strip_prefix('@') {
+        Some(path) => fs::read_to_string(path.trim()).map_err(|e| format!("cannot read {}: {}", path.trim(), e))?
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or("").to_string())
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => value.to_string(),
+    };
+    let targets = list.split([' ', ',', '\t']).filter(|entry| !entry.is_empty()).map(Target::parse).collect::<Result<Vec<_>, _>>()?;
+    if targets.is_empty() {
+        return Err("no hosts given".to_string());
+    }
+    Ok(targets)
+}
+
+fn host_list(value: &str) -> Result<(), String> {
+    parse_hosts(value).map(|_| ())
+}
+
+/// Popup asking for the hosts to run the generated script on.
+pub struct RemoteSetup {
+    wizard: Wizard,
+    script: String,
+}
+
+impl RemoteSetup {
+    pub fn new(script: String) -> RemoteSetup {
+        let hosts = Field::new("hosts", "Hosts as [user@]host[:port], separated by spaces or commas, or @file with one per line", FieldKind::Text, "")
+            .required()
+            .validate(host_list);
+        let steps = vec![Step::new("Hosts", vec![hosts]), Step::review("Review hosts")];
+        RemoteSetup { wizard: Wizard::new("Run on Remote Hosts", steps).finishing("start the run"), script }
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> WizardOutcome {
+        self.wizard.handle_key(code)
+    }
+
+    /// Starts the run on the entered hosts; `waker` is woken as their status and output come in.
+    pub fn start(&self, waker: Option<Waker>) -> Result<RemoteRun, String> {
+        Ok(RemoteRun::start(parse_hosts(self.wizard.value("hosts"))?, &self.script, waker))
+    }
+
+    pub fn draw(&self, f: &mut Frame, theme: &Theme) {
+        let review = match self.wizard.on_review().then(|| parse_hosts(self.wizard.value("hosts"))) {
+            Some(Ok(targets)) => format!(
+                "The script ({} lines) is copied over SSH to each of\nthese {} hosts and run there, {} at a time:\n\n{}\n\n\
+                 SSH has to log in with a key, and sudo has to\nwork without a password: nothing can be typed in.",
+                self.script.lines().count(), targets.len(), MAX_PARALLEL,
+                targets.iter().map(|target| format!("  {}", target.label())).collect::<Vec<_>>().join("\n"),
+            ),
+            Some(Err(e)) => e,
+            None => String::new(),
+        };
+        self.wizard.draw(f, theme, &review);
+    }
+}
+
+#[derive(Clone, PartialEq)]
+enum Status {
+    Waiting,
+    Copying,
+    Running,
+    Succeeded,
+    Failed(i32),
+    /// The script could not be copied, with `ssh`'s last error line.
+    Unreachable(String),
+}
+
+impl Status {
+    fn is_done(&self) -> bool {
+        matches!(self, Status::Succeeded | Status::Failed(_) | Status::Unreachable(_))
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Status::Waiting => "waiting".to_string(),
+            Status::Copying => "copying".to_string(),
+            Status::Running => "running".to_string(),
+            Status::Succeeded => "succeeded".to_string(),
+            Status::Failed(code) => format!("failed (exit {})", code),
+            Status::Unreachable(_) => "unreachable".to_string(),
+        }
+    }
+}
+
+enum Event {
+    Status(Status),
+    Line(String),
+}
+
+/// How the workers hand a host's status changes and output to the table.
+#[derive(Clone)]
+struct Reporter {
+    sender: Sender<(usize, Event)>,
+    waker: Option<Waker>,
+}
+
+impl Reporter {
+    fn send(&self, index: usize, event: Event) {
+        let _ = self.sender.send((index, event));
+        if let Some(waker) = &self.waker {
+            waker.wake();
+        }
+    }
+}
+
+struct HostRun {
+    target: Target,
+    status: Status,
+    output: Vec<String>,
+    started: Option<Instant>,
+    elapsed: Option<Duration>,
+}
+
+/// The generated script running on several hosts at once, shown as a table of per-host status
+/// with each host's output a key press away.
+pub struct RemoteRun {
+    hosts: Vec<HostRun>,
+    events: Receiver<(usize, Event)>,
+    selected: usize,
+    /// Host whose output is shown instead of the table, and how far it is scrolled back.
+    viewing: Option<(usize, usize)>,
+}
+
+impl RemoteRun {
+    fn start(targets: Vec<Target>, script: &str, waker: Option<Waker>) -> RemoteRun {
+        let (sender, events) = mpsc::channel();
+        let sender = Reporter { sender, waker };
+        let queue = Arc::new(Mutex::new((0..targets.len()).collect::<VecDeque<_>>()));
+        let script = Arc::new(script.to_string());
+        for _ in 0..MAX_PARALLEL.min(targets.len()) {
+            let (queue, script, sender, targets) = (queue.clone(), script.clone(), sender.clone(), targets.clone());
+            thread::spawn(move || {
+                while let Some(index) = queue.lock().ok().and_then(|mut queue| queue.pop_front()) {
+                    run_host(index, &targets[index], &script, &sender);
+                }
+            });
+        }
+        let hosts = targets.into_iter()
+            .map(|target| HostRun { target, status: Status::Waiting, output: Vec::new(), started: None, elapsed: None })
+            .collect();
+        RemoteRun { hosts, events, selected: 0, viewing: None }
+    }
+
+    /// Takes in the status changes and output the workers sent since the last call.
+    pub fn pump(&mut self) {
+        while let Ok((index, event)) = self.events.try_recv() {
+            let host = &mut self.hosts[index];
+            match event {
+                Event::Line(line) => host.output.push(line),
+                Event::Status(status) => {
+                    if status == Status::Copying {
+                        host.started = Some(Instant::now());
+                    }
+                    if status.is_done() {
+                        host.elapsed = host.started.map(|started| started.elapsed());
+                    }
+                    if let Status::Unreachable(error) = &status {
+                        host.output.push(error.clone());
+                    }
+                    host.status = status;
+                }
+            }
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.hosts.iter().all(|host| host.status.is_done())
+    }
+
+    /// Handles navigation; returns `false` when the user leaves the table.
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        if let Some((index, scroll)) = self.viewing.as_mut() {
+            let len = self.hosts[*index].output.len();
+            match code {
+                KeyCode::Up => *scroll = (*scroll + 1).min(len),
+                KeyCode::Down => *scroll = scroll.saturating_sub(1),
+                KeyCode::PageUp => *scroll = (*scroll + 20).min(len),
+                KeyCode::PageDown => *scroll = scroll.saturating_sub(20),
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Backspace => self.viewing = None,
+                _ => {}
+            }
+            return true;
+        }
+        let len = self.hosts.len();
+        match code {
+            KeyCode::Down => self.selected = (self.selected + 1) % len,
+            KeyCode::Up => self.selected = (self.selected + len - 1) % len,
+            KeyCode::Enter => self.viewing = Some((self.selected, 0)),
+            KeyCode::Esc | KeyCode::Backspace => return false,
+            _ => {}
+        }
+        true
+    }
+
+    pub fn draw(&self, f: &mut Frame, theme: &Theme) {
+        let chunks = Layout::default().direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref()).split(f.size());
+        let done = self.hosts.iter().filter(|host| host.status.is_done()).count();
+        let failed = self.hosts.iter().filter(|host| matches!(host.status, Status::Failed(_) | Status::Unreachable(_))).count();
+
+        let footer = if let Some((index, scroll)) = self.viewing {
+            let host = &self.hosts[index];
+            let height = chunks[0].height.saturating_sub(2) as usize;
+            let end = host.output.len().saturating_sub(scroll);
+            let lines = host.output[end.saturating_sub(height)..end].join("\n");
+            let title = format!("{}: {}", host.target.label(), host.status.label());
+            f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title).border_style(theme.focused)), chunks[0]);
+            "Scroll [↑↓ PgUp/PgDn] | [Esc/Enter] Back to the hosts"
+        } else {
+            let rows: Vec<Row> = self.hosts.iter().map(|host| {
+                let style = match host.status {
+                    Status::Succeeded => theme.added,
+                    Status::Failed(_) | Status::Unreachable(_) => theme.error,
+                    Status::Waiting => theme.muted,
+                    Status::Copying | Status::Running => theme.warning,
+                };
+                let elapsed = host.elapsed.or_else(|| host.started.map(|started| started.elapsed()))
+                    .map(|elapsed| format!("{}s", elapsed.as_secs()))
+                    .unwrap_or_default();
+                Row::new(vec![
+                    Cell::from(host.target.label()),
+                    Cell::from(host.status.label()).style(style),
+                    Cell::from(elapsed),
+                    Cell::from(host.output.last().cloned().unwrap_or_default()).style(theme.muted),
+                ])
+            }).collect();
+            let title = format!("Remote Run: {} of {} hosts done, {} failed", done, self.hosts.len(), failed);
+            let table = Table::new(rows, [Constraint::Percentage(25), Constraint::Length(18), Constraint::Length(7), Constraint::Percentage(60)])
+                .header(Row::new(vec!["Host", "Status", "Time", "Last output"]).style(theme.title))
+                .block(Block::default().borders(Borders::ALL).title(title).border_style(theme.focused))
+                .highlight_style(theme.highlight)
+                .highlight_symbol(">> ");
+            let mut state = TableState::default();
+            state.select(Some(self.selected));
+            f.render_stateful_widget(table, chunks[0], &mut state);
+            if self.is_finished() {
+                "Navigate [↑↓] | [Enter] Host Output | [Esc] Back | [q] Quit"
+            } else {
+                "Navigate [↑↓] | [Enter] Host Output | [Esc] Back (hosts keep running)"
+            }
+        };
+        f.render_widget(Paragraph::new(footer).style(theme.footer).block(Block::default().borders(Borders::ALL)), chunks[1]);
+    }
+}
+
+/// Copies the script to a private temporary file on the host, runs it and removes it again,
+/// reporting each step and every output line to the table.
+fn run_host(index: usize, target: &Target, script: &str, sender: &Reporter) {
+    let send = |event| sender.send(index, event);
+    send(Event::Status(Status::Copying));
+    let copy = target.ssh("umask 077 && f=$(mktemp /tmp/redcent-remote.XXXXXX) && cat > \"$f\" && echo \"$f\"")
+        .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            // An unreachable host closes the pipe early; its error is read from stderr below.
+            if let Some(mut stdin) = child.stdin.take() {
+                let _ = stdin.write_all(script.as_bytes());
+            }
+            child.wait_with_output()
+        });
+    let path = match copy {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        Ok(output) => {
+            let error = String::from_utf8_lossy(&output.stderr).lines().last().unwrap_or("ssh failed").to_string();
+            return send(Event::Status(Status::Unreachable(error)));
+        }
+        Err(e) => return send(Event::Status(Status::Unreachable(format!("could not start ssh: {}", e)))),
+    };
+
+    send(Event::Status(Status::Running));
+    let run = target.ssh(&format!("bash {path}; status=$?; rm -f {path}; exit $status", path = path))
+        .stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped())
+        .spawn();
+    let mut child = match run {
+        Ok(child) => child,
+        Err(e) => return send(Event::Status(Status::Unreachable(format!("could not start ssh: {}", e)))),
+    };
+    let stderr = child.stderr.take().map(|stderr| {
+        let sender = sender.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                sender.send(index, Event::Line(plain(&line)));
+            }
+        })
+    });
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            send(Event::Line(plain(&line)));
+        }
+    }
+    if let Some(reader) = stderr {
+        let _ = reader.join();
+    }
+    let code = child.wait().ok().and_then(|status| status.code()).unwrap_or(-1);
+    send(Event::Status(if code == 0 { Status::Succeeded } else { Status::Failed(code) }));
+}
+
+/// A line of output without color escape sequences and with only the text after the last
+/// carriage return, as a terminal would have left it.
+fn plain(line: &str) -> String {
+    let line = line.trim_end_matches('\r').rsplit('\r').next().unwrap_or("");
+    let mut text = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skips a CSI sequence up to its final letter.
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+        } else {
+            text.push(c);
+        }
+    }
+    text
+}
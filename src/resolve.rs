@@ -0,0 +1,103 @@
+// src/resolve.rs
+
+use crate::{centered_rect, theme::Theme};
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use redcent_tui::{Fix, Problem};
+
+/// What a key press in the resolution dialog asks of the caller.
+pub enum ResolveOutcome {
+    Continue,
+    Cancel,
+    /// Apply this fix, then look for problems again.
+    Apply(Fix),
+}
+
+/// Lists what keeps the selection from generating a working script, each problem with the fixes
+/// on offer: ↑↓ pick a problem, ←→ or Tab a fix, Enter applies it. Opened instead of the
+/// generated script while problems remain.
+pub struct ResolveDialog {
+    problems: Vec<Problem>,
+    selected: usize,
+    /// Highlighted fix of the selected problem.
+    choice: usize,
+    /// Whether the script is generated with a final reboot once everything is resolved.
+    pub reboot: bool,
+    /// Why the last fix was refused.
+    pub message: Option<String>,
+}
+
+impl ResolveDialog {
+    pub fn new(problems: Vec<Problem>, reboot: bool) -> ResolveDialog {
+        ResolveDialog { problems, selected: 0, choice: 0, reboot, message: None }
+    }
+
+    /// Replaces the problems after a fix, keeping the position where possible.
+    pub fn refresh(&mut self, problems: Vec<Problem>) {
+        self.problems = problems;
+        self.selected = self.selected.min(self.problems.len().saturating_sub(1));
+        self.choice = 0;
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> ResolveOutcome {
+        let fixes = self.problems.get(self.selected).map(Problem::fixes).unwrap_or_default();
+        match code {
+            KeyCode::Up if !self.problems.is_empty() => {
+                self.selected = (self.selected + self.problems.len() - 1) % self.problems.len();
+                self.choice = 0;
+            }
+            KeyCode::Down if !self.problems.is_empty() => {
+                self.selected = (self.selected + 1) % self.problems.len();
+                self.choice = 0;
+            }
+            KeyCode::Left | KeyCode::BackTab if !fixes.is_empty() => self.choice = (self.choice + fixes.len() - 1) % fixes.len(),
+            KeyCode::Right | KeyCode::Tab if !fixes.is_empty() => self.choice = (self.choice + 1) % fixes.len(),
+            KeyCode::Enter => {
+                if let Some(fix) = fixes.into_iter().nth(self.choice) {
+                    self.message = None;
+                    return ResolveOutcome::Apply(fix);
+                }
+            }
+            KeyCode::Esc => return ResolveOutcome::Cancel,
+            _ => {}
+        }
+        ResolveOutcome::Continue
+    }
+
+    pub fn draw(&self, f: &mut Frame, theme: &Theme) {
+        let area = centered_rect(70, 60, f.size());
+        f.render_widget(Clear, area);
+        let title = format!("Resolve {} problem(s) before generating", self.problems.len());
+        f.render_widget(Block::default().title(title).borders(Borders::ALL).border_style(theme.warning), area);
+        let chunks = Layout::default().direction(Direction::Vertical).margin(2)
+            .constraints([Constraint::Min(3), Constraint::Length(3), Constraint::Length(2), Constraint::Length(1)])
+            .split(area);
+
+        let items: Vec<ListItem> = self.problems.iter().map(|problem| ListItem::new(problem.describe())).collect();
+        let mut state = ListState::default();
+        state.select(Some(self.selected));
+        let list = List::new(items).block(Block::default().borders(Borders::ALL).title("Problems")).highlight_style(theme.highlight);
+        f.render_stateful_widget(list, chunks[0], &mut state);
+
+        let fixes = self.problems.get(self.selected).map(Problem::fixes).unwrap_or_default();
+        let mut spans = Vec::new();
+        for (n, fix) in fixes.iter().enumerate() {
+            if n > 0 {
+                spans.push(Span::raw("   "));
+            }
+            let style = if n == self.choice { theme.highlight } else { theme.menu };
+            spans.push(Span::styled(format!(" {} ", fix.label()), style));
+        }
+        f.render_widget(Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::ALL).title("Fix")), chunks[1]);
+
+        if let Some(message) = &self.message {
+            f.render_widget(Paragraph::new(message.as_str()).style(theme.error).wrap(Wrap { trim: true }), chunks[2]);
+        }
+        f.render_widget(Paragraph::new("↑↓ problem | ←→/Tab fix | Enter apply | Esc back to the menu").style(theme.muted), chunks[3]);
+    }
+}
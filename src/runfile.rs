@@ -0,0 +1,110 @@
+// src/runfile.rs
+
+use std::{
+    env,
+    fs::{self, DirBuilder, File, OpenOptions},
+    io::{self, Read, Write},
+    os::unix::fs::{DirBuilderExt, MetadataExt, OpenOptionsExt, PermissionsExt},
+    path::{Path, PathBuf},
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const SCRIPT_NAME: &str = "run.sh";
+/// Attempts at a fresh directory name before giving up; a clash means someone is guessing.
+const ATTEMPTS: usize = 8;
+
+/// The script being run, in a directory of its own under the temp directory: the directory is
+/// created with a random name and mode 0700 and the script inside it exclusively, so nobody else
+/// can plant a symbolic link or swap the file between writing and running it as root. Dropping
+/// it overwrites the script and removes the directory, whether or not the run succeeded.
+pub struct RunFile {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl RunFile {
+    pub fn create(script: &str) -> io::Result<RunFile> {
+        let dir = private_dir()?;
+        let path = dir.join(SCRIPT_NAME);
+        let file = RunFile { dir, path };
+        let mut out = OpenOptions::new().write(true).create_new(true).mode(0o600).open(&file.path)?;
+        out.write_all(script.as_bytes())?;
+        out.sync_all()?;
+        Ok(file)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Checks, right before the script is handed to sudo, that the directory and the script are
+    /// still this user's, private, and not symbolic links.
+    pub fn verify(&self) -> io::Result<()> {
+        let uid = current_uid()?;
+        for (path, want_dir) in [(&self.dir, true), (&self.path, false)] {
+            let meta = fs::symlink_metadata(path)?;
+            let kind_ok = if want_dir { meta.is_dir() } else { meta.is_file() };
+            if !kind_ok || meta.uid() != uid || meta.permissions().mode() & 0o077 != 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!("{} was tampered with: it is no longer a private file of this user", path.display()),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn read(&self) -> io::Result<String> {
+        fs::read_to_string(&self.path)
+    }
+
+    /// Overwrites the script with zeros and removes it and its directory.
+    pub fn remove(&mut self) -> io::Result<()> {
+        if !self.dir.exists() {
+            return Ok(());
+        }
+        if let Ok(meta) = fs::symlink_metadata(&self.path)
+            && meta.is_file()
+            && let Ok(mut out) = OpenOptions::new().write(true).open(&self.path)
+        {
+            let _ = out.write_all(&vec![0; meta.len() as usize]).and_then(|()| out.sync_all());
+        }
+        fs::remove_dir_all(&self.dir)
+    }
+}
+
+impl Drop for RunFile {
+    fn drop(&mut self) {
+        let _ = self.remove();
+    }
+}
+
+/// Creates `redcent-run-<random>` under the temp directory, mode 0700; never reuses one that exists.
+fn private_dir() -> io::Result<PathBuf> {
+    let mut last_error = None;
+    for _ in 0..ATTEMPTS {
+        let dir = env::temp_dir().join(format!("redcent-run-{}", random_suffix()));
+        match DirBuilder::new().mode(0o700).create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => last_error = Some(e),
+            Err(e) => return Err(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| io::Error::other("could not create a private directory")))
+}
+
+/// 16 hex digits from the kernel's random source, or from the clock and process ID without one.
+fn random_suffix() -> String {
+    let mut bytes = [0u8; 8];
+    if File::open("/dev/urandom").and_then(|mut random| random.read_exact(&mut bytes)).is_err() {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+        bytes = ((nanos as u64) ^ ((process::id() as u64) << 32)).to_le_bytes();
+    }
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// This process's effective user ID, as the owner of its `/proc` entry.
+fn current_uid() -> io::Result<u32> {
+    Ok(fs::metadata("/proc/self")?.uid())
+}
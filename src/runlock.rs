@@ -0,0 +1,86 @@
+// src/runlock.rs
+
+use crate::history;
+use std::{
+    env,
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{self, Read, Seek, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+const LOCK_FILE: &str = "run.lock";
+
+/// Exclusive hold on the run lock; released when dropped, or by the kernel if the process dies.
+pub struct RunLock {
+    _file: File,
+}
+
+/// The process recorded in a lock file that is currently held.
+pub struct Holder {
+    pub pid: u32,
+    /// Timestamp in the same form as run directory names.
+    pub started: String,
+}
+
+impl Holder {
+    /// Whether the recorded process no longer exists, e.g. the lock was inherited by an orphaned child.
+    pub fn is_stale(&self) -> bool {
+        !Path::new(&format!("/proc/{}", self.pid)).exists()
+    }
+
+    pub fn describe(&self) -> String {
+        format!("PID {}, started {}", self.pid, history::display_timestamp(&self.started))
+    }
+}
+
+pub enum LockError {
+    Busy(Holder),
+    Io(io::Error),
+}
+
+impl From<io::Error> for LockError {
+    fn from(e: io::Error) -> LockError {
+        LockError::Io(e)
+    }
+}
+
+/// Lock file under the state directory, or in the temp directory when there is no home.
+fn lock_path() -> PathBuf {
+    history::state_dir().map(|dir| dir.join(LOCK_FILE)).unwrap_or_else(|| env::temp_dir().join("redcent-tui.lock"))
+}
+
+/// Takes the run lock without blocking, recording this process as the holder.
+pub fn acquire() -> Result<RunLock, LockError> {
+    let path = lock_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(&path)?;
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => {
+            let mut content = String::new();
+            file.read_to_string(&mut content)?;
+            let mut lines = content.lines();
+            let pid = lines.next().and_then(|pid| pid.trim().parse().ok()).unwrap_or(0);
+            let started = lines.next().unwrap_or("unknown").trim().to_string();
+            return Err(LockError::Busy(Holder { pid, started }));
+        }
+        Err(TryLockError::Error(e)) => return Err(e.into()),
+    }
+    file.set_len(0)?;
+    file.rewind()?;
+    writeln!(file, "{}\n{}", process::id(), history::timestamp_now())?;
+    Ok(RunLock { _file: file })
+}
+
+/// Removes a stale lock file and takes a fresh lock. The old holder keeps its lock on the unlinked
+/// file, so this must only be offered once [`Holder::is_stale`] says that holder is gone.
+pub fn take_over() -> Result<RunLock, LockError> {
+    match fs::remove_file(lock_path()) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+    acquire()
+}
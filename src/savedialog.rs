@@ -0,0 +1,236 @@
+// src/savedialog.rs
+
+use crate::{centered_rect, theme::Theme};
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// What a key press in the save dialog asks of the caller.
+pub enum SaveOutcome {
+    Continue,
+    Cancel,
+    /// Write to this path; overwriting was confirmed if [`SaveDialog::overwrite_confirmed`] says so.
+    Save(PathBuf),
+}
+
+struct Entry {
+    name: String,
+    is_dir: bool,
+}
+
+/// Save popup with a listing of the directory being saved into: ↑↓ browse it, Enter opens a
+/// directory or saves, Tab completes the typed name and existing files need confirming.
+pub struct SaveDialog {
+    dir: PathBuf,
+    name: String,
+    entries: Vec<Entry>,
+    /// Highlighted listing entry; `None` while the name is being typed.
+    selected: Option<usize>,
+    /// Extension added to names typed without one, e.g. `.sh`.
+    extension: Option<&'static str>,
+    /// Files that would be overwritten, while waiting for y/n.
+    overwrite: Option<Vec<PathBuf>>,
+    pub overwrite_confirmed: bool,
+    /// Completion candidates or an error, shown under the name.
+    message: Option<String>,
+}
+
+impl SaveDialog {
+    pub fn new(extension: Option<&'static str>) -> SaveDialog {
+        let dir = env::current_dir().unwrap_or_else(|_| PathBuf::from("/"));
+        let mut dialog = SaveDialog {
+            dir, name: String::new(), entries: Vec::new(), selected: None, extension,
+            overwrite: None, overwrite_confirmed: false, message: None,
+        };
+        dialog.read_dir();
+        dialog
+    }
+
+    /// Changes the extension added to bare names, when the format is switched.
+    pub fn set_extension(&mut self, extension: Option<&'static str>) {
+        self.extension = extension;
+    }
+
+    fn read_dir(&mut self) {
+        self.entries = list_dir(&self.dir, false);
+        if self.dir.parent().is_some() {
+            self.entries.insert(0, Entry { name: "..".to_string(), is_dir: true });
+        }
+        self.selected = None;
+    }
+
+    /// The path the typed name resolves to, with the default extension added to a bare name.
+    pub fn path(&self) -> PathBuf {
+        let mut path = resolve(&self.dir, &self.name);
+        if let Some(extension) = self.extension
+            && path.extension().is_none()
+            && let Some(name) = path.file_name().map(|name| format!("{}{}", name.to_string_lossy(), extension))
+        {
+            path.set_file_name(name);
+        }
+        path
+    }
+
+    /// Asks before `paths` are overwritten; the next y saves again with `overwrite_confirmed` set.
+    pub fn confirm_overwrite(&mut self, paths: Vec<PathBuf>) {
+        self.overwrite = Some(paths);
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> SaveOutcome {
+        if self.overwrite.is_some() {
+            match code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    self.overwrite = None;
+                    self.overwrite_confirmed = true;
+                    return SaveOutcome::Save(self.path());
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => self.overwrite = None,
+                _ => {}
+            }
+            return SaveOutcome::Continue;
+        }
+        self.message = None;
+        match code {
+            KeyCode::Esc => return SaveOutcome::Cancel,
+            KeyCode::Down if !self.entries.is_empty() => {
+                self.select(self.selected.map_or(0, |i| (i + 1) % self.entries.len()));
+            }
+            KeyCode::Up if !self.entries.is_empty() => {
+                let len = self.entries.len();
+                self.select(self.selected.map_or(len - 1, |i| (i + len - 1) % len));
+            }
+            KeyCode::Tab => self.complete(),
+            KeyCode::Enter => match self.selected.map(|i| &self.entries[i]) {
+                Some(entry) if entry.is_dir => {
+                    self.dir = if entry.name == ".." { self.dir.parent().unwrap_or(&self.dir).to_path_buf() } else { self.dir.join(&entry.name) };
+                    self.read_dir();
+                }
+                _ if self.name.trim().is_empty() => self.message = Some("Type a file name".to_string()),
+                _ => return SaveOutcome::Save(self.path()),
+            },
+            // Backspace on an empty name goes up a directory.
+            KeyCode::Backspace if self.name.is_empty() => {
+                if let Some(parent) = self.dir.parent() {
+                    self.dir = parent.to_path_buf();
+                    self.read_dir();
+                }
+            }
+            KeyCode::Backspace => {
+                self.name.pop();
+                self.selected = None;
+            }
+            KeyCode::Char(c) => {
+                self.name.push(c);
+                self.selected = None;
+            }
+            _ => {}
+        }
+        SaveOutcome::Continue
+    }
+
+    /// Highlights a listing entry, taking a file's name as the name to save under.
+    fn select(&mut self, index: usize) {
+        self.selected = Some(index);
+        if !self.entries[index].is_dir {
+            self.name = self.entries[index].name.clone();
+        }
+    }
+
+    /// Completes the last component of the typed name as far as the matching entries agree; a
+    /// unique directory is entered, several matches are listed.
+    fn complete(&mut self) {
+        let (typed_dir, prefix) = match self.name.rfind('/') {
+            Some(slash) => (&self.name[..=slash], &self.name[slash + 1..]),
+            None => ("", self.name.as_str()),
+        };
+        let dir = resolve(&self.dir, typed_dir);
+        let matches: Vec<Entry> = list_dir(&dir, prefix.starts_with('.')).into_iter().filter(|entry| entry.name.starts_with(prefix)).collect();
+        let Some(first) = matches.first() else {
+            self.message = Some(format!("Nothing in {} starts with '{}'", dir.display(), prefix));
+            return;
+        };
+        let common = matches.iter().fold(first.name.clone(), |common, entry| {
+            common.chars().zip(entry.name.chars()).take_while(|(a, b)| a == b).map(|(a, _)| a).collect()
+        });
+        match matches.as_slice() {
+            [only] if only.is_dir => {
+                self.dir = dir.join(&only.name);
+                self.name.clear();
+                self.read_dir();
+            }
+            [only] => self.name = format!("{}{}", typed_dir, only.name),
+            _ => {
+                self.name = format!("{}{}", typed_dir, common);
+                let names: Vec<String> = matches.iter().map(|entry| if entry.is_dir { format!("{}/", entry.name) } else { entry.name.clone() }).collect();
+                self.message = Some(names.join("  "));
+            }
+        }
+    }
+
+    pub fn draw(&self, f: &mut Frame, title: &str, hint: &str, theme: &Theme) {
+        let area = centered_rect(70, 70, f.size());
+        f.render_widget(Clear, area);
+        f.render_widget(Block::default().title(title.to_string()).borders(Borders::ALL), area);
+        let chunks = Layout::default().direction(Direction::Vertical).margin(2)
+            .constraints([Constraint::Length(2), Constraint::Length(3), Constraint::Length(2), Constraint::Min(0)].as_ref())
+            .split(area);
+        f.render_widget(Paragraph::new(hint).wrap(Wrap { trim: true }), chunks[0]);
+
+        let target = if self.name.is_empty() { "type a file name".to_string() } else { self.path().display().to_string() };
+        let name = Paragraph::new(format!("{}_", self.name))
+            .block(Block::default().borders(Borders::ALL).title(format!("Save as: {}", target)).border_style(theme.focused));
+        f.render_widget(name, chunks[1]);
+
+        let status = match (&self.overwrite, &self.message) {
+            (Some(paths), _) => {
+                let names: Vec<String> = paths.iter().map(|path| path.display().to_string()).collect();
+                Line::styled(format!("{} already exists. Overwrite? [y] Yes [n] No", names.join(" and ")), theme.warning)
+            }
+            (None, Some(message)) => Line::styled(message.clone(), theme.muted),
+            (None, None) => Line::from(""),
+        };
+        f.render_widget(Paragraph::new(status).wrap(Wrap { trim: true }), chunks[2]);
+
+        let items: Vec<ListItem> = self.entries.iter().map(|entry| {
+            if entry.is_dir {
+                ListItem::new(Line::from(Span::styled(format!("{}/", entry.name), theme.title)))
+            } else {
+                ListItem::new(entry.name.as_str())
+            }
+        }).collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(self.dir.display().to_string()))
+            .highlight_style(theme.highlight)
+            .highlight_symbol(">> ");
+        let mut state = ListState::default();
+        state.select(self.selected);
+        f.render_stateful_widget(list, chunks[3], &mut state);
+    }
+}
+
+/// `name` relative to `dir`, unless it is absolute or starts with `~/`.
+fn resolve(dir: &Path, name: &str) -> PathBuf {
+    match name.strip_prefix("~/").zip(env::var_os("HOME")) {
+        Some((rest, home)) => Path::new(&home).join(rest),
+        None => dir.join(name),
+    }
+}
+
+/// Directories first, then files, each sorted by name; dotfiles only when asked for.
+fn list_dir(dir: &Path, hidden: bool) -> Vec<Entry> {
+    let Ok(read) = fs::read_dir(dir) else { return Vec::new() };
+    let mut entries: Vec<Entry> = read.filter_map(Result::ok)
+        .map(|entry| Entry { name: entry.file_name().to_string_lossy().into_owned(), is_dir: entry.path().is_dir() })
+        .filter(|entry| hidden || !entry.name.starts_with('.'))
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    entries
+}
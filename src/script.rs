@@ -0,0 +1,229 @@
+// src/script.rs
+
+use crate::{backend::PackageBackend, menu::{render_params, Param}, template::render_conditionals, OsDistribution};
+
+/// One shell block of an item's script. A block may span several lines (a heredoc, an `if`),
+/// and is treated as a unit when the generator adds error handling around commands.
+pub struct Cmd {
+    pub code: String,
+}
+
+impl Cmd {
+    /// Whether the block only holds comments, i.e. does nothing when run.
+    pub fn is_comment(&self) -> bool {
+        self.code.lines().all(|line| line.trim().is_empty() || line.trim_start().starts_with('#'))
+    }
+}
+
+/// Where a script runs within the generated script. Blocks are grouped by phase in this order,
+/// so repositories are set up before the packages they provide and services are enabled once
+/// their configuration is in place.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Phase {
+    RepoSetup,
+    PackageInstall,
+    Configuration,
+    ServiceEnable,
+    /// Changes that only take full effect after a reboot, last so one reboot covers them all.
+    PostReboot,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 5] = [Phase::RepoSetup, Phase::PackageInstall, Phase::Configuration, Phase::ServiceEnable, Phase::PostReboot];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Phase::RepoSetup => "Repository setup",
+            Phase::PackageInstall => "Package installation",
+            Phase::Configuration => "Configuration",
+            Phase::ServiceEnable => "Services",
+            Phase::PostReboot => "Changes applied by a reboot",
+        }
+    }
+}
+
+/// What selecting an item does to the host, built by the item's script function.
+pub struct Script {
+    /// ID of the item the script belongs to; filled in by [`crate::MenuNode::script`].
+    pub id: &'static str,
+    pub description: String,
+    pub commands: Vec<Cmd>,
+    /// Commands that succeed once the script has taken effect, e.g. `systemctl is-active cockpit.socket`.
+    pub verify: Vec<String>,
+    /// Ports the script's services listen on, as (`port/protocol`, systemd unit), e.g. `("9090/tcp", "cockpit.socket")`.
+    pub listens: Vec<(String, String)>,
+    /// Host-wide settings of which only one value can take effect, as (setting, value), e.g.
+    /// `("display manager", "sddm")`; see [`Script::exclusive`].
+    pub exclusive: Vec<(String, String)>,
+    /// Packages installed with [`Script::install`], for sizing the script before it runs.
+    pub packages: Vec<String>,
+    /// Estimated MiB the script adds on disk, dependencies included; set with [`Script::size_mib`]
+    /// where sizing `packages` falls short: package groups, downloads and large dependency trees.
+    pub size_mib: Option<u64>,
+    /// Downloads packages or other content.
+    pub requires_network: bool,
+    /// Only takes full effect after a reboot.
+    pub requires_reboot: bool,
+    /// Safe to run again on a host where it already ran.
+    pub idempotent: bool,
+    /// Set with [`Script::phase`]; otherwise derived by [`Script::run_phase`].
+    pub phase: Option<Phase>,
+}
+
+impl Script {
+    pub fn new(description: &str) -> Script {
+        Script {
+            id: "",
+            description: description.to_string(),
+            commands: Vec::new(),
+            verify: Vec::new(),
+            listens: Vec::new(),
+            exclusive: Vec::new(),
+            packages: Vec::new(),
+            size_mib: None,
+            requires_network: false,
+            requires_reboot: false,
+            idempotent: false,
+            phase: None,
+        }
+    }
+
+    /// A script for an item that has no variant for `backend`; it only emits an explanatory comment.
+    pub fn unsupported(backend: &dyn PackageBackend, what: &str) -> Script {
+        Script::new(&format!("{} (not available with {})", what, backend.name()))
+            .cmd(format!("# {} has no {} variant; skipped.", what, backend.name()))
+            .idempotent()
+    }
+
+    pub fn cmd(mut self, code: impl Into<String>) -> Script {
+        self.commands.push(Cmd { code: code.into() });
+        self
+    }
+
+    /// Installs `packages` and verifies afterwards that they are present.
+    pub fn install(mut self, backend: &dyn PackageBackend, packages: &[&str]) -> Script {
+        self.packages.extend(packages.iter().map(|package| package.to_string()));
+        self.cmd(backend.install(packages)).verify(backend.query(packages)).network()
+    }
+
+    pub fn group_install(self, backend: &dyn PackageBackend, group: &str) -> Script {
+        self.cmd(backend.group_install(group)).network()
+    }
+
+    pub fn enable_repo(self, backend: &dyn PackageBackend, repo: &str) -> Script {
+        self.cmd(backend.enable_repo(repo))
+    }
+
+    pub fn verify(mut self, check: impl Into<String>) -> Script {
+        self.verify.push(check.into());
+        self
+    }
+
+    /// Records that `unit` listens on `port`, written as `port/protocol`.
+    pub fn listens(mut self, port: &str, unit: &str) -> Script {
+        self.listens.push((port.to_string(), unit.to_string()));
+        self
+    }
+
+    /// Records that the script sets `setting` to `value`, where whichever script runs last would
+    /// silently win: two selected items setting it to different values are a
+    /// [`crate::Problem::Collision`].
+    pub fn exclusive(mut self, setting: &str, value: &str) -> Script {
+        self.exclusive.push((setting.to_string(), value.to_string()));
+        self
+    }
+
+    /// Records that the script takes about `mib` MiB on disk, in place of the sizes of its packages.
+    pub fn size_mib(mut self, mib: u64) -> Script {
+        self.size_mib = Some(mib);
+        self
+    }
+
+    pub fn network(mut self) -> Script {
+        self.requires_network = true;
+        self
+    }
+
+    pub fn reboot(mut self) -> Script {
+        self.requires_reboot = true;
+        self
+    }
+
+    /// Makes the system boot to the graphical target from the reboot this asks for, as a desktop
+    /// or display manager item does.
+    pub fn boot_graphical(self) -> Script {
+        self.cmd("sudo systemctl set-default graphical.target").verify("systemctl get-default | grep -qx graphical.target").reboot()
+    }
+
+    pub fn idempotent(mut self) -> Script {
+        self.idempotent = true;
+        self
+    }
+
+    /// Places the script in `phase` instead of the one derived from its commands.
+    pub fn phase(mut self, phase: Phase) -> Script {
+        self.phase = Some(phase);
+        self
+    }
+
+    /// The phase the script runs in: the one set with [`Script::phase`], otherwise
+    /// [`Phase::PostReboot`] when it needs a reboot, [`Phase::ServiceEnable`] when it enables a
+    /// unit, [`Phase::PackageInstall`] when it downloads content, and [`Phase::Configuration`].
+    pub fn run_phase(&self) -> Phase {
+        if let Some(phase) = self.phase {
+            phase
+        } else if self.requires_reboot {
+            Phase::PostReboot
+        } else if self.commands.iter().any(|cmd| cmd.code.contains("systemctl enable")) {
+            Phase::ServiceEnable
+        } else if self.requires_network {
+            Phase::PackageInstall
+        } else {
+            Phase::Configuration
+        }
+    }
+
+    /// Whether the script fetches content the distribution's repositories cannot provide: a
+    /// command line with an `https://` URL, as for Flathub, rustup or third-party repositories.
+    pub fn needs_internet(&self) -> bool {
+        self.commands.iter()
+            .flat_map(|cmd| cmd.code.lines())
+            .any(|line| !line.trim_start().starts_with('#') && line.contains("https://"))
+    }
+
+    /// Resolves the `{{#if <id>}}` blocks in every command and verification for `distro`,
+    /// dropping commands that are left empty.
+    pub fn for_distro(mut self, distro: OsDistribution) -> Script {
+        for cmd in &mut self.commands {
+            cmd.code = render_conditionals(&cmd.code, distro);
+        }
+        self.commands.retain(|cmd| !cmd.code.trim().is_empty());
+        for check in &mut self.verify {
+            *check = render_conditionals(check, distro);
+        }
+        self.verify.retain(|check| !check.trim().is_empty());
+        self
+    }
+
+    /// Substitutes `{{key}}` placeholders in every command and verification.
+    pub fn with_params(mut self, params: &[Param]) -> Script {
+        for cmd in &mut self.commands {
+            cmd.code = render_params(&cmd.code, params);
+        }
+        for check in &mut self.verify {
+            *check = render_params(check, params);
+        }
+        for (port, _) in &mut self.listens {
+            *port = render_params(port, params);
+        }
+        for package in &mut self.packages {
+            *package = render_params(package, params);
+        }
+        self
+    }
+
+    /// The commands as plain bash, one block per line group.
+    pub fn render(&self) -> String {
+        self.commands.iter().map(|cmd| cmd.code.as_str()).collect::<Vec<_>>().join("\n")
+    }
+}
@@ -1,47 +1,75 @@
 // src/scripts.rs
 
-use crate::{MenuNode, OsDistribution};
-use std::{cell::RefCell, rc::Rc};
-
-// Helper macro to create a leaf node (an item)
-macro_rules! item {
-    ($name:expr, $func:expr) => {
-        Rc::new(RefCell::new(MenuNode::Item {
-            name: $name.to_string(),
-            script_fn: $func,
-            selected: false,
-        }))
-    };
-}
-
-// Helper macro to create a branch node (a sub-menu)
-macro_rules! menu {
-    ($name:expr, $($child:expr),*) => {
-        Rc::new(RefCell::new(MenuNode::Menu {
-            name: $name.to_string(),
-            children: vec![$($child),*],
-        }))
-    };
+use redcent_tui::{item, menu, MenuNode, OsDistribution};
+use std::{cell::RefCell, process::Command, rc::Rc};
+
+/// Returns the major GNOME Shell version installed on this host, defaulting to the EL10 release (47).
+fn detect_gnome_shell_version() -> String {
+    Command::new("gnome-shell").arg("--version").output().ok()
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .and_then(|text| text.split_whitespace().last().map(|v| v.split('.').next().unwrap_or(v).to_string()))
+        .filter(|major| major.parse::<u32>().is_ok())
+        .unwrap_or_else(|| "47".to_string())
 }
 
 pub fn build_menu_tree(os: OsDistribution) -> Rc<RefCell<MenuNode>> {
+    let shell = detect_gnome_shell_version();
     menu!("Main Menu",
+        menu!("Basics",
+            item!("basics.hostname", "Hostname", scripts_basics::hostname,
+                params: [("hostname", "Host name, e.g. web01.example.com", "")], description: descriptions::BASICS_HOSTNAME),
+            item!("basics.timezone", "Time Zone", scripts_basics::timezone,
+                params: [("timezone", "Time zone", "UTC")], description: descriptions::BASICS_TIMEZONE),
+            item!("basics.ntp", "Time Synchronization (chrony)", scripts_basics::ntp,
+                params: [("ntp_servers", "NTP servers, comma-separated", "")], description: descriptions::BASICS_NTP),
+            item!("basics.locale", "Default Locale", scripts_basics::locale,
+                params: [("locale", "Locale, e.g. en_US.UTF-8 or de_DE.UTF-8", "en_US.UTF-8")], description: descriptions::BASICS_LOCALE)
+        ),
+        menu!("Users & Access",
+            item!("users.admin", "Admin User", scripts_users::admin_user, tags: ["users"],
+                params: [("admin_user", "User name", ""), ("password", "Initial password (blank = SSH keys only)", "", optional)],
+                description: descriptions::USERS_ADMIN),
+            item!("users.ssh-key", "SSH Public Key", scripts_users::ssh_key, tags: ["users", "ssh"],
+                params: [("key_user", "Install for user", ""), ("ssh_key", "Public key (paste the .pub line)", "")],
+                description: descriptions::USERS_SSH_KEY),
+            item!("users.ssh-no-password", "Disable SSH Password Authentication", scripts_users::ssh_no_password, requires: ["users.ssh-key"],
+                tags: ["ssh", "security"], description: descriptions::USERS_SSH_NO_PASSWORD),
+            item!("users.sudoers", "sudoers Drop-in for a User", scripts_users::sudoers, tags: ["users"],
+                params: [("sudo_user", "User or %group", ""), ("nopasswd", "Run sudo without a password", "no"),
+                    ("sudo_file", "Drop-in name (/etc/sudoers.d/redcent-<name>)", "admin")], description: descriptions::USERS_SUDOERS)
+        ),
         menu!("Graphical Environments",
             menu!("Gnome DE",
                 menu!("Environment Installation",
-                    item!("Minimal Installation", scripts_gnome::minimal_install),
-                    item!("Full Installation", scripts_gnome::full_install)
+                    item!("gnome.minimal", "Minimal Installation", scripts_gnome::minimal_install, conflicts: ["gnome.full"], tags: ["gui"], description: descriptions::GNOME_MINIMAL),
+                    item!("gnome.full", "Full Installation", scripts_gnome::full_install, tags: ["gui", "long-running"], description: descriptions::GNOME_FULL)
                 ),
                 menu!("Customization",
                     menu!("Extensions",
                         menu!("Tiling WM",
-                        ), // Placeholder for Forge, PaperWM, Tiling, etc.
+                            item!("gnome.ext-forge", "Forge", scripts_gnome_ext::forge, conflicts: ["gnome.ext-paperwm"], tags: ["gui"],
+                                params: [("shell", "GNOME Shell version", &shell), ("ext_version", "Pinned version tag (blank = latest)", "", optional)]),
+                            item!("gnome.ext-paperwm", "PaperWM", scripts_gnome_ext::paperwm, tags: ["gui"],
+                                params: [("shell", "GNOME Shell version", &shell), ("ext_version", "Pinned version tag (blank = latest)", "", optional)])
+                        ),
                         menu!("Top Bar",
-                        ), // Placeholder for Vitals, Status area horizontal spacing, etc.
+                            item!("gnome.ext-vitals", "Vitals", scripts_gnome_ext::vitals, tags: ["gui"],
+                                params: [("shell", "GNOME Shell version", &shell), ("ext_version", "Pinned version tag (blank = latest)", "", optional)])
+                        ), // Placeholder for Status area horizontal spacing, etc.
                         menu!("Desktop Functions",
-                        ), // Placeholder for Just Perfection, etc.
+                            item!("gnome.ext-just-perfection", "Just Perfection", scripts_gnome_ext::just_perfection, tags: ["gui"],
+                                params: [("shell", "GNOME Shell version", &shell), ("ext_version", "Pinned version tag (blank = latest)", "", optional)])
+                        ),
                         menu!("Search / Launchers", 
-                        ) // Placeholder for Search Light
+                            item!("gnome.ext-search-light", "Search Light", scripts_gnome_ext::search_light, tags: ["gui"],
+                                params: [("shell", "GNOME Shell version", &shell), ("ext_version", "Pinned version tag (blank = latest)", "", optional)])
+                        )
+                    ),
+                    menu!("System Policies (dconf)",
+                        item!("gnome.policy-lock-notifications", "Hide Notifications on Lock Screen", scripts_gnome::policy_lock_notifications, tags: ["gui"]),
+                        item!("gnome.policy-wallpaper", "Set Default Wallpaper (locked)", scripts_gnome::policy_wallpaper, tags: ["gui"],
+                            params: [("wallpaper", "Wallpaper file path", "/usr/share/backgrounds/default.png")]),
+                        item!("gnome.policy-no-user-switching", "Disable User Switching", scripts_gnome::policy_no_user_switching, tags: ["gui"])
                     )
                 ),
                 menu!("Applications / Packages",
@@ -49,130 +77,2378 @@ pub fn build_menu_tree(os: OsDistribution) -> Rc<RefCell<MenuNode>> {
                     )
                 ) // Placeholder for ghosty, allacrity, ptyxis, etc.  
             ),
+            menu!("KDE Plasma",
+                menu!("Environment Installation",
+                    item!("kde.minimal", "Minimal Installation", scripts_kde::minimal_install, requires: ["repos.epel"], conflicts: ["kde.full"], tags: ["gui"], description: descriptions::KDE_MINIMAL),
+                    item!("kde.full", "Full Installation", scripts_kde::full_install, requires: ["repos.epel"], tags: ["gui", "long-running"], description: descriptions::KDE_FULL)
+                )
+            ),
+            menu!("XFCE",
+                menu!("Environment Installation",
+                    item!("xfce.minimal", "Minimal Installation", scripts_xfce::minimal_install, requires: ["repos.epel"], conflicts: ["xfce.full"], tags: ["gui"], description: descriptions::XFCE_MINIMAL),
+                    item!("xfce.full", "Full Installation", scripts_xfce::full_install, requires: ["repos.epel"], tags: ["gui", "long-running"], description: descriptions::XFCE_FULL)
+                )
+            ),
             menu!("Sway WM",
                 menu!("Environment Installation",
-                    item!("Compile from Source", scripts_sway::compile_from_source)
+                    item!("sway.packages", "Full Environment from Packages", scripts_sway::install_packages, requires: ["repos.epel", "repos.copr-sway"], tags: ["gui"], description: descriptions::SWAY_PACKAGES),
+                    item!("sway.source", "Compile from Source", scripts_sway::compile_from_source, requires: ["repos.crb"], tags: ["gui"], description: descriptions::SWAY_SOURCE)
+                ),
+                menu!("Login Manager",
+                    item!("sway.greetd", "greetd (text greeter)", scripts_sway::greetd_session, requires: ["sway.packages"], conflicts: ["sway.sddm"], tags: ["gui"], description: descriptions::SWAY_GREETD),
+                    item!("sway.sddm", "SDDM", scripts_sway::sddm_session, requires: ["sway.packages"], tags: ["gui"], description: descriptions::SWAY_SDDM)
                 ),
                 menu!("Customization",
-                    item!("Wofi", scripts_sway::install_wofi)
+                    item!("sway.wofi", "Wofi", scripts_sway::install_wofi, requires: ["repos.epel"], tags: ["gui"])
                 )
+            ),
+            menu!("Display Manager",
+                item!("dm.sddm", "SDDM", scripts_display::sddm, requires: ["repos.epel"], conflicts: ["dm.lightdm", "sway.greetd"], tags: ["gui"], description: descriptions::DM_SDDM),
+                item!("dm.lightdm", "LightDM", scripts_display::lightdm, requires: ["repos.epel"], conflicts: ["sway.greetd", "sway.sddm"], tags: ["gui"], description: descriptions::DM_LIGHTDM)
             )
         ),
         menu!("Repositories",
             menu!("Add Repositories",
-                item!("CEPH", scripts_repos::add_ceph),
-                item!(if os == OsDistribution::Rhel { "CodeReady Builder" } else { "CRB" }, scripts_repos::add_crb),
-                item!("EPEL", scripts_repos::add_epel),
-                item!("Flathub", scripts_repos::add_flathub),
-                item!("Real-Time (RT)", scripts_repos::add_rt),
-                item!("High Availability (HA)", scripts_repos::add_ha)
+                item!("repos.ceph", "CEPH", scripts_repos::add_ceph, description: descriptions::CEPH),
+                item!("repos.crb", if os == OsDistribution::Rhel { "CodeReady Builder" } else { "CRB" }, scripts_repos::add_crb, description: descriptions::CRB),
+                item!("repos.epel", "EPEL", scripts_repos::add_epel, requires: ["repos.crb"], description: descriptions::EPEL),
+                item!("repos.flathub", "Flathub", scripts_repos::add_flathub, description: descriptions::FLATHUB),
+                item!("repos.rt", "Real-Time (RT)", scripts_repos::add_rt, description: descriptions::RT),
+                item!("repos.ha", "High Availability (HA)", scripts_repos::add_ha, description: descriptions::HA)
+            ),
+            menu!("COPR",
+                item!("repos.copr-custom", "Custom Project", scripts_repos::add_copr_custom, requires: ["repos.epel"],
+                    params: [("copr", "COPR project (owner/project)", "")], description: descriptions::COPR_CUSTOM),
+                item!("repos.copr-sway", "Sway Extras (alebastr/sway-extras)", scripts_repos::add_copr_sway, requires: ["repos.epel"], description: descriptions::COPR_SWAY),
+                item!("repos.copr-caddy", "Caddy (@caddy/caddy)", scripts_repos::add_copr_caddy, requires: ["repos.epel"], description: descriptions::COPR_CADDY),
+                item!("repos.copr-lazygit", "lazygit (atim/lazygit)", scripts_repos::add_copr_lazygit, requires: ["repos.epel"], description: descriptions::COPR_LAZYGIT)
+            )
+        ),
+        menu!("System Updates",
+            item!("updates.upgrade", "Full System Upgrade", scripts_updates::full_upgrade, tags: ["long-running"], description: descriptions::UPDATES_UPGRADE),
+            item!("updates.automatic", "Automatic Updates", scripts_updates::automatic,
+                params: [("upgrade_type", "Updates to install", "security"), ("apply_updates", "Install them, not just download", "yes")],
+                description: descriptions::UPDATES_AUTOMATIC),
+            item!("updates.needs-restarting", "Restart Check after Updates", scripts_updates::needs_restarting, description: descriptions::UPDATES_NEEDS_RESTARTING),
+            menu!("Kernel",
+                item!("updates.kpatch", "Live Patching (kpatch)", scripts_updates::kpatch, conflicts: ["updates.kernel-rt"], description: descriptions::UPDATES_KPATCH),
+                item!("updates.kernel-rt", "Switch to kernel-rt", scripts_updates::kernel_rt, requires: ["repos.rt"], description: descriptions::UPDATES_KERNEL_RT)
             )
         ),
         menu!("Virtualization",
             menu!("Virtualization Engines",
-                item!("KVM (Core & Tools)", scripts_virt::install_kvm)
+                item!("virt.kvm", "KVM (Core & Tools)", scripts_virt::install_kvm, tags: ["kvm"], description: descriptions::KVM)
                 // add elf, ovirt, harvester? - split to type1 type2 a virtualization tools
             ),
             menu!("Cockpit",
-                item!("Minimal Install", scripts_virt::install_cockpit_minimal),
-                item!("Full Install (with Machines)", scripts_virt::install_cockpit_full)
+                item!("virt.cockpit-minimal", "Minimal Install", scripts_virt::install_cockpit_minimal, conflicts: ["virt.cockpit-full"], tags: ["web"], description: descriptions::COCKPIT_MINIMAL),
+                item!("virt.cockpit-full", "Full Install (with Machines)", scripts_virt::install_cockpit_full, requires: ["virt.kvm"], tags: ["web", "kvm"], description: descriptions::COCKPIT_FULL),
+                item!("virt.cockpit-redcent", "redcent-tui Status Page", scripts_virt::install_status_page, tags: ["web"], description: descriptions::COCKPIT_REDCENT)
+            ),
+            menu!("Host Tuning",
+                item!("virt.cpu-isolation", "CPU Isolation (wizard)", scripts_virt::cpu_isolation, tags: ["kvm", "realtime"],
+                    params: [("isolated_cores", "Isolated CPUs", "")], description: descriptions::CPU_ISOLATION)
+            )
+        ),
+        menu!("Containers",
+            menu!("Runtimes",
+                item!("containers.podman", "Podman + podman-compose", scripts_containers::podman, requires: ["repos.epel"], tags: ["containers"], description: descriptions::PODMAN),
+                item!("containers.docker", "Docker CE (upstream repo)", scripts_containers::docker_ce, conflicts: ["containers.podman"], tags: ["containers", "firewall"], description: descriptions::DOCKER),
+                item!("containers.crio", "CRI-O", scripts_containers::crio, tags: ["containers", "kubernetes"],
+                    params: [("k8s_version", "Kubernetes minor version", "v1.34")], description: descriptions::CRIO)
+            ),
+            menu!("Kubernetes",
+                item!("containers.kubeadm", "kubeadm, kubelet & kubectl", scripts_containers::kubernetes_tools, requires: ["containers.crio"],
+                    tags: ["containers", "kubernetes", "firewall"], params: [("k8s_version", "Kubernetes minor version", "v1.34")])
+            ),
+            menu!("Tools",
+                item!("containers.toolbox", "Toolbox", scripts_containers::toolbox, tags: ["containers"])
+            )
+        ),
+        menu!("Storage",
+            menu!("Local Storage",
+                item!("storage.lvm", "LVM Tools", scripts_storage::lvm, tags: ["storage"]),
+                item!("storage.stratis", "Stratis", scripts_storage::stratis, tags: ["storage"], description: descriptions::STRATIS),
+                item!("storage.zfs", "ZFS (OpenZFS, DKMS)", scripts_storage::zfs, requires: ["repos.epel"], tags: ["storage", "long-running"], description: descriptions::ZFS),
+                item!("storage.btrfs", "Btrfs Tools", scripts_storage::btrfs, tags: ["storage"])
+            ),
+            menu!("Network Storage",
+                item!("storage.nfs-server", "NFS Server + Export", scripts_storage::nfs_server, tags: ["storage", "firewall"],
+                    params: [("path", "Exported directory", "/srv/nfs"), ("clients", "Allowed clients", "192.168.1.0/24"),
+                        ("options", "Export options", "rw,sync,no_subtree_check")]),
+                item!("storage.iscsi", "iSCSI Initiator", scripts_storage::iscsi_initiator, tags: ["storage"])
+            ),
+            menu!("Disk Encryption",
+                item!("storage.luks-tpm2", "Unlock LUKS with TPM2", scripts_luks::enroll_tpm2, tags: ["storage", "encryption"],
+                    params: [("luks_device", "LUKS device", ""), ("tpm2_pcrs", "TPM2 PCRs to bind to", "7")],
+                    description: descriptions::LUKS_TPM2),
+                item!("storage.luks-fido2", "Unlock LUKS with a FIDO2 Key", scripts_luks::enroll_fido2, tags: ["storage", "encryption"],
+                    params: [("luks_device", "LUKS device", "")], description: descriptions::LUKS_FIDO2)
+            ),
+            menu!("Management",
+                item!("storage.cockpit-storaged", "Cockpit Storage Plugin", scripts_storage::cockpit_storaged, tags: ["storage", "web"])
             )
         ),
         menu!("Networking",
             menu!("NetworkManager",
-                item!("OpenVPN", scripts_net::install_vpn_ovpn),
-                item!("OpenConnect", scripts_net::install_vpn_oconn),
-                item!("L2TP", scripts_net::install_vpn_l2tp),
-                item!("LibreSwan", scripts_net::install_vpn_lswan),
-                item!("StrongSwan", scripts_net::install_vpn_sswan),
-                item!("PPTP", scripts_net::install_vpn_pptp)
+                item!("net.vpn-openvpn", "OpenVPN", scripts_net::install_vpn_ovpn, requires: ["repos.epel"], tags: ["vpn"]),
+                item!("net.vpn-openconnect", "OpenConnect", scripts_net::install_vpn_oconn, requires: ["repos.epel"], tags: ["vpn"]),
+                item!("net.vpn-l2tp", "L2TP", scripts_net::install_vpn_l2tp, requires: ["repos.epel"], tags: ["vpn"]),
+                item!("net.vpn-libreswan", "LibreSwan", scripts_net::install_vpn_lswan, tags: ["vpn"]),
+                item!("net.vpn-strongswan", "StrongSwan", scripts_net::install_vpn_sswan, requires: ["repos.epel"], tags: ["vpn"]),
+                item!("net.vpn-pptp", "PPTP", scripts_net::install_vpn_pptp, requires: ["repos.epel"], tags: ["vpn"])
                 // Placeholders for VPN scripts
             ),
             menu!("KVM (libvirt networks)",
-                // Placeholders for libvirt network scripts
+                item!("net.libvirt-nat", "NAT Network", scripts_libvirt::nat_network, requires: ["virt.kvm"], tags: ["kvm"],
+                    params: [("name", "Network name", "natnet"), ("gateway", "Gateway address", "192.168.150.1"),
+                        ("netmask", "Netmask", "255.255.255.0"), ("dhcp_start", "DHCP range start", "192.168.150.100"),
+                        ("dhcp_end", "DHCP range end", "192.168.150.200")]),
+                item!("net.libvirt-bridge", "Host Bridge (wizard)", scripts_libvirt::host_bridge, requires: ["virt.kvm"], tags: ["kvm", "destructive"],
+                    params: [("iface", "Host interface", ""), ("bridge", "Bridge device name", "br0"),
+                        ("name", "libvirt network name", "host-bridge")]),
+                item!("net.libvirt-macvtap", "Macvtap Network (wizard)", scripts_libvirt::macvtap_network, requires: ["virt.kvm"], tags: ["kvm"],
+                    params: [("iface", "Host interface", ""), ("name", "libvirt network name", "macvtap")])
+            ),
+            menu!("Performance",
+                item!("net.perf-tuning", "Server Network Tuning (wizard)", scripts_net::performance_tuning, tags: ["tuning"],
+                    params: [("iface", "NIC to tune", "")], description: descriptions::NET_PERF_TUNING),
+                item!("net.mtu", "MTU / Jumbo Frames (wizard)", scripts_net::set_mtu, tags: ["storage", "kvm"],
+                    params: [("iface", "Interface", ""), ("mtu", "MTU in bytes", "9000"),
+                        ("ping_target", "Peer to ping with full-size frames (optional)", "", optional)],
+                    description: descriptions::MTU)
             )
         ),
         menu!("Hardening",
-            // Placeholders for hardening scripts (openscap)
+            menu!("System Hardening",
+                item!("hardening.ssh-no-root", "Disable Root SSH Login", scripts_hardening::disable_root_ssh, tags: ["security", "ssh"]),
+                item!("hardening.firewalld", "Firewalld Defaults", scripts_hardening::firewalld_defaults, tags: ["security", "firewall", "destructive"], description: descriptions::FIREWALLD),
+                item!("hardening.auditd", "Auditd Baseline Rules", scripts_hardening::auditd_rules, tags: ["security"]),
+                item!("hardening.fapolicyd", "Fapolicyd (Application Allowlisting)", scripts_hardening::fapolicyd, tags: ["security", "destructive"], description: descriptions::FAPOLICYD),
+                item!("hardening.aide", "AIDE (File Integrity)", scripts_hardening::aide, tags: ["security", "long-running"])
+            ),
+            menu!("Bootloader",
+                item!("hardening.grub-password", "GRUB Superuser Password (asked at run time)", scripts_bootloader::grub_password,
+                    tags: ["security", "destructive"], description: descriptions::GRUB_PASSWORD),
+                item!("hardening.grub-permissions", "Restrict GRUB Config Permissions", scripts_bootloader::grub_permissions, tags: ["security"])
+            ),
+            menu!("OpenSCAP",
+                item!("hardening.oscap-tools", "OpenSCAP Scanner & SSG Content", scripts_oscap::install_tools, tags: ["security"], description: descriptions::OSCAP_TOOLS),
+                item!("hardening.oscap-cis-l1", "Remediate: CIS Server Level 1", scripts_oscap::cis_server_l1,
+                    requires: ["hardening.oscap-tools"], conflicts: ["hardening.oscap-cis-l2", "hardening.oscap-stig", "hardening.oscap-stig-gui"],
+                    tags: ["security", "destructive", "long-running"], description: descriptions::OSCAP_REMEDIATE),
+                item!("hardening.oscap-cis-l2", "Remediate: CIS Server Level 2", scripts_oscap::cis_server_l2,
+                    requires: ["hardening.oscap-tools"], conflicts: ["hardening.oscap-stig", "hardening.oscap-stig-gui"],
+                    tags: ["security", "destructive", "long-running"], description: descriptions::OSCAP_REMEDIATE),
+                item!("hardening.oscap-cis-ws-l1", "Remediate: CIS Workstation Level 1", scripts_oscap::cis_workstation_l1,
+                    requires: ["hardening.oscap-tools"], conflicts: ["hardening.oscap-cis-l1", "hardening.oscap-cis-l2", "hardening.oscap-stig"],
+                    tags: ["security", "gui", "destructive", "long-running"], description: descriptions::OSCAP_REMEDIATE),
+                item!("hardening.oscap-stig", "Remediate: DISA STIG", scripts_oscap::stig,
+                    requires: ["hardening.oscap-tools"], conflicts: ["hardening.oscap-stig-gui"], tags: ["security", "destructive", "long-running"], description: descriptions::OSCAP_REMEDIATE),
+                item!("hardening.oscap-stig-gui", "Remediate: DISA STIG with GUI", scripts_oscap::stig_gui,
+                    requires: ["hardening.oscap-tools"], tags: ["security", "gui", "destructive", "long-running"], description: descriptions::OSCAP_REMEDIATE)
+            ),
+            menu!("Session Timeouts",
+                item!("hardening.tmout", "Shell Idle Timeout (TMOUT)", scripts_timeout::shell_tmout, tags: ["security", "ssh"],
+                    params: [("seconds", "Idle timeout (seconds)", "900")]),
+                item!("hardening.ssh-alive", "SSH Idle Disconnect (ClientAlive)", scripts_timeout::ssh_client_alive, tags: ["security", "ssh"],
+                    params: [("interval", "ClientAliveInterval (seconds)", "300"), ("alive_count", "ClientAliveCountMax", "1")]),
+                item!("hardening.gnome-lock", "GNOME Screen Lock Timeout", scripts_timeout::gnome_screen_lock, tags: ["security", "gui"],
+                    params: [("idle", "Idle delay before blanking (seconds)", "600"), ("lock", "Lock delay after blanking (seconds)", "0")])
+            ),
+            menu!("Login Banners",
+                item!("hardening.login-banner", "Legal Login Banner (/etc/issue, sshd)", scripts_banner::login_banner, tags: ["security", "ssh"],
+                    params: [("org", "Organization name", "Example Corp")]),
+                item!("hardening.motd", "Dynamic MOTD (updates, reboot status)", scripts_banner::dynamic_motd,
+                    tags: ["security"], params: [("org", "Organization name", "Example Corp")])
+            )
+        ),
+        menu!("Security",
+            menu!("SELinux",
+                item!("security.selinux-tools", "Troubleshooting & semanage Tools", scripts_security::selinux_tools, tags: ["security", "selinux"])
+            ),
+            menu!("Intrusion Prevention",
+                item!("security.fail2ban", "Fail2ban (sshd jail)", scripts_security::fail2ban, requires: ["repos.epel"], tags: ["security", "ssh", "firewall"],
+                    params: [("maxretry", "Failed logins before a ban", "5"), ("bantime", "Ban duration", "1h")], description: descriptions::FAIL2BAN),
+                item!("security.usbguard", "USBGuard (allow attached devices)", scripts_security::usbguard, tags: ["security", "destructive"], description: descriptions::USBGUARD)
+            ),
+            menu!("Entropy",
+                item!("security.rngd", "rngd (rng-tools)", scripts_entropy::rngd, tags: ["security", "entropy"], description: descriptions::RNGD),
+                item!("security.jitterentropy", "jitterentropy-rngd", scripts_entropy::jitterentropy, tags: ["security", "entropy"],
+                    description: descriptions::JITTERENTROPY),
+                item!("security.entropy-check", "Entropy Health Check", scripts_entropy::health_check, requires: ["security.rngd"],
+                    tags: ["security", "entropy"], description: descriptions::ENTROPY_CHECK)
+            ),
+            menu!("Firewalld Zones",
+                item!("security.zone-public", "Public Zone", scripts_security::zone_public, tags: ["security", "firewall"],
+                    params: [("iface", "Interface", "")]),
+                item!("security.zone-dmz", "DMZ Zone", scripts_security::zone_dmz, tags: ["security", "firewall"],
+                    params: [("iface", "Interface", "")]),
+                item!("security.zone-trusted", "Trusted Zone", scripts_security::zone_trusted, tags: ["security", "firewall", "destructive"],
+                    params: [("iface", "Interface", "")])
+            )
+        ),
+        menu!("System Configuration",
+            menu!("Environment",
+                item!("config.env-vars", "System-wide Environment Variables (profile.d)", scripts_config::env_vars,
+                    params: [("env_file", "Drop-in name (/etc/profile.d/redcent-<name>.sh)", "site"),
+                        ("env_vars", "Variables (NAME=value, space-separated)", "EDITOR=vim")], description: descriptions::ENV_VARS)
+            ),
+            menu!("Sudo Policy",
+                item!("config.sudo-nopasswd", "Admin Group sudo Without Password (toggle)", scripts_config::sudo_nopasswd, tags: ["users"],
+                    params: [("nopasswd", "Run sudo without a password", "no")], description: descriptions::SUDO_NOPASSWD),
+                item!("config.sudo-commands", "Per-user Command Whitelist", scripts_config::sudo_commands, tags: ["users"],
+                    params: [("sudo_user", "User or %group", "deploy"),
+                        ("sudo_commands", "Allowed commands (absolute paths, comma-separated)", "/usr/bin/systemctl restart httpd"),
+                        ("sudo_file", "Drop-in name (/etc/sudoers.d/redcent-<name>)", "deploy")], description: descriptions::SUDO_COMMANDS)
+            )
         ),
         menu!("Monitoring",
-            // Placeholders for monitoring (cockpit-pcp, etc.)
+            menu!("Prometheus node_exporter",
+                item!("monitoring.node-exporter-pkg", "Distribution Package", scripts_monitoring::node_exporter_package, requires: ["repos.epel"],
+                    conflicts: ["monitoring.node-exporter-bin"], tags: ["monitoring", "firewall"]),
+                item!("monitoring.node-exporter-bin", "Upstream Binary", scripts_monitoring::node_exporter_binary, tags: ["monitoring", "firewall"],
+                    params: [("exporter_version", "node_exporter version", "1.9.1")])
+            ),
+            menu!("Dashboards",
+                item!("monitoring.netdata", "Netdata", scripts_monitoring::netdata, tags: ["monitoring", "firewall", "web"]),
+                item!("monitoring.pcp", "PCP + Cockpit Metrics", scripts_monitoring::pcp, tags: ["monitoring", "web"]),
+                item!("monitoring.grafana", "Grafana", scripts_monitoring::grafana, tags: ["monitoring", "firewall", "web"])
+            )
+        ),
+        menu!("Development",
+            menu!("Compilers & Toolchains",
+                item!("dev.tools", "Development Tools (group)", scripts_dev::development_tools, tags: ["dev"]),
+                item!("dev.gcc-toolset", "GCC Toolset", scripts_dev::gcc_toolset, requires: ["dev.tools"], tags: ["dev"],
+                    params: [("toolset_version", "gcc-toolset version", "14")]),
+                item!("dev.rustup", "Rust (rustup)", scripts_dev::rustup, requires: ["dev.tools"], tags: ["dev"]),
+                item!("dev.golang", "Go", scripts_dev::golang, tags: ["dev"])
+            ),
+            menu!("Runtimes",
+                item!("dev.nodejs", "Node.js (module stream)", scripts_dev::nodejs, tags: ["dev"],
+                    params: [("stream", "nodejs module stream", "22")]),
+                item!("dev.python312", "Python 3.12", scripts_dev::python312, tags: ["dev"])
+            ),
+            menu!("Editors",
+                item!("dev.vscode", "Visual Studio Code", scripts_dev::vscode, tags: ["dev", "gui"], description: descriptions::VSCODE)
+            )
+        ),
+        menu!("Lab / Classroom",
+            item!("lab.accounts", "Bulk Student Accounts (wizard)", scripts_lab::bulk_accounts, tags: ["users"],
+                params: [("prefix", "Account name prefix", "student"), ("count", "Number of accounts", "20"),
+                    ("password", "Initial password (must be changed at first login)", "ChangeMe123!"),
+                    ("max_days", "Maximum password age (days)", "90"), ("desktop", "Desktop preset", "GNOME (minimal)")])
+        ),
+        menu!("Maintenance",
+            menu!("Presets",
+                item!("maint.weekly-timer", "Weekly Maintenance Timer", scripts_maint::weekly_timer)
+            ),
+            item!("maint.scheduled-job", "Scheduled Job (wizard)", scripts_maint::scheduled_job,
+                params: [("schedule_type", "Schedule with", "systemd timer"), ("job_name", "Job name", "backup"),
+                    ("command", "Command", "/usr/local/bin/backup.sh"), ("job_user", "Run as user", "root"),
+                    ("schedule", "Schedule", "*-*-* 03:00:00")])
         )
     )
 }
 
+// --- Item Descriptions ---
+
+/// Long-form help shown in the details pane when an item is highlighted.
+mod descriptions {
+    pub const GNOME_MINIMAL: &str = "The GNOME Shell session with the GDM login screen and the browser connector for \
+        extensions.gnome.org, without the Workstation group's applications. The system boots to the graphical target \
+        afterwards; a reboot starts the login screen.";
+    pub const GNOME_FULL: &str = "The complete Workstation package group: GNOME with its default applications, \
+        printing, multimedia codecs available in the base repositories and NetworkManager applets. Expect a large \
+        download. The system boots to the graphical target afterwards.";
+    pub const KDE_MINIMAL: &str = "The Plasma desktop and workspace with the Konsole terminal and the Dolphin \
+        file manager, without the rest of the KDE applications. On RHEL and CentOS Plasma comes from EPEL. \
+        Pick a display manager under Graphical Environments → Display Manager to boot into it.\n\n\
+        https://kde.org/plasma-desktop/";
+    pub const KDE_FULL: &str = "The KDE Plasma Workspaces group from EPEL (the kde-desktop task on Debian and \
+        Ubuntu): Plasma with the KDE applications, printing and network tools. Expect a large download. Pick a \
+        display manager under Graphical Environments → Display Manager to boot into it.";
+    pub const XFCE_MINIMAL: &str = "The XFCE session, window manager, panel, desktop and settings with the Thunar \
+        file manager and the XFCE terminal. On RHEL and CentOS XFCE comes from EPEL. Pick a display manager \
+        under Graphical Environments → Display Manager to boot into it.\n\n\
+        https://www.xfce.org";
+    pub const XFCE_FULL: &str = "The Xfce group from EPEL (the xfce-desktop task on Debian and Ubuntu): XFCE \
+        with its applications and panel plugins. Pick a display manager under Graphical Environments → Display \
+        Manager to boot into it.";
+    pub const DM_SDDM: &str = "Boots to the SDDM login screen, KDE's display manager, which lists every \
+        installed desktop session. Any other display manager is replaced as display-manager.service and the \
+        system boots to the graphical target; a reboot starts the login screen.";
+    pub const DM_LIGHTDM: &str = "Boots to LightDM with its GTK greeter, the usual choice for XFCE, which lists \
+        every installed desktop session. Any other display manager is replaced as display-manager.service and \
+        the system boots to the graphical target; a reboot starts the login screen.";
+    pub const SWAY_PACKAGES: &str = "A complete Sway session from packages: the compositor, the Waybar panel, \
+        swaylock and swayidle for locking and blanking, the foot terminal and the wlroots screen-sharing portal. \
+        On RHEL and CentOS they come from EPEL and the Sway Extras COPR; Debian and Ubuntu ship them in their \
+        main archives. Pick a login manager below to boot into it.\n\n\
+        https://swaywm.org";
+    pub const SWAY_GREETD: &str = "Boots to greetd with its agreety text greeter on the first virtual terminal, \
+        which starts Sway after login. Any other display manager is replaced as display-manager.service; a \
+        reboot starts the greeter.";
+    pub const SWAY_SDDM: &str = "Boots to the SDDM login screen, which lists the Sway session next to any other \
+        installed desktops. Any other display manager is replaced as display-manager.service; a reboot starts \
+        the login screen.";
+    pub const COPR_SWAY: &str = "The alebastr/sway-extras COPR, maintained by Fedora's Sway packager, carries \
+        Waybar and newer Sway builds for Enterprise Linux on top of EPEL. COPR repositories are community \
+        builds without Red Hat support.\n\n\
+        https://copr.fedorainfracloud.org/coprs/alebastr/sway-extras/";
+    pub const COPR_CUSTOM: &str = "Enables any COPR project, given as owner/project or @group/project. COPR \
+        builds packages for Fedora and EPEL from community sources; check that the project builds for your \
+        Enterprise Linux version, which preflight (p on the script screen) does against the COPR server.\n\n\
+        https://copr.fedorainfracloud.org";
+    pub const COPR_CADDY: &str = "The Caddy project's own COPR with the Caddy web server for Enterprise Linux, \
+        as its installation guide recommends. Install the caddy package afterwards.\n\n\
+        https://caddyserver.com/docs/install#fedora-redhat-centos";
+    pub const COPR_LAZYGIT: &str = "The lazygit terminal UI for git, packaged in a COPR by one of its \
+        Fedora users. Install the lazygit package afterwards.\n\n\
+        https://copr.fedorainfracloud.org/coprs/atim/lazygit/";
+    pub const SWAY_SOURCE: &str = "Installs meson, ninja and the Wayland, libinput, xcb and xkbcommon development \
+        headers needed to build Sway and wlroots from a git checkout; the build itself is left to you.\n\n\
+        https://github.com/swaywm/sway";
+    pub const CEPH: &str = "The Ceph client tools (ceph-common): the ceph and rbd commands and the libraries needed \
+        to mount CephFS or map RBD images from an existing cluster. It does not install a cluster.";
+    pub const CRB: &str = "CodeReady Builder (CRB on CentOS Stream) carries the development headers and build \
+        tools that Red Hat ships but does not support for production. EPEL packages depend on it, so it is \
+        selected together with EPEL. On RHEL it needs an active subscription.\n\n\
+        https://access.redhat.com/articles/4348511";
+    pub const EPEL: &str = "Extra Packages for Enterprise Linux: community-maintained Fedora packages rebuilt for \
+        RHEL and CentOS Stream, such as fail2ban, htop, wofi and the VPN plugins. Packages never replace base \
+        packages, but they are not supported by Red Hat.\n\n\
+        https://docs.fedoraproject.org/en-US/epel/";
+    pub const FLATHUB: &str = "Adds the Flathub remote to Flatpak, the main source of desktop applications \
+        packaged as Flatpaks. Applications are installed separately with `flatpak install`.\n\n\
+        https://flathub.org";
+    pub const RT: &str = "The Real-Time repository with the kernel-rt kernel and tuned profiles for \
+        low-latency workloads. It needs a subscription that includes RHEL for Real Time.";
+    pub const HA: &str = "The High Availability repository with Pacemaker, Corosync and the pcs tool for \
+        building failover clusters. On RHEL it needs the High Availability add-on.";
+    pub const BASICS_HOSTNAME: &str = "Sets the static host name with hostnamectl. A fully qualified name \
+        is kept whole; the short name is its first label.";
+    pub const BASICS_TIMEZONE: &str = "Sets the system time zone with timedatectl. The list offered is the one \
+        timedatectl list-timezones prints on this machine; type part of a name to jump to it.";
+    pub const BASICS_NTP: &str = "Installs chrony and points it at the given NTP servers instead of the \
+        distribution's pool, then turns on network time synchronization. The servers are kept in a marked \
+        block of chrony.conf, so running the item again replaces them.\n\n\
+        https://docs.redhat.com/en/documentation/red_hat_enterprise_linux/9/html/configuring_basic_system_settings/configuring-time-synchronization_configuring-basic-system-settings";
+    pub const BASICS_LOCALE: &str = "Installs the language pack for the locale (glibc-langpack on EL, a \
+        generated locale on Debian and Ubuntu) and makes it the system default LANG with localectl. \
+        Sessions started afterwards use it.";
+    pub const USERS_ADMIN: &str = "Creates a user with a home directory and adds it to wheel (sudo on Debian and \
+        Ubuntu), so it can administer the host with sudo. An existing user is only added to the group. Without a \
+        password the account can log in with SSH keys only.";
+    pub const USERS_SSH_KEY: &str = "Appends a public key to the user's ~/.ssh/authorized_keys, unless it is \
+        there already, with the ownership, permissions and SELinux labels sshd insists on. Paste the whole line \
+        of the .pub file; the user must exist, e.g. through Admin User.";
+    pub const USERS_SSH_NO_PASSWORD: &str = "Turns off password and keyboard-interactive logins in sshd, so only \
+        keys are accepted. Selecting it selects SSH Public Key as well, so the script installs a key first and \
+        the host cannot be locked out for lack of one. The setting is checked with sshd -t before sshd reloads.";
+    pub const USERS_SUDOERS: &str = "Lets a user, or a %group, run any command as root through a drop-in in \
+        /etc/sudoers.d, with or without their password. The drop-in is checked with `visudo -c` before it is \
+        installed; for a fixed list of commands use Per-user Command Whitelist instead.";
+    pub const UPDATES_UPGRADE: &str = "Refreshes the repository metadata and upgrades every installed package, \
+        the kernel included (apt full-upgrade on Debian and Ubuntu). Afterwards it reports whether a reboot is \
+        needed to run the new kernel and libraries.";
+    pub const UPDATES_AUTOMATIC: &str = "Installs dnf-automatic and enables its daily timer, which downloads \
+        security or all updates and installs them unless only downloading is asked for. Debian and Ubuntu get \
+        unattended-upgrades instead, which handles security updates.\n\n\
+        https://dnf.readthedocs.io/en/latest/automatic.html";
+    pub const UPDATES_NEEDS_RESTARTING: &str = "After every dnf transaction, runs dnf needs-restarting and puts \
+        its findings in the login message: whether the host needs a reboot for a new kernel or core library, \
+        and which services still run outdated code. Debian and Ubuntu get needrestart, which asks after each \
+        apt run.";
+    pub const UPDATES_KPATCH: &str = "Applies kernel security fixes without rebooting: kpatch-dnf installs the \
+        live patches released for the running kernel, now and with every kernel update. Live patches are \
+        published for RHEL kernels only; CentOS Stream gets none.\n\n\
+        https://docs.redhat.com/en/documentation/red_hat_enterprise_linux/9/html/managing_monitoring_and_updating_the_kernel/applying-patches-with-kernel-live-patching_managing-monitoring-and-updating-the-kernel";
+    pub const UPDATES_KERNEL_RT: &str = "Installs kernel-rt from the Real-Time repository and boots it by \
+        default from the next reboot. The standard kernel stays installed as a fallback in the boot menu. \
+        kpatch does not cover the real-time kernel.";
+    pub const KVM: &str = "The virtualization package group: QEMU/KVM, libvirt and virt-install. libvirtd is \
+        enabled and started, so virsh and Cockpit Machines can manage VMs right away. The CPU needs VT-x or AMD-V \
+        enabled in the firmware.";
+    pub const COCKPIT_MINIMAL: &str = "The Cockpit web console on port 9090 for logs, services, storage, \
+        networking and a terminal in the browser. The socket is enabled and the cockpit service is opened in \
+        firewalld.\n\n\
+        https://cockpit-project.org";
+    pub const COCKPIT_FULL: &str = "Cockpit with its Storage, Podman and Machines pages. Machines manages KVM \
+        virtual machines, which is why KVM is selected with it.";
+    pub const COCKPIT_REDCENT: &str = "A Cockpit page showing the last run of this tool: which items were applied, \
+        when, and with what exit code. It re-runs each item's verification commands to report drift since.";
+    pub const PODMAN: &str = "Daemonless, rootless-capable containers compatible with the Docker CLI, plus \
+        podman-compose for compose files. The podman.socket API lets Docker clients and tools talk to Podman.";
+    pub const DOCKER: &str = "Docker CE from Docker's own repository rather than the distribution. It conflicts \
+        with Podman's Docker compatibility and manages its own firewall rules, so the docker0 bridge is put into \
+        the trusted zone.\n\n\
+        https://docs.docker.com/engine/install/";
+    pub const CRIO: &str = "The CRI-O container runtime from pkgs.k8s.io, the runtime kubelet talks to on a \
+        Kubernetes node. The version parameter must match the Kubernetes minor version.";
+    pub const ZFS: &str = "OpenZFS built as a DKMS module, so it is recompiled for every new kernel. The build \
+        pulls in the kernel headers and compilers and takes a while. ZFS is not part of the distribution and can \
+        lag behind new kernels.\n\n\
+        https://openzfs.github.io/openzfs-docs/";
+    pub const STRATIS: &str = "Stratis, Red Hat's pool-based local storage manager on top of XFS, with thin \
+        provisioning and snapshots. stratisd is enabled and started.";
+    pub const NET_PERF_TUNING: &str = "Common tuning for busy servers: raises the RX and TX ring buffers of the \
+        chosen NIC to the maximum its driver reports (ethtool -g), switches TCP to BBR congestion control with \
+        the fq queueing discipline, allows socket buffers of up to 16 MiB and runs irqbalance to spread NIC \
+        interrupts across CPUs.\n\n\
+        The ring sizes are detected on the host when the script runs; NICs that do not report them (some \
+        virtual ones) are left alone. A redcent-nic-rings@<nic> service applies them again at every boot.";
+
+    pub const MTU: &str = "Sets the MTU of the NetworkManager connection active on the chosen interface, e.g. 9000 \
+        for jumbo frames on storage (iSCSI, NFS, Ceph) and VM migration networks, and applies it in place. With a \
+        peer given, the script then pings it with unfragmented frames that fill the new MTU and fails when they \
+        do not get through, which usually means a switch port or the peer still uses a smaller MTU.";
+
+    pub const FIREWALLD: &str = "Enables firewalld, makes public the default zone and allows only SSH in it. \
+        Any other service you reach this host with has to be allowed again afterwards.";
+    pub const FAPOLICYD: &str = "Application allowlisting: only executables from trusted sources, by default \
+        RPM-installed files, may run. Software installed outside the package manager is blocked until it is added \
+        to the trust database. Review the denials in the journal after enabling it.";
+    pub const USBGUARD: &str = "Allows the USB devices attached right now and blocks every device plugged in \
+        later until it is allowed with `usbguard allow-device`. Attach keyboards and other devices you need \
+        before running the script.";
+    pub const OSCAP_TOOLS: &str = "The OpenSCAP scanner and the SCAP Security Guide content, needed by the \
+        remediation items. Scan without changing anything with `oscap xccdf eval --profile ...`.\n\n\
+        https://www.open-scap.org";
+    pub const OSCAP_REMEDIATE: &str = "Runs `oscap xccdf eval --remediate` with the profile, changing the \
+        configuration of the host to comply, and writes the results and an HTML report to /root. Remediation can \
+        lock out accounts, disable services and tighten SSH; try it on a test machine first.";
+    pub const FAIL2BAN: &str = "Bans addresses that repeatedly fail SSH logins, as logged in the systemd journal. \
+        The parameters set how many failures trigger a ban and how long it lasts.";
+    pub const ENV_VARS: &str = "Exports the variables to every login shell from a drop-in in /etc/profile.d. \
+        Values are shell-quoted; they must not contain spaces or quotes. Already running sessions pick them up at \
+        their next login.";
+    pub const SUDO_NOPASSWD: &str = "With yes, members of the admin group (wheel, or sudo on Debian and Ubuntu) \
+        run sudo without being asked for their password; with no, the drop-in is removed and the password prompt \
+        comes back. The drop-in is checked with `visudo -c` before it is installed, so a broken file cannot lock \
+        you out of sudo.";
+    pub const SUDO_COMMANDS: &str = "Lets one user, or a %group, run only the listed commands as root, e.g. for a \
+        deployment account. Each command needs its absolute path and may carry fixed arguments. The drop-in is \
+        checked with `visudo -c` before it is installed.";
+    pub const GRUB_PASSWORD: &str = "Protects the boot menu with a GRUB superuser password: editing boot entries \
+        (e.g. adding rd.break or init=/bin/bash to get a root shell) and the GRUB command line then need it, while \
+        the default entries still boot unattended. The script asks for the password when it runs, so run it from \
+        the TUI or a terminal; only its PBKDF2 hash is stored.\n\n\
+        Lockout warning: there is no way to recover a forgotten password short of booting rescue media, and on \
+        Debian and Ubuntu the recovery entries need it too. Keep it in your password manager before rebooting.";
+    pub const RNGD: &str = "rngd from rng-tools feeds the kernel's random pool from hardware sources: the \
+        CPU's RDRAND/RDSEED instructions, a TPM, /dev/hwrng (virtio-rng in VMs) and the CPU jitter source. Headless \
+        VMs have no keyboard, mouse or disk timing to draw on, so key generation and TLS handshakes early in boot can \
+        block or start from a thin pool. Giving the VM a virtio-rng device (e.g. <rng model='virtio'> in libvirt) \
+        is the best source.";
+    pub const JITTERENTROPY: &str = "A small daemon that collects entropy from CPU execution timing jitter \
+        and feeds it to the kernel, independent of any hardware RNG. RHEL and CentOS have no separate package: their \
+        rngd includes the same jitter source.\n\n\
+        https://www.chronox.de/jent/";
+    pub const ENTROPY_CHECK: &str = "Prints the kernel's entropy estimate, the hardware RNG in use and whether \
+        the CPU offers RDRAND, then runs the FIPS 140-2 tests of rngtest on 1000 blocks from /dev/random. The \
+        script fails if more than a few blocks fail, which points at a broken source. Since Linux 5.18 the \
+        estimate always reads 256 once the pool is initialized, so the test result is the meaningful part.";
+    pub const CPU_ISOLATION: &str = "Reserves CPUs for pinned KVM guest vCPUs and real-time tasks: the kernel keeps \
+        the scheduler, timer ticks, RCU callbacks and IRQs on the remaining housekeeping CPUs. On RHEL and CentOS \
+        this applies tuned's cpu-partitioning profile, whose bootloader plugin adds nohz_full, rcu_nocbs and the \
+        related parameters to the kernel command line; on Debian and Ubuntu the isolcpus, nohz_full and rcu_nocbs \
+        parameters go into a GRUB drop-in.\n\n\
+        The wizard reads the topology with lscpu and isolates whole cores, so sibling threads stay together. CPU 0 \
+        always stays a housekeeping CPU. Takes effect after a reboot.";
+
+    pub const LUKS_TPM2: &str = "Enrolls the TPM2 chip as a key for an existing LUKS volume with \
+        systemd-cryptenroll and adds tpm2-device=auto to its /etc/crypttab entry, so it unlocks at boot without \
+        a passphrase as long as the measured state in the chosen PCRs is unchanged (PCR 7: the Secure Boot \
+        policy). The existing passphrase stays valid and is asked for when the script runs. Press L in the main \
+        menu to see the LUKS volumes, crypttab and enrolled tokens.\n\n\
+        Unlocking in the initramfs needs dracut (RHEL, CentOS) or a systemd-based initramfs; Debian and Ubuntu's \
+        default initramfs-tools ignore the option.\n\n\
+        https://www.freedesktop.org/software/systemd/man/latest/systemd-cryptenroll.html";
+    pub const LUKS_FIDO2: &str = "Enrolls a FIDO2 security key (YubiKey, Nitrokey, SoloKey) as a key for an \
+        existing LUKS volume with systemd-cryptenroll and adds fido2-device=auto to its /etc/crypttab entry, so it \
+        unlocks with a touch of the key at boot. Plug the key in before running; the existing passphrase stays \
+        valid and is asked for when the script runs, followed by the key's PIN and a touch. The same initramfs \
+        caveat as for TPM2 applies.";
+    pub const VSCODE: &str = "Visual Studio Code from Microsoft's repository, with Microsoft's signing key \
+        imported. It is proprietary software with telemetry enabled by default.";
+}
+
 // --- Script Functions ---
 
+mod scripts_basics {
+    use redcent_tui::{PackageBackend, Script};
+
+    pub fn hostname(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Set the host name")
+            .cmd("sudo hostnamectl set-hostname '{{hostname}}'")
+            .verify("[ \"$(hostnamectl --static)\" = '{{hostname}}' ]")
+            .idempotent()
+    }
+    pub fn timezone(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Set the time zone")
+            .cmd("sudo timedatectl set-timezone '{{timezone}}'")
+            .verify("[ \"$(timedatectl show -p Timezone --value)\" = '{{timezone}}' ]")
+            .idempotent()
+    }
+    pub fn ntp(pm: &dyn PackageBackend) -> Script {
+        let (conf, service) = match pm.name() {
+            "apt" => ("/etc/chrony/chrony.conf", "chrony"),
+            _ => ("/etc/chrony.conf", "chronyd"),
+        };
+        Script::new("Synchronize the clock with the given NTP servers through chrony")
+            .install(pm, &["chrony"])
+            // Drops the servers added by an earlier run and comments out the distribution's pool.
+            .cmd(format!("sudo sed -i -E -e '/^# BEGIN redcent-tui/,/^# END redcent-tui/d' -e 's/^(pool|server) /#&/' {}", conf))
+            .cmd(format!(concat!(
+                "{{ echo '# BEGIN redcent-tui'; for server in $(echo '{{{{ntp_servers}}}}' | tr ',' ' '); do echo \"server $server iburst\"; done; ",
+                "echo '# END redcent-tui'; }} | sudo tee -a {} > /dev/null"
+            ), conf))
+            .cmd(format!("sudo systemctl enable {}", service))
+            .cmd(format!("sudo systemctl restart {}", service))
+            .cmd("sudo timedatectl set-ntp true")
+            .verify(format!("systemctl is-active {}", service))
+            .idempotent()
+    }
+    pub fn locale(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Make the locale the system default");
+        match pm.name() {
+            "apt" => script
+                .install(pm, &["locales"])
+                .cmd("sudo sed -i -E 's/^# *({{locale}} )/\\1/' /etc/locale.gen")
+                .cmd("sudo locale-gen"),
+            // C.UTF-8 ships with glibc itself.
+            _ => script.cmd("lang=$(echo '{{locale}}' | cut -d_ -f1 | cut -d. -f1); [ \"$lang\" = C ] || sudo dnf install -y \"glibc-langpack-$lang\"").network(),
+        }
+        .cmd("sudo localectl set-locale 'LANG={{locale}}'")
+        .verify("localectl status | grep -q 'LANG={{locale}}'")
+        .idempotent()
+    }
+}
+
+mod scripts_users {
+    use super::scripts_config::sudoers_drop_in;
+    use redcent_tui::{write_file, PackageBackend, Script};
+
+    fn admin_group(pm: &dyn PackageBackend) -> &'static str {
+        if pm.name() == "apt" { "sudo" } else { "wheel" }
+    }
+
+    // Pasted values reach the shell through quoted heredocs, so quotes and `$` in them stay literal.
+    pub fn admin_user(pm: &dyn PackageBackend) -> Script {
+        let group = admin_group(pm);
+        Script::new(&format!("Create an administrator in the {} group", group))
+            .cmd(format!("id '{{{{admin_user}}}}' >/dev/null 2>&1 || sudo useradd -m -G {} '{{{{admin_user}}}}'", group))
+            .cmd(format!("sudo usermod -aG {} '{{{{admin_user}}}}'", group))
+            .cmd(concat!(
+                "password=$(cat <<'REDCENT_EOF'\n",
+                "{{password}}\n",
+                "REDCENT_EOF\n",
+                ")\n",
+                "if [ -n \"$password\" ]; then printf '%s:%s\\n' '{{admin_user}}' \"$password\" | sudo chpasswd; fi"
+            ))
+            .verify(format!("id -nG '{{{{admin_user}}}}' | grep -qw {}", group))
+            .idempotent()
+    }
+    pub fn ssh_key(_pm: &dyn PackageBackend) -> Script {
+        // One block, so a resumed run does not reach the later commands without $home.
+        Script::new("Authorize an SSH public key for a user")
+            .cmd(concat!(
+                "key=$(cat <<'REDCENT_EOF'\n",
+                "{{ssh_key}}\n",
+                "REDCENT_EOF\n",
+                ")\n",
+                "home=$(getent passwd '{{key_user}}' | cut -d: -f6)\n",
+                "if [ -z \"$home\" ]; then\n",
+                "    echo 'User {{key_user}} does not exist' >&2\n",
+                "    false\n",
+                "else\n",
+                "    keys=\"$home/.ssh/authorized_keys\"\n",
+                "    sudo install -d -m 0700 -o '{{key_user}}' -g \"$(id -gn '{{key_user}}')\" \"$home/.ssh\" \\\n",
+                "        && sudo touch \"$keys\" \\\n",
+                "        && { sudo grep -qxF \"$key\" \"$keys\" || printf '%s\\n' \"$key\" | sudo tee -a \"$keys\" > /dev/null; } \\\n",
+                "        && sudo chown '{{key_user}}': \"$keys\" && sudo chmod 0600 \"$keys\" \\\n",
+                "        && { ! command -v restorecon > /dev/null || sudo restorecon -R \"$home/.ssh\"; }\n",
+                "fi"
+            ))
+            .verify("sudo test -s \"$(getent passwd '{{key_user}}' | cut -d: -f6)/.ssh/authorized_keys\"")
+            .idempotent()
+    }
+    pub fn ssh_no_password(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Accept only key logins over SSH")
+            .cmd(write_file!("/etc/ssh/sshd_config.d/10-redcent-no-password.conf", "PasswordAuthentication no\nKbdInteractiveAuthentication no\n"))
+            .cmd("sudo sshd -t && sudo systemctl reload sshd")
+            .verify("sudo sshd -T | grep -qx 'passwordauthentication no'")
+            .idempotent()
+    }
+    pub fn sudoers(_pm: &dyn PackageBackend) -> Script {
+        let install = sudoers_drop_in("redcent-{{sudo_file}}", concat!(
+            "if [ '{{nopasswd}}' = yes ]; then tag='NOPASSWD: '; else tag=''; fi\n",
+            "printf '%s ALL=(ALL) %sALL\\n' '{{sudo_user}}' \"$tag\""
+        ));
+        Script::new("Let a user or group run any command as root")
+            .cmd(install)
+            .cmd("sudo visudo -c")
+            .verify("sudo test -f /etc/sudoers.d/redcent-{{sudo_file}}")
+            .idempotent()
+    }
+}
+
 mod scripts_gnome {
-    pub fn minimal_install() -> &'static str {
-        "sudo dnf install -y gdm gnome-browser-connector\nsudo systemctl set-default graphical.target"
+    use redcent_tui::{dconf::DconfPolicy, PackageBackend, Script};
+
+    pub fn minimal_install(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install a minimal GNOME session with GDM and boot to it");
+        let script = match pm.name() {
+            "apt" => script.install(pm, &["gnome-core", "gdm3", "gnome-browser-connector"]),
+            _ => script.install(pm, &["gdm", "gnome-browser-connector"]),
+        };
+        // gdm and gnome-core pull in the GNOME Shell stack.
+        script.size_mib(1200).boot_graphical().idempotent()
+    }
+    pub fn full_install(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install the full GNOME Workstation environment and boot to it");
+        let script = match pm.name() {
+            "apt" => script.group_install(pm, "gnome-desktop"),
+            _ => script.group_install(pm, "Workstation"),
+        };
+        script.size_mib(2500).boot_graphical().idempotent()
+    }
+    pub fn policy_lock_notifications(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Lock notifications off the lock screen system-wide")
+            .cmd(DconfPolicy::new("10-redcent-notifications")
+                .lock("org/gnome/desktop/notifications", "show-in-lock-screen", "false")
+                .render())
+            .idempotent()
+    }
+    pub fn policy_wallpaper(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Enforce a wallpaper for all users")
+            .cmd(DconfPolicy::new("10-redcent-wallpaper")
+                .lock("org/gnome/desktop/background", "picture-uri", "'file://{{wallpaper}}'")
+                .lock("org/gnome/desktop/background", "picture-uri-dark", "'file://{{wallpaper}}'")
+                .set("org/gnome/desktop/background", "picture-options", "'zoom'")
+                .render())
+            .idempotent()
+    }
+    pub fn policy_no_user_switching(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Disable fast user switching")
+            .cmd(DconfPolicy::new("10-redcent-user-switching")
+                .lock("org/gnome/desktop/lockdown", "disable-user-switching", "true")
+                .render())
+            .idempotent()
     }
-    pub fn full_install() -> &'static str {
-        "sudo dnf groupinstall -y 'Workstation'\nsudo systemctl set-default graphical.target"
+}
+
+mod scripts_gnome_ext {
+    use redcent_tui::{PackageBackend, Script};
+
+    // Installs an extension system-wide from extensions.gnome.org, pinned to a version tag when one is given,
+    // and enables it for the user who invoked sudo through their session bus.
+    macro_rules! install_extension {
+        ($uuid:expr) => {
+            concat!(
+                "ext_uuid='", $uuid, "'\n",
+                "if [ -n '{{ext_version}}' ]; then ext_query='version_tag={{ext_version}}'; else ext_query='shell_version={{shell}}'; fi\n",
+                "curl -fsSL -o \"/tmp/${ext_uuid}.zip\" \"https://extensions.gnome.org/download-extension/${ext_uuid}.shell-extension.zip?${ext_query}\"\n",
+                "sudo rm -rf \"/usr/share/gnome-shell/extensions/${ext_uuid}\"\n",
+                "sudo unzip -q -o \"/tmp/${ext_uuid}.zip\" -d \"/usr/share/gnome-shell/extensions/${ext_uuid}\"\n",
+                "if [ -d \"/usr/share/gnome-shell/extensions/${ext_uuid}/schemas\" ]; then\n",
+                "    sudo cp \"/usr/share/gnome-shell/extensions/${ext_uuid}\"/schemas/*.gschema.xml /usr/share/glib-2.0/schemas/\n",
+                "    sudo glib-compile-schemas /usr/share/glib-2.0/schemas/\n",
+                "fi\n",
+                "sudo chmod -R a+rX \"/usr/share/gnome-shell/extensions/${ext_uuid}\"\n",
+                "rm -f \"/tmp/${ext_uuid}.zip\"\n",
+                "if [ -n \"${SUDO_USER:-}\" ]; then\n",
+                "    sudo -u \"$SUDO_USER\" DBUS_SESSION_BUS_ADDRESS=\"unix:path=/run/user/$(id -u \"$SUDO_USER\")/bus\" gnome-extensions enable \"$ext_uuid\" \\\n",
+                "        || echo \"Log in to GNOME and run: gnome-extensions enable $ext_uuid\"\n",
+                "fi"
+            )
+        };
+    }
+
+    fn extension(pm: &dyn PackageBackend, description: &str, install: &str) -> Script {
+        Script::new(description).install(pm, &["curl", "unzip"]).cmd(install).network().idempotent()
+    }
+
+    pub fn forge(pm: &dyn PackageBackend) -> Script {
+        extension(pm, "Install the Forge tiling extension", install_extension!("forge@jmmaranan.com"))
+    }
+    pub fn paperwm(pm: &dyn PackageBackend) -> Script {
+        extension(pm, "Install the PaperWM scrollable tiling extension", install_extension!("paperwm@paperwm.github.com"))
+    }
+    pub fn vitals(pm: &dyn PackageBackend) -> Script {
+        extension(pm, "Install the Vitals system monitor extension", install_extension!("Vitals@CoreCoding.com"))
+    }
+    pub fn just_perfection(pm: &dyn PackageBackend) -> Script {
+        extension(pm, "Install the Just Perfection tweak extension", install_extension!("just-perfection-desktop@just-perfection"))
+    }
+    pub fn search_light(pm: &dyn PackageBackend) -> Script {
+        extension(pm, "Install the Search Light launcher extension", install_extension!("search-light@icedman.github.com"))
+    }
+}
+
+mod scripts_kde {
+    use redcent_tui::{PackageBackend, Script};
+
+    pub fn minimal_install(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install a minimal KDE Plasma session");
+        match pm.name() {
+            "apt" => script.install(pm, &["kde-plasma-desktop"]),
+            _ => script.install(pm, &["plasma-desktop", "plasma-workspace", "konsole", "dolphin"]),
+        }.size_mib(900).idempotent()
+    }
+    pub fn full_install(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install the full KDE Plasma Workspaces environment");
+        match pm.name() {
+            "apt" => script.group_install(pm, "kde-desktop"),
+            _ => script.group_install(pm, "KDE Plasma Workspaces"),
+        }.size_mib(2500).verify("command -v startplasma-wayland").idempotent()
+    }
+}
+
+mod scripts_xfce {
+    use redcent_tui::{PackageBackend, Script};
+
+    pub fn minimal_install(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install a minimal XFCE session");
+        match pm.name() {
+            "apt" => script.install(pm, &["xfce4"]),
+            _ => script.install(pm, &["xfce4-session", "xfwm4", "xfce4-panel", "xfdesktop", "xfce4-settings", "Thunar", "xfce4-terminal"]),
+        }.size_mib(300).idempotent()
+    }
+    pub fn full_install(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install the full XFCE environment with its applications and panel plugins");
+        match pm.name() {
+            "apt" => script.group_install(pm, "xfce-desktop"),
+            _ => script.group_install(pm, "Xfce"),
+        }.size_mib(700).verify("command -v xfce4-session").idempotent()
+    }
+}
+
+mod scripts_display {
+    use redcent_tui::{PackageBackend, Script};
+
+    /// Makes `unit` the display manager and boots to it.
+    pub fn display_manager(script: Script, unit: &str) -> Script {
+        script
+            .cmd(format!("sudo systemctl enable --force {}", unit))
+            .verify(format!("systemctl is-enabled {}", unit))
+            .exclusive("display manager", unit)
+            .boot_graphical()
+            .idempotent()
+    }
+    pub fn sddm(pm: &dyn PackageBackend) -> Script {
+        display_manager(Script::new("Boot to the SDDM login screen").install(pm, &["sddm"]), "sddm")
+    }
+    pub fn lightdm(pm: &dyn PackageBackend) -> Script {
+        let greeter = if pm.name() == "apt" { "lightdm-gtk-greeter" } else { "lightdm-gtk" };
+        display_manager(Script::new("Boot to the LightDM login screen").install(pm, &["lightdm", greeter]), "lightdm")
     }
 }
 
 mod scripts_sway {
-    pub fn compile_from_source() -> &'static str {
-        "# This script is a placeholder for the required commands.\nsudo dnf install -y ninja-build meson gcc wayland-devel wayland-protocols-devel libinput-devel libxcb-devel libxkbcommon-devel pixman-devel"
+    use super::scripts_display::display_manager;
+    use redcent_tui::{PackageBackend, Script};
+
+    pub fn compile_from_source(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install the build dependencies for compiling Sway")
+            .cmd("# This script is a placeholder for the required commands.");
+        match pm.name() {
+            "apt" => script.install(pm, &[
+                "ninja-build", "meson", "gcc", "libwayland-dev", "wayland-protocols", "libinput-dev", "libxcb1-dev", "libxkbcommon-dev", "libpixman-1-dev",
+            ]),
+            _ => script.install(pm, &[
+                "ninja-build", "meson", "gcc", "wayland-devel", "wayland-protocols-devel", "libinput-devel", "libxcb-devel", "libxkbcommon-devel", "pixman-devel",
+            ]),
+        }.idempotent()
+    }
+    pub fn install_packages(pm: &dyn PackageBackend) -> Script {
+        Script::new("Install Sway with Waybar, swaylock, swayidle, foot and the wlroots desktop portal")
+            .install(pm, &["sway", "waybar", "swaylock", "swayidle", "foot", "xdg-desktop-portal-wlr"])
+            .size_mib(150)
+            .verify("command -v sway")
+            .idempotent()
+    }
+    pub fn greetd_session(pm: &dyn PackageBackend) -> Script {
+        // Debian and Ubuntu run the greeter as _greetd.
+        let user = if pm.name() == "apt" { "_greetd" } else { "greetd" };
+        let config = format!(
+            "sudo mkdir -p /etc/greetd\nsudo tee /etc/greetd/config.toml > /dev/null <<'EOF'\n\
+             [terminal]\nvt = 1\n\n[default_session]\ncommand = \"agreety --cmd sway\"\nuser = \"{}\"\nEOF",
+            user,
+        );
+        display_manager(Script::new("Boot to greetd, which starts Sway after a text login").install(pm, &["greetd"]).cmd(config), "greetd")
     }
-    pub fn install_wofi() -> &'static str {
-        "sudo dnf install -y wofi"
+    pub fn sddm_session(pm: &dyn PackageBackend) -> Script {
+        display_manager(Script::new("Boot to the SDDM login screen with the Sway session").install(pm, &["sddm"]), "sddm")
+    }
+    pub fn install_wofi(pm: &dyn PackageBackend) -> Script {
+        Script::new("Install the Wofi launcher").install(pm, &["wofi"]).idempotent()
     }
 }
 
 mod scripts_repos {
-    pub fn add_ceph() -> &'static str {
-        "sudo dnf install -y ceph-common"
+    use redcent_tui::{PackageBackend, Phase, Script};
+
+    pub fn add_ceph(pm: &dyn PackageBackend) -> Script {
+        Script::new("Install the Ceph client tools").install(pm, &["ceph-common"]).idempotent()
+    }
+    pub fn add_crb(pm: &dyn PackageBackend) -> Script {
+        if pm.name() == "apt" {
+            return Script::unsupported(pm, "CodeReady Builder");
+        }
+        // RHEL names the repository after the release and architecture and enables it through the
+        // subscription; CentOS Stream and the rebuilds call it crb.
+        Script::new("Enable the CodeReady Builder (CRB) repository")
+            .cmd(format!(
+                "{{{{#if rhel}}}}\n# RHUI cloud images without a subscription use: {}\n{}\n{{{{else}}}}\n{}\n{{{{/if}}}}",
+                pm.enable_repo("codeready-builder-for-rhel-10-rhui-rpms"),
+                "sudo subscription-manager repos --enable codeready-builder-for-rhel-10-$(arch)-rpms",
+                pm.enable_repo("crb"),
+            ))
+            .phase(Phase::RepoSetup)
+            .idempotent()
+    }
+    pub fn add_epel(pm: &dyn PackageBackend) -> Script {
+        match pm.name() {
+            "apt" => Script::unsupported(pm, "EPEL"),
+            _ => Script::new("Install the EPEL repository").install(pm, &["epel-release"]).phase(Phase::RepoSetup).idempotent(),
+        }
+    }
+    /// Enables the COPR `project` (`owner/project`) with the dnf copr plugin.
+    fn copr(pm: &dyn PackageBackend, description: &str, project: &str) -> Script {
+        match pm.name() {
+            "apt" => Script::unsupported(pm, "COPR"),
+            _ => Script::new(description)
+                .install(pm, &["dnf-plugins-core"])
+                .cmd(format!("sudo dnf copr enable -y {}", project))
+                .network()
+                .phase(Phase::RepoSetup)
+                .idempotent(),
+        }
+    }
+    pub fn add_copr_custom(pm: &dyn PackageBackend) -> Script {
+        copr(pm, "Enable a COPR repository", "'{{copr}}'")
     }
-    pub fn add_crb() -> &'static str {
-        // The command depends on the OS, which is handled by the script generation logic,
-        // but we can provide a generic placeholder or the RHEL version.
-        "sudo dnf config-manager --set-enabled codeready-builder-for-rhel-10-rhui-rpms || sudo dnf config-manager --set-enabled crb"
+    pub fn add_copr_sway(pm: &dyn PackageBackend) -> Script {
+        copr(pm, "Enable the alebastr/sway-extras COPR repository", "alebastr/sway-extras")
     }
-    pub fn add_epel() -> &'static str {
-        "sudo dnf install -y epel-release"
+    pub fn add_copr_caddy(pm: &dyn PackageBackend) -> Script {
+        copr(pm, "Enable the @caddy/caddy COPR repository", "@caddy/caddy")
     }
-    pub fn add_flathub() -> &'static str {
-        "sudo flatpak remote-add --if-not-exists flathub https://flathub.org/repo/flathub.flatpakrepo"
+    pub fn add_copr_lazygit(pm: &dyn PackageBackend) -> Script {
+        copr(pm, "Enable the atim/lazygit COPR repository", "atim/lazygit")
     }
-    pub fn add_rt() -> &'static str {
-        "sudo dnf config-manager --set-enabled rt"
+    pub fn add_flathub(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Add the Flathub Flatpak remote")
+            .cmd("sudo flatpak remote-add --if-not-exists flathub https://flathub.org/repo/flathub.flatpakrepo")
+            .network()
+            .phase(Phase::RepoSetup)
+            .idempotent()
     }
-    pub fn add_ha() -> &'static str {
-        "sudo dnf config-manager --set-enabled ha"
+    pub fn add_rt(pm: &dyn PackageBackend) -> Script {
+        match pm.name() {
+            "apt" => Script::unsupported(pm, "The Real-Time repository"),
+            _ => Script::new("Enable the Real-Time repository").enable_repo(pm, "rt").phase(Phase::RepoSetup).idempotent(),
+        }
+    }
+    pub fn add_ha(pm: &dyn PackageBackend) -> Script {
+        match pm.name() {
+            "apt" => Script::unsupported(pm, "The High Availability repository"),
+            _ => Script::new("Enable the High Availability repository").enable_repo(pm, "ha").phase(Phase::RepoSetup).idempotent(),
+        }
+    }
+}
+
+mod scripts_updates {
+    use redcent_tui::{write_file, PackageBackend, Phase, Script};
+
+    // Run by the path unit below after each dnf transaction.
+    macro_rules! needs_restarting_template {
+        () => {
+            concat!(
+                "#!/bin/bash\n",
+                "# Restart check installed by redcent-tui; its findings become part of the login message.\n",
+                "motd=/etc/motd.d/redcent-needs-restarting\n",
+                "{\n",
+                "    dnf -q needs-restarting -r > /dev/null || echo 'A reboot is needed to finish installing updates.'\n",
+                "    services=$(dnf -q needs-restarting -s 2> /dev/null | tr '\\n' ' ')\n",
+                "    [ -n \"$services\" ] && echo \"Services running outdated code: $services\"\n",
+                "} > \"$motd.new\"\n",
+                "if [ -s \"$motd.new\" ]; then mv \"$motd.new\" \"$motd\"; else rm -f \"$motd.new\" \"$motd\"; fi\n"
+            )
+        };
+    }
+    macro_rules! needs_restarting_service_template {
+        () => {
+            concat!(
+                "[Unit]\n",
+                "Description=Check for restarts needed after package updates\n\n",
+                "[Service]\n",
+                "Type=oneshot\n",
+                "ExecStart=/usr/local/sbin/redcent-needs-restarting\n"
+            )
+        };
+    }
+    macro_rules! needs_restarting_path_template {
+        () => {
+            concat!(
+                "[Unit]\n",
+                "Description=Check for needed restarts after dnf transactions\n\n",
+                "[Path]\n",
+                "PathChanged=/var/lib/dnf/history.sqlite\n",
+                "Unit=redcent-needs-restarting.service\n\n",
+                "[Install]\n",
+                "WantedBy=paths.target\n"
+            )
+        };
+    }
+
+    pub fn full_upgrade(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Upgrade all installed packages and report whether a reboot is needed");
+        match pm.name() {
+            "apt" => script
+                .cmd("sudo apt-get update && sudo env DEBIAN_FRONTEND=noninteractive apt-get full-upgrade -y")
+                .cmd("if [ -f /var/run/reboot-required ]; then cat /var/run/reboot-required; fi"),
+            _ => script
+                .cmd("sudo dnf upgrade -y --refresh")
+                .cmd("sudo dnf -q needs-restarting -r > /dev/null || echo 'A reboot is needed to finish the upgrade.'"),
+        }.network().phase(Phase::PackageInstall).idempotent()
+    }
+    pub fn automatic(pm: &dyn PackageBackend) -> Script {
+        match pm.name() {
+            // unattended-upgrades installs security updates unless its origins are extended.
+            "apt" => Script::new("Install security updates daily with unattended-upgrades")
+                .install(pm, &["unattended-upgrades"])
+                .cmd(concat!(
+                    "if [ '{{apply_updates}}' = yes ]; then apply=1; else apply=0; fi\n",
+                    "printf 'APT::Periodic::Update-Package-Lists \"1\";\\nAPT::Periodic::Download-Upgradeable-Packages \"1\";\\nAPT::Periodic::Unattended-Upgrade \"%s\";\\n' \"$apply\" \\\n",
+                    "    | sudo tee /etc/apt/apt.conf.d/20auto-upgrades > /dev/null"
+                ))
+                .verify("grep -q Unattended-Upgrade /etc/apt/apt.conf.d/20auto-upgrades")
+                .idempotent(),
+            _ => Script::new("Download and install updates daily with dnf-automatic")
+                .install(pm, &["dnf-automatic"])
+                .cmd(concat!(
+                    "sudo sed -i -E -e 's/^#?\\s*upgrade_type\\s*=.*/upgrade_type = {{upgrade_type}}/' ",
+                    "-e 's/^#?\\s*apply_updates\\s*=.*/apply_updates = {{apply_updates}}/' /etc/dnf/automatic.conf"
+                ))
+                .cmd("sudo systemctl enable --now dnf-automatic.timer")
+                .verify("systemctl is-enabled dnf-automatic.timer")
+                .idempotent(),
+        }
+    }
+    pub fn needs_restarting(pm: &dyn PackageBackend) -> Script {
+        match pm.name() {
+            "apt" => Script::new("Install needrestart to check for restarts after each apt run").install(pm, &["needrestart"]).idempotent(),
+            _ => Script::new("Report needed reboots and service restarts in the login message after each dnf transaction")
+                .install(pm, &["dnf-plugins-core"])
+                .cmd(concat!(
+                    "# Files written:\n",
+                    "#   /usr/local/sbin/redcent-needs-restarting\n",
+                    "#   /etc/systemd/system/redcent-needs-restarting.service\n",
+                    "#   /etc/systemd/system/redcent-needs-restarting.path"
+                ))
+                .cmd("sudo mkdir -p /etc/motd.d")
+                .cmd(write_file!("/usr/local/sbin/redcent-needs-restarting", needs_restarting_template!()))
+                .cmd("sudo chmod 755 /usr/local/sbin/redcent-needs-restarting")
+                .cmd(write_file!("/etc/systemd/system/redcent-needs-restarting.service", needs_restarting_service_template!()))
+                .cmd(write_file!("/etc/systemd/system/redcent-needs-restarting.path", needs_restarting_path_template!()))
+                .cmd("sudo systemctl daemon-reload")
+                .cmd("sudo systemctl enable --now redcent-needs-restarting.path")
+                .verify("systemctl is-enabled redcent-needs-restarting.path")
+                .idempotent(),
+        }
+    }
+    pub fn kpatch(pm: &dyn PackageBackend) -> Script {
+        match pm.name() {
+            "apt" => Script::unsupported(pm, "kpatch live patching"),
+            _ => Script::new("Install kernel live patches now and with every kernel update")
+                .install(pm, &["kpatch", "kpatch-dnf"])
+                .cmd("sudo dnf kpatch auto")
+                .cmd("sudo systemctl enable --now kpatch.service")
+                .verify("systemctl is-enabled kpatch.service")
+                .idempotent(),
+        }
+    }
+    pub fn kernel_rt(pm: &dyn PackageBackend) -> Script {
+        match pm.name() {
+            "apt" => Script::unsupported(pm, "kernel-rt"),
+            _ => Script::new("Install kernel-rt and boot it by default")
+                .install(pm, &["kernel-rt"])
+                .cmd("sudo grubby --set-default \"$(ls -1t /boot/vmlinuz-*rt* | head -n 1)\"")
+                .verify("sudo grubby --default-kernel | grep -q rt")
+                .reboot()
+                .idempotent(),
+        }
     }
 }
 
 mod scripts_virt {
-    pub fn install_kvm() -> &'static str {
-        "sudo dnf install -y @virtualization\nsudo systemctl enable --now libvirtd"
+    use redcent_tui::{write_file, PackageBackend, Script};
+
+    fn enable_cockpit(script: Script) -> Script {
+        script
+            .cmd("sudo systemctl enable --now cockpit.socket")
+            .verify("systemctl is-active cockpit.socket")
+            .listens("9090/tcp", "cockpit.socket")
+            .cmd("sudo firewall-cmd --add-service=cockpit --permanent")
+            .cmd("sudo firewall-cmd --reload")
+            .idempotent()
+    }
+
+    pub fn install_kvm(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install KVM/libvirt virtualization and start libvirtd");
+        match pm.name() {
+            "apt" => script.install(pm, &["qemu-system-x86", "libvirt-daemon-system", "virtinst"]),
+            _ => script.group_install(pm, "virtualization"),
+        }.size_mib(500).cmd("sudo systemctl enable --now libvirtd").verify("systemctl is-active libvirtd").idempotent()
+    }
+    /// Isolates `{{isolated_cores}}` (a CPU list such as `2-7,10-15`) from the scheduler, timer ticks and
+    /// RCU callbacks.
+    pub fn cpu_isolation(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Isolate CPUs for pinned KVM guests and real-time tasks");
+        match pm.name() {
+            "apt" => script
+                .cmd("sudo mkdir -p /etc/default/grub.d")
+                .cmd(write_file!("/etc/default/grub.d/90-redcent-cpu-isolation.cfg", concat!(
+                    "# CPU isolation installed by redcent-tui\n",
+                    "GRUB_CMDLINE_LINUX_DEFAULT=\"$GRUB_CMDLINE_LINUX_DEFAULT isolcpus=managed_irq,domain,{{isolated_cores}} ",
+                    "nohz_full={{isolated_cores}} rcu_nocbs={{isolated_cores}}\"\n"
+                )))
+                .cmd("sudo update-grub")
+                .verify("grep -q 'isolcpus=managed_irq,domain,{{isolated_cores}} ' /etc/default/grub.d/90-redcent-cpu-isolation.cfg"),
+            _ => script
+                .install(pm, &["tuned", "tuned-profiles-cpu-partitioning"])
+                .cmd(write_file!("/etc/tuned/cpu-partitioning-variables.conf", concat!(
+                    "# CPU isolation installed by redcent-tui\n",
+                    "isolated_cores={{isolated_cores}}\n"
+                )))
+                .cmd("sudo systemctl enable --now tuned")
+                // The profile's bootloader plugin writes nohz_full, rcu_nocbs and friends into the GRUB environment.
+                .cmd("sudo tuned-adm profile cpu-partitioning")
+                .verify("tuned-adm active | grep -qw cpu-partitioning")
+                .verify("grep -q 'nohz_full=' /etc/tuned/bootcmdline"),
+        }.reboot().idempotent()
+    }
+    pub fn install_cockpit_minimal(pm: &dyn PackageBackend) -> Script {
+        enable_cockpit(Script::new("Install the Cockpit web console").install(pm, &["cockpit"]))
+    }
+    /// Installs a Cockpit page showing the last run recorded in the logged-in user's
+    /// `~/.local/state/redcent-tui/cockpit-status.json`, re-running each item's verification
+    /// commands to report drift.
+    pub fn install_status_page(pm: &dyn PackageBackend) -> Script {
+        enable_cockpit(Script::new("Install the redcent-tui status page for Cockpit").install(pm, &["cockpit-bridge"]))
+            .cmd("sudo mkdir -p /usr/share/cockpit/redcent")
+            .cmd(write_file!("/usr/share/cockpit/redcent/manifest.json", concat!(
+                "{\n",
+                "  \"version\": 1,\n",
+                "  \"menu\": { \"index\": { \"label\": \"redcent-tui\", \"order\": 90 } }\n",
+                "}\n"
+            )))
+            .cmd(write_file!("/usr/share/cockpit/redcent/index.html", concat!(
+                "<!DOCTYPE html>\n",
+                "<html>\n",
+                "<head>\n",
+                "  <meta charset=\"utf-8\">\n",
+                "  <title>redcent-tui</title>\n",
+                "  <script src=\"../base1/cockpit.js\"></script>\n",
+                "  <style>body { font-family: sans-serif; margin: 1.5em; } td, th { padding: 0.3em 1em; text-align: left; }</style>\n",
+                "</head>\n",
+                "<body>\n",
+                "  <h1>redcent-tui</h1>\n",
+                "  <p id=\"summary\">Loading...</p>\n",
+                "  <table><thead><tr><th>Item</th><th>ID</th><th>Drift</th></tr></thead><tbody id=\"items\"></tbody></table>\n",
+                "  <script src=\"redcent.js\"></script>\n",
+                "</body>\n",
+                "</html>\n"
+            )))
+            .cmd(write_file!("/usr/share/cockpit/redcent/redcent.js", concat!(
+                "\"use strict\";\n",
+                "\n",
+                "// Runs an item's verification commands; null when it has none.\n",
+                "async function verified(commands) {\n",
+                "    if (commands.length === 0) return null;\n",
+                "    for (const command of commands) {\n",
+                "        try {\n",
+                "            await cockpit.spawn([\"bash\", \"-c\", command], { superuser: \"try\", err: \"ignore\" });\n",
+                "        } catch (e) {\n",
+                "            return false;\n",
+                "        }\n",
+                "    }\n",
+                "    return true;\n",
+                "}\n",
+                "\n",
+                "async function load() {\n",
+                "    const summary = document.getElementById(\"summary\");\n",
+                "    const user = await cockpit.user();\n",
+                "    const path = user.home + \"/.local/state/redcent-tui/cockpit-status.json\";\n",
+                "    const status = await cockpit.file(path, { syntax: JSON }).read();\n",
+                "    if (!status) {\n",
+                "        summary.textContent = \"No run recorded yet in \" + path + \".\";\n",
+                "        return;\n",
+                "    }\n",
+                "    const outcome = status.exit_code === null ? \"in progress\" : status.exit_code === 0 ? \"succeeded\" : \"failed (exit \" + status.exit_code + \")\";\n",
+                "    summary.textContent = \"Last run \" + status.started + \" for \" + status.manifest.target + \": \" + outcome + \".\";\n",
+                "    const body = document.getElementById(\"items\");\n",
+                "    for (const item of status.manifest.items) {\n",
+                "        const row = body.insertRow();\n",
+                "        row.insertCell().textContent = item.name;\n",
+                "        row.insertCell().textContent = item.id;\n",
+                "        const drift = row.insertCell();\n",
+                "        drift.textContent = \"checking...\";\n",
+                "        verified(item.verify).then(ok => {\n",
+                "            drift.textContent = ok === null ? \"no checks\" : ok ? \"in sync\" : \"drifted\";\n",
+                "        });\n",
+                "    }\n",
+                "}\n",
+                "\n",
+                "load().catch(e => { document.getElementById(\"summary\").textContent = String(e); });\n"
+            )))
+            .verify("test -f /usr/share/cockpit/redcent/manifest.json")
+    }
+    pub fn install_cockpit_full(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install Cockpit with its machines, storage and podman plugins");
+        enable_cockpit(match pm.name() {
+            "apt" => script.install(pm, &[
+                "cockpit", "cockpit-bridge", "cockpit-system", "cockpit-packagekit", "cockpit-ws", "cockpit-storaged", "cockpit-machines", "cockpit-podman",
+            ]),
+            _ => script.install(pm, &[
+                "cockpit", "cockpit-files", "cockpit-bridge", "cockpit-system", "cockpit-ws-selinux", "cockpit-packagekit", "cockpit-ws",
+                "cockpit-storaged", "subscription-manager-cockpit", "cockpit-machines", "cockpit-podman",
+            ]),
+        })
+    }
+}
+mod scripts_containers {
+    use redcent_tui::{write_file, PackageBackend, Script};
+
+    // Shell snippet yielding the distro ID (rhel, centos, debian, ubuntu) Docker's repositories are keyed by.
+    const OS_ID: &str = "$(. /etc/os-release; echo \"$ID\")";
+
+    /// Kernel modules, sysctls and swap settings kubelet and CRI-O expect on every node.
+    fn kubernetes_prereqs(script: Script) -> Script {
+        script
+            .cmd(write_file!("/etc/modules-load.d/redcent-k8s.conf", "overlay\nbr_netfilter\n"))
+            .cmd("sudo modprobe overlay && sudo modprobe br_netfilter")
+            .cmd(write_file!("/etc/sysctl.d/90-redcent-k8s.conf", concat!(
+                "net.bridge.bridge-nf-call-iptables = 1\n",
+                "net.bridge.bridge-nf-call-ip6tables = 1\n",
+                "net.ipv4.ip_forward = 1\n"
+            )))
+            .cmd("sudo sysctl --system")
+            .cmd("# kubelet refuses to start with swap enabled.")
+            .cmd("sudo swapoff -a && sudo sed -i '/\\sswap\\s/ s/^[^#]/#&/' /etc/fstab")
+    }
+
+    /// Adds a pkgs.k8s.io repository (`core:/stable` or `addons:/cri-o:/stable`) for the `{{k8s_version}}` parameter.
+    fn k8s_repo(pm: &dyn PackageBackend, script: Script, name: &str, path: &str) -> Script {
+        match pm.name() {
+            "apt" => script
+                .cmd("sudo install -m 0755 -d /etc/apt/keyrings")
+                .cmd(format!(
+                    "curl -fsSL https://pkgs.k8s.io/{path}:/{{{{k8s_version}}}}/deb/Release.key | sudo gpg --dearmor --yes -o /etc/apt/keyrings/{name}.gpg"
+                ))
+                .cmd(format!(
+                    "echo 'deb [signed-by=/etc/apt/keyrings/{name}.gpg] https://pkgs.k8s.io/{path}:/{{{{k8s_version}}}}/deb/ /' | sudo tee /etc/apt/sources.list.d/{name}.list > /dev/null"
+                ))
+                .cmd("sudo apt-get update"),
+            _ => script.cmd(format!(
+                "sudo tee /etc/yum.repos.d/{name}.repo > /dev/null <<'REDCENT_EOF'\n[{name}]\nname={name}\nbaseurl=https://pkgs.k8s.io/{path}:/{{{{k8s_version}}}}/rpm/\nenabled=1\ngpgcheck=1\ngpgkey=https://pkgs.k8s.io/{path}:/{{{{k8s_version}}}}/rpm/repodata/repomd.xml.key\nREDCENT_EOF"
+            )),
+        }
+        .network()
+    }
+
+    pub fn podman(pm: &dyn PackageBackend) -> Script {
+        Script::new("Install Podman with podman-compose and enable the Docker-compatible API socket")
+            .install(pm, &["podman", "podman-compose"])
+            .cmd("sudo systemctl enable --now podman.socket")
+            .verify("systemctl is-active podman.socket")
+            .idempotent()
+    }
+    pub fn docker_ce(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install Docker CE from download.docker.com and trust the docker0 bridge in firewalld");
+        let script = match pm.name() {
+            "apt" => script
+                .cmd("sudo install -m 0755 -d /etc/apt/keyrings")
+                .cmd(format!("curl -fsSL https://download.docker.com/linux/{OS_ID}/gpg | sudo gpg --dearmor --yes -o /etc/apt/keyrings/docker.gpg"))
+                .cmd(format!(
+                    "echo \"deb [signed-by=/etc/apt/keyrings/docker.gpg] https://download.docker.com/linux/{OS_ID} $(. /etc/os-release; echo \"$VERSION_CODENAME\") stable\" | sudo tee /etc/apt/sources.list.d/docker.list > /dev/null"
+                ))
+                .cmd("sudo apt-get update"),
+            _ => script.cmd(format!("sudo dnf config-manager --add-repo https://download.docker.com/linux/{OS_ID}/docker-ce.repo")),
+        };
+        let script = script
+            .install(pm, &["docker-ce", "docker-ce-cli", "containerd.io", "docker-buildx-plugin", "docker-compose-plugin"])
+            .cmd("sudo systemctl enable --now docker")
+            .verify("systemctl is-active docker")
+            .cmd("if [ -n \"${SUDO_USER:-}\" ]; then sudo usermod -aG docker \"$SUDO_USER\"; fi");
+        match pm.name() {
+            // Debian and Ubuntu default to ufw, which Docker manages through iptables itself.
+            "apt" => script,
+            _ => script
+                .cmd("sudo firewall-cmd --permanent --zone=trusted --add-interface=docker0")
+                .cmd("sudo firewall-cmd --reload"),
+        }
+        .idempotent()
+    }
+    pub fn crio(pm: &dyn PackageBackend) -> Script {
+        let script = k8s_repo(pm, Script::new("Install the CRI-O container runtime for Kubernetes"), "cri-o", "addons:/cri-o:/stable");
+        kubernetes_prereqs(script.install(pm, &["cri-o"]))
+            .cmd("sudo systemctl enable --now crio")
+            .verify("systemctl is-active crio")
+            .idempotent()
+    }
+    pub fn kubernetes_tools(pm: &dyn PackageBackend) -> Script {
+        let script = k8s_repo(pm, Script::new("Install kubeadm, kubelet and kubectl and open the control plane ports"), "kubernetes", "core:/stable");
+        let script = match pm.name() {
+            "apt" => script
+                .install(pm, &["kubelet", "kubeadm", "kubectl"])
+                .cmd("sudo apt-mark hold kubelet kubeadm kubectl"),
+            _ => script
+                .install(pm, &["kubelet", "kubeadm", "kubectl"])
+                .cmd("# Pin the packages so a routine dnf upgrade cannot move the cluster to a new minor version.")
+                .cmd("echo 'exclude=kubelet kubeadm kubectl' | sudo tee -a /etc/yum.repos.d/kubernetes.repo > /dev/null")
+                .cmd("sudo firewall-cmd --permanent --add-port=6443/tcp --add-port=2379-2380/tcp --add-port=10250/tcp --add-port=10257/tcp --add-port=10259/tcp")
+                .cmd("sudo firewall-cmd --reload"),
+        };
+        // The kernel and swap prerequisites come with the required CRI-O item.
+        script
+            .cmd("sudo systemctl enable --now kubelet")
+            .verify("systemctl is-enabled kubelet")
+            .listens("10250/tcp", "kubelet.service")
+            .cmd("# Initialize the control plane with: sudo kubeadm init --cri-socket unix:///var/run/crio/crio.sock")
+            .idempotent()
+    }
+    pub fn toolbox(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install Toolbox for containerized development environments");
+        match pm.name() {
+            "apt" => script.install(pm, &["podman-toolbox"]),
+            _ => script.install(pm, &["toolbox"]),
+        }.idempotent()
+    }
+}
+
+mod scripts_storage {
+    use redcent_tui::{write_file, PackageBackend, Script};
+
+    pub fn lvm(pm: &dyn PackageBackend) -> Script {
+        Script::new("Install the LVM2 volume management tools").install(pm, &["lvm2"]).idempotent()
+    }
+    pub fn stratis(pm: &dyn PackageBackend) -> Script {
+        Script::new("Install Stratis and start the stratisd daemon")
+            .install(pm, &["stratisd", "stratis-cli"])
+            .cmd("sudo systemctl enable --now stratisd")
+            .verify("systemctl is-active stratisd")
+            .idempotent()
+    }
+    pub fn zfs(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install OpenZFS and load the zfs kernel module");
+        let script = match pm.name() {
+            "apt" => script
+                .cmd("# Debian ships ZFS as DKMS source in the contrib component, which must be enabled in the APT sources; Ubuntu kernels include the module.")
+                .cmd("if [ \"$(. /etc/os-release; echo \"$ID\")\" = debian ]; then sudo apt-get install -y \"linux-headers-$(uname -r)\" zfs-dkms; fi")
+                .install(pm, &["zfsutils-linux"]),
+            _ => script
+                .cmd("# The module is built with DKMS (from EPEL) for every installed kernel. A kernel update can ship before OpenZFS supports it,")
+                .cmd("# so keep the previous kernel around; with Secure Boot the DKMS signing key must be enrolled with mokutil.")
+                .cmd("sudo dnf install -y \"https://zfsonlinux.org/epel/zfs-release-2-8$(rpm --eval '%{dist}').noarch.rpm\"")
+                .install(pm, &["kernel-devel", "zfs"]),
+        };
+        script
+            .cmd(write_file!("/etc/modules-load.d/redcent-zfs.conf", "zfs\n"))
+            .cmd("sudo modprobe zfs")
+            .verify("lsmod | grep -q '^zfs '")
+            .idempotent()
+    }
+    pub fn btrfs(pm: &dyn PackageBackend) -> Script {
+        match pm.name() {
+            "apt" => Script::new("Install the Btrfs filesystem tools").install(pm, &["btrfs-progs"]).idempotent(),
+            // RHEL and CentOS Stream kernels are built without Btrfs support.
+            _ => Script::unsupported(pm, "Btrfs"),
+        }
     }
-    pub fn install_cockpit_minimal() -> &'static str {
-        "sudo dnf install -y cockpit\nsudo systemctl enable --now cockpit.socket\nsudo firewall-cmd --add-service=cockpit --permanent\nsudo firewall-cmd --reload"
+    pub fn nfs_server(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install an NFS server and export a directory");
+        let script = match pm.name() {
+            "apt" => script.install(pm, &["nfs-kernel-server"]),
+            _ => script.install(pm, &["nfs-utils"]),
+        };
+        let script = script
+            .cmd("sudo mkdir -p '{{path}}' /etc/exports.d")
+            .cmd(write_file!("/etc/exports.d/redcent.exports", "{{path}} {{clients}}({{options}})\n"))
+            .cmd("sudo systemctl enable --now nfs-server")
+            .cmd("sudo exportfs -ra")
+            .verify("systemctl is-active nfs-server")
+            .listens("2049/tcp", "nfs-server.service")
+            .verify("sudo exportfs -s | awk '{ print $1 }' | grep -qxF '{{path}}'");
+        match pm.name() {
+            "apt" => script,
+            _ => script
+                .cmd("sudo firewall-cmd --permanent --add-service=nfs --add-service=rpc-bind --add-service=mountd")
+                .cmd("sudo firewall-cmd --reload"),
+        }
+        .idempotent()
     }
-    pub fn install_cockpit_full() -> &'static str {
-        "sudo dnf install -y cockpit cockpit-files cockpit-bridge cockpit-system cockpit-ws-selinux cockpit-packagekit cockpit-ws cockpit-storaged subscription-manager-cockpit cockpit-machines cockpit-podman\nsudo systemctl enable --now cockpit.socket\nsudo firewall-cmd --add-service=cockpit --permanent\nsudo firewall-cmd --reload"
+    pub fn iscsi_initiator(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install the iSCSI initiator and start iscsid");
+        match pm.name() {
+            "apt" => script.install(pm, &["open-iscsi"]),
+            _ => script.install(pm, &["iscsi-initiator-utils"]),
+        }
+            .cmd("sudo systemctl enable --now iscsid")
+            .verify("systemctl is-active iscsid")
+            .cmd("# Discover and log in to targets with: sudo iscsiadm -m discovery -t st -p <portal> && sudo iscsiadm -m node --login")
+            .idempotent()
+    }
+    pub fn cockpit_storaged(pm: &dyn PackageBackend) -> Script {
+        Script::new("Install the Cockpit storage plugin (udisks2)")
+            .install(pm, &["cockpit-storaged"])
+            .cmd("sudo systemctl enable --now cockpit.socket")
+            .verify("systemctl is-active cockpit.socket")
+            .listens("9090/tcp", "cockpit.socket")
+            .idempotent()
     }
 }
+
+mod scripts_entropy {
+    use redcent_tui::{PackageBackend, Script};
+
+    pub fn rngd(pm: &dyn PackageBackend) -> Script {
+        let package = match pm.name() {
+            "apt" => "rng-tools5",
+            _ => "rng-tools",
+        };
+        Script::new("Install rngd to feed the kernel random pool from hardware sources")
+            .install(pm, &[package])
+            .cmd("sudo systemctl enable --now rngd")
+            .verify("systemctl is-active rngd")
+            .idempotent()
+    }
+    pub fn jitterentropy(pm: &dyn PackageBackend) -> Script {
+        match pm.name() {
+            "apt" => Script::new("Install jitterentropy-rngd to feed the kernel random pool from CPU timing jitter")
+                .install(pm, &["jitterentropy-rngd"])
+                .cmd("sudo systemctl enable --now jitterentropy")
+                .verify("systemctl is-active jitterentropy")
+                .idempotent(),
+            // rngd's built-in jitter source covers it.
+            _ => Script::unsupported(pm, "The standalone jitterentropy daemon"),
+        }
+    }
+    pub fn health_check(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Report entropy sources and run the FIPS 140-2 tests on /dev/random")
+            .cmd("echo \"Entropy estimate: $(cat /proc/sys/kernel/random/entropy_avail) bits\"")
+            .cmd(concat!(
+                "if [ -r /sys/class/misc/hw_random/rng_current ]; then\n",
+                "    echo \"Hardware RNG: $(cat /sys/class/misc/hw_random/rng_current)\"\n",
+                "else\n",
+                "    echo 'Hardware RNG: none (add a virtio-rng device to VMs)'\n",
+                "fi"))
+            .cmd("if grep -qw rdrand /proc/cpuinfo; then echo 'CPU RDRAND: yes'; else echo 'CPU RDRAND: no'; fi")
+            .cmd(concat!(
+                "# rngtest exits non-zero on any failed block; about 1 in 1000 fails by chance.\n",
+                "failed=$(sudo head -c 2500032 /dev/random | rngtest -c 1000 2>&1 | sed -n 's/^rngtest: FIPS 140-2 failures: //p')\n",
+                "echo \"FIPS 140-2 failures: ${failed:-unknown} of 1000 blocks\"\n",
+                "[ -n \"$failed\" ] && [ \"$failed\" -le 3 ]"))
+            .verify("[ \"$(cat /proc/sys/kernel/random/entropy_avail)\" -ge 256 ]")
+            .idempotent()
+    }
+}
+
+mod scripts_luks {
+    use redcent_tui::{PackageBackend, Script};
+
+    /// Enrolls a `token` (`tpm2`, `fido2`) for `{{luks_device}}` with `systemd-cryptenroll <enroll>`
+    /// after the shell `elif` branches in `precheck` pass, adds `<token>-device=auto` to the volume's
+    /// crypttab entry and rebuilds the initramfs that reads it.
+    fn enroll(pm: &dyn PackageBackend, description: &str, packages: &[&str], token: &str, precheck: &str, enroll: &str) -> Script {
+        let initramfs = match pm.name() {
+            "apt" => "sudo update-initramfs -u",
+            _ => "sudo dracut -f --regenerate-all",
+        };
+        Script::new(description)
+            .install(pm, packages)
+            .cmd(format!(concat!(
+                "luks_dev='{{{{luks_device}}}}'\n",
+                "if ! sudo cryptsetup isLuks \"$luks_dev\"; then\n",
+                "    echo \"$luks_dev is not a LUKS volume\" >&2\n",
+                "    false\n",
+                "{precheck}",
+                "elif sudo systemd-cryptenroll \"$luks_dev\" | grep -qw {token}; then\n",
+                "    echo \"$luks_dev already has a {token} token\"\n",
+                "else\n",
+                "    # Asks for an existing passphrase of the volume.\n",
+                "    sudo systemd-cryptenroll {enroll} \"$luks_dev\"\n",
+                "fi"), precheck = precheck, token = token, enroll = enroll))
+            .cmd(format!(concat!(
+                "luks_dev='{{{{luks_device}}}}'\n",
+                "luks_uuid=$(sudo cryptsetup luksUUID \"$luks_dev\")\n",
+                "if ! sudo systemd-cryptenroll \"$luks_dev\" | grep -qw {token}; then\n",
+                "    echo \"No {token} token enrolled for $luks_dev; /etc/crypttab left unchanged\" >&2\n",
+                "    false\n",
+                "elif sudo awk -v uuid=\"UUID=$luks_uuid\" -v dev=\"$luks_dev\" '!/^[[:space:]]*#/ && ($2 == uuid || $2 == dev) {{ found = 1 }} END {{ exit !found }}' /etc/crypttab 2>/dev/null; then\n",
+                "    crypttab=$(sudo awk -v uuid=\"UUID=$luks_uuid\" -v dev=\"$luks_dev\" -v opt={token}-device=auto 'BEGIN {{ OFS = \"\\t\" }}\n",
+                "        !/^[[:space:]]*#/ && ($2 == uuid || $2 == dev) && index($4, opt) == 0 {{ if (NF < 3) $3 = \"none\"; $4 = (NF < 4 || $4 == \"\") ? opt : $4 \",\" opt }}\n",
+                "        {{ print }}' /etc/crypttab)\n",
+                "    # tee keeps the file's permissions and SELinux label.\n",
+                "    printf '%s\\n' \"$crypttab\" | sudo tee /etc/crypttab > /dev/null\n",
+                "    {initramfs}\n",
+                "else\n",
+                "    echo \"No /etc/crypttab entry for $luks_dev; add {token}-device=auto to its options to unlock it at boot\"\n",
+                "fi"), token = token, initramfs = initramfs))
+            .verify(format!("sudo systemd-cryptenroll '{{{{luks_device}}}}' | grep -qw {}", token))
+            .verify(format!("grep -q '{}-device=auto' /etc/crypttab", token))
+            .reboot()
+            .idempotent()
+    }
+
+    pub fn enroll_tpm2(pm: &dyn PackageBackend) -> Script {
+        let precheck = "elif [ ! -e /dev/tpmrm0 ]; then\n    echo 'No TPM2 device found (/dev/tpmrm0)' >&2\n    false\n";
+        enroll(pm, "Enroll the TPM2 chip to unlock a LUKS volume at boot", &["tpm2-tools"], "tpm2", precheck,
+            "--tpm2-device=auto --tpm2-pcrs={{tpm2_pcrs}}")
+    }
+
+    pub fn enroll_fido2(pm: &dyn PackageBackend) -> Script {
+        let packages: &[&str] = match pm.name() {
+            "apt" => &["libfido2-1"],
+            _ => &["libfido2"],
+        };
+        enroll(pm, "Enroll a FIDO2 security key to unlock a LUKS volume at boot", packages, "fido2", "", "--fido2-device=auto")
+    }
+}
+
 mod scripts_net {
-    pub fn install_vpn_ovpn() -> &'static str {
-        "sudo dnf install -y NetworkManager-openvpn NetworkManager-openvpn-gnome"
+    use redcent_tui::{write_file, PackageBackend, Script};
+
+    /// Installs a NetworkManager VPN plugin; Debian and Ubuntu name the packages `network-manager-<plugin>`.
+    fn nm_plugin(pm: &dyn PackageBackend, plugin: &str) -> Script {
+        let base = match pm.name() {
+            "apt" => format!("network-manager-{}", plugin),
+            _ => format!("NetworkManager-{}", plugin),
+        };
+        Script::new(&format!("Install the NetworkManager {} VPN plugin", plugin))
+            .install(pm, &[&base, &format!("{}-gnome", base)])
+            .idempotent()
+    }
+
+    pub fn install_vpn_ovpn(pm: &dyn PackageBackend) -> Script {
+        nm_plugin(pm, "openvpn")
+    }
+    pub fn install_vpn_l2tp(pm: &dyn PackageBackend) -> Script {
+        nm_plugin(pm, "l2tp")
+    }
+    pub fn install_vpn_sswan(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install strongSwan with its NetworkManager plugin");
+        match pm.name() {
+            "apt" => script.install(pm, &["strongswan", "network-manager-strongswan"]),
+            _ => script.install(pm, &["strongswan", "strongswan-charon-nm"]),
+        }.idempotent()
+    }
+    pub fn install_vpn_lswan(pm: &dyn PackageBackend) -> Script {
+        match pm.name() {
+            "apt" => Script::unsupported(pm, "The Libreswan NetworkManager plugin"),
+            _ => nm_plugin(pm, "libreswan"),
+        }
+    }
+    pub fn install_vpn_pptp(pm: &dyn PackageBackend) -> Script {
+        nm_plugin(pm, "pptp")
+    }
+    pub fn install_vpn_oconn(pm: &dyn PackageBackend) -> Script {
+        nm_plugin(pm, "openconnect")
+    }
+
+    /// Sets the MTU of the connection on `{{iface}}` and pings `{{ping_target}}`, when given, with
+    /// don't-fragment frames of that size.
+    pub fn set_mtu(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Set the MTU of a NetworkManager connection and check full-size frames")
+            .cmd(concat!(
+                "iface='{{iface}}'\n",
+                "con=$(nmcli -g GENERAL.CONNECTION device show \"$iface\" 2>/dev/null)\n",
+                "if [ -z \"$con\" ]; then\n",
+                "    echo \"$iface has no active NetworkManager connection\" >&2\n",
+                "    false\n",
+                "else\n",
+                "    sudo nmcli connection modify \"$con\" 802-3-ethernet.mtu {{mtu}}\n",
+                "    # reapply changes the MTU in place; older NetworkManager versions need the connection brought up again.\n",
+                "    sudo nmcli device reapply \"$iface\" || sudo nmcli connection up \"$con\"\n",
+                "fi"))
+            .cmd(concat!(
+                "iface='{{iface}}'\n",
+                "target='{{ping_target}}'\n",
+                "if [ -z \"$target\" ]; then\n",
+                "    echo \"No peer given; check with: ping -M do -s $(({{mtu}} - 28)) -I $iface <peer>\"\n",
+                "# The IPv4 and ICMP headers take 28 bytes of the frame.\n",
+                "elif ! ping -M do -s $(({{mtu}} - 28)) -c 3 -W 2 -I \"$iface\" \"$target\"; then\n",
+                "    echo \"Frames of {{mtu}} bytes do not reach $target; the switch ports and the peer need the same MTU\" >&2\n",
+                "    false\n",
+                "fi"))
+            .verify("ip -o link show '{{iface}}' | grep -qw 'mtu {{mtu}}'")
+            .idempotent()
+    }
+
+    /// Ring buffers of `{{iface}}` at their hardware maximum (also at boot), BBR with fq, larger
+    /// socket buffers and irqbalance.
+    pub fn performance_tuning(pm: &dyn PackageBackend) -> Script {
+        Script::new("Tune the network stack: NIC ring buffers, BBR congestion control and irqbalance")
+            .install(pm, &["ethtool", "irqbalance"])
+            .cmd(write_file!("/usr/local/sbin/redcent-nic-rings", concat!(
+                "#!/bin/sh\n",
+                "# Raises the RX/TX ring buffers of a NIC to its hardware maximum; installed by redcent-tui\n",
+                "nic=\"$1\"\n",
+                "if ! rings=$(ethtool -g \"$nic\" 2>/dev/null); then\n",
+                "    echo \"$nic does not report ring buffer sizes; leaving them unchanged\"\n",
+                "    exit 0\n",
+                "fi\n",
+                "# ethtool lists the pre-set maximums first and the current sizes second.\n",
+                "set --\n",
+                "for ring in rx tx; do\n",
+                "    sizes=$(printf '%s\\n' \"$rings\" | awk -v ring=\"$(echo $ring | tr a-z A-Z):\" '$1 == ring { print $2 }')\n",
+                "    max=$(echo \"$sizes\" | sed -n 1p)\n",
+                "    current=$(echo \"$sizes\" | sed -n 2p)\n",
+                "    case \"$max\" in ''|0|n/a) continue ;; esac\n",
+                "    echo \"$nic $ring ring: $current of at most $max\"\n",
+                "    [ \"$max\" = \"$current\" ] || set -- \"$@\" \"$ring\" \"$max\"\n",
+                "done\n",
+                "if [ $# -eq 0 ]; then\n",
+                "    echo \"$nic ring buffers are at their maximum\"\n",
+                "else\n",
+                "    ethtool -G \"$nic\" \"$@\"\n",
+                "fi\n"
+            )))
+            .cmd("sudo chmod 755 /usr/local/sbin/redcent-nic-rings")
+            .cmd(write_file!("/etc/systemd/system/redcent-nic-rings@.service", concat!(
+                "[Unit]\n",
+                "Description=Raise the ring buffers of %I to their maximum\n",
+                "BindsTo=sys-subsystem-net-devices-%i.device\n",
+                "After=sys-subsystem-net-devices-%i.device\n",
+                "\n",
+                "[Service]\n",
+                "Type=oneshot\n",
+                "RemainAfterExit=yes\n",
+                "ExecStart=/usr/local/sbin/redcent-nic-rings %I\n",
+                "\n",
+                "[Install]\n",
+                "WantedBy=multi-user.target\n"
+            )))
+            .cmd("sudo systemctl daemon-reload")
+            // The instance is the escaped name, as in the device unit it binds to (`-` becomes `\x2d`).
+            .cmd(concat!(
+                "unit=\"redcent-nic-rings@$(systemd-escape '{{iface}}').service\"\n",
+                "sudo systemctl enable \"$unit\"\n",
+                "sudo systemctl restart \"$unit\""))
+            .cmd(concat!(
+                "if ! sudo modprobe tcp_bbr; then\n",
+                "    echo 'This kernel has no tcp_bbr module; BBR congestion control is not available' >&2\n",
+                "    false\n",
+                "fi"))
+            .cmd("echo tcp_bbr | sudo tee /etc/modules-load.d/redcent-bbr.conf > /dev/null")
+            .cmd(write_file!("/etc/sysctl.d/90-redcent-net-tuning.conf", concat!(
+                "# Server network tuning installed by redcent-tui\n",
+                "net.core.default_qdisc = fq\n",
+                "net.ipv4.tcp_congestion_control = bbr\n",
+                "net.core.rmem_max = 16777216\n",
+                "net.core.wmem_max = 16777216\n",
+                "net.ipv4.tcp_rmem = 4096 131072 16777216\n",
+                "net.ipv4.tcp_wmem = 4096 16384 16777216\n"
+            )))
+            .cmd("sudo sysctl --system > /dev/null")
+            .cmd("sudo systemctl enable --now irqbalance")
+            .verify("systemctl is-active \"redcent-nic-rings@$(systemd-escape '{{iface}}').service\"")
+            .verify("sysctl -n net.ipv4.tcp_congestion_control | grep -qx bbr")
+            .verify("systemctl is-active irqbalance")
+            .idempotent()
+    }
+
+}
+
+mod scripts_hardening {
+    use redcent_tui::{write_file, PackageBackend, Script};
+
+    pub fn disable_root_ssh(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Forbid root logins over SSH")
+            .cmd(write_file!("/etc/ssh/sshd_config.d/10-redcent-no-root.conf", "PermitRootLogin no\n"))
+            .cmd("sudo sshd -t && sudo systemctl reload sshd")
+            .idempotent()
+    }
+    pub fn firewalld_defaults(pm: &dyn PackageBackend) -> Script {
+        Script::new("Enable firewalld with the public zone allowing only SSH")
+            .install(pm, &["firewalld"])
+            .cmd("sudo systemctl enable --now firewalld")
+            .verify("systemctl is-active firewalld")
+            .cmd("sudo firewall-cmd --set-default-zone=public")
+            .cmd("sudo firewall-cmd --permanent --zone=public --add-service=ssh")
+            .cmd("sudo firewall-cmd --reload")
+            .idempotent()
+    }
+    pub fn auditd_rules(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install auditd with baseline identity, privilege and login rules");
+        match pm.name() {
+            "apt" => script.install(pm, &["auditd"]),
+            _ => script.install(pm, &["audit"]),
+        }
+            .cmd(write_file!("/etc/audit/rules.d/50-redcent.rules", concat!(
+                "## Baseline rules installed by redcent-tui\n",
+                "-w /etc/passwd -p wa -k identity\n",
+                "-w /etc/group -p wa -k identity\n",
+                "-w /etc/shadow -p wa -k identity\n",
+                "-w /etc/sudoers -p wa -k privilege\n",
+                "-w /etc/sudoers.d/ -p wa -k privilege\n",
+                "-w /etc/ssh/sshd_config -p wa -k sshd\n",
+                "-w /var/log/lastlog -p wa -k logins\n",
+                "-a always,exit -F arch=b64 -S adjtimex,settimeofday,clock_settime -k time-change\n",
+                "-a always,exit -F arch=b64 -S execve -F euid=0 -F auid>=1000 -F auid!=unset -k root-commands\n"
+            )))
+            .cmd("sudo augenrules --load")
+            .cmd("sudo systemctl enable --now auditd")
+            .verify("systemctl is-active auditd")
+            .idempotent()
+    }
+    pub fn fapolicyd(pm: &dyn PackageBackend) -> Script {
+        if pm.name() == "apt" {
+            return Script::unsupported(pm, "fapolicyd");
+        }
+        Script::new("Enable the fapolicyd application allowlisting daemon")
+            .cmd("# fapolicyd blocks untrusted executables; review /var/log/messages for denials after enabling.")
+            .install(pm, &["fapolicyd"])
+            .cmd("sudo systemctl enable --now fapolicyd")
+            .verify("systemctl is-active fapolicyd")
+            .idempotent()
+    }
+    pub fn aide(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Initialize the AIDE file integrity database with a nightly check").install(pm, &["aide"]);
+        match pm.name() {
+            // Debian's aide-common ships aideinit and its own daily check, so only the database is initialized.
+            "apt" => script.cmd("sudo aideinit -y -f"),
+            _ => script
+                .cmd("sudo aide --init")
+                .cmd("sudo mv /var/lib/aide/aide.db.new.gz /var/lib/aide/aide.db.gz")
+                .cmd(write_file!("/etc/cron.d/redcent-aide", "0 4 * * * root /usr/sbin/aide --check\n")),
+        }
+    }
+}
+
+mod scripts_bootloader {
+    use redcent_tui::{PackageBackend, Script};
+
+    /// Paths and tools of GRUB 2, which Debian and Ubuntu install without the `2` suffix.
+    struct Grub {
+        mkpasswd: &'static str,
+        config: &'static str,
+        mkconfig: &'static str,
+    }
+
+    fn grub(pm: &dyn PackageBackend) -> Grub {
+        match pm.name() {
+            "apt" => Grub { mkpasswd: "grub-mkpasswd-pbkdf2", config: "/boot/grub/grub.cfg", mkconfig: "sudo update-grub" },
+            _ => Grub { mkpasswd: "grub2-mkpasswd-pbkdf2", config: "/boot/grub2/grub.cfg", mkconfig: "sudo grub2-mkconfig -o /boot/grub2/grub.cfg" },
+        }
+    }
+
+    pub fn grub_password(pm: &dyn PackageBackend) -> Script {
+        let grub = grub(pm);
+        // RHEL's grub.cfg reads GRUB2_PASSWORD from user.cfg and leaves its entries unrestricted, as
+        // grub2-setpassword does; Debian needs the superuser in grub.d and its entries unrestricted.
+        let store = match pm.name() {
+            "apt" => concat!(
+                "{\n",
+                "        printf '#!/bin/sh\\n# GRUB superuser installed by redcent-tui\\ncat <<EOF\\n'\n",
+                "        printf 'set superusers=\"root\"\\npassword_pbkdf2 root %s\\nEOF\\n' \"$grub_hash\"\n",
+                "    } | sudo tee /etc/grub.d/01_redcent-password > /dev/null\n",
+                "    sudo chmod 0700 /etc/grub.d/01_redcent-password\n",
+                "    # Only editing and the GRUB shell need the password; 10_linux is reset by grub package updates.\n",
+                "    sudo sed -i '/^CLASS=/{/--unrestricted/!s/\"$/ --unrestricted\"/}' /etc/grub.d/10_linux"
+            ),
+            _ => concat!(
+                "printf 'GRUB2_PASSWORD=%s\\n' \"$grub_hash\" | sudo tee /boot/grub2/user.cfg > /dev/null\n",
+                "    sudo chmod 0600 /boot/grub2/user.cfg"
+            ),
+        };
+        Script::new("Require a GRUB superuser password to edit boot entries or use the GRUB shell")
+            .cmd(concat!(
+                "echo 'WARNING: a forgotten GRUB password can only be reset from rescue media.' >&2\n",
+                "echo 'Store the password you enter now before rebooting.' >&2"
+            ))
+            .cmd(format!(
+                concat!(
+                    "# The password is read from the terminal and only its PBKDF2 hash is kept.\n",
+                    "grub_hash=''\n",
+                    "if {{ read -rsp 'New GRUB superuser password: ' grub_pw; echo; read -rsp 'Repeat it: ' grub_pw2; echo; }} < /dev/tty \\\n",
+                    "    && [ -n \"$grub_pw\" ] && [ \"$grub_pw\" = \"$grub_pw2\" ]; then\n",
+                    "    grub_hash=$(printf '%s\\n%s\\n' \"$grub_pw\" \"$grub_pw\" | {mkpasswd} | sed -n 's/^.* is //p')\n",
+                    "fi\n",
+                    "unset grub_pw grub_pw2\n",
+                    "if [ -z \"$grub_hash\" ]; then\n",
+                    "    echo 'GRUB password not set: it was empty, did not match, or no terminal was available.' >&2\n",
+                    "    false\n",
+                    "else\n",
+                    "    {store}\n",
+                    "    {mkconfig}\n",
+                    "fi"
+                ),
+                mkpasswd = grub.mkpasswd, store = store, mkconfig = grub.mkconfig,
+            ))
+            .verify(match pm.name() {
+                "apt" => "sudo grep -q '^set superusers=' /boot/grub/grub.cfg",
+                _ => "sudo grep -q '^GRUB2_PASSWORD=grub.pbkdf2' /boot/grub2/user.cfg",
+            })
+    }
+
+    pub fn grub_permissions(pm: &dyn PackageBackend) -> Script {
+        let grub = grub(pm);
+        let directory = grub.config.rsplit_once('/').map_or("/boot", |(directory, _)| directory);
+        Script::new("Make the GRUB configuration readable by root only")
+            .cmd(format!("sudo chown root:root {}", grub.config))
+            .cmd(format!("sudo chmod 0600 {}", grub.config))
+            .cmd(format!("[ ! -f {0}/user.cfg ] || sudo chmod 0600 {0}/user.cfg", directory))
+            .verify(format!("test \"$(sudo stat -c '%a %U' {})\" = '600 root'", grub.config))
+            .idempotent()
+    }
+}
+
+mod scripts_security {
+    use redcent_tui::{write_file, PackageBackend, Script};
+
+    pub fn selinux_tools(pm: &dyn PackageBackend) -> Script {
+        match pm.name() {
+            "apt" => Script::new("Install the SELinux policy and management tools")
+                .cmd("# SELinux is not active by default on Debian and Ubuntu; run 'sudo selinux-activate' and reboot to enable it.")
+                .install(pm, &["selinux-basics", "selinux-policy-default", "policycoreutils-python-utils", "setools"])
+                .idempotent(),
+            _ => Script::new("Install setroubleshoot and the semanage/SETools utilities")
+                .install(pm, &["setroubleshoot-server", "policycoreutils-python-utils", "setools-console"])
+                .cmd("# Denials are explained in the journal; list them with: sudo sealert -a /var/log/audit/audit.log")
+                .verify("getenforce | grep -qx Enforcing")
+                .idempotent(),
+        }
+    }
+    pub fn fail2ban(pm: &dyn PackageBackend) -> Script {
+        Script::new("Install fail2ban with an sshd jail reading the systemd journal")
+            .install(pm, &["fail2ban"])
+            .cmd(write_file!("/etc/fail2ban/jail.d/50-redcent-sshd.local", concat!(
+                "[sshd]\n",
+                "enabled = true\n",
+                "backend = systemd\n",
+                "maxretry = {{maxretry}}\n",
+                "bantime = {{bantime}}\n"
+            )))
+            .cmd("sudo systemctl enable --now fail2ban")
+            .cmd("sudo systemctl restart fail2ban")
+            .verify("systemctl is-active fail2ban")
+            .verify("sudo fail2ban-client status sshd")
+            .idempotent()
+    }
+    pub fn usbguard(pm: &dyn PackageBackend) -> Script {
+        Script::new("Enable USBGuard, allowing only the USB devices attached now")
+            .cmd("# Devices plugged in later, including replacement keyboards, are blocked until allowed with 'usbguard allow-device'.")
+            .install(pm, &["usbguard"])
+            .cmd("sudo sh -c 'usbguard generate-policy > /etc/usbguard/rules.conf'")
+            .cmd("sudo chmod 0600 /etc/usbguard/rules.conf")
+            .cmd("sudo systemctl enable --now usbguard")
+            .verify("systemctl is-active usbguard")
+            .idempotent()
+    }
+
+    /// Moves `{{iface}}` into a firewalld zone; `note` explains what the zone lets through. The
+    /// name is spliced in unquoted, relying on the interface name check of its field.
+    fn zone_preset(pm: &dyn PackageBackend, zone: &str, note: &str) -> Script {
+        Script::new(&format!("Assign an interface to the firewalld {} zone", zone))
+            .cmd(format!("# {}", note))
+            .install(pm, &["firewalld"])
+            .cmd("sudo systemctl enable --now firewalld")
+            .cmd(format!("sudo firewall-cmd --permanent --zone={} --change-interface={{{{iface}}}}", zone))
+            .cmd("sudo firewall-cmd --reload")
+            .verify(format!("firewall-cmd --get-zone-of-interface={{{{iface}}}} | grep -qx {}", zone))
+            .idempotent()
+    }
+    pub fn zone_public(pm: &dyn PackageBackend) -> Script {
+        zone_preset(pm, "public", "public: untrusted network; only ssh, dhcpv6-client and cockpit (where installed) are allowed in.")
+    }
+    pub fn zone_dmz(pm: &dyn PackageBackend) -> Script {
+        zone_preset(pm, "dmz", "dmz: publicly reachable hosts with limited access to the internal network; only ssh is allowed in.")
+    }
+    pub fn zone_trusted(pm: &dyn PackageBackend) -> Script {
+        zone_preset(pm, "trusted", "trusted: all incoming traffic on this interface is accepted.")
+    }
+}
+
+mod scripts_oscap {
+    use redcent_tui::{PackageBackend, Script};
+
+    // Resolves the SCAP Security Guide datastream for the running distro (ssg-rhel10-ds.xml, ssg-cs10-ds.xml, ...).
+    // oscap exits with 2 when some rules still fail after remediation, so that status does not abort the script.
+    macro_rules! oscap_remediate {
+        ($profile:expr) => {
+            concat!(
+                "ds=/usr/share/xml/scap/ssg/content/ssg-$(. /etc/os-release; echo \"${ID/centos/cs}${VERSION_ID%%.*}\")-ds.xml\n",
+                "sudo oscap xccdf eval --remediate --profile xccdf_org.ssgproject.content_profile_", $profile,
+                " --results /root/oscap-", $profile, "-results.xml --report /root/oscap-", $profile, "-report.html \"$ds\" || [ $? -eq 2 ]"
+            )
+        };
+    }
+
+    fn remediate(pm: &dyn PackageBackend, profile: &str, script: &str) -> Script {
+        match pm.name() {
+            "apt" => Script::unsupported(pm, "SCAP Security Guide remediation"),
+            _ => Script::new(&format!("Remediate the host against the {} profile and write a report to /root", profile))
+                .cmd(script)
+                .reboot()
+                .idempotent(),
+        }
+    }
+
+    pub fn install_tools(pm: &dyn PackageBackend) -> Script {
+        match pm.name() {
+            "apt" => Script::unsupported(pm, "OpenSCAP with the SCAP Security Guide"),
+            _ => Script::new("Install the OpenSCAP scanner and SCAP Security Guide content")
+                .install(pm, &["openscap-scanner", "scap-security-guide"])
+                .idempotent(),
+        }
+    }
+    pub fn cis_server_l1(pm: &dyn PackageBackend) -> Script {
+        remediate(pm, "CIS Server Level 1", oscap_remediate!("cis_server_l1"))
+    }
+    pub fn cis_server_l2(pm: &dyn PackageBackend) -> Script {
+        remediate(pm, "CIS Server Level 2", oscap_remediate!("cis"))
+    }
+    pub fn cis_workstation_l1(pm: &dyn PackageBackend) -> Script {
+        remediate(pm, "CIS Workstation Level 1", oscap_remediate!("cis_workstation_l1"))
+    }
+    pub fn stig(pm: &dyn PackageBackend) -> Script {
+        remediate(pm, "DISA STIG", oscap_remediate!("stig"))
+    }
+    pub fn stig_gui(pm: &dyn PackageBackend) -> Script {
+        remediate(pm, "DISA STIG with GUI", oscap_remediate!("stig_gui"))
+    }
+}
+
+mod scripts_timeout {
+    use redcent_tui::{dconf::DconfPolicy, write_file, PackageBackend, Script};
+
+    pub fn shell_tmout(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Log out idle interactive shells")
+            .cmd(write_file!("/etc/profile.d/redcent-tmout.sh", "# Log out idle interactive shells; installed by redcent-tui\nTMOUT={{seconds}}\nreadonly TMOUT\nexport TMOUT\n"))
+            .idempotent()
+    }
+    pub fn ssh_client_alive(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Disconnect unresponsive SSH clients")
+            .cmd(write_file!("/etc/ssh/sshd_config.d/10-redcent-client-alive.conf", "ClientAliveInterval {{interval}}\nClientAliveCountMax {{alive_count}}\n"))
+            .cmd("sudo sshd -t && sudo systemctl reload sshd")
+            .idempotent()
+    }
+    pub fn gnome_screen_lock(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Lock idle GNOME sessions")
+            .cmd(DconfPolicy::new("00-redcent-screensaver")
+                .lock("org/gnome/desktop/session", "idle-delay", "uint32 {{idle}}")
+                .lock("org/gnome/desktop/screensaver", "lock-enabled", "true")
+                .lock("org/gnome/desktop/screensaver", "lock-delay", "uint32 {{lock}}")
+                .render())
+            .idempotent()
+    }
+}
+
+mod scripts_banner {
+    use redcent_tui::{write_file, PackageBackend, Script};
+
+    macro_rules! banner_template {
+        () => {
+            concat!(
+                "**WARNING** This system is the property of {{org}} and is for authorized use only.\n",
+                "Activity may be monitored, recorded and reported to law enforcement.\n",
+                "Unauthorized or improper use may result in disciplinary action and civil or criminal penalties.\n"
+            )
+        };
+    }
+    // The organization name reaches the MOTD script through a quoted heredoc of its own, so
+    // quotes and `$` in it stay literal; the banner is one already.
+    macro_rules! motd_script_template {
+        () => {
+            concat!(
+                "#!/bin/bash\n",
+                "# Regenerates the dynamic MOTD; installed by redcent-tui\n",
+                "updates=$(dnf -q check-update 2>/dev/null | grep -c '^[[:alnum:]]')\n",
+                "security=$(dnf -q updateinfo list --security 2>/dev/null | wc -l)\n",
+                "if needs-restarting -r >/dev/null 2>&1; then reboot='no'; else reboot='YES'; fi\n",
+                "org=$(cat <<'REDCENT_ORG'\n",
+                "{{org}}\n",
+                "REDCENT_ORG\n",
+                ")\n",
+                "{\n",
+                "    printf '%s - managed host\\n' \"$org\"\n",
+                "    echo \"Pending updates: ${updates} (${security} security)\"\n",
+                "    echo \"Reboot required: ${reboot}\"\n",
+                "} > /etc/motd.d/50-redcent-status\n"
+            )
+        };
+    }
+
+    pub fn login_banner(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Show a legal warning banner at console and SSH logins")
+            .cmd(write_file!("/etc/issue", banner_template!()))
+            .cmd("sudo cp /etc/issue /etc/issue.net")
+            .cmd(write_file!("/etc/ssh/sshd_config.d/50-redcent-banner.conf", "Banner /etc/issue.net\n"))
+            .cmd("sudo sshd -t && sudo systemctl reload sshd")
+            .idempotent()
+    }
+    pub fn dynamic_motd(pm: &dyn PackageBackend) -> Script {
+        // The generated MOTD script queries dnf for pending updates.
+        if pm.name() == "apt" {
+            return Script::unsupported(pm, "The dynamic MOTD");
+        }
+        Script::new("Show pending updates and reboot status in an hourly refreshed MOTD")
+            .install(pm, &["dnf-utils"])
+            .cmd("sudo mkdir -p /etc/motd.d")
+            .cmd(write_file!("/usr/local/sbin/redcent-motd.sh", motd_script_template!()))
+            .cmd("sudo chmod 0755 /usr/local/sbin/redcent-motd.sh")
+            .cmd(write_file!("/etc/systemd/system/redcent-motd.service", "[Unit]\nDescription=Refresh redcent-tui dynamic MOTD\n\n[Service]\nType=oneshot\nExecStart=/usr/local/sbin/redcent-motd.sh\n"))
+            .cmd(write_file!("/etc/systemd/system/redcent-motd.timer", "[Unit]\nDescription=Refresh redcent-tui dynamic MOTD hourly\n\n[Timer]\nOnBootSec=2min\nOnUnitActiveSec=1h\n\n[Install]\nWantedBy=timers.target\n"))
+            .cmd("sudo systemctl daemon-reload")
+            .cmd("sudo systemctl enable --now redcent-motd.timer")
+            .verify("systemctl is-active redcent-motd.timer")
+            .idempotent()
+    }
+}
+
+mod scripts_libvirt {
+    use redcent_tui::{PackageBackend, Script};
+
+    // Defines, autostarts and starts a libvirt network from inline XML.
+    fn define_network(script: Script, xml: &str) -> Script {
+        script
+            .cmd(format!("sudo virsh net-define /dev/stdin <<'REDCENT_EOF'\n{}REDCENT_EOF", xml))
+            .cmd("sudo virsh net-autostart {{name}}")
+            .cmd("sudo virsh net-start {{name}}")
+    }
+
+    pub fn nat_network(_pm: &dyn PackageBackend) -> Script {
+        define_network(Script::new("Define a NAT libvirt network with DHCP"), concat!(
+            "<network>\n",
+            "  <name>{{name}}</name>\n",
+            "  <forward mode='nat'/>\n",
+            "  <ip address='{{gateway}}' netmask='{{netmask}}'>\n",
+            "    <dhcp><range start='{{dhcp_start}}' end='{{dhcp_end}}'/></dhcp>\n",
+            "  </ip>\n",
+            "</network>\n"
+        ))
+    }
+    pub fn host_bridge(_pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Bridge a host interface and expose it as a libvirt network")
+            .cmd("# Moves the host's connection on {{iface}} onto bridge {{bridge}}; run from a console, not over SSH on {{iface}}.")
+            .cmd("sudo nmcli connection add type bridge ifname {{bridge}} con-name {{bridge}} bridge.stp no")
+            .cmd("sudo nmcli connection add type bridge-slave ifname {{iface}} master {{bridge}} con-name {{bridge}}-port-{{iface}}")
+            .cmd("sudo nmcli connection up {{bridge}}");
+        define_network(script, "<network>\n  <name>{{name}}</name>\n  <forward mode='bridge'/>\n  <bridge name='{{bridge}}'/>\n</network>\n")
+    }
+    pub fn macvtap_network(_pm: &dyn PackageBackend) -> Script {
+        define_network(
+            Script::new("Define a macvtap libvirt network on a host interface"),
+            "<network>\n  <name>{{name}}</name>\n  <forward mode='bridge'>\n    <interface dev='{{iface}}'/>\n  </forward>\n</network>\n",
+        )
+    }
+}
+
+mod scripts_config {
+    use redcent_tui::{PackageBackend, Script};
+
+    /// Validates the sudoers lines printed by `content` in a temporary file and installs them as
+    /// `/etc/sudoers.d/<file>` only if `visudo` accepts them.
+    pub fn sudoers_drop_in(file: &str, content: &str) -> String {
+        format!(
+            concat!(
+                "tmp=$(mktemp)\n",
+                "{} > \"$tmp\"\n",
+                "sudo visudo -cqf \"$tmp\" && sudo install -m 0440 -o root -g root \"$tmp\" /etc/sudoers.d/{}\n",
+                "status=$?\n",
+                "rm -f \"$tmp\"\n",
+                "[ \"$status\" -eq 0 ]"
+            ),
+            content, file,
+        )
     }
-    pub fn install_vpn_l2tp() -> &'static str {
-        "sudo dnf install -y NetworkManager-l2tp NetworkManager-l2tp-gnome"
+
+    pub fn env_vars(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Export system-wide environment variables to login shells")
+            .cmd(concat!(
+                "{\n",
+                "    echo '# System-wide environment variables; installed by redcent-tui'\n",
+                "    for assignment in {{env_vars}}; do\n",
+                "        printf 'export %s=%q\\n' \"${assignment%%=*}\" \"${assignment#*=}\"\n",
+                "    done\n",
+                "} | sudo tee /etc/profile.d/redcent-{{env_file}}.sh > /dev/null"
+            ))
+            .cmd("sudo chmod 0644 /etc/profile.d/redcent-{{env_file}}.sh")
+            .cmd("bash -n /etc/profile.d/redcent-{{env_file}}.sh")
+            .verify("test -r /etc/profile.d/redcent-{{env_file}}.sh")
+            .idempotent()
+    }
+    pub fn sudo_nopasswd(pm: &dyn PackageBackend) -> Script {
+        let group = if pm.name() == "apt" { "sudo" } else { "wheel" };
+        let install = sudoers_drop_in("redcent-nopasswd", &format!("printf '%%{} ALL=(ALL) NOPASSWD: ALL\\n'", group));
+        Script::new(&format!("Let {} members run sudo without a password, or ask for it again", group))
+            .cmd(format!("if [ '{{{{nopasswd}}}}' = yes ]; then\n    {}\nelse\n    sudo rm -f /etc/sudoers.d/redcent-nopasswd\nfi", install.replace('\n', "\n    ")))
+            .cmd("sudo visudo -c")
+            .verify("sudo visudo -c")
+            .idempotent()
+    }
+    pub fn sudo_commands(_pm: &dyn PackageBackend) -> Script {
+        let install = sudoers_drop_in("redcent-{{sudo_file}}", "printf '%s ALL=(root) %s\\n' '{{sudo_user}}' '{{sudo_commands}}'");
+        Script::new("Allow a user or group to run a fixed list of commands as root")
+            .cmd(install)
+            .cmd("sudo visudo -c")
+            .verify("sudo test -f /etc/sudoers.d/redcent-{{sudo_file}}")
+            .idempotent()
+    }
+}
+
+mod scripts_monitoring {
+    use redcent_tui::{write_file, PackageBackend, Script};
+
+    // Opens a TCP port in firewalld. Debian and Ubuntu default to ufw, which is left to the administrator.
+    fn open_port(pm: &dyn PackageBackend, script: Script, port: &str) -> Script {
+        match pm.name() {
+            "apt" => script.cmd(format!("# Allow {}/tcp in your firewall if one is active (e.g. sudo ufw allow {}/tcp).", port, port)),
+            _ => script
+                .cmd(format!("sudo firewall-cmd --permanent --add-port={}/tcp", port))
+                .cmd("sudo firewall-cmd --reload"),
+        }
     }
-    pub fn install_vpn_sswan() -> &'static str {
-        "sudo dnf install -y strongswan strongswan-charon-nm"
+
+    pub fn node_exporter_package(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install the Prometheus node_exporter package and expose it on port 9100");
+        let script = match pm.name() {
+            "apt" => script
+                .install(pm, &["prometheus-node-exporter"])
+                .cmd("sudo systemctl enable --now prometheus-node-exporter")
+                .verify("systemctl is-active prometheus-node-exporter")
+                .listens("9100/tcp", "prometheus-node-exporter.service"),
+            _ => script
+                .install(pm, &["golang-github-prometheus-node-exporter"])
+                .cmd("sudo systemctl enable --now node_exporter")
+                .verify("systemctl is-active node_exporter")
+                .listens("9100/tcp", "node_exporter.service"),
+        };
+        open_port(pm, script, "9100").idempotent()
     }
-    pub fn install_vpn_lswan() -> &'static str {
-        "sudo dnf install -y NetworkManager-libreswan NetworkManager-libreswan-gnome"
+    pub fn node_exporter_binary(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install the upstream Prometheus node_exporter binary as a systemd service on port 9100")
+            .cmd(concat!(
+                "curl -fsSL -o /tmp/node_exporter.tar.gz ",
+                "https://github.com/prometheus/node_exporter/releases/download/v{{exporter_version}}/node_exporter-{{exporter_version}}.linux-amd64.tar.gz"
+            ))
+            .cmd("sudo tar -xzf /tmp/node_exporter.tar.gz -C /usr/local/bin --strip-components=1 node_exporter-{{exporter_version}}.linux-amd64/node_exporter")
+            .cmd("rm -f /tmp/node_exporter.tar.gz")
+            .cmd("id node_exporter >/dev/null 2>&1 || sudo useradd --system --no-create-home --shell /sbin/nologin node_exporter")
+            .cmd(write_file!("/etc/systemd/system/node_exporter.service", concat!(
+                "[Unit]\n",
+                "Description=Prometheus node_exporter\n",
+                "After=network-online.target\n",
+                "Wants=network-online.target\n",
+                "\n",
+                "[Service]\n",
+                "User=node_exporter\n",
+                "ExecStart=/usr/local/bin/node_exporter\n",
+                "Restart=on-failure\n",
+                "\n",
+                "[Install]\n",
+                "WantedBy=multi-user.target\n"
+            )))
+            .cmd("sudo restorecon -v /usr/local/bin/node_exporter 2>/dev/null || true")
+            .cmd("sudo systemctl daemon-reload")
+            .cmd("sudo systemctl enable --now node_exporter")
+            .verify("systemctl is-active node_exporter")
+            .verify("curl -fsS http://localhost:9100/metrics > /dev/null")
+            .listens("9100/tcp", "node_exporter.service")
+            .size_mib(25)
+            .network();
+        open_port(pm, script, "9100").idempotent()
     }
-    pub fn install_vpn_pptp() -> &'static str {
-        "sudo dnf install -y NetworkManager-pptp NetworkManager-pptp-gnome"
+    pub fn netdata(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install Netdata with the official kickstart script and expose its dashboard on port 19999")
+            .cmd("curl -fsSL https://get.netdata.cloud/kickstart.sh -o /tmp/netdata-kickstart.sh")
+            .cmd("sudo sh /tmp/netdata-kickstart.sh --non-interactive --stable-channel --disable-telemetry")
+            .cmd("rm -f /tmp/netdata-kickstart.sh")
+            .verify("systemctl is-active netdata")
+            .listens("19999/tcp", "netdata.service")
+            .size_mib(300)
+            .network();
+        open_port(pm, script, "19999").idempotent()
     }
-    pub fn install_vpn_oconn() -> &'static str {
-        "sudo dnf install -y NetworkManager-openconnect NetworkManager-openconnect-gnome"
+    pub fn pcp(pm: &dyn PackageBackend) -> Script {
+        Script::new("Install Performance Co-Pilot with metrics history in Cockpit")
+            .install(pm, &["pcp", "cockpit-pcp"])
+            .cmd("sudo systemctl enable --now pmcd pmlogger")
+            .verify("systemctl is-active pmcd")
+            .listens("44321/tcp", "pmcd.service")
+            .verify("systemctl is-active pmlogger")
+            .idempotent()
     }
+    pub fn grafana(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install Grafana and expose it on port 3000");
+        let script = match pm.name() {
+            "apt" => script
+                .cmd("sudo install -m 0755 -d /etc/apt/keyrings")
+                .cmd("curl -fsSL https://apt.grafana.com/gpg.key | sudo gpg --dearmor --yes -o /etc/apt/keyrings/grafana.gpg")
+                .cmd("echo 'deb [signed-by=/etc/apt/keyrings/grafana.gpg] https://apt.grafana.com stable main' | sudo tee /etc/apt/sources.list.d/grafana.list > /dev/null")
+                .cmd("sudo apt-get update"),
+            // AppStream ships Grafana on RHEL and CentOS Stream.
+            _ => script,
+        };
+        let script = script
+            .install(pm, &["grafana"])
+            .cmd("sudo systemctl enable --now grafana-server")
+            .verify("systemctl is-active grafana-server")
+            .listens("3000/tcp", "grafana-server.service")
+            .cmd("# Log in at http://<host>:3000 as admin/admin and change the password when prompted.");
+        open_port(pm, script, "3000").idempotent()
+    }
+}
 
+mod scripts_dev {
+    use redcent_tui::{write_file, PackageBackend, Script};
+
+    // The account the script was started for: the sudo caller, or the current user when run without sudo.
+    const TARGET_USER: &str = "\"${SUDO_USER:-$(id -un)}\"";
+
+    pub fn development_tools(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install compilers, make, autotools and debuggers");
+        match pm.name() {
+            "apt" => script.install(pm, &["build-essential", "gdb", "autoconf", "automake", "libtool", "pkg-config"]),
+            _ => script.group_install(pm, "Development Tools").verify(pm.query(&["gcc", "make"])),
+        }.size_mib(300).idempotent()
+    }
+    pub fn gcc_toolset(pm: &dyn PackageBackend) -> Script {
+        if pm.name() == "apt" {
+            return Script::unsupported(pm, "GCC Toolset");
+        }
+        Script::new("Install a newer GCC from the gcc-toolset Software Collection")
+            .install(pm, &["gcc-toolset-{{toolset_version}}"])
+            .cmd("# Use it with: scl enable gcc-toolset-{{toolset_version}} bash")
+            .verify("/opt/rh/gcc-toolset-{{toolset_version}}/root/usr/bin/gcc --version")
+            .idempotent()
+    }
+    pub fn rustup(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Install the Rust toolchain with rustup for the invoking user")
+            .cmd("curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs -o /tmp/rustup-init.sh")
+            .cmd(format!("sudo -u {TARGET_USER} sh /tmp/rustup-init.sh -y --profile default"))
+            .cmd("rm -f /tmp/rustup-init.sh")
+            .verify(format!("sudo -u {TARGET_USER} -i rustc --version"))
+            .size_mib(1400)
+            .network()
+            .idempotent()
+    }
+    pub fn golang(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install the Go toolchain");
+        match pm.name() {
+            "apt" => script.install(pm, &["golang-go"]),
+            _ => script.install(pm, &["golang"]),
+        }.verify("go version").idempotent()
+    }
+    pub fn nodejs(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Install Node.js and npm from a selected module stream");
+        match pm.name() {
+            "apt" => script
+                .cmd("# Debian and Ubuntu ship a single Node.js version; the stream parameter is not used.")
+                .install(pm, &["nodejs", "npm"]),
+            _ => script
+                .cmd("# Module streams exist on RHEL 8 and 9; list them with: dnf module list nodejs")
+                .cmd("sudo dnf module reset -y nodejs")
+                .cmd("sudo dnf module enable -y nodejs:{{stream}}")
+                .install(pm, &["nodejs", "npm"])
+                .verify("node --version | grep -q '^v{{stream}}\\.'"),
+        }.idempotent()
+    }
+    pub fn python312(pm: &dyn PackageBackend) -> Script {
+        match pm.name() {
+            "apt" => Script::new("Install Python 3.12 with pip and venv")
+                .cmd("# Ubuntu 24.04 ships Python 3.12 as python3; Debian 12 ships 3.11.")
+                .install(pm, &["python3", "python3-pip", "python3-venv"])
+                .idempotent(),
+            _ => Script::new("Install Python 3.12 alongside the system Python")
+                .install(pm, &["python3.12", "python3.12-pip", "python3.12-devel"])
+                .verify("python3.12 --version")
+                .idempotent(),
+        }
+    }
+    pub fn vscode(pm: &dyn PackageBackend) -> Script {
+        let script = Script::new("Add Microsoft's Visual Studio Code repository and install code");
+        match pm.name() {
+            "apt" => script
+                .cmd("sudo install -m 0755 -d /etc/apt/keyrings")
+                .cmd("curl -fsSL https://packages.microsoft.com/keys/microsoft.asc | sudo gpg --dearmor --yes -o /etc/apt/keyrings/microsoft.gpg")
+                .cmd("echo 'deb [arch=amd64,arm64 signed-by=/etc/apt/keyrings/microsoft.gpg] https://packages.microsoft.com/repos/code stable main' | sudo tee /etc/apt/sources.list.d/vscode.list > /dev/null")
+                .cmd("sudo apt-get update"),
+            _ => script
+                .cmd("sudo rpm --import https://packages.microsoft.com/keys/microsoft.asc")
+                .cmd(write_file!("/etc/yum.repos.d/vscode.repo", concat!(
+                    "[code]\n",
+                    "name=Visual Studio Code\n",
+                    "baseurl=https://packages.microsoft.com/yumrepos/vscode\n",
+                    "enabled=1\n",
+                    "gpgcheck=1\n",
+                    "gpgkey=https://packages.microsoft.com/keys/microsoft.asc\n"
+                ))),
+        }
+            .install(pm, &["code"])
+            .idempotent()
+    }
+}
+
+mod scripts_lab {
+    use redcent_tui::{PackageBackend, Script};
+
+    pub fn bulk_accounts(_pm: &dyn PackageBackend) -> Script {
+        Script::new("Create numbered lab accounts that must change their password at first login")
+            .cmd("# Desktop preset: {{desktop}}")
+            // The password reaches the shell through a quoted heredoc, so quotes and `$` in it stay literal.
+            .cmd(concat!(
+                "password=$(cat <<'REDCENT_EOF'\n",
+                "{{password}}\n",
+                "REDCENT_EOF\n",
+                ")\n",
+                "for i in $(seq -w 1 {{count}}); do\n",
+                "    user=\"{{prefix}}${i}\"\n",
+                "    if ! id \"$user\" >/dev/null 2>&1; then\n",
+                "        sudo useradd -m -c \"Lab account ${i}\" \"$user\"\n",
+                "        printf '%s:%s\\n' \"$user\" \"$password\" | sudo chpasswd\n",
+                "        sudo chage -d 0 -M {{max_days}} \"$user\"\n",
+                "    fi\n",
+                "done"
+            ))
+            .idempotent()
+    }
+}
+
+mod scripts_maint {
+    use redcent_tui::{write_file, PackageBackend, Script};
+
+    // File templates for the weekly maintenance preset, expanded at compile time into heredocs.
+    macro_rules! maint_script_template {
+        () => {
+            concat!(
+                "#!/bin/bash\n",
+                "# Weekly maintenance installed by redcent-tui\n",
+                "dnf upgrade -y --security\n",
+                "fstrim --all --verbose\n",
+                "journalctl --vacuum-time=4weeks\n",
+                "# Restic backup only runs when a repository has been configured in /etc/restic/env\n",
+                "if [ -r /etc/restic/env ]; then\n",
+                "    set -a; . /etc/restic/env; set +a\n",
+                "    restic backup --one-file-system --exclude-caches / && restic forget --keep-weekly 8 --prune\n",
+                "fi\n"
+            )
+        };
+    }
+    macro_rules! service_unit_template {
+        () => {
+            concat!(
+                "[Unit]\n",
+                "Description=redcent-tui weekly maintenance\n",
+                "Wants=network-online.target\n",
+                "After=network-online.target\n\n",
+                "[Service]\n",
+                "Type=oneshot\n",
+                "ExecStart=/usr/local/sbin/redcent-maintenance.sh\n"
+            )
+        };
+    }
+    macro_rules! timer_unit_template {
+        () => {
+            concat!(
+                "[Unit]\n",
+                "Description=Run redcent-tui weekly maintenance\n\n",
+                "[Timer]\n",
+                "OnCalendar=Sun *-*-* 03:00:00\n",
+                "RandomizedDelaySec=1h\n",
+                "Persistent=true\n\n",
+                "[Install]\n",
+                "WantedBy=timers.target\n"
+            )
+        };
+    }
+
+    pub fn weekly_timer(pm: &dyn PackageBackend) -> Script {
+        // The maintenance script itself runs dnf.
+        if pm.name() == "apt" {
+            return Script::unsupported(pm, "The weekly maintenance timer");
+        }
+        Script::new("Schedule weekly security updates, TRIM, journal cleanup and an optional restic backup")
+            .cmd(concat!(
+                "# Files written:\n",
+                "#   /usr/local/sbin/redcent-maintenance.sh\n",
+                "#   /etc/systemd/system/redcent-maintenance.service\n",
+                "#   /etc/systemd/system/redcent-maintenance.timer"
+            ))
+            .cmd(write_file!("/usr/local/sbin/redcent-maintenance.sh", maint_script_template!()))
+            .cmd("sudo chmod 0755 /usr/local/sbin/redcent-maintenance.sh")
+            .cmd(write_file!("/etc/systemd/system/redcent-maintenance.service", service_unit_template!()))
+            .cmd(write_file!("/etc/systemd/system/redcent-maintenance.timer", timer_unit_template!()))
+            .cmd("sudo systemctl daemon-reload")
+            .cmd("sudo systemctl enable --now redcent-maintenance.timer")
+            .verify("systemctl is-active redcent-maintenance.timer")
+            .idempotent()
+    }
+
+    pub fn scheduled_job(pm: &dyn PackageBackend) -> Script {
+        let (cron_package, cron_service) = if pm.name() == "apt" { ("cron", "cron") } else { ("cronie", "crond") };
+        // Both variants run the command through a wrapper script, which keeps it out of crontab
+        // (where % is special) and ExecStart= quoting; switching variants removes the other one.
+        Script::new("Run a command on a schedule, from cron or a systemd timer")
+            .cmd(write_file!("/usr/local/sbin/redcent-job-{{job_name}}", concat!(
+                "#!/bin/bash\n",
+                "# Scheduled job {{job_name}} installed by redcent-tui\n",
+                "{{command}}\n"
+            )))
+            .cmd("sudo chmod 0755 /usr/local/sbin/redcent-job-{{job_name}}")
+            .cmd(format!(
+                concat!(
+                    "if [ '{{{{schedule_type}}}}' = cron ]; then\n",
+                    "{}\n",
+                    "{}\n",
+                    "sudo chmod 0644 /etc/cron.d/redcent-{{{{job_name}}}}\n",
+                    "sudo systemctl enable --now {}\n",
+                    "sudo systemctl disable --now redcent-job-{{{{job_name}}}}.timer 2>/dev/null\n",
+                    "sudo rm -f /etc/systemd/system/redcent-job-{{{{job_name}}}}.service /etc/systemd/system/redcent-job-{{{{job_name}}}}.timer\n",
+                    "sudo systemctl daemon-reload\n",
+                    "fi"
+                ),
+                pm.install(&[cron_package]),
+                write_file!("/etc/cron.d/redcent-{{job_name}}", concat!(
+                    "# Scheduled job {{job_name}} installed by redcent-tui\n",
+                    "SHELL=/bin/bash\n",
+                    "PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin\n",
+                    "{{schedule}} {{job_user}} /usr/local/sbin/redcent-job-{{job_name}}\n"
+                )),
+                cron_service,
+            ))
+            .cmd(concat!(
+                "if [ '{{schedule_type}}' = 'systemd timer' ] && ! systemd-analyze calendar '{{schedule}}' > /dev/null; then\n",
+                "    echo 'systemd does not accept the schedule {{schedule}}' >&2\n",
+                "    false\n",
+                "elif [ '{{schedule_type}}' = 'systemd timer' ]; then\n",
+                "sudo rm -f /etc/cron.d/redcent-{{job_name}}\n",
+                write_file!("/etc/systemd/system/redcent-job-{{job_name}}.service", concat!(
+                    "[Unit]\n",
+                    "Description=redcent-tui scheduled job {{job_name}}\n\n",
+                    "[Service]\n",
+                    "Type=oneshot\n",
+                    "User={{job_user}}\n",
+                    "ExecStart=/usr/local/sbin/redcent-job-{{job_name}}\n"
+                )), "\n",
+                write_file!("/etc/systemd/system/redcent-job-{{job_name}}.timer", concat!(
+                    "[Unit]\n",
+                    "Description=Run redcent-tui scheduled job {{job_name}}\n\n",
+                    "[Timer]\n",
+                    "OnCalendar={{schedule}}\n",
+                    "Persistent=true\n\n",
+                    "[Install]\n",
+                    "WantedBy=timers.target\n"
+                )), "\n",
+                "sudo systemctl daemon-reload\n",
+                "sudo systemctl enable --now redcent-job-{{job_name}}.timer\n",
+                "fi"
+            ))
+            .verify("test -x /usr/local/sbin/redcent-job-{{job_name}}")
+            .idempotent()
+    }
 }
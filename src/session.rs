@@ -0,0 +1,199 @@
+// src/session.rs
+
+use crate::{
+    cockpit::{json_array, json_string},
+    draft::Draft,
+    history::{self, state_dir},
+};
+use redcent_tui::{has_script_order, ordered_selected_items, MenuNode};
+use std::{cell::RefCell, fs, io, iter::Peekable, rc::Rc, str::Chars};
+
+pub const SESSION_FILE: &str = "session.json";
+
+/// Where the TUI left off at the last quit: the selection, the menus opened and the highlighted row.
+pub struct Session {
+    /// The selection, restored the same way as a crash-recovery draft.
+    pub selection: Draft,
+    /// Names of the opened menus below the root.
+    pub nav_path: Vec<String>,
+    pub selected_index: usize,
+}
+
+/// Writes `session.json` in the state directory on quit, or removes it when nothing is selected.
+pub fn save(tree: &Rc<RefCell<MenuNode>>, target: &str, nav_path: &[Rc<RefCell<MenuNode>>], selected_index: usize) -> io::Result<()> {
+    let dir = state_dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "cannot determine state directory"))?;
+    let items = ordered_selected_items(tree);
+    if items.is_empty() {
+        discard();
+        return Ok(());
+    }
+    let mut params = Vec::new();
+    for item in &items {
+        if let MenuNode::Item { id, params: values, .. } = &*item.borrow() {
+            params.extend(values.iter().map(|param| format!(
+                "{{\"item\": {}, \"key\": {}, \"value\": {}}}", json_string(id), json_string(param.key), json_string(&param.value),
+            )));
+        }
+    }
+    let json = format!(
+        "{{\n  \"saved\": {},\n  \"target\": {},\n  \"arranged\": {},\n  \"items\": {},\n  \"params\": [{}],\n  \"nav_path\": {},\n  \"selected_index\": {}\n}}\n",
+        json_string(&history::timestamp_now()),
+        json_string(target),
+        has_script_order(tree),
+        json_array(items.iter().map(|item| item.borrow().id().to_string())),
+        params.join(", "),
+        json_array(nav_path.iter().skip(1).map(|menu| menu.borrow().name().to_string())),
+        selected_index,
+    );
+    fs::create_dir_all(&dir)?;
+    let tmp = dir.join(format!("{}.tmp", SESSION_FILE));
+    fs::write(&tmp, json)?;
+    fs::rename(tmp, dir.join(SESSION_FILE))
+}
+
+/// Removes `session.json`; called when the user declines to restore it.
+pub fn discard() {
+    if let Some(dir) = state_dir() {
+        let _ = fs::remove_file(dir.join(SESSION_FILE));
+    }
+}
+
+impl Session {
+    /// Reads `session.json`; `None` if there is none, it does not parse or it selects nothing.
+    pub fn load() -> Option<Session> {
+        let content = fs::read_to_string(state_dir()?.join(SESSION_FILE)).ok()?;
+        let Json::Object(fields) = parse(&content)? else { return None };
+        let field = |key: &str| fields.iter().find(|(k, _)| k == key).map(|(_, value)| value);
+        let string = |value: Option<&Json>| match value {
+            Some(Json::String(s)) => s.clone(),
+            _ => String::new(),
+        };
+        let strings = |value: Option<&Json>| match value {
+            Some(Json::Array(values)) => values.iter().map(|value| string(Some(value))).collect(),
+            _ => Vec::new(),
+        };
+        let params = match field("params") {
+            Some(Json::Array(params)) => params.iter().filter_map(|param| {
+                let Json::Object(param) = param else { return None };
+                let get = |key: &str| string(param.iter().find(|(k, _)| k == key).map(|(_, value)| value));
+                Some((get("item"), get("key"), get("value")))
+            }).collect(),
+            _ => Vec::new(),
+        };
+        let selection = Draft {
+            saved: string(field("saved")),
+            target: string(field("target")),
+            items: strings(field("items")),
+            arranged: matches!(field("arranged"), Some(Json::Bool(true))),
+            params,
+        };
+        let selected_index = match field("selected_index") {
+            Some(Json::Number(n)) if *n >= 0.0 => *n as usize,
+            _ => 0,
+        };
+        (!selection.items.is_empty()).then_some(Session { selection, nav_path: strings(field("nav_path")), selected_index })
+    }
+}
+
+/// A parsed JSON value; enough of JSON to read back what [`save`] and `--dump-json` write, and
+/// hand edits of them.
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+pub fn parse(text: &str) -> Option<Json> {
+    let mut chars = text.chars().peekable();
+    let value = parse_value(&mut chars)?;
+    skip_whitespace(&mut chars);
+    chars.peek().is_none().then_some(value)
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Option<Json> {
+    skip_whitespace(chars);
+    match *chars.peek()? {
+        '{' => {
+            chars.next();
+            let mut fields = Vec::new();
+            skip_whitespace(chars);
+            if chars.next_if_eq(&'}').is_some() {
+                return Some(Json::Object(fields));
+            }
+            loop {
+                skip_whitespace(chars);
+                let key = parse_string(chars)?;
+                skip_whitespace(chars);
+                chars.next_if_eq(&':')?;
+                fields.push((key, parse_value(chars)?));
+                skip_whitespace(chars);
+                match chars.next()? {
+                    ',' => continue,
+                    '}' => return Some(Json::Object(fields)),
+                    _ => return None,
+                }
+            }
+        }
+        '[' => {
+            chars.next();
+            let mut values = Vec::new();
+            skip_whitespace(chars);
+            if chars.next_if_eq(&']').is_some() {
+                return Some(Json::Array(values));
+            }
+            loop {
+                values.push(parse_value(chars)?);
+                skip_whitespace(chars);
+                match chars.next()? {
+                    ',' => continue,
+                    ']' => return Some(Json::Array(values)),
+                    _ => return None,
+                }
+            }
+        }
+        '"' => parse_string(chars).map(Json::String),
+        _ => {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.')) {
+                word.push(c);
+            }
+            match word.as_str() {
+                "null" => Some(Json::Null),
+                "true" => Some(Json::Bool(true)),
+                "false" => Some(Json::Bool(false)),
+                _ => word.parse().ok().map(Json::Number),
+            }
+        }
+    }
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Option<String> {
+    chars.next_if_eq(&'"')?;
+    let mut out = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(out),
+            '\\' => match chars.next()? {
+                'n' => out.push('\n'),
+                't' => out.push('\t'),
+                'r' => out.push('\r'),
+                'b' => out.push('\u{8}'),
+                'f' => out.push('\u{c}'),
+                'u' => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?).unwrap_or(char::REPLACEMENT_CHARACTER));
+                }
+                c => out.push(c),
+            },
+            c => out.push(c),
+        }
+    }
+}
+
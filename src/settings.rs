@@ -0,0 +1,191 @@
+// src/settings.rs
+
+use crate::{
+    centered_rect,
+    form::WizardOutcome,
+    privilege::{Escalation, PrivilegeInfo},
+    theme::{Scheme, Theme},
+};
+use crossterm::event::KeyCode;
+use redcent_tui::{ErrorMode, DEFAULT_OFFLINE_BASEURL};
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::Style,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+/// Moves `current` one step through `all`, wrapping around.
+fn cycle<T: PartialEq + Copy>(all: &[T], current: T, forward: bool) -> T {
+    let index = all.iter().position(|e| *e == current).unwrap_or(0);
+    let step = if forward { 1 } else { all.len() - 1 };
+    all[(index + step) % all.len()]
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum Section {
+    Escalation,
+    Errors,
+    Transcript,
+    Offline,
+    Mirror,
+    Scheme,
+}
+
+const SECTIONS: [Section; 6] = [Section::Escalation, Section::Errors, Section::Transcript, Section::Offline, Section::Mirror, Section::Scheme];
+
+/// Popup for session-wide options; edits a copy that is only applied on confirmation.
+pub struct SettingsPopup {
+    pub escalation: Escalation,
+    pub errors: ErrorMode,
+    /// Saved scripts keep a log of their output.
+    pub transcript: bool,
+    /// Install packages from the local repository at `baseurl` only.
+    pub offline: bool,
+    pub baseurl: String,
+    /// Color scheme; unlike the other settings it is saved to the config file.
+    pub scheme: Scheme,
+    /// Section ↑↓ change.
+    section: Section,
+}
+
+impl SettingsPopup {
+    /// `offline` is the base URL of the local repository when offline mode is on.
+    pub fn new(escalation: Escalation, errors: ErrorMode, transcript: bool, offline: Option<&str>, scheme: Scheme) -> SettingsPopup {
+        SettingsPopup {
+            escalation,
+            errors,
+            transcript,
+            offline: offline.is_some(),
+            baseurl: offline.unwrap_or(DEFAULT_OFFLINE_BASEURL).to_string(),
+            scheme,
+            section: Section::Escalation,
+        }
+    }
+
+    /// The base URL to apply: `None` unless offline mode is on.
+    pub fn offline_baseurl(&self) -> Option<String> {
+        let baseurl = self.baseurl.trim().trim_end_matches('/');
+        (self.offline && !baseurl.is_empty()).then(|| baseurl.to_string())
+    }
+
+    /// Turns offline mode on and focuses the base URL; preflight opens the popup this way when
+    /// the default mirrors are unreachable.
+    pub fn focus_mirror(&mut self) {
+        self.offline = true;
+        self.section = Section::Mirror;
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> WizardOutcome {
+        match code {
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.section = cycle(&SECTIONS, self.section, code == KeyCode::Tab);
+            }
+            KeyCode::Char(c) if self.section == Section::Mirror => self.baseurl.push(c),
+            KeyCode::Backspace if self.section == Section::Mirror => { self.baseurl.pop(); },
+            KeyCode::Down | KeyCode::Right | KeyCode::Char(' ') | KeyCode::Up | KeyCode::Left => {
+                let forward = !matches!(code, KeyCode::Up | KeyCode::Left);
+                match self.section {
+                    Section::Escalation => self.escalation = cycle(&Escalation::ALL, self.escalation, forward),
+                    Section::Errors => self.errors = cycle(&ErrorMode::ALL, self.errors, forward),
+                    Section::Transcript => self.transcript = !self.transcript,
+                    Section::Offline => self.offline = !self.offline,
+                    Section::Mirror => {}
+                    Section::Scheme => self.scheme = cycle(&Scheme::ALL, self.scheme, forward),
+                }
+            }
+            KeyCode::Enter => return WizardOutcome::Finish,
+            KeyCode::Esc => return WizardOutcome::Cancel,
+            _ => {}
+        }
+        WizardOutcome::Continue
+    }
+
+    /// Draws the popup; the color scheme being edited is previewed in the popup itself.
+    pub fn draw(&self, f: &mut Frame, info: &PrivilegeInfo) {
+        let theme = Theme::new(self.scheme);
+        let area = centered_rect(60, 85, f.size());
+        f.render_widget(Clear, area);
+        f.render_widget(Block::default().title("Settings").borders(Borders::ALL), area);
+        let chunks = Layout::default().direction(Direction::Vertical).margin(2)
+            .constraints([
+                Constraint::Length(2), Constraint::Min(6), Constraint::Length(5), Constraint::Length(4), Constraint::Length(4),
+                Constraint::Length(3), Constraint::Length(6), Constraint::Length(3), Constraint::Length(1),
+            ])
+            .split(area);
+
+        f.render_widget(Paragraph::new(info.summary()).wrap(Wrap { trim: true }), chunks[0]);
+
+        let options: Vec<ListItem> = Escalation::ALL.iter().map(|e| {
+            let marker = if *e == self.escalation { "(•)" } else { "( )" };
+            let note = if info.supports(*e) { "" } else { "  — unavailable" };
+            ListItem::new(format!("{} {}{}", marker, e.label(), note))
+        }).collect();
+        let mut state = ListState::default();
+        state.select(Escalation::ALL.iter().position(|e| *e == self.escalation));
+        let list = List::new(options)
+            .block(section("Privilege escalation", self.section == Section::Escalation, &theme))
+            .highlight_style(theme.highlight);
+        f.render_stateful_widget(list, chunks[1], &mut state);
+
+        let modes: Vec<ListItem> = ErrorMode::ALL.iter().map(|mode| {
+            let marker = if *mode == self.errors { "(•)" } else { "( )" };
+            ListItem::new(format!("{} {}", marker, mode.label()))
+        }).collect();
+        let mut state = ListState::default();
+        state.select(ErrorMode::ALL.iter().position(|mode| *mode == self.errors));
+        let list = List::new(modes)
+            .block(section("Error handling", self.section == Section::Errors, &theme))
+            .highlight_style(theme.highlight);
+        f.render_stateful_widget(list, chunks[2], &mut state);
+
+        let modes: Vec<ListItem> = [(false, "Off"), (true, "On: log to /var/log/redcent-setup-<time>.log, timestamp phases, summarize blocks")].iter()
+            .map(|(on, label)| ListItem::new(format!("{} {}", if *on == self.transcript { "(•)" } else { "( )" }, label)))
+            .collect();
+        let mut state = ListState::default();
+        state.select(Some(self.transcript as usize));
+        let list = List::new(modes)
+            .block(section("Transcript in saved scripts", self.section == Section::Transcript, &theme))
+            .highlight_style(theme.highlight);
+        f.render_stateful_widget(list, chunks[3], &mut state);
+
+        let modes: Vec<ListItem> = [(false, "Off: use the host's repositories"), (true, "On: install packages from a local repository only")].iter()
+            .map(|(on, label)| ListItem::new(format!("{} {}", if *on == self.offline { "(•)" } else { "( )" }, label)))
+            .collect();
+        let mut state = ListState::default();
+        state.select(Some(self.offline as usize));
+        let list = List::new(modes)
+            .block(section("Offline mode (items needing the internet are unavailable)", self.section == Section::Offline, &theme))
+            .highlight_style(theme.highlight);
+        f.render_stateful_widget(list, chunks[4], &mut state);
+
+        let cursor = if self.section == Section::Mirror { "_" } else { "" };
+        let style = if self.offline { Style::default() } else { theme.muted };
+        f.render_widget(
+            Paragraph::new(format!("{}{}", self.baseurl, cursor)).style(style)
+                .block(section("Local repository base URL (DVD mount or mirror)", self.section == Section::Mirror, &theme)),
+            chunks[5],
+        );
+
+        let schemes: Vec<ListItem> = Scheme::ALL.iter().map(|scheme| {
+            let marker = if *scheme == self.scheme { "(•)" } else { "( )" };
+            ListItem::new(format!("{} {}", marker, scheme.label()))
+        }).collect();
+        let mut state = ListState::default();
+        state.select(Scheme::ALL.iter().position(|scheme| *scheme == self.scheme));
+        let list = List::new(schemes)
+            .block(section("Color scheme (saved to the config file)", self.section == Section::Scheme, &theme))
+            .highlight_style(theme.highlight);
+        f.render_stateful_widget(list, chunks[6], &mut state);
+
+        if let Some(warning) = info.warning(self.escalation) {
+            f.render_widget(Paragraph::new(warning).style(theme.error).wrap(Wrap { trim: true }), chunks[7]);
+        }
+        f.render_widget(Paragraph::new("Tab switch section | ↑↓ change | type the base URL | Enter apply | Esc cancel"), chunks[8]);
+    }
+}
+
+fn section<'a>(title: &'a str, focused: bool, theme: &Theme) -> Block<'a> {
+    let border = if focused { theme.focused } else { Style::default() };
+    Block::default().title(title).borders(Borders::ALL).border_style(border)
+}
@@ -0,0 +1,147 @@
+// src/space.rs
+
+use redcent_tui::SpaceClaim;
+use std::{collections::HashMap, process::Command};
+
+/// Space that should still be free once the selection is installed.
+pub const RESERVE_MIB: u64 = 2 * 1024;
+
+/// How the selection's estimated size compares with the free space.
+#[derive(PartialEq, Clone, Copy)]
+pub enum Verdict {
+    Fits,
+    /// Fits, but leaves less than [`RESERVE_MIB`] free.
+    Tight,
+    DoesNotFit,
+    /// `df` could not be read.
+    Unknown,
+}
+
+/// The selection's estimated disk use against the free space of the filesystem packages are
+/// installed to. Packages are sized from the package manager's local metadata, and only when
+/// the script is authored for this host's package manager; scripts that set
+/// [`redcent_tui::Script::size_mib`] use that instead.
+pub struct SpaceCheck {
+    /// Each sized item with its estimate in MiB, in script order.
+    pub items: Vec<(String, u64)>,
+    /// Packages whose size is unknown: not in the local metadata, or authored for another distribution.
+    pub not_sized: Vec<String>,
+    /// Mount point holding `/usr` and its free MiB.
+    pub free: Option<(String, u64)>,
+}
+
+impl SpaceCheck {
+    /// `package_manager` is the backend name (`dnf`, `apt`) to query, `None` when the script
+    /// targets another distribution than this host.
+    pub fn run(claims: Vec<SpaceClaim>, package_manager: Option<&str>) -> SpaceCheck {
+        let packages: Vec<&str> = claims.iter()
+            .filter(|claim| claim.size_mib.is_none())
+            .flat_map(|claim| claim.packages.iter().map(String::as_str))
+            .collect();
+        let sizes = match package_manager {
+            Some(pm) if !packages.is_empty() => package_sizes(pm, &packages),
+            _ => HashMap::new(),
+        };
+        let mut items = Vec::new();
+        let mut not_sized = Vec::new();
+        for claim in claims {
+            let mib = match claim.size_mib {
+                Some(mib) => mib,
+                None => claim.packages.iter().map(|package| match sizes.get(package) {
+                    Some(kib) => *kib,
+                    None => {
+                        not_sized.push(package.clone());
+                        0
+                    }
+                }).sum::<u64>().div_ceil(1024),
+            };
+            if mib > 0 {
+                items.push((claim.item, mib));
+            }
+        }
+        SpaceCheck { items, not_sized, free: free_space("/usr") }
+    }
+
+    pub fn needed_mib(&self) -> u64 {
+        self.items.iter().map(|(_, mib)| mib).sum()
+    }
+
+    pub fn verdict(&self) -> Verdict {
+        match self.free {
+            None => Verdict::Unknown,
+            Some((_, free)) if self.needed_mib() > free => Verdict::DoesNotFit,
+            Some((_, free)) if self.needed_mib() + RESERVE_MIB > free => Verdict::Tight,
+            Some(_) => Verdict::Fits,
+        }
+    }
+
+    /// One line such as `about 2.7 GiB needed, 10.3 GiB free on / (2 package(s) not sized)`.
+    pub fn summary(&self) -> String {
+        let mut summary = format!("about {} needed", format_mib(self.needed_mib()));
+        match &self.free {
+            Some((mount, free)) => summary.push_str(&format!(", {} free on {}", format_mib(*free), mount)),
+            None => summary.push_str(", free space unknown (could not read df output)"),
+        }
+        if !self.not_sized.is_empty() {
+            summary.push_str(&format!(" ({} package(s) not sized)", self.not_sized.len()));
+        }
+        summary
+    }
+}
+
+pub fn format_mib(mib: u64) -> String {
+    if mib >= 1024 {
+        format!("{:.1} GiB", mib as f64 / 1024.0)
+    } else {
+        format!("{} MiB", mib)
+    }
+}
+
+/// Installed sizes in KiB of the packages that are not installed yet; installed packages count as 0.
+fn package_sizes(package_manager: &str, packages: &[&str]) -> HashMap<String, u64> {
+    let run = |program: &str, args: &[&str]| {
+        Command::new(program).args(args).args(packages).output().map(|out| String::from_utf8_lossy(&out.stdout).into_owned()).unwrap_or_default()
+    };
+    let mut sizes = HashMap::new();
+    if package_manager == "apt" {
+        for line in run("dpkg-query", &["-W", "-f", "${Package} ${db:Status-Status}\\n"]).lines() {
+            if let Some((name, "installed")) = line.split_once(' ') {
+                sizes.insert(name.to_string(), 0);
+            }
+        }
+        // Stanzas list `Package:` before `Installed-Size:`, in KiB.
+        let mut name = String::new();
+        for line in run("apt-cache", &["show", "--no-all-versions"]).lines() {
+            if let Some(value) = line.strip_prefix("Package: ") {
+                name = value.trim().to_string();
+            } else if let Some(value) = line.strip_prefix("Installed-Size: ")
+                && let Ok(kib) = value.trim().parse()
+            {
+                sizes.entry(name.clone()).or_insert(kib);
+            }
+        }
+    } else {
+        // Lines of a package that is not installed read "package foo is not installed".
+        for line in run("rpm", &["-q", "--qf", "%{NAME}\\n"]).lines().filter(|line| !line.contains(' ')) {
+            sizes.insert(line.to_string(), 0);
+        }
+        // -C keeps to the cached metadata, so the check does not wait on the network.
+        for line in run("dnf", &["-q", "-C", "repoquery", "--latest-limit", "1", "--qf", "%{name} %{installsize}\\n"]).lines() {
+            if let Some((name, bytes)) = line.split_once(' ')
+                && let Ok(bytes) = bytes.trim().parse::<u64>()
+            {
+                sizes.entry(name.to_string()).or_insert(bytes.div_ceil(1024));
+            }
+        }
+    }
+    sizes
+}
+
+/// The mount point holding `path` and its free MiB, from `df`.
+fn free_space(path: &str) -> Option<(String, u64)> {
+    let out = Command::new("df").args(["-Pk", path]).output().ok()?;
+    let text = String::from_utf8(out.stdout).ok()?;
+    let fields: Vec<&str> = text.lines().nth(1)?.split_whitespace().collect();
+    let kib: u64 = fields.get(3)?.parse().ok()?;
+    Some((fields.get(5)?.to_string(), kib / 1024))
+}
@@ -0,0 +1,76 @@
+// src/template.rs
+
+use crate::OsDistribution;
+
+/// A `{{...}}` tag steering [`render_conditionals`].
+enum Control<'a> {
+    If(&'a str),
+    Else,
+    End,
+}
+
+fn control(tag: &str) -> Option<Control<'_>> {
+    match tag {
+        "else" => Some(Control::Else),
+        "/if" => Some(Control::End),
+        _ => tag.strip_prefix("#if ").map(Control::If),
+    }
+}
+
+/// Resolves the distribution conditionals in an item script for `distro`. The content of
+/// `{{#if rhel}}...{{/if}}` is kept only when authoring for RHEL, `{{#if debian ubuntu}}` for
+/// either of them, and an optional `{{else}}` branch otherwise; the names are the `ID` values of
+/// `/etc/os-release`, see [`OsDistribution::id`]. Blocks nest. A tag alone on its line goes with
+/// the line, so blocks spanning lines leave no blank lines behind. Other `{{key}}` placeholders
+/// are left for [`crate::render_params`].
+pub fn render_conditionals(text: &str, distro: OsDistribution) -> String {
+    let mut out = String::with_capacity(text.len());
+    // Per open block: whether its current branch is kept, and whether the text around it is.
+    let mut open: Vec<(bool, bool)> = Vec::new();
+    let mut at_line_start = true;
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}").map(|end| end + 2) else { break };
+        let kept = open.last().is_none_or(|&(branch, outer)| branch && outer);
+        let Some(tag) = control(rest[start + 2..start + len - 2].trim()) else {
+            if kept {
+                out.push_str(&rest[..start + len]);
+            }
+            rest = &rest[start + len..];
+            at_line_start = false;
+            continue;
+        };
+        let (mut before, mut after) = (&rest[..start], &rest[start + len..]);
+        let line_start = before.rfind('\n').map_or(0, |n| n + 1);
+        let alone = before[line_start..].trim().is_empty()
+            && (line_start > 0 || at_line_start)
+            && after.find('\n').map_or(after.trim().is_empty(), |n| after[..n].trim().is_empty());
+        if alone {
+            // The line break goes with the tag's line: the one after it, or before it at the end.
+            match after.find('\n') {
+                Some(n) => {
+                    before = &before[..line_start];
+                    after = &after[n + 1..];
+                }
+                None => {
+                    before = &before[..line_start.saturating_sub(1)];
+                    after = "";
+                }
+            }
+        }
+        if kept {
+            out.push_str(before);
+        }
+        match tag {
+            Control::If(names) => open.push((names.split_whitespace().any(|name| Some(name) == distro.id()), kept)),
+            Control::Else => if let Some(block) = open.last_mut() { block.0 = !block.0 },
+            Control::End => { open.pop(); }
+        }
+        rest = after;
+        at_line_start = alone;
+    }
+    if open.last().is_none_or(|&(branch, outer)| branch && outer) {
+        out.push_str(rest);
+    }
+    out
+}
@@ -0,0 +1,172 @@
+// src/terminal.rs
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crate::{events::Waker, theme::Theme};
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders},
+    Frame,
+};
+use std::{
+    io::{self, Read, Write},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// Lines kept above the visible screen for scrolling back.
+const SCROLLBACK: usize = 5000;
+
+/// How often the screen is redrawn while a command runs.
+pub const REFRESH: Duration = Duration::from_millis(50);
+
+/// A command running in a pseudo-terminal, drawn as a pane so it can prompt for input (sudo
+/// passwords, `subscription-manager register`, `passwd`) without leaving the TUI.
+pub struct TerminalPane {
+    parser: vt100::Parser,
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    child: Box<dyn Child + Send + Sync>,
+    /// Output read from the terminal by a background thread.
+    output: Receiver<Vec<u8>>,
+    exit_code: Option<i32>,
+}
+
+impl TerminalPane {
+    /// Starts `argv` in a new pseudo-terminal of the given size, in the current directory; `waker`
+    /// is woken whenever output arrives.
+    pub fn spawn(argv: &[String], rows: u16, cols: u16, waker: Option<Waker>) -> io::Result<TerminalPane> {
+        let pair = native_pty_system()
+            .openpty(PtySize { rows, cols, pixel_width: 0, pixel_height: 0 })
+            .map_err(|e| io::Error::other(format!("could not open a pseudo-terminal: {}", e)))?;
+        let mut command = CommandBuilder::new(&argv[0]);
+        command.args(&argv[1..]);
+        command.cwd(std::env::current_dir()?);
+        command.env("TERM", "xterm");
+        let child = pair.slave.spawn_command(command).map_err(|e| io::Error::other(e.to_string()))?;
+        // Only the child may hold the terminal open, so reading ends when it exits.
+        drop(pair.slave);
+        let mut reader = pair.master.try_clone_reader().map_err(|e| io::Error::other(e.to_string()))?;
+        let writer = pair.master.take_writer().map_err(|e| io::Error::other(e.to_string()))?;
+        let (sender, output) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buffer = [0u8; 4096];
+            while let Ok(read) = reader.read(&mut buffer) {
+                if read == 0 || sender.send(buffer[..read].to_vec()).is_err() {
+                    break;
+                }
+                if let Some(waker) = &waker {
+                    waker.wake();
+                }
+            }
+        });
+        Ok(TerminalPane { parser: vt100::Parser::new(rows, cols, SCROLLBACK), master: pair.master, writer, child, output, exit_code: None })
+    }
+
+    /// Feeds new output to the screen and notices when the command has exited; returns the new output.
+    pub fn pump(&mut self) -> Vec<u8> {
+        let mut output = Vec::new();
+        while let Ok(bytes) = self.output.try_recv() {
+            self.parser.process(&bytes);
+            output.extend(bytes);
+        }
+        // Later output, from processes the command left running, is still shown.
+        if self.exit_code.is_none()
+            && let Ok(Some(status)) = self.child.try_wait()
+        {
+            self.exit_code = Some(status.exit_code() as i32);
+        }
+        output
+    }
+
+    /// The command's exit code, once it has exited.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    /// Sends a key press to the command as the bytes a terminal would.
+    pub fn send_key(&mut self, key: KeyEvent) {
+        let application_cursor = self.parser.screen().application_cursor();
+        let cursor = |code: u8| if application_cursor { vec![0x1b, b'O', code] } else { vec![0x1b, b'[', code] };
+        let mut bytes = match key.code {
+            KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) && c.is_ascii_alphabetic() => vec![c.to_ascii_lowercase() as u8 & 0x1f],
+            KeyCode::Char(c) => c.to_string().into_bytes(),
+            KeyCode::Enter => vec![b'\r'],
+            KeyCode::Backspace => vec![0x7f],
+            KeyCode::Tab => vec![b'\t'],
+            KeyCode::BackTab => b"\x1b[Z".to_vec(),
+            KeyCode::Esc => vec![0x1b],
+            KeyCode::Up => cursor(b'A'),
+            KeyCode::Down => cursor(b'B'),
+            KeyCode::Right => cursor(b'C'),
+            KeyCode::Left => cursor(b'D'),
+            KeyCode::Home => cursor(b'H'),
+            KeyCode::End => cursor(b'F'),
+            KeyCode::Delete => b"\x1b[3~".to_vec(),
+            KeyCode::PageUp => b"\x1b[5~".to_vec(),
+            KeyCode::PageDown => b"\x1b[6~".to_vec(),
+            _ => return,
+        };
+        if key.modifiers.contains(KeyModifiers::ALT) {
+            bytes.insert(0, 0x1b);
+        }
+        // A command that stopped reading its input simply misses the key.
+        let _ = self.writer.write_all(&bytes).and_then(|_| self.writer.flush());
+        self.parser.set_scrollback(0);
+    }
+
+    /// Scrolls the view back (positive) or forward through the output.
+    pub fn scroll(&mut self, lines: isize) {
+        let current = self.parser.screen().scrollback();
+        self.parser.set_scrollback(current.saturating_add_signed(lines));
+    }
+
+    /// Draws the screen inside a bordered block, resizing the terminal to fit `area` first.
+    pub fn draw(&mut self, f: &mut Frame, area: Rect, title: &str, theme: &Theme) {
+        let block = Block::default().borders(Borders::ALL).title(title.to_string()).border_style(theme.focused);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        if inner.width == 0 || inner.height == 0 {
+            return;
+        }
+        if self.parser.screen().size() != (inner.height, inner.width) {
+            self.parser.set_size(inner.height, inner.width);
+            let _ = self.master.resize(PtySize { rows: inner.height, cols: inner.width, pixel_width: 0, pixel_height: 0 });
+        }
+        let screen = self.parser.screen();
+        let buffer = f.buffer_mut();
+        for row in 0..inner.height {
+            for col in 0..inner.width {
+                let Some(cell) = screen.cell(row, col) else { continue };
+                if cell.is_wide_continuation() {
+                    continue;
+                }
+                let mut style = Style::default().fg(color(cell.fgcolor())).bg(color(cell.bgcolor()));
+                for (on, modifier) in [(cell.bold(), Modifier::BOLD), (cell.italic(), Modifier::ITALIC),
+                    (cell.underline(), Modifier::UNDERLINED), (cell.inverse(), Modifier::REVERSED)] {
+                    if on {
+                        style = style.add_modifier(modifier);
+                    }
+                }
+                let contents = cell.contents();
+                buffer.get_mut(inner.x + col, inner.y + row)
+                    .set_symbol(if contents.is_empty() { " " } else { &contents })
+                    .set_style(style);
+            }
+        }
+        let (row, col) = screen.cursor_position();
+        if self.exit_code.is_none() && !screen.hide_cursor() && screen.scrollback() == 0 && row < inner.height && col < inner.width {
+            f.set_cursor(inner.x + col, inner.y + row);
+        }
+    }
+}
+
+fn color(color: vt100::Color) -> Color {
+    match color {
+        vt100::Color::Default => Color::Reset,
+        vt100::Color::Idx(index) => Color::Indexed(index),
+        vt100::Color::Rgb(r, g, b) => Color::Rgb(r, g, b),
+    }
+}
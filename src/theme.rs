@@ -0,0 +1,187 @@
+// src/theme.rs
+
+use ratatui::style::{Color, Modifier, Style};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+const CONFIG_FILE: &str = "config";
+const THEME_KEY: &str = "theme";
+
+/// Selectable color schemes, stored in the config file by [`Scheme::key`].
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Scheme {
+    #[default]
+    Default,
+    HighContrast,
+    Solarized,
+    /// No colors at all, only bold/reverse attributes, for limited terminals.
+    Monochrome,
+}
+
+impl Scheme {
+    pub const ALL: [Scheme; 4] = [Scheme::Default, Scheme::HighContrast, Scheme::Solarized, Scheme::Monochrome];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Scheme::Default => "Default",
+            Scheme::HighContrast => "High contrast",
+            Scheme::Solarized => "Solarized",
+            Scheme::Monochrome => "Monochrome (no colors)",
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Scheme::Default => "default",
+            Scheme::HighContrast => "high-contrast",
+            Scheme::Solarized => "solarized",
+            Scheme::Monochrome => "monochrome",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Scheme> {
+        Scheme::ALL.into_iter().find(|scheme| scheme.key() == key)
+    }
+}
+
+/// Styles shared by every screen, derived from a [`Scheme`].
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub scheme: Scheme,
+    /// Title bar text.
+    pub title: Style,
+    /// Menu list text and border.
+    pub menu: Style,
+    /// Border of the focused pane.
+    pub focused: Style,
+    /// Highlighted row in lists.
+    pub highlight: Style,
+    /// Key hints at the bottom of the screen.
+    pub footer: Style,
+    /// Toasts and status messages.
+    pub warning: Style,
+    /// Missing required values and failures.
+    pub error: Style,
+    /// Secondary text such as parameter values.
+    pub muted: Style,
+    /// Search match the viewer is positioned on, and the other matches.
+    pub current_match: Style,
+    pub other_match: Style,
+    /// Unresolved `{{key}}` placeholders in scripts.
+    pub placeholder: Style,
+    /// Lines only in one side of a diff; removed lines use `error`.
+    pub added: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::new(Scheme::Default)
+    }
+}
+
+impl Theme {
+    pub fn new(scheme: Scheme) -> Theme {
+        let bold = Modifier::BOLD;
+        match scheme {
+            Scheme::Default => Theme {
+                scheme,
+                title: Style::default().fg(Color::Green).add_modifier(bold),
+                menu: Style::default().fg(Color::Yellow),
+                focused: Style::default().fg(Color::Yellow),
+                highlight: Style::default().add_modifier(bold).bg(Color::DarkGray),
+                footer: Style::default().fg(Color::Cyan),
+                warning: Style::default().fg(Color::Yellow),
+                error: Style::default().fg(Color::Red),
+                muted: Style::default().fg(Color::Gray),
+                current_match: Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(bold),
+                other_match: Style::default().fg(Color::Black).bg(Color::DarkGray),
+                placeholder: Style::default().fg(Color::White).bg(Color::Red).add_modifier(bold),
+                added: Style::default().fg(Color::Green),
+            },
+            Scheme::HighContrast => Theme {
+                scheme,
+                title: Style::default().fg(Color::White).bg(Color::Blue).add_modifier(bold),
+                menu: Style::default().fg(Color::White),
+                focused: Style::default().fg(Color::LightYellow).add_modifier(bold),
+                highlight: Style::default().fg(Color::Black).bg(Color::LightYellow).add_modifier(bold),
+                footer: Style::default().fg(Color::White).add_modifier(bold),
+                warning: Style::default().fg(Color::LightYellow).add_modifier(bold),
+                error: Style::default().fg(Color::LightRed).add_modifier(bold),
+                muted: Style::default().fg(Color::White),
+                current_match: Style::default().fg(Color::Black).bg(Color::LightYellow).add_modifier(bold),
+                other_match: Style::default().fg(Color::Black).bg(Color::White),
+                placeholder: Style::default().fg(Color::White).bg(Color::Red).add_modifier(bold),
+                added: Style::default().fg(Color::LightGreen).add_modifier(bold),
+            },
+            Scheme::Solarized => {
+                let base1 = Color::Rgb(0x93, 0xa1, 0xa1);
+                let base02 = Color::Rgb(0x07, 0x36, 0x42);
+                let yellow = Color::Rgb(0xb5, 0x89, 0x00);
+                Theme {
+                    scheme,
+                    title: Style::default().fg(Color::Rgb(0x26, 0x8b, 0xd2)).add_modifier(bold),
+                    menu: Style::default().fg(base1),
+                    focused: Style::default().fg(yellow),
+                    highlight: Style::default().fg(Color::Rgb(0xfd, 0xf6, 0xe3)).bg(base02).add_modifier(bold),
+                    footer: Style::default().fg(Color::Rgb(0x2a, 0xa1, 0x98)),
+                    warning: Style::default().fg(Color::Rgb(0xcb, 0x4b, 0x16)),
+                    error: Style::default().fg(Color::Rgb(0xdc, 0x32, 0x2f)),
+                    muted: Style::default().fg(Color::Rgb(0x58, 0x6e, 0x75)),
+                    current_match: Style::default().fg(base02).bg(yellow).add_modifier(bold),
+                    other_match: Style::default().fg(base1).bg(base02),
+                    placeholder: Style::default().fg(Color::Rgb(0xfd, 0xf6, 0xe3)).bg(Color::Rgb(0xdc, 0x32, 0x2f)).add_modifier(bold),
+                    added: Style::default().fg(Color::Rgb(0x85, 0x99, 0x00)),
+                }
+            }
+            Scheme::Monochrome => Theme {
+                scheme,
+                title: Style::default().add_modifier(bold),
+                menu: Style::default(),
+                focused: Style::default().add_modifier(bold),
+                highlight: Style::default().add_modifier(Modifier::REVERSED),
+                footer: Style::default(),
+                warning: Style::default().add_modifier(bold),
+                error: Style::default().add_modifier(bold | Modifier::UNDERLINED),
+                muted: Style::default(),
+                current_match: Style::default().add_modifier(Modifier::REVERSED | bold),
+                other_match: Style::default().add_modifier(Modifier::UNDERLINED),
+                placeholder: Style::default().add_modifier(Modifier::REVERSED | bold),
+                added: Style::default().add_modifier(bold),
+            },
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/redcent-tui/config`, falling back to `~/.config/redcent-tui/config`.
+pub fn config_path() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_home.join("redcent-tui").join(CONFIG_FILE))
+}
+
+/// Reads `theme = <key>` from the config file; unknown or missing values give the default scheme.
+pub fn load_scheme() -> Scheme {
+    let Some(content) = config_path().and_then(|path| fs::read_to_string(path).ok()) else { return Scheme::default() };
+    content.lines()
+        .filter_map(|line| line.split_once('='))
+        .find(|(key, _)| key.trim() == THEME_KEY)
+        .and_then(|(_, value)| Scheme::from_key(value.trim()))
+        .unwrap_or_default()
+}
+
+/// Writes the `theme` line to the config file, keeping any other lines as they are.
+pub fn save_scheme(scheme: Scheme) -> io::Result<()> {
+    let path = config_path().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "cannot determine config directory"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut lines: Vec<String> = fs::read_to_string(&path).unwrap_or_default().lines()
+        .filter(|line| line.split_once('=').is_none_or(|(key, _)| key.trim() != THEME_KEY))
+        .map(String::from)
+        .collect();
+    lines.push(format!("{} = {}", THEME_KEY, scheme.key()));
+    fs::write(&path, lines.join("\n") + "\n")
+}
@@ -0,0 +1,360 @@
+// src/troubleshoot.rs
+
+use crate::{centered_rect, events::Waker, privilege::Escalation, progress::strip_escapes, terminal::TerminalPane, theme::Theme};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::Line,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use redcent_tui::{find_item, MenuNode, PackageBackend, BLOCK_MARKER, NOT_VERIFIED_MARKER, VERIFIED_MARKER};
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    io,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Output lines kept for matching against [`HINTS`].
+const KEPT_LINES: usize = 200;
+
+/// Advice offered when the output contains one of the patterns.
+const HINTS: [(&[&str], &str); 8] = [
+    (
+        &["Could not resolve host", "Temporary failure resolving", "Failed to download metadata", "Cannot download repomd.xml", "Curl error"],
+        "Mirrors were unreachable: run preflight (p) to check DNS, the proxy and the repositories, or use offline mode with a local repository.",
+    ),
+    (
+        &["No match for argument", "Unable to find a match", "Unable to locate package", "has no installation candidate"],
+        "A package was not found: enable the repository that provides it (EPEL needs CRB on RHEL) or check the package name for this release.",
+    ),
+    (
+        &["Could not get lock", "is locked by another process", "Waiting for process with pid"],
+        "Another package manager held its lock, e.g. dnf-automatic or unattended-upgrades: wait for it to finish, then resume.",
+    ),
+    (&["No space left on device"], "A filesystem ran full: free space under /var and /usr, then resume."),
+    (
+        &["GPG check FAILED", "NO_PUBKEY", "public key for"],
+        "A repository's signing key is missing or wrong: import the vendor's key, then resume.",
+    ),
+    (
+        &["This system is not registered", "not registered with an entitlement server"],
+        "RHEL is not registered: run subscription-manager register, then resume.",
+    ),
+    (
+        &["avc:  denied", "SELinux is preventing", "Permission denied"],
+        "Access was denied: check the SELinux denials; restorecon -Rv on the paths involved, or a boolean from semanage boolean -l, usually settles them.",
+    ),
+    (
+        &["Job for ", "failed because the control process exited"],
+        "A service failed to start: its journal tells why.",
+    ),
+];
+
+/// Collects what a run prints about its failures while it runs: the blocks reported as failed,
+/// the last block started, the verification results and the last lines of output.
+pub struct FailureLog {
+    /// When the run started, in seconds since the epoch.
+    started: u64,
+    /// Labels of the blocks reported as failed, e.g. `repos.epel (block 2)`.
+    failed: Vec<String>,
+    /// Item ID of the last block started.
+    last_block: Option<String>,
+    /// Verification commands that succeeded.
+    verified: usize,
+    /// Verification commands that failed, as (item ID, command).
+    unverified: Vec<(String, String)>,
+    lines: VecDeque<String>,
+    /// Output after the last line break.
+    partial: String,
+}
+
+impl FailureLog {
+    /// Starts collecting as the run starts.
+    pub fn start() -> FailureLog {
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        FailureLog { started, failed: Vec::new(), last_block: None, verified: 0, unverified: Vec::new(), lines: VecDeque::new(), partial: String::new() }
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.partial.push_str(&String::from_utf8_lossy(bytes));
+        while let Some(end) = self.partial.find('\n') {
+            // A progress bar redraws its line after carriage returns; only the last state counts.
+            let line = strip_escapes(self.partial[..end].rsplit('\r').find(|part| !part.trim().is_empty()).unwrap_or_default());
+            self.partial.drain(..=end);
+            self.line(line.trim());
+        }
+    }
+
+    fn line(&mut self, line: &str) {
+        if let Some(label) = line.strip_prefix("FAILED: ") {
+            self.failed.push(label.to_string());
+        } else if let Some((_, id)) = line.strip_prefix(BLOCK_MARKER).and_then(|rest| rest.split_once(": ")) {
+            self.last_block = Some(id.to_string());
+        } else if line.starts_with(VERIFIED_MARKER) {
+            self.verified += 1;
+        } else if let Some((id, check)) = line.strip_prefix(NOT_VERIFIED_MARKER).and_then(|rest| rest.split_once(": ")) {
+            self.unverified.push((id.to_string(), check.to_string()));
+        }
+        if self.lines.len() == KEPT_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line.to_string());
+    }
+
+    /// The failing steps as (item ID, command number within the item): the blocks reported as
+    /// failed and the items that failed verification, or the item the script stopped in when it
+    /// reports neither.
+    pub fn steps(&self) -> Vec<(String, Option<usize>)> {
+        let mut steps: Vec<(String, Option<usize>)> = self.failed.iter().map(|label| match label.strip_suffix(')').and_then(|label| label.split_once(" (block ")) {
+            Some((id, n)) => (id.to_string(), n.parse().ok()),
+            None => (label.clone(), None),
+        }).collect();
+        for (id, _) in &self.unverified {
+            if !steps.iter().any(|(step, _)| step == id) {
+                steps.push((id.clone(), None));
+            }
+        }
+        if steps.is_empty() {
+            steps.extend(self.last_block.iter().map(|id| (id.clone(), None)));
+        }
+        steps
+    }
+
+    /// How many verification commands passed and failed, once any ran.
+    pub fn verification(&self) -> Option<(usize, usize)> {
+        (self.verified + self.unverified.len() > 0).then_some((self.verified, self.unverified.len()))
+    }
+
+    /// The verification commands of `id` that failed.
+    fn unverified(&self, id: &str) -> Vec<String> {
+        self.unverified.iter().filter(|(item, _)| item == id).map(|(_, check)| check.clone()).collect()
+    }
+
+    /// Advice matching the output, in the order of [`HINTS`].
+    pub fn hints(&self) -> Vec<&'static str> {
+        HINTS.iter()
+            .filter(|(patterns, _)| self.lines.iter().any(|line| patterns.iter().any(|pattern| line.contains(pattern))))
+            .map(|(_, hint)| *hint)
+            .collect()
+    }
+}
+
+/// A step of a failed run, as its item renders it now.
+pub struct FailedStep {
+    pub id: String,
+    pub name: String,
+    /// The failing commands: the reported block, or all of the item's commands.
+    pub commands: Vec<String>,
+    /// Its verification commands that failed.
+    pub unverified: Vec<String>,
+}
+
+/// Something to look at after a failure, run in the pane of the troubleshooting screen.
+pub enum Check {
+    /// Runs the step's commands again with `bash -x`.
+    Rerun(usize),
+    /// The journal of a service the failing steps touch, since the run started.
+    Journal(String),
+    /// SELinux denials logged today.
+    Selinux,
+}
+
+/// Helps after a failed run instead of leaving only its exit code: lists the failing steps and
+/// advice matching the output, and runs checks in a pane below: the failing step again with
+/// tracing, the journal of the services it touches, SELinux denials. ↑↓ pick a check, Enter
+/// runs it.
+pub struct Troubleshooter {
+    steps: Vec<FailedStep>,
+    checks: Vec<Check>,
+    hints: Vec<&'static str>,
+    /// Start of the failed run, in seconds since the epoch.
+    since: u64,
+    selected: usize,
+    /// The check running or run last, with its label.
+    pane: Option<(String, TerminalPane)>,
+    /// Why the last check could not be started.
+    message: Option<String>,
+    /// Wakes the event loop when a check prints something.
+    waker: Option<Waker>,
+}
+
+impl Troubleshooter {
+    /// Looks up the steps `log` reports as failed among the items of `tree`.
+    pub fn new(log: &FailureLog, tree: &Rc<RefCell<MenuNode>>, backend: &dyn PackageBackend, waker: Option<Waker>) -> Troubleshooter {
+        let mut steps = Vec::new();
+        let mut units = Vec::new();
+        for (id, block) in log.steps() {
+            let Some(item) = find_item(tree, &id) else { continue };
+            let item = item.borrow();
+            let Some(script) = item.script(backend) else { continue };
+            let commands: Vec<String> = match block.and_then(|n| script.commands.get(n.wrapping_sub(1))) {
+                Some(cmd) => vec![cmd.code.clone()],
+                None => script.commands.iter().filter(|cmd| !cmd.is_comment()).map(|cmd| cmd.code.clone()).collect(),
+            };
+            for unit in commands.iter().flat_map(|code| service_units(code)).chain(script.listens.iter().map(|(_, unit)| unit.clone())) {
+                if !units.contains(&unit) {
+                    units.push(unit);
+                }
+            }
+            let unverified = log.unverified(&id);
+            steps.push(FailedStep { id, name: item.name().to_string(), commands, unverified });
+        }
+        let mut checks: Vec<Check> = (0..steps.len()).map(Check::Rerun).collect();
+        checks.extend(units.into_iter().map(Check::Journal));
+        checks.push(Check::Selinux);
+        Troubleshooter { steps, checks, hints: log.hints(), since: log.started, selected: 0, pane: None, message: None, waker }
+    }
+
+    pub fn check_labels(&self) -> Vec<String> {
+        self.checks.iter().map(|check| match check {
+            Check::Rerun(n) => format!("Re-run {} with tracing", self.steps[*n].name),
+            Check::Journal(unit) => format!("Journal of {} since the run started", unit),
+            Check::Selinux => "SELinux denials logged today".to_string(),
+        }).collect()
+    }
+
+    /// The command line running `check`, with root gained through `escalation`.
+    pub fn argv(&self, check: &Check, escalation: Escalation) -> Vec<String> {
+        let shell = |code: String| vec!["bash".to_string(), "-c".to_string(), escalation.apply(&code)];
+        match check {
+            Check::Rerun(n) => vec![
+                "bash".to_string(),
+                "-c".to_string(),
+                format!("{}bash -x -c \"$0\"", escalation.prefix()),
+                escalation.apply(&self.steps[*n].commands.join("\n")),
+            ],
+            Check::Journal(unit) => shell(format!("sudo journalctl --no-pager -u {} --since @{} -n 200", unit, self.since)),
+            Check::Selinux => shell(
+                "if ! command -v getenforce > /dev/null || [ \"$(getenforce)\" = Disabled ]; then\n    \
+                 echo 'SELinux is disabled on this host.'\nelse\n    \
+                 sudo ausearch -m AVC,USER_AVC,SELINUX_ERR -ts today -i\nfi".to_string(),
+            ),
+        }
+    }
+
+    /// Runs the highlighted check in the pane.
+    fn run(&mut self, escalation: Escalation, rows: u16, cols: u16) -> io::Result<()> {
+        let Some(check) = self.checks.get(self.selected) else { return Ok(()) };
+        let pane = TerminalPane::spawn(&self.argv(check, escalation), rows, cols, self.waker.clone())?;
+        self.pane = Some((self.check_labels().swap_remove(self.selected), pane));
+        Ok(())
+    }
+
+    /// Whether a check is still running, so keys go to it.
+    pub fn is_running(&self) -> bool {
+        self.pane.as_ref().is_some_and(|(_, pane)| pane.exit_code().is_none())
+    }
+
+    pub fn pump(&mut self) {
+        if let Some((_, pane)) = self.pane.as_mut() {
+            pane.pump();
+        }
+    }
+
+    /// Handles a key; returns `false` once the screen is closed.
+    pub fn handle_key(&mut self, key: KeyEvent, escalation: Escalation) -> bool {
+        if let Some((_, pane)) = self.pane.as_mut() {
+            match key.code {
+                KeyCode::PageUp if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                    pane.scroll(10);
+                    return true;
+                }
+                KeyCode::PageDown if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                    pane.scroll(-10);
+                    return true;
+                }
+                _ if pane.exit_code().is_none() => {
+                    pane.send_key(key);
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        match key.code {
+            KeyCode::Up => self.selected = (self.selected + self.checks.len() - 1) % self.checks.len(),
+            KeyCode::Down => self.selected = (self.selected + 1) % self.checks.len(),
+            KeyCode::Enter => {
+                let (cols, rows) = crossterm::terminal::size().unwrap_or((80, 24));
+                self.message = self.run(escalation, (rows / 2).saturating_sub(2).max(1), cols.saturating_sub(2).max(1))
+                    .err()
+                    .map(|e| format!("Could not start the check: {}", e));
+            }
+            KeyCode::Esc | KeyCode::Char('q') => return false,
+            _ => {}
+        }
+        true
+    }
+
+    pub fn draw(&mut self, f: &mut Frame, theme: &Theme) {
+        let area = centered_rect(90, 90, f.size());
+        f.render_widget(Clear, area);
+        let title = format!("Troubleshoot {} failed step(s)", self.steps.len());
+        f.render_widget(Block::default().title(title).borders(Borders::ALL).border_style(theme.warning), area);
+        let chunks = Layout::default().direction(Direction::Vertical).margin(1)
+            .constraints([Constraint::Percentage(45), Constraint::Min(3), Constraint::Length(1)])
+            .split(area);
+        let top = Layout::default().direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[0]);
+
+        let mut findings: Vec<Line> = Vec::new();
+        if self.steps.is_empty() {
+            findings.push(Line::styled("The output does not say which step failed.", theme.muted));
+        }
+        for step in &self.steps {
+            findings.push(Line::styled(format!("{} ({})", step.name, step.id), theme.error));
+            findings.extend(step.commands.iter().flat_map(|code| code.lines()).take(4).map(|line| Line::styled(format!("  {}", line), theme.muted)));
+            findings.extend(step.unverified.iter().map(|check| Line::styled(format!("  Not verified: {}", check), theme.warning)));
+        }
+        if !self.hints.is_empty() {
+            findings.push(Line::from(""));
+        }
+        findings.extend(self.hints.iter().map(|hint| Line::from(format!("• {}", hint))));
+        let findings = Paragraph::new(findings).wrap(Wrap { trim: false }).block(Block::default().borders(Borders::ALL).title("Failed Steps and Advice"));
+        f.render_widget(findings, top[0]);
+
+        let items: Vec<ListItem> = self.check_labels().into_iter().map(ListItem::new).collect();
+        let mut state = ListState::default();
+        state.select(Some(self.selected));
+        let list = List::new(items).block(Block::default().borders(Borders::ALL).title("Checks")).highlight_style(theme.highlight);
+        f.render_stateful_widget(list, top[1], &mut state);
+
+        let footer = match &mut self.pane {
+            Some((label, pane)) => {
+                let title = match pane.exit_code() {
+                    None => format!("{} (running)", label),
+                    Some(code) => format!("{} (exit code {})", label, code),
+                };
+                pane.draw(f, chunks[1], &title, theme);
+                if pane.exit_code().is_none() { "Keys go to the check | [Ctrl-C] Interrupt | [Shift+PgUp/PgDn] Scroll" } else { "↑↓ check | Enter run | [Shift+PgUp/PgDn] Scroll | Esc back to the run" }
+            }
+            None => {
+                let hint = match &self.message {
+                    Some(message) => Paragraph::new(message.as_str()).style(theme.error),
+                    None => Paragraph::new("Pick a check and press Enter; its output appears here.").style(theme.muted),
+                };
+                f.render_widget(hint.wrap(Wrap { trim: true }).block(Block::default().borders(Borders::ALL)), chunks[1]);
+                "↑↓ check | Enter run | Esc back to the run"
+            }
+        };
+        f.render_widget(Paragraph::new(footer).style(theme.muted), chunks[2]);
+    }
+}
+
+/// Units `code` enables, starts or restarts with systemctl.
+fn service_units(code: &str) -> Vec<String> {
+    let mut units = Vec::new();
+    for line in code.lines() {
+        let mut words = line.split_whitespace().skip_while(|word| *word != "systemctl").skip(1).filter(|word| !word.starts_with('-'));
+        if let Some("enable" | "start" | "restart" | "reload" | "try-restart" | "reload-or-restart") = words.next() {
+            units.extend(words
+                .take_while(|word| !matches!(*word, "&&" | "||" | ";" | "|"))
+                .filter(|word| word.chars().all(|c| c.is_ascii_alphanumeric() || "-_.@:".contains(c)))
+                .map(String::from));
+        }
+    }
+    units
+}
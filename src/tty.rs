@@ -0,0 +1,115 @@
+// src/tty.rs
+
+use crate::{console, draft::DRAFT_FILE, history};
+use crossterm::{
+    cursor::Show,
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::{
+    backtrace::Backtrace,
+    fs, io,
+    panic::{self, PanicHookInfo},
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
+/// Crash report written to the state directory when the TUI panics.
+const CRASH_FILE: &str = "crash.log";
+
+/// Whether the TUI has the terminal in raw mode, and whether it draws inline; shared with the
+/// panic hook, which has no access to the guard.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static INLINE: AtomicBool = AtomicBool::new(false);
+
+/// Holds the terminal in raw mode with mouse capture and bracketed paste, on the alternate screen
+/// unless `inline`. Dropping it gives the terminal back, so an early return or a panic does not
+/// leave the shell without echo.
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    pub fn enter(inline: bool) -> io::Result<TerminalGuard> {
+        INLINE.store(inline, Ordering::Relaxed);
+        enable_raw_mode()?;
+        ACTIVE.store(true, Ordering::Relaxed);
+        // From here on a failure is undone by the drop.
+        let guard = TerminalGuard;
+        if inline {
+            execute!(io::stdout(), EnableMouseCapture, EnableBracketedPaste)?;
+        } else {
+            execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
+        }
+        Ok(guard)
+    }
+
+    /// Gives the terminal back now rather than on drop, e.g. to print below the TUI.
+    pub fn restore(&self) -> io::Result<()> {
+        restore()
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = restore();
+    }
+}
+
+/// Leaves raw mode and the alternate screen; does nothing when the terminal was already given back.
+fn restore() -> io::Result<()> {
+    if !ACTIVE.swap(false, Ordering::Relaxed) {
+        return Ok(());
+    }
+    disable_raw_mode()?;
+    if INLINE.load(Ordering::Relaxed) {
+        execute!(io::stdout(), DisableMouseCapture, DisableBracketedPaste, Show)
+    } else {
+        execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, DisableBracketedPaste, Show)
+    }
+}
+
+/// Replaces the default panic output, which would land garbled on the TUI's screen: a panic on
+/// the main thread gives the terminal back first and then reports what happened in plain lines,
+/// with the backtrace written to [`CRASH_FILE`] in the state directory. A panicking background
+/// thread only writes the report while the TUI is drawing, and the TUI carries on.
+pub fn install_panic_hook() {
+    panic::set_hook(Box::new(|info| {
+        let message = panic_message(info);
+        let location = info.location().map(|at| format!("{}:{}", at.file(), at.line())).unwrap_or_else(|| "unknown location".to_string());
+        let thread = thread::current().name().unwrap_or("unnamed").to_string();
+        let report = format!(
+            "redcent-tui {} crashed at {}\nthread '{}' panicked at {}:\n{}\n\n{}\n",
+            env!("CARGO_PKG_VERSION"), history::display_timestamp(&history::timestamp_now()), thread, location, message,
+            Backtrace::force_capture(),
+        );
+        let saved = save_report(&report);
+        if thread != "main" && ACTIVE.load(Ordering::Relaxed) {
+            return;
+        }
+        let _ = restore();
+        console::error(&format!("redcent-tui crashed: {} ({})", message, location));
+        match saved {
+            Some(path) => console::note(&format!("The report with a backtrace was written to {}; please attach it when filing a bug.", path.display())),
+            None => eprintln!("{}", report),
+        }
+        if history::state_dir().is_some_and(|dir| dir.join(DRAFT_FILE).is_file()) {
+            console::note("The selection was autosaved as a draft; the next start offers to restore it.");
+        }
+    }));
+}
+
+fn panic_message(info: &PanicHookInfo) -> String {
+    let payload = info.payload();
+    payload.downcast_ref::<&str>().map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "(no message)".to_string())
+}
+
+fn save_report(report: &str) -> Option<PathBuf> {
+    let dir = history::state_dir()?;
+    fs::create_dir_all(&dir).ok()?;
+    let path = dir.join(CRASH_FILE);
+    fs::write(&path, report).ok()?;
+    Some(path)
+}
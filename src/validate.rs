@@ -0,0 +1,487 @@
+// src/validate.rs
+
+//! Checks for typed form input. Each validator takes the trimmed value and returns the message
+//! shown under the field when it is rejected, so front ends can refuse to emit a script with a
+//! malformed address, port or size.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+pub fn ipv4(value: &str) -> Result<(), String> {
+    value.parse::<Ipv4Addr>().map(|_| ()).map_err(|_| format!("'{}' is not an IPv4 address", value))
+}
+
+pub fn ipv6(value: &str) -> Result<(), String> {
+    value.parse::<Ipv6Addr>().map(|_| ()).map_err(|_| format!("'{}' is not an IPv6 address", value))
+}
+
+/// An IPv4 or IPv6 address.
+pub fn ip(value: &str) -> Result<(), String> {
+    parse_ip(value).map(|_| ())
+}
+
+fn parse_ip(value: &str) -> Result<IpAddr, String> {
+    value.parse::<IpAddr>().map_err(|_| format!("'{}' is not an IP address", value))
+}
+
+/// A network in `address/prefix` form, IPv4 or IPv6.
+pub fn cidr(value: &str) -> Result<(), String> {
+    let Some((address, prefix)) = value.split_once('/') else {
+        return Err("needs a /prefix length, e.g. 192.168.1.0/24".to_string());
+    };
+    let max = if parse_ip(address)?.is_ipv4() { 32 } else { 128 };
+    match prefix.parse::<u8>() {
+        Ok(bits) if bits <= max => Ok(()),
+        _ => Err(format!("prefix length must be 0-{}", max)),
+    }
+}
+
+/// An address, with or without a `/prefix`.
+pub fn ip_or_cidr(value: &str) -> Result<(), String> {
+    if value.contains('/') { cidr(value) } else { ip(value) }
+}
+
+/// A host name or FQDN per RFC 1123: dot-separated labels of up to 63 letters, digits and
+/// hyphens, not starting or ending with a hyphen, 253 characters at most.
+pub fn hostname(value: &str) -> Result<(), String> {
+    let name = value.strip_suffix('.').unwrap_or(value);
+    if name.is_empty() || name.len() > 253 {
+        return Err("must be 1-253 characters".to_string());
+    }
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err("each dot-separated part must be 1-63 characters".to_string());
+        }
+        if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("'{}' may only contain letters, digits and '-'", label));
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err(format!("'{}' must not start or end with '-'", label));
+        }
+    }
+    Ok(())
+}
+
+/// An IP address or a host name.
+pub fn host(value: &str) -> Result<(), String> {
+    ip(value).or_else(|_| hostname(value)).map_err(|_| format!("'{}' is neither an IP address nor a host name", value))
+}
+
+/// Host names or addresses separated by commas or spaces, e.g. a list of NTP servers.
+pub fn hosts(value: &str) -> Result<(), String> {
+    let mut entries = value.split([',', ' ']).filter(|entry| !entry.is_empty()).peekable();
+    if entries.peek().is_none() {
+        return Err("name at least one host".to_string());
+    }
+    entries.try_for_each(host)
+}
+
+/// A time zone name from the tz database, e.g. `Europe/Prague` or `UTC`.
+pub fn timezone(value: &str) -> Result<(), String> {
+    let valid = value.split('/').all(|part| {
+        part.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'))
+    });
+    if valid { Ok(()) } else { Err(format!("'{}' is not a time zone name such as Europe/Prague or UTC", value)) }
+}
+
+/// A locale as `localectl set-locale` takes it: `language_TERRITORY.codeset`, e.g. `en_US.UTF-8`,
+/// or `C.UTF-8`.
+pub fn locale(value: &str) -> Result<(), String> {
+    let (name, codeset) = value.split_once('.').unwrap_or((value, ""));
+    let (language, territory) = name.split_once('_').unwrap_or((name, ""));
+    let language_ok = language == "C" || (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_lowercase());
+    let territory_ok = territory.is_empty() || territory.len() == 2 && territory.chars().all(|c| c.is_ascii_uppercase());
+    let codeset_ok = codeset.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if language_ok && territory_ok && codeset_ok {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a locale such as en_US.UTF-8", value))
+    }
+}
+
+/// A whole number of zero or more, such as a count of days or seconds.
+pub fn number(value: &str) -> Result<(), String> {
+    value.parse::<u32>().map(|_| ()).map_err(|_| "must be a whole number".to_string())
+}
+
+/// An interface MTU from the IPv6 minimum of 1280 bytes up to the 9216-byte jumbo frames most
+/// switches accept.
+pub fn mtu(value: &str) -> Result<(), String> {
+    match value.parse::<u16>() {
+        Ok(1280..=9216) => Ok(()),
+        _ => Err("must be a number of bytes between 1280 and 9216".to_string()),
+    }
+}
+
+/// A network interface or bridge name as the kernel accepts it, limited to characters that need
+/// no quoting: up to 15 letters, digits, `_`, `.` and `-`, not starting with `-`.
+pub fn interface_name(value: &str) -> Result<(), String> {
+    if value.len() > 15 {
+        Err("must be at most 15 characters".to_string())
+    } else if value.starts_with('-') || value == "." || value == ".." {
+        Err("must not start with '-' or be '.' or '..'".to_string())
+    } else if !value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')) {
+        Err("may only contain letters, digits, '_', '.' and '-'".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// A release number in `X.Y.Z` form, such as `1.9.1`.
+pub fn semver(value: &str) -> Result<(), String> {
+    let parts: Vec<&str> = value.split('.').collect();
+    match parts.len() == 3 && parts.iter().all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit())) {
+        true => Ok(()),
+        false => Err("must be a version such as 1.9.1".to_string()),
+    }
+}
+
+/// A login name as `useradd` accepts it by default: a lowercase letter or `_`, then up to 31
+/// lowercase letters, digits, `_` or `-`.
+pub fn user_name(value: &str) -> Result<(), String> {
+    let mut chars = value.chars();
+    let first_ok = chars.next().is_some_and(|c| c.is_ascii_lowercase() || c == '_');
+    if value.len() > 32 {
+        Err("must be at most 32 characters".to_string())
+    } else if !first_ok || !chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-') {
+        Err("must start with a lowercase letter or '_' followed by lowercase letters, digits, '_' or '-'".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// An OpenSSH public key line as in `~/.ssh/id_ed25519.pub`: the key type, the base64 key and an
+/// optional comment.
+pub fn ssh_public_key(value: &str) -> Result<(), String> {
+    const TYPES: [&str; 7] = [
+        "ssh-ed25519", "ssh-rsa", "ecdsa-sha2-nistp256", "ecdsa-sha2-nistp384", "ecdsa-sha2-nistp521",
+        "sk-ssh-ed25519@openssh.com", "sk-ecdsa-sha2-nistp256@openssh.com",
+    ];
+    if value.contains("PRIVATE KEY") {
+        return Err("this is a private key; paste the contents of the .pub file instead".to_string());
+    }
+    if value.chars().any(char::is_control) {
+        return Err("must be a single line".to_string());
+    }
+    let mut parts = value.split_whitespace();
+    let (Some(kind), Some(key)) = (parts.next(), parts.next()) else {
+        return Err("must be a key type, the key and an optional comment, e.g. ssh-ed25519 AAAA... user@host".to_string());
+    };
+    if !TYPES.contains(&kind) {
+        return Err(format!("'{}' is not an SSH key type such as ssh-ed25519 or ssh-rsa", kind));
+    }
+    if key.len() < 16 || !key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=')) {
+        return Err("the key after the type is not base64".to_string());
+    }
+    Ok(())
+}
+
+/// A name for a drop-in file: letters, digits, `_` and `-`. Dots are excluded because
+/// `/etc/sudoers.d` skips files with a dot in their name.
+pub fn drop_in_name(value: &str) -> Result<(), String> {
+    match value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        true => Ok(()),
+        false => Err("may only contain letters, digits, '_' and '-'".to_string()),
+    }
+}
+
+/// Space-separated `NAME=value` assignments with shell variable names and values without
+/// whitespace, quotes, `$`, backticks, backslashes or glob characters.
+pub fn env_assignments(value: &str) -> Result<(), String> {
+    for assignment in value.split_whitespace() {
+        let Some((name, value)) = assignment.split_once('=') else {
+            return Err(format!("'{}' is not NAME=value", assignment));
+        };
+        let mut chars = name.chars();
+        if !chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("'{}' is not a valid variable name", name));
+        }
+        if value.contains(['\'', '"', '$', '`', '\\', '*', '?', '[']) {
+            return Err(format!("the value of {} must not contain quotes, $, `, \\ or *?[", name));
+        }
+    }
+    Ok(())
+}
+
+/// A port (`8080`) or an inclusive range of ports (`60000-61000`).
+pub fn port_range(value: &str) -> Result<(), String> {
+    let port = |text: &str| match text.trim().parse::<u16>() {
+        Ok(port) if port > 0 => Ok(port),
+        _ => Err(format!("'{}' is not a port between 1 and 65535", text.trim())),
+    };
+    match value.split_once('-') {
+        Some((first, last)) if port(first)? > port(last)? => Err("the range must not end before it starts".to_string()),
+        Some(_) => Ok(()),
+        None => port(value).map(|_| ()),
+    }
+}
+
+/// A kernel CPU list such as `3` or `2-7,10-15`: CPU numbers and inclusive ranges joined with commas.
+pub fn cpu_list(value: &str) -> Result<(), String> {
+    cpu_list_numbers(value).map(|_| ())
+}
+
+/// The CPU numbers in a list accepted by [`cpu_list`], in the order given.
+pub fn cpu_list_numbers(value: &str) -> Result<Vec<u32>, String> {
+    let cpu = |text: &str| text.parse::<u32>().map_err(|_| format!("'{}' is not a CPU number", text));
+    let mut cpus = Vec::new();
+    for part in value.split(',') {
+        match part.split_once('-') {
+            Some((first, last)) => {
+                let (first, last) = (cpu(first)?, cpu(last)?);
+                if first > last {
+                    return Err(format!("the range {} must not end before it starts", part));
+                }
+                cpus.extend(first..=last);
+            }
+            None => cpus.push(cpu(part)?),
+        }
+    }
+    Ok(cpus)
+}
+
+pub fn absolute_path(value: &str) -> Result<(), String> {
+    if !value.starts_with('/') {
+        Err("must be an absolute path".to_string())
+    } else if value.contains(['\n', '\0']) {
+        Err("must not contain line breaks".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// An absolute path of letters, digits, `/`, `_`, `.`, `+` and `-`, which needs no quoting in a
+/// shell command or a configuration file such as `/etc/exports`.
+pub fn plain_path(value: &str) -> Result<(), String> {
+    absolute_path(value)?;
+    match value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '.' | '+' | '-')) {
+        true => Ok(()),
+        false => Err("may only contain letters, digits, '/', '_', '.', '+' and '-'".to_string()),
+    }
+}
+
+/// A block device path such as `/dev/nvme0n1p3` or `/dev/disk/by-uuid/...`: below `/dev/`, of
+/// letters, digits, `/`, `_`, `.`, `:` and `-`.
+pub fn block_device(value: &str) -> Result<(), String> {
+    if !value.starts_with("/dev/") {
+        Err("must be a device path below /dev/".to_string())
+    } else if !value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '.' | ':' | '-')) {
+        Err("may only contain letters, digits, '/', '_', '.', ':' and '-'".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// An NFS client as `/etc/exports` names it: an address, a network (`192.168.1.0/24`), a host
+/// name, a wildcard such as `*.example.com` or `*`, or an `@netgroup`.
+pub fn nfs_client(value: &str) -> Result<(), String> {
+    let pattern = value.strip_prefix('@').unwrap_or(value);
+    let named = !pattern.is_empty() && !pattern.starts_with('-')
+        && pattern.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '*' | '?'));
+    match named || ip_or_cidr(value).is_ok() {
+        true => Ok(()),
+        false => Err("must be one address, network, host name, wildcard or @netgroup".to_string()),
+    }
+}
+
+/// TPM2 PCR indexes joined with `+`, such as `7` or `0+7`, each between 0 and 23.
+pub fn tpm2_pcrs(value: &str) -> Result<(), String> {
+    for pcr in value.split('+') {
+        match pcr.parse::<u8>() {
+            Ok(index) if index <= 23 => {}
+            _ => return Err(format!("'{}' is not a PCR index between 0 and 23; join several with +", pcr)),
+        }
+    }
+    Ok(())
+}
+
+/// A size such as `512M`, `20G` or `1.5GiB`: a number and an optional `K`, `M`, `G`, `T` or `P`
+/// suffix (binary multiples, optionally followed by `iB` or `B`).
+pub fn size(value: &str) -> Result<(), String> {
+    size_bytes(value).map(|_| ())
+}
+
+/// The number of bytes in a size accepted by [`size`].
+pub fn size_bytes(value: &str) -> Result<u64, String> {
+    let split = value.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().map_err(|_| format!("'{}' does not start with a number", value))?;
+    let unit = unit.trim().to_ascii_uppercase();
+    let unit = unit.strip_suffix("IB").or_else(|| unit.strip_suffix('B')).unwrap_or(&unit);
+    let exponent = match unit {
+        "" => 0,
+        "K" => 1,
+        "M" => 2,
+        "G" => 3,
+        "T" => 4,
+        "P" => 5,
+        _ => return Err(format!("unknown unit '{}'; use K, M, G, T or P", unit)),
+    };
+    Ok((number * 1024f64.powi(exponent)) as u64)
+}
+
+const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const CRON_DAYS: [&str; 8] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+const WEEKDAYS: [&str; 7] = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+
+/// A crontab schedule: five fields (minute, hour, day of month, month, day of week) of `*`,
+/// numbers, ranges, lists and `/steps`, or a nickname such as `@daily`.
+pub fn cron(value: &str) -> Result<(), String> {
+    if value.starts_with('@') {
+        return match value {
+            "@reboot" | "@yearly" | "@annually" | "@monthly" | "@weekly" | "@daily" | "@midnight" | "@hourly" => Ok(()),
+            _ => Err(format!("'{}' is not a cron nickname such as @daily", value)),
+        };
+    }
+    let fields: Vec<&str> = value.split_whitespace().collect();
+    if fields.len() != 5 {
+        return Err("needs 5 fields: minute hour day-of-month month day-of-week".to_string());
+    }
+    let specs: [(&str, u32, u32, &[&str]); 5] =
+        [("minute", 0, 59, &[]), ("hour", 0, 23, &[]), ("day of month", 1, 31, &[]), ("month", 1, 12, &MONTHS), ("day of week", 0, 7, &CRON_DAYS)];
+    for (field, (name, min, max, names)) in fields.iter().zip(specs) {
+        calendar_values(field, "-", min, max, names).map_err(|e| format!("{}: {}", name, e))?;
+    }
+    Ok(())
+}
+
+/// A systemd `OnCalendar=` expression: a shorthand such as `daily`, or an optional weekday
+/// list, date and time (`Mon..Fri *-*-* 03:00:00`), optionally followed by a time zone.
+pub fn on_calendar(value: &str) -> Result<(), String> {
+    const SHORTHANDS: [&str; 9] = ["minutely", "hourly", "daily", "weekly", "monthly", "yearly", "annually", "quarterly", "semiannually"];
+    if SHORTHANDS.contains(&value.to_ascii_lowercase().as_str()) {
+        return Ok(());
+    }
+    if value.contains("*/") {
+        return Err("repetitions start from a value: 0/15 rather than */15".to_string());
+    }
+    let mut tokens: Vec<&str> = value.split_whitespace().collect();
+    if let Some(zone) = tokens.last()
+        && tokens.len() > 1
+        && (*zone == "UTC" || zone.contains('/') && zone.starts_with(|c: char| c.is_ascii_uppercase()))
+    {
+        tokens.pop();
+    }
+    let mut tokens = tokens.into_iter().peekable();
+    if tokens.peek().is_none() {
+        return Err("needs a weekday, date or time, e.g. *-*-* 03:00:00".to_string());
+    }
+    if let Some(days) = tokens.next_if(|token| token.starts_with(|c: char| c.is_ascii_alphabetic())) {
+        for range in days.split(',') {
+            for day in range.split("..") {
+                let day = day.to_ascii_lowercase();
+                if !WEEKDAYS.iter().any(|name| *name == day || name[..3] == day) {
+                    return Err(format!("'{}' is not a weekday such as Mon", day));
+                }
+            }
+        }
+    }
+    if let Some(date) = tokens.next_if(|token| token.contains('-')) {
+        let parts: Vec<&str> = date.split('-').collect();
+        let specs: &[(&str, u32, u32)] = match parts.len() {
+            3 => &[("year", 1970, 2199), ("month", 1, 12), ("day", 1, 31)],
+            2 => &[("month", 1, 12), ("day", 1, 31)],
+            _ => return Err(format!("'{}' is not a date such as *-*-01 or 2026-10-16", date)),
+        };
+        for (part, (name, min, max)) in parts.iter().zip(specs) {
+            calendar_values(part, "..", *min, *max, &[]).map_err(|e| format!("{}: {}", name, e))?;
+        }
+    }
+    if let Some(time) = tokens.next_if(|token| token.contains(':')) {
+        let parts: Vec<&str> = time.split(':').collect();
+        if !(2..=3).contains(&parts.len()) {
+            return Err(format!("'{}' is not a time such as 03:00 or 03:00:00", time));
+        }
+        for (part, (name, max)) in parts.iter().zip([("hour", 23), ("minute", 59), ("second", 59)]) {
+            calendar_values(part, "..", 0, max, &[]).map_err(|e| format!("{}: {}", name, e))?;
+        }
+    }
+    match tokens.next() {
+        Some(token) => Err(format!("'{}' is not a weekday, date or time in that position", token)),
+        None => Ok(()),
+    }
+}
+
+/// A comma-separated list of `*`, values and ranges, each optionally with a `/step`. Values are
+/// numbers between `min` and `max` or, case-insensitively, one of `names`, standing for
+/// `min`, `min + 1` and so on.
+fn calendar_values(text: &str, range_separator: &str, min: u32, max: u32, names: &[&str]) -> Result<(), String> {
+    let value = |text: &str| {
+        let number = match names.iter().position(|name| name.eq_ignore_ascii_case(text)) {
+            Some(index) => Some(min + index as u32),
+            None => text.parse::<u32>().ok(),
+        };
+        match number {
+            Some(number) if (min..=max).contains(&number) => Ok(number),
+            Some(_) => Err(format!("'{}' is outside {}-{}", text, min, max)),
+            None => Err(format!("'{}' is not a number", text)),
+        }
+    };
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step)),
+            None => (part, None),
+        };
+        if let Some(step) = step
+            && !step.parse::<u32>().is_ok_and(|step| step > 0)
+        {
+            return Err(format!("'{}' has an invalid /step", part));
+        }
+        if range == "*" {
+            continue;
+        }
+        let (first, last) = range.split_once(range_separator).unwrap_or((range, range));
+        if value(first)? > value(last)? {
+            return Err(format!("the range '{}' ends before it starts", range));
+        }
+    }
+    Ok(())
+}
+
+/// A proxy URL: `http://` or `https://`, optional `user:password@`, a host and an optional port,
+/// e.g. `http://proxy.example.com:3128`.
+pub fn proxy_url(value: &str) -> Result<(), String> {
+    let Some(rest) = value.strip_prefix("http://").or_else(|| value.strip_prefix("https://")) else {
+        return Err("must start with http:// or https://".to_string());
+    };
+    if value.contains(|c: char| c.is_whitespace() || matches!(c, '\'' | '"' | '\\')) {
+        return Err("must not contain spaces, quotes or \\".to_string());
+    }
+    let authority = rest.trim_end_matches('/');
+    let address = authority.rsplit_once('@').map_or(authority, |(_, address)| address);
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) if !host.ends_with(':') => (host, Some(port)),
+        _ => (address, None),
+    };
+    if let Some(port) = port
+        && !port.parse::<u16>().is_ok_and(|port| port > 0)
+    {
+        return Err(format!("'{}' is not a port between 1 and 65535", port));
+    }
+    self::host(host.trim_start_matches('[').trim_end_matches(']'))
+}
+
+/// A `no_proxy` list: comma-separated host names, `.domain` suffixes, addresses or networks.
+pub fn no_proxy(value: &str) -> Result<(), String> {
+    for entry in value.split(',').map(str::trim) {
+        if entry == "*" {
+            continue;
+        }
+        host(entry.trim_start_matches('.'))
+            .or_else(|_| cidr(entry))
+            .map_err(|_| format!("'{}' is not a host name, .domain, address or network", entry))?;
+    }
+    Ok(())
+}
+
+/// A COPR project as `owner/project`, or `@group/project` for a group's project.
+pub fn copr_project(value: &str) -> Result<(), String> {
+    let Some((owner, project)) = value.split_once('/') else {
+        return Err("must be owner/project, e.g. atim/lazygit".to_string());
+    };
+    let name = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if !name(owner.strip_prefix('@').unwrap_or(owner)) || !name(project) {
+        return Err("owner and project may only contain letters, digits, '_', '-' and '.'".to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,228 @@
+// src/viewer.rs
+
+use crossterm::event::KeyCode;
+use crate::theme::Theme;
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+/// State for the scrollable, searchable script viewer used by the preview pane and the finished screen.
+#[derive(Default)]
+pub struct ScriptViewer {
+    pub focused: bool,
+    pub searching: bool,
+    pub query: String,
+    scroll: usize,
+    h_scroll: u16,
+    matches: Vec<usize>,
+    current_match: usize,
+    viewport_height: usize,
+    pub theme: Theme,
+}
+
+impl ScriptViewer {
+    pub fn new(theme: Theme) -> ScriptViewer {
+        ScriptViewer { theme, ..ScriptViewer::default() }
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    pub fn scroll_down(&mut self, lines: usize, content: &str) {
+        let max_scroll = content.lines().count().saturating_sub(self.viewport_height.max(1));
+        self.scroll = (self.scroll + lines).min(max_scroll);
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll_up(self.viewport_height.max(1));
+    }
+
+    pub fn page_down(&mut self, content: &str) {
+        self.scroll_down(self.viewport_height.max(1), content);
+    }
+
+    pub fn scroll_left(&mut self) {
+        self.h_scroll = self.h_scroll.saturating_sub(4);
+    }
+
+    pub fn scroll_right(&mut self) {
+        self.h_scroll = self.h_scroll.saturating_add(4);
+    }
+
+    pub fn home(&mut self) {
+        self.scroll = 0;
+        self.h_scroll = 0;
+    }
+
+    pub fn end(&mut self, content: &str) {
+        self.scroll_down(usize::MAX / 2, content);
+    }
+
+    /// Enters search input mode, clearing any previous query.
+    pub fn start_search(&mut self) {
+        self.focused = true;
+        self.searching = true;
+        self.query.clear();
+        self.matches.clear();
+    }
+
+    /// Finds all lines containing the query (case-insensitive) and jumps to the first match.
+    pub fn commit_search(&mut self, content: &str) {
+        self.searching = false;
+        let needle = self.query.to_lowercase();
+        self.matches = if needle.is_empty() {
+            Vec::new()
+        } else {
+            content.lines().enumerate()
+                .filter(|(_, line)| line.to_lowercase().contains(&needle))
+                .map(|(i, _)| i)
+                .collect()
+        };
+        self.current_match = 0;
+        self.jump_to_current_match();
+    }
+
+    pub fn cancel_search(&mut self) {
+        self.searching = false;
+        self.query.clear();
+        self.matches.clear();
+    }
+
+    pub fn next_match(&mut self) {
+        if !self.matches.is_empty() {
+            self.current_match = (self.current_match + 1) % self.matches.len();
+            self.jump_to_current_match();
+        }
+    }
+
+    pub fn prev_match(&mut self) {
+        if !self.matches.is_empty() {
+            self.current_match = (self.current_match + self.matches.len() - 1) % self.matches.len();
+            self.jump_to_current_match();
+        }
+    }
+
+    /// Scrolls so the 0-based `line` is a third of the way down the viewport.
+    pub fn jump_to_line(&mut self, line: usize) {
+        self.scroll = line.saturating_sub(self.viewport_height / 3);
+    }
+
+    fn jump_to_current_match(&mut self) {
+        if let Some(&line) = self.matches.get(self.current_match) {
+            self.jump_to_line(line);
+        }
+    }
+
+    /// Handles a key press aimed at the viewer. Returns `true` if the key was consumed.
+    pub fn handle_key(&mut self, code: KeyCode, content: &str) -> bool {
+        if self.searching {
+            match code {
+                KeyCode::Char(c) => self.query.push(c),
+                KeyCode::Backspace => { self.query.pop(); },
+                KeyCode::Enter => self.commit_search(content),
+                KeyCode::Esc => self.cancel_search(),
+                _ => {}
+            }
+            return true;
+        }
+        match code {
+            KeyCode::PageUp => self.page_up(),
+            KeyCode::PageDown => self.page_down(content),
+            KeyCode::Char('/') => self.start_search(),
+            _ if !self.focused => return false,
+            KeyCode::Up => self.scroll_up(1),
+            KeyCode::Down => self.scroll_down(1, content),
+            KeyCode::Left => self.scroll_left(),
+            KeyCode::Right => self.scroll_right(),
+            KeyCode::Home => self.home(),
+            KeyCode::End => self.end(content),
+            KeyCode::Char('n') => self.next_match(),
+            KeyCode::Char('N') => self.prev_match(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Describes scroll position and search state for the block title.
+    fn status(&self, total_lines: usize) -> String {
+        let mut status = format!("{}-{}/{}", (self.scroll + 1).min(total_lines), (self.scroll + self.viewport_height).min(total_lines), total_lines);
+        if self.searching {
+            status.push_str(&format!(" | /{}_", self.query));
+        } else if !self.query.is_empty() {
+            if self.matches.is_empty() {
+                status.push_str(&format!(" | '{}' not found", self.query));
+            } else {
+                status.push_str(&format!(" | '{}' {}/{}", self.query, self.current_match + 1, self.matches.len()));
+            }
+        }
+        status
+    }
+
+    /// Renders the content inside a bordered block, highlighting search matches and unresolved placeholders.
+    pub fn render(&mut self, f: &mut Frame, area: Rect, content: &str, title: &str) {
+        self.viewport_height = area.height.saturating_sub(2) as usize;
+        let total_lines = content.lines().count();
+        self.scroll = self.scroll.min(total_lines.saturating_sub(self.viewport_height.max(1)));
+
+        let needle = self.query.to_lowercase();
+        let current_line = self.matches.get(self.current_match).copied();
+        let lines: Vec<Line> = content.lines().enumerate().map(|(i, line)| {
+            if needle.is_empty() || self.searching || !self.matches.contains(&i) {
+                return highlight_placeholders(line, self.theme.placeholder);
+            }
+            let match_style = if Some(i) == current_line { self.theme.current_match } else { self.theme.other_match };
+            highlight_line(line, &needle, match_style)
+        }).collect();
+
+        let border_style = if self.focused { self.theme.focused } else { Style::default() };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(format!("{} [{}]", title, self.status(total_lines)));
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .scroll((self.scroll.min(u16::MAX as usize) as u16, self.h_scroll));
+        f.render_widget(paragraph, area);
+    }
+}
+
+/// Splits a line into spans so every case-insensitive occurrence of `needle` gets `style`.
+pub fn highlight_line<'a>(line: &'a str, needle: &str, style: Style) -> Line<'a> {
+    // Lowercasing non-ASCII text can move byte offsets, even where the total length is kept, so
+    // such lines are highlighted whole rather than sliced at offsets found in the lowercased copy.
+    if !line.is_ascii() {
+        return Line::from(Span::styled(line, style));
+    }
+    let lower = line.to_ascii_lowercase();
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for (start, _) in lower.match_indices(needle) {
+        if start < last {
+            continue;
+        }
+        spans.push(Span::raw(&line[last..start]));
+        spans.push(Span::styled(&line[start..start + needle.len()], style));
+        last = start + needle.len();
+    }
+    spans.push(Span::raw(&line[last..]));
+    Line::from(spans)
+}
+
+/// Splits a line into spans so unresolved `{{key}}` placeholders stand out in red.
+pub fn highlight_placeholders(line: &str, style: Style) -> Line<'_> {
+    let mut spans = Vec::new();
+    let mut last = 0;
+    while let Some(start) = line[last..].find("{{").map(|i| last + i) {
+        let Some(end) = line[start..].find("}}").map(|i| start + i + 2) else { break };
+        spans.push(Span::raw(&line[last..start]));
+        spans.push(Span::styled(&line[start..end], style));
+        last = end;
+    }
+    spans.push(Span::raw(&line[last..]));
+    Line::from(spans)
+}
@@ -0,0 +1,102 @@
+// src/watchdog.rs
+
+use crate::{progress::strip_escapes, theme::Theme};
+use ratatui::{
+    layout::Rect,
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// How long a script may go without output before the watchdog speaks up. dnf transactions and
+/// scriptlets are often quiet for a while; a prompt waiting for an answer is too.
+pub const SILENT_AFTER: Duration = Duration::from_secs(30);
+/// Output lines kept for the tail view.
+const TAIL_LINES: usize = 5;
+
+/// Watches a running script's output so a step that has gone quiet can be told from a hang:
+/// once nothing arrived for [`SILENT_AFTER`], a box over the bottom of the pane says when the
+/// last output came, with the last few lines below it unless collapsed.
+pub struct Watchdog {
+    last_output: Instant,
+    /// Wall-clock time of the last output, `HH:MM:SS` UTC.
+    last_clock: String,
+    tail: VecDeque<String>,
+    /// Output after the last line break, e.g. a prompt.
+    partial: String,
+    /// Whether the tail is shown under the indicator; F2 toggles it.
+    pub expanded: bool,
+}
+
+impl Watchdog {
+    /// Starts watching as the script starts.
+    pub fn start() -> Watchdog {
+        Watchdog { last_output: Instant::now(), last_clock: clock_now(), tail: VecDeque::new(), partial: String::new(), expanded: true }
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        if bytes.is_empty() {
+            return;
+        }
+        self.last_output = Instant::now();
+        self.last_clock = clock_now();
+        self.partial.push_str(&String::from_utf8_lossy(bytes));
+        while let Some(end) = self.partial.find('\n') {
+            // A progress bar redraws its line after carriage returns; only the last state counts.
+            let line = self.partial[..end].rsplit('\r').find(|part| !part.trim().is_empty()).map(strip_escapes);
+            self.partial.drain(..=end);
+            if let Some(line) = line.filter(|line| !line.trim().is_empty()) {
+                if self.tail.len() == TAIL_LINES {
+                    self.tail.pop_front();
+                }
+                self.tail.push_back(line.trim_end().to_string());
+            }
+        }
+    }
+
+    /// How long the script has been quiet as of `now`, once that is at least [`SILENT_AFTER`].
+    pub fn silent_for(&self, now: Instant) -> Option<Duration> {
+        let silence = now.saturating_duration_since(self.last_output);
+        (silence >= SILENT_AFTER).then_some(silence)
+    }
+
+    /// The last output lines, oldest first, with an unfinished line such as a prompt last.
+    pub fn tail(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.tail.iter().cloned().collect();
+        let partial = self.partial.rsplit('\r').next().map(strip_escapes).unwrap_or_default();
+        if !partial.trim().is_empty() {
+            if lines.len() == TAIL_LINES {
+                lines.remove(0);
+            }
+            lines.push(partial.trim_end().to_string());
+        }
+        lines
+    }
+
+    /// Draws the indicator over the bottom of `area` (the terminal pane) while the script is quiet.
+    pub fn draw(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let Some(silence) = self.silent_for(Instant::now()) else { return };
+        let lines = if self.expanded { self.tail() } else { Vec::new() };
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let rect = Rect { x: area.x, y: area.y + area.height - height, width: area.width, height };
+        let secs = silence.as_secs();
+        let hint = if self.expanded { "[F2] Hide" } else { "[F2] Show last output" };
+        let title = format!(
+            "Still running — last output at {} UTC ({}m {:02}s ago) | {}",
+            self.last_clock, secs / 60, secs % 60, hint,
+        );
+        f.render_widget(Clear, rect);
+        let block = Block::default().title(title).borders(Borders::ALL).border_style(theme.warning);
+        f.render_widget(Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>()).style(theme.muted).block(block), rect);
+    }
+}
+
+/// The current time of day as `HH:MM:SS` UTC.
+fn clock_now() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0) % 86400;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+}